@@ -0,0 +1,28 @@
+//! fuzzing of the minifier: any input must be minified, as best it can be,
+//! or return an error, but never panic, with every pass turned on.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rcss::{format, level::Level, minifier::Minifier, stylesheet::Stylesheet};
+
+fuzz_target!(|data: &[u8]| {
+    let css = String::from_utf8_lossy(data);
+    let minified = rcss::css::minify(&css);
+    let _ = Stylesheet::parse(&css).to_css();
+    let _ = Stylesheet::parse(&minified).to_css();
+    let _ = format::format(&css, format::Indent::default());
+    let minifier = Minifier::new()
+        .level(Level::Restructure)
+        .flatten_nesting(true)
+        .inline_variables(true)
+        .remove_unused_variables(true)
+        .remove_unused_at_rules(true)
+        .add_prefixes(true)
+        .strip_prefixes(true)
+        .strip_hacks(true)
+        .normalize_case(true)
+        .downlevel_colors(true)
+        .browsers("ie 11, last 2 versions")
+        .strict(data.first().is_some_and(|byte| byte % 2 == 0));
+    let _ = minifier.minify(&css);
+});
@@ -0,0 +1,16 @@
+//! fuzzing of the tokenizer, which every pass of rcss is built on: any
+//! input must be split into tokens that join back into exactly that input.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rcss::tokenizer::{tokenize, validate};
+
+fuzz_target!(|data: &[u8]| {
+    let source = String::from_utf8_lossy(data);
+    let joined = tokenize(&source)
+        .iter()
+        .map(|token| token.text)
+        .collect::<String>();
+    assert_eq!(joined, source);
+    let _ = validate(&source);
+});
@@ -0,0 +1,234 @@
+//! analyses of a tree of stylesheets for the `analyze` subcommand, like
+//! which selectors are the most specific, to find the ones worth
+//! simplifying.
+use std::{fmt, path::Path};
+
+use crate::{
+    error::Result,
+    glob, ordering, paths,
+    selectors::{self, compound_count, specificity, Specificity},
+    tokenizer::{tokenize, Token, TokenKind},
+    Options,
+};
+
+/// [`MAX_COMPOUNDS`] is how many compound selectors a selector can chain
+/// together, like the three of `nav .item>a`, before it is flagged.
+pub const MAX_COMPOUNDS: usize = 4;
+
+/// [`Spike`] is why a selector is more specific, or longer, than it
+/// likely needs to be.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Spike {
+    /// the selector has an id, like `#nav`, which no number of classes
+    /// can override.
+    Id,
+    /// the selector chains more than [`MAX_COMPOUNDS`] compound selectors
+    /// together, this many.
+    LongChain(usize),
+}
+
+impl fmt::Display for Spike {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Spike::Id => write!(f, "id"),
+            Spike::LongChain(count) => write!(f, "chain of {count}"),
+        }
+    }
+}
+
+/// [`SelectorSpecificity`] is a selector, where it is, and how specific it
+/// is.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SelectorSpecificity {
+    pub path: String,
+    pub line: usize,
+    pub column: usize,
+    /// the selector, minified, without the rest of its selector list.
+    pub selector: String,
+    pub specificity: Specificity,
+    pub spikes: Vec<Spike>,
+}
+
+impl fmt::Display for SelectorSpecificity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:>8}  {}:{}:{}  {}",
+            self.specificity.to_string(),
+            self.path,
+            self.line,
+            self.column,
+            self.selector
+        )?;
+        if !self.spikes.is_empty() {
+            let spikes = self
+                .spikes
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>();
+            write!(f, "  ({})", spikes.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// [`analyze_specificity`] returns every selector of every rule within the
+/// stylesheet `css`, reporting them as found in the file at `path`, in the
+/// order they appear.
+///
+/// # notes
+/// the selectors of nested rules are reported as written, like `&:hover`,
+/// rather than resolved against their parents. the keyframe selectors of
+/// `@keyframes`, like `50%`, aren't selectors, so they are left out.
+///
+/// # example
+/// [`analyze_specificity`] can be used to find the selectors with an id:
+/// ```rust
+/// use rcss::{analyze::{analyze_specificity, Spike}, selectors::Specificity};
+///
+/// let selectors = analyze_specificity("style.css", ".a, #nav .item > a { color: red }");
+/// assert_eq!(selectors[1].selector, "#nav .item>a");
+/// assert_eq!(selectors[1].specificity, Specificity(1, 1, 1));
+/// assert_eq!(selectors[1].spikes, [Spike::Id]);
+/// assert_eq!(selectors[1].to_string(), "   1,1,1  style.css:1:5  #nav .item>a  (id)");
+/// ```
+pub fn analyze_specificity(path: &str, css: &str) -> Vec<SelectorSpecificity> {
+    let mut selectors = vec![];
+    // whether every open block is a `@keyframes`
+    let mut keyframes = vec![];
+    let mut statement: Vec<Token> = vec![];
+    let mut nesting = 0usize;
+    for token in tokenize(css) {
+        match token.kind {
+            TokenKind::Comment => continue,
+            TokenKind::Function | TokenKind::OpenParen | TokenKind::OpenBracket => nesting += 1,
+            TokenKind::CloseParen | TokenKind::CloseBracket => nesting = nesting.saturating_sub(1),
+            _ => {}
+        }
+        if nesting > 0 {
+            statement.push(token);
+            continue;
+        }
+        match token.kind {
+            TokenKind::OpenBrace => {
+                let start = statement
+                    .iter()
+                    .position(|token| token.kind != TokenKind::Whitespace);
+                let is_keyframes = match start.map(|start| &statement[start]) {
+                    Some(start) if start.kind == TokenKind::AtKeyword => {
+                        start.text.to_ascii_lowercase().ends_with("keyframes")
+                    }
+                    Some(_) if keyframes.last() != Some(&true) => {
+                        for selector in split_selectors(&statement) {
+                            selectors.push(selector_specificity(path, selector));
+                        }
+                        false
+                    }
+                    _ => false,
+                };
+                keyframes.push(is_keyframes);
+                statement.clear();
+            }
+            TokenKind::CloseBrace => {
+                keyframes.pop();
+                statement.clear();
+            }
+            TokenKind::Semicolon => statement.clear(),
+            _ => statement.push(token),
+        }
+    }
+    selectors
+}
+
+/// [`split_selectors`] splits the tokens of a selector list on their
+/// top-level commas, leaving out the whitespace around every selector.
+fn split_selectors<'a, 'b>(tokens: &'b [Token<'a>]) -> Vec<&'b [Token<'a>]> {
+    let mut selectors = vec![];
+    let mut start = 0;
+    let mut depth = 0usize;
+    for (index, token) in tokens.iter().enumerate() {
+        match token.kind {
+            TokenKind::Function | TokenKind::OpenParen | TokenKind::OpenBracket => depth += 1,
+            TokenKind::CloseParen | TokenKind::CloseBracket => depth = depth.saturating_sub(1),
+            TokenKind::Comma if depth == 0 => {
+                selectors.push(&tokens[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    selectors.push(&tokens[start..]);
+    selectors
+        .into_iter()
+        .map(|selector| {
+            let is_space = |token: &Token| token.kind == TokenKind::Whitespace;
+            let start = selector.iter().position(|token| !is_space(token));
+            let end = selector.iter().rposition(|token| !is_space(token));
+            match start.zip(end) {
+                Some((start, end)) => &selector[start..=end],
+                None => &selector[..0],
+            }
+        })
+        .filter(|selector| !selector.is_empty())
+        .collect()
+}
+
+/// [`selector_specificity`] returns the specificity of the selector made of
+/// `tokens`.
+fn selector_specificity(path: &str, tokens: &[Token]) -> SelectorSpecificity {
+    let selector = tokens.iter().map(|token| token.text).collect::<String>();
+    let selector = selectors::minify(&selector);
+    let specificity = specificity(&selector);
+    let mut spikes = vec![];
+    if specificity.0 > 0 {
+        spikes.push(Spike::Id);
+    }
+    let compounds = compound_count(&selector);
+    if compounds > MAX_COMPOUNDS {
+        spikes.push(Spike::LongChain(compounds));
+    }
+    SelectorSpecificity {
+        path: path.to_string(),
+        line: tokens[0].line,
+        column: tokens[0].column,
+        selector,
+        specificity,
+        spikes,
+    }
+}
+
+/// [`specificity_directory`] returns every selector of the css files within
+/// `directory`, except `output` (the minified file rcss writes there), from
+/// the most specific to the least, and in the order they would be combined
+/// in for the same specificity.
+///
+/// # example
+/// [`specificity_directory`] can be used to print the ten most specific
+/// selectors in `./assets/css`:
+/// ```rust,no_run
+/// let options = rcss::Options::default();
+/// let selectors = rcss::analyze::specificity_directory("./assets/css", "style.css", &options).unwrap();
+/// for selector in selectors.iter().take(10) {
+///     println!("{selector}");
+/// }
+/// ```
+pub fn specificity_directory(
+    directory: &str,
+    output: &str,
+    options: &Options,
+) -> Result<Vec<SelectorSpecificity>> {
+    let filter = glob::FileFilter::new(&options.include, &options.exclude);
+    let output_path = Path::new(directory).join(output);
+    let files = crate::recurse_files(directory, &filter, options)?
+        .into_iter()
+        .filter(|file| crate::has_extension(file, "css") && !paths::same_file(file, &output_path))
+        .collect();
+    let files = ordering::order_files(files, directory, &options.order, options.sort)?;
+    let mut selectors = vec![];
+    for (file_path, content) in crate::read_sources(&files, options)? {
+        let path = ordering::relative_path(&file_path, directory);
+        selectors.extend(analyze_specificity(&path, &content));
+    }
+    selectors.sort_by_key(|selector| std::cmp::Reverse(selector.specificity));
+    Ok(selectors)
+}
@@ -0,0 +1,105 @@
+//! banners and footers around the combined output, like a comment with the
+//! version, date, and commit it was built from, so shipped files record
+//! where they came from.
+use std::{
+    fs,
+    path::Path,
+    process::Command,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::error::{RcssError, Result};
+
+/// [`read`] returns the content of the banner or footer `value`: the file
+/// at that path if there is one (without its last line break), or `value`
+/// itself otherwise.
+pub fn read(value: &str) -> Result<String> {
+    let path = Path::new(value);
+    if !path.is_file() {
+        return Ok(value.to_string());
+    }
+    let content = fs::read_to_string(path).map_err(RcssError::io(path))?;
+    let content = content.strip_suffix('\n').unwrap_or(&content);
+    Ok(content.strip_suffix('\r').unwrap_or(content).to_string())
+}
+
+/// [`expand`] replaces every placeholder within `template`: `{version}`
+/// with the version of rcss, `{date}` with the date of the build (or of the
+/// `SOURCE_DATE_EPOCH` environment variable, for reproducible builds), like
+/// `2024-05-01`, and `{commit}` with the short hash of the git commit that
+/// `directory` is checked out at, or `unknown` outside of a repository.
+///
+/// # example
+/// [`expand`] can be used to stamp the rcss version onto a bundle:
+/// ```rust
+/// let banner = rcss::banner::expand("/*! built by rcss {version} */", ".");
+/// assert_eq!(banner, format!("/*! built by rcss {} */", env!("CARGO_PKG_VERSION")));
+/// ```
+pub fn expand(template: &str, directory: impl AsRef<Path>) -> String {
+    let mut expanded = template.replace("{version}", env!("CARGO_PKG_VERSION"));
+    if expanded.contains("{date}") {
+        expanded = expanded.replace("{date}", &build_date());
+    }
+    if expanded.contains("{commit}") {
+        let commit = commit(directory.as_ref()).unwrap_or_else(|| "unknown".to_string());
+        expanded = expanded.replace("{commit}", &commit);
+    }
+    expanded
+}
+
+/// [`render`] returns the banner or footer `value` read by [`read`], with
+/// its placeholders replaced by [`expand`].
+pub fn render(value: &str, directory: impl AsRef<Path>) -> Result<String> {
+    Ok(expand(&read(value)?, directory))
+}
+
+/// [`build_date`] returns the date of the build as `year-month-day`, in utc.
+fn build_date() -> String {
+    let seconds = std::env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|epoch| epoch.trim().parse::<i64>().ok())
+        .unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_secs() as i64)
+        });
+    let (year, month, day) = civil_date(seconds.div_euclid(86_400));
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+/// [`civil_date`] returns the year, month, and day that are `days` after
+/// 1970-01-01, in the proleptic gregorian calendar.
+fn civil_date(days: i64) -> (i64, i64, i64) {
+    // shifted so that years start in march, and leap days come last
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = match shifted_month {
+        0..10 => shifted_month + 3,
+        _ => shifted_month - 9,
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// [`commit`] returns the short hash of the git commit that `directory` is
+/// checked out at, or `None` if it isn't in a repository.
+fn commit(directory: &Path) -> Option<String> {
+    let directory = match directory.as_os_str().is_empty() {
+        true => Path::new("."),
+        false => directory,
+    };
+    let output = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .current_dir(directory)
+        .output()
+        .ok()?;
+    let commit = String::from_utf8(output.stdout).ok()?;
+    let commit = commit.trim();
+    (output.status.success() && !commit.is_empty()).then(|| commit.to_string())
+}
@@ -0,0 +1,294 @@
+//! browser targets, read from queries like `"last 2 versions"` or
+//! `"safari >= 15"`, which decide the vendor prefixes that are needed.
+use std::fmt;
+
+use crate::error::{RcssError, Result};
+
+/// [`Browser`] is a browser that vendor prefixes are tracked for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Browser {
+    Chrome,
+    Edge,
+    Firefox,
+    Safari,
+    Ios,
+    Ie,
+}
+
+/// [`BROWSERS`] are every [`Browser`], with the names they are written as
+/// in a query and their recent releases, oldest first, when this list was
+/// last updated.
+///
+/// # notes
+/// versions aren't always one major version apart, like safari going from
+/// `18.6` to `26.0`, so `last <n> versions` takes the last `n` releases of
+/// these lists rather than counting down from the latest one.
+const BROWSERS: &[(Browser, &[&str], &[Version])] = &[
+    (Browser::Chrome, &["chrome"], CHROME),
+    (Browser::Edge, &["edge"], EDGE),
+    (Browser::Firefox, &["firefox", "ff"], FIREFOX),
+    (Browser::Safari, &["safari"], SAFARI),
+    (Browser::Ios, &["ios", "ios_saf"], SAFARI),
+    (Browser::Ie, &["ie", "explorer"], IE),
+];
+
+const CHROME: &[Version] = &[
+    Version(130, 0),
+    Version(131, 0),
+    Version(132, 0),
+    Version(133, 0),
+    Version(134, 0),
+    Version(135, 0),
+    Version(136, 0),
+    Version(137, 0),
+    Version(138, 0),
+    Version(139, 0),
+    Version(140, 0),
+];
+
+const EDGE: &[Version] = &[
+    Version(130, 0),
+    Version(131, 0),
+    Version(132, 0),
+    Version(133, 0),
+    Version(134, 0),
+    Version(135, 0),
+    Version(136, 0),
+    Version(137, 0),
+    Version(138, 0),
+    Version(139, 0),
+    Version(140, 0),
+];
+
+const FIREFOX: &[Version] = &[
+    Version(133, 0),
+    Version(134, 0),
+    Version(135, 0),
+    Version(136, 0),
+    Version(137, 0),
+    Version(138, 0),
+    Version(139, 0),
+    Version(140, 0),
+    Version(141, 0),
+    Version(142, 0),
+    Version(143, 0),
+];
+
+/// [`SAFARI`] are the releases of safari, which ios shares.
+const SAFARI: &[Version] = &[
+    Version(17, 0),
+    Version(17, 1),
+    Version(17, 2),
+    Version(17, 3),
+    Version(17, 4),
+    Version(17, 5),
+    Version(17, 6),
+    Version(18, 0),
+    Version(18, 1),
+    Version(18, 2),
+    Version(18, 3),
+    Version(18, 4),
+    Version(18, 5),
+    Version(18, 6),
+    Version(26, 0),
+];
+
+const IE: &[Version] = &[
+    Version(6, 0),
+    Version(7, 0),
+    Version(8, 0),
+    Version(9, 0),
+    Version(10, 0),
+    Version(11, 0),
+];
+
+/// [`DEFAULT_QUERY`] is used when no query is given.
+pub const DEFAULT_QUERY: &str = "last 2 versions";
+
+impl Browser {
+    /// [`Browser::parse`] reads a browser from its name in a query, like
+    /// `"ios_saf"`.
+    pub fn parse(name: &str) -> Option<Browser> {
+        BROWSERS
+            .iter()
+            .find(|(_, names, _)| names.contains(&name.to_ascii_lowercase().as_str()))
+            .map(|(browser, _, _)| *browser)
+    }
+
+    /// [`Browser::latest`] returns the latest version of the browser that
+    /// rcss knows about.
+    pub fn latest(&self) -> Version {
+        self.versions().last().copied().unwrap_or_default()
+    }
+
+    /// [`Browser::versions`] returns the releases of the browser that rcss
+    /// knows about, oldest first.
+    pub fn versions(&self) -> &'static [Version] {
+        BROWSERS
+            .iter()
+            .find(|(browser, _, _)| browser == self)
+            .map(|(_, _, versions)| *versions)
+            .unwrap_or_default()
+    }
+
+    /// [`Browser::last`] returns the oldest of the last `count` releases of
+    /// the browser, or its oldest known release if `count` is more than
+    /// rcss knows about.
+    ///
+    /// # example
+    /// [`Browser::last`] counts releases, not major versions:
+    /// ```rust
+    /// use rcss::browsers::{Browser, Version};
+    ///
+    /// assert_eq!(Browser::Chrome.last(2), Version(139, 0));
+    /// assert_eq!(Browser::Safari.last(2), Version(18, 6));
+    /// ```
+    pub fn last(&self, count: u32) -> Version {
+        let versions = self.versions();
+        let index = versions.len().saturating_sub(count as usize);
+        versions.get(index).copied().unwrap_or_default()
+    }
+
+    /// [`Browser::is_dead`] returns `true` for browsers that no longer get
+    /// updates, which `last <n> versions` leaves out.
+    pub fn is_dead(&self) -> bool {
+        *self == Browser::Ie
+    }
+}
+
+/// [`Version`] is a major and minor browser version, like `15.4`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Version(pub u32, pub u32);
+
+impl Version {
+    /// [`Version::parse`] reads a version like `"15"` or `"15.4"`.
+    pub fn parse(version: &str) -> Option<Version> {
+        let (major, minor) = version.split_once('.').unwrap_or((version, "0"));
+        Some(Version(major.parse().ok()?, minor.parse().ok()?))
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.0, self.1)
+    }
+}
+
+/// [`Targets`] are the oldest version of every browser that must be
+/// supported. browsers that aren't targeted at all don't need to be
+/// supported.
+///
+/// # example
+/// [`Targets`] can be read from a query of comma separated targets:
+/// ```rust
+/// use rcss::browsers::{Browser, Targets, Version};
+///
+/// let targets = Targets::parse("safari >= 15.4, last 2 chrome versions").unwrap();
+/// assert_eq!(targets.oldest(Browser::Safari), Some(Version(15, 4)));
+/// assert_eq!(targets.oldest(Browser::Chrome), Some(Version(139, 0)));
+/// assert_eq!(targets.oldest(Browser::Firefox), None);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Targets {
+    oldest: Vec<(Browser, Version)>,
+}
+
+impl Targets {
+    /// [`Targets::parse`] reads `query`, which is a comma separated list of
+    /// `last <n> versions`, `last <n> <browser> versions`, `<browser> >=
+    /// <version>`, `<browser> <version>`, or `defaults` (which is
+    /// [`DEFAULT_QUERY`]). an empty `query` is the same as `defaults`.
+    pub fn parse(query: &str) -> Result<Targets> {
+        let query = match query.trim() {
+            "" => DEFAULT_QUERY,
+            query => query,
+        };
+        let mut targets = Targets::default();
+        for target in query.split(',') {
+            let lowercase = target.to_ascii_lowercase();
+            let words = lowercase.split_whitespace().collect::<Vec<_>>();
+            let (browsers, oldest) = match words.as_slice() {
+                ["defaults"] => {
+                    targets.extend(&Targets::parse(DEFAULT_QUERY)?);
+                    continue;
+                }
+                ["last", count, "versions" | "version"] => {
+                    let browsers = BROWSERS
+                        .iter()
+                        .map(|(browser, _, _)| *browser)
+                        .filter(|browser| !browser.is_dead())
+                        .collect();
+                    (browsers, Oldest::Last(parse_count(count, target)?))
+                }
+                ["last", count, browser, "versions" | "version"] => (
+                    vec![parse_browser(browser, target)?],
+                    Oldest::Last(parse_count(count, target)?),
+                ),
+                [browser, ">=", version] | [browser, version] => (
+                    vec![parse_browser(browser, target)?],
+                    Oldest::Exactly(parse_version(version, target)?),
+                ),
+                _ => return Err(invalid(target)),
+            };
+            for browser in browsers {
+                let version = match oldest {
+                    Oldest::Last(count) => browser.last(count),
+                    Oldest::Exactly(version) => version,
+                };
+                targets.add(browser, version);
+            }
+        }
+        Ok(targets)
+    }
+
+    /// [`Targets::oldest`] returns the oldest version of `browser` that
+    /// must be supported, or `None` if it isn't targeted.
+    pub fn oldest(&self, browser: Browser) -> Option<Version> {
+        self.oldest
+            .iter()
+            .find(|(target, _)| *target == browser)
+            .map(|(_, version)| *version)
+    }
+
+    fn add(&mut self, browser: Browser, version: Version) {
+        match self
+            .oldest
+            .iter_mut()
+            .find(|(target, _)| *target == browser)
+        {
+            Some((_, oldest)) => *oldest = (*oldest).min(version),
+            None => self.oldest.push((browser, version)),
+        }
+    }
+
+    fn extend(&mut self, other: &Targets) {
+        for (browser, version) in &other.oldest {
+            self.add(*browser, *version);
+        }
+    }
+}
+
+enum Oldest {
+    Last(u32),
+    Exactly(Version),
+}
+
+fn parse_count(count: &str, target: &str) -> Result<u32> {
+    count
+        .parse()
+        .ok()
+        .filter(|count| *count > 0)
+        .ok_or_else(|| invalid(target))
+}
+
+fn parse_browser(browser: &str, target: &str) -> Result<Browser> {
+    Browser::parse(browser).ok_or_else(|| invalid(target))
+}
+
+fn parse_version(version: &str, target: &str) -> Result<Version> {
+    Version::parse(version).ok_or_else(|| invalid(target))
+}
+
+fn invalid(target: &str) -> RcssError {
+    RcssError::Config(format!("unknown browser target \"{}\"", target.trim()))
+}
@@ -0,0 +1,88 @@
+//! byte-size budgets, which fail a build whose output grew too big, so
+//! rcss can be used as a performance gate in ci.
+use std::fmt;
+
+/// [`LARGEST_FILES`] is how many of the largest files an [`OverBudget`]
+/// error lists.
+const LARGEST_FILES: usize = 5;
+
+/// [`parse_size`] reads a size like `"50kb"`, `"1.5 MB"`, or `"2048"` (in
+/// bytes), or returns `None` if it isn't one. a kilobyte is 1024 bytes.
+///
+/// # example
+/// [`parse_size`] can be used to read the size given to `--max-size`:
+/// ```rust
+/// use rcss::budget::parse_size;
+///
+/// assert_eq!(parse_size("50kb"), Some(51_200));
+/// assert_eq!(parse_size("1.5 MB"), Some(1_572_864));
+/// assert_eq!(parse_size("2048"), Some(2048));
+/// assert_eq!(parse_size("fifty"), None);
+/// ```
+pub fn parse_size(size: &str) -> Option<usize> {
+    let size = size.trim().to_ascii_lowercase();
+    let digits = size
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(size.len());
+    let (number, unit) = size.split_at(digits);
+    let multiplier = match unit.trim() {
+        "" | "b" => 1,
+        "k" | "kb" | "kib" => 1024,
+        "m" | "mb" | "mib" => 1024 * 1024,
+        _ => return None,
+    };
+    let number = number.parse::<f64>().ok()?;
+    Some((number * multiplier as f64).round() as usize)
+}
+
+/// [`OverBudget`] describes an output that is bigger than its budget, and
+/// the files that contributed the most to it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OverBudget {
+    /// the path of the output that would have been written.
+    pub output: String,
+    pub bytes: usize,
+    pub budget: usize,
+    /// the largest combined files and their minified sizes, largest first.
+    pub largest: Vec<(String, usize)>,
+}
+
+impl OverBudget {
+    /// [`OverBudget::new`] describes `output`, which is `bytes` long, going
+    /// over `budget`, keeping only the largest of `files`.
+    pub fn new(
+        output: impl Into<String>,
+        bytes: usize,
+        budget: usize,
+        mut files: Vec<(String, usize)>,
+    ) -> OverBudget {
+        files.sort_by(|(_, left), (_, right)| right.cmp(left));
+        files.truncate(LARGEST_FILES);
+        OverBudget {
+            output: output.into(),
+            bytes,
+            budget,
+            largest: files,
+        }
+    }
+}
+
+impl fmt::Display for OverBudget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} is {} B, which is {} B over the {} B budget",
+            self.output,
+            self.bytes,
+            self.bytes.saturating_sub(self.budget),
+            self.budget
+        )?;
+        if !self.largest.is_empty() {
+            write!(f, "\nlargest files:")?;
+        }
+        for (path, bytes) in &self.largest {
+            write!(f, "\n  {path}  {bytes} B")?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,166 @@
+//! bundles, which pair a source directory with the file that its
+//! files are combined into, so several outputs can be built at once.
+use crate::{
+    config,
+    error::{RcssError, Result},
+    rules, Options,
+};
+
+/// [`Bundle`] is a source directory, and the name of the file (within
+/// that directory) that its files are combined into.
+///
+/// # example
+/// [`Bundle`] can be parsed from the `<directory>:<output>` form that
+/// `--bundle` and the `bundles` config key use:
+/// ```rust
+/// let bundle = rcss::bundle::Bundle::parse("assets/css/critical:critical.css").unwrap();
+/// assert_eq!(bundle.directory, "assets/css/critical");
+/// assert_eq!(bundle.output, "critical.css");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Bundle {
+    pub directory: String,
+    pub output: String,
+}
+
+impl Bundle {
+    /// [`Bundle::new`] creates a bundle that combines the files within
+    /// `directory` into `output`.
+    pub fn new(directory: impl Into<String>, output: impl Into<String>) -> Bundle {
+        Bundle {
+            directory: directory.into(),
+            output: output.into(),
+        }
+    }
+
+    /// [`Bundle::parse`] parses a `<directory>:<output>` bundle. it splits
+    /// on the last `:`, so windows directories like `c:\css` still work.
+    pub fn parse(bundle: &str) -> std::result::Result<Bundle, String> {
+        match bundle.rsplit_once(':') {
+            Some((directory, output)) if !directory.is_empty() && !output.is_empty() => {
+                Ok(Bundle::new(directory, output))
+            }
+            _ => Err(format!(
+                "bundle {bundle} must look like <directory>:<output>"
+            )),
+        }
+    }
+
+    /// [`Bundle::from_config`] reads the `bundles` array from the root
+    /// table of a [`config::Config`], like:
+    /// ```toml
+    /// bundles = ["assets/css/critical:critical.css", "assets/css/main:main.css"]
+    /// ```
+    pub fn from_config(config: &config::Config) -> Result<Vec<Bundle>> {
+        let Some(value) = config.get("", "bundles") else {
+            return Ok(vec![]);
+        };
+        value
+            .as_strings()
+            .ok_or_else(|| RcssError::Config("bundles must be an array of strings".to_string()))?
+            .iter()
+            .map(|bundle| Bundle::parse(bundle).map_err(RcssError::Config))
+            .collect()
+    }
+}
+
+/// [`NamedBundle`] is a bundle from a `[bundles.<name>]` table of a config
+/// file, with its own extension and [`Options`], so one config can build
+/// several sites or themes that are minified differently.
+#[derive(Clone, Debug)]
+pub struct NamedBundle {
+    pub name: String,
+    pub bundle: Bundle,
+    pub extension: String,
+    pub options: Options,
+}
+
+impl NamedBundle {
+    /// [`NamedBundle::from_config`] reads every `[bundles.<name>]` table of
+    /// a [`config::Config`], in the order of their names, like:
+    /// ```toml
+    /// [bundles.marketing]
+    /// directories = ["sites/marketing/css", "shared/css"]
+    /// output = "dist/marketing.css"
+    /// level = 2
+    ///
+    /// [bundles.docs]
+    /// directory = "sites/docs/js"
+    /// extension = "js"
+    /// ```
+    /// the files of every directory are combined in the order they are
+    /// listed. an `output` with a directory, like `dist/marketing.css`, is
+    /// written there (relative to the current directory), and one without,
+    /// like `marketing.css`, is written within the first directory (or
+    /// `out-dir`). it defaults to `style.<extension>` for css, and to
+    /// `bundle.<extension>` for anything else. every other key is read like
+    /// [`Options::from_config`], on top of the root table.
+    pub fn from_config(config: &config::Config) -> Result<Vec<NamedBundle>> {
+        let mut named_bundles = vec![];
+        for table in config.tables.keys() {
+            let Some(name) = table.strip_prefix("bundles.") else {
+                continue;
+            };
+            let string = |key: &str| match config.get(table, key) {
+                Some(value) => value
+                    .as_str()
+                    .map(|value| Some(value.to_string()))
+                    .ok_or_else(|| {
+                        RcssError::Config(format!("bundles.{name}.{key} must be a string"))
+                    }),
+                None => Ok(None),
+            };
+            let mut directories = match (
+                config.get(table, "directory"),
+                config.get(table, "directories"),
+            ) {
+                (Some(_), Some(_)) => {
+                    return Err(RcssError::Config(format!(
+                        "bundles.{name} can't have both a directory and directories"
+                    )))
+                }
+                (Some(_), None) => string("directory")?.into_iter().collect(),
+                (None, Some(value)) => value.as_strings().ok_or_else(|| {
+                    RcssError::Config(format!(
+                        "bundles.{name}.directories must be an array of strings"
+                    ))
+                })?,
+                (None, None) => vec![],
+            };
+            if directories.is_empty() {
+                return Err(RcssError::Config(format!(
+                    "bundles.{name} must have a directory"
+                )));
+            }
+            let extension = string("extension")?
+                .map(|extension| extension.trim_start_matches('.').to_string())
+                .unwrap_or_else(|| "css".to_string());
+            let mut options = Options::from_config(&config.overlay(table))?;
+            let output = match string("output")? {
+                Some(output) => match output.rsplit_once(['/', '\\']) {
+                    Some((_, "")) => {
+                        return Err(RcssError::Config(format!(
+                            "bundles.{name}.output must be a file, not a directory"
+                        )))
+                    }
+                    Some((out_dir, output)) => {
+                        options.out_dir = Some(out_dir.to_string());
+                        output.to_string()
+                    }
+                    None => output,
+                },
+                None if rules::find(&extension).is_css() => format!("style.{extension}"),
+                None => format!("bundle.{extension}"),
+            };
+            let directory = directories.remove(0);
+            options.directories = directories;
+            named_bundles.push(NamedBundle {
+                name: name.to_string(),
+                bundle: Bundle::new(directory, output),
+                extension,
+                options,
+            });
+        }
+        Ok(named_bundles)
+    }
+}
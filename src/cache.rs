@@ -0,0 +1,275 @@
+//! an incremental build cache, which keeps the minified output of every
+//! combined file so unchanged files aren't read or minified again.
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    fs,
+    hash::{Hash, Hasher},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    time::UNIX_EPOCH,
+};
+
+use crate::{content::Content, output, source_map::Mapping};
+
+/// [`CACHE_FILE_NAME`] is the name of the cache file, which is written to
+/// the directory being minified.
+pub const CACHE_FILE_NAME: &str = ".rcss-cache";
+
+/// [`HEADER`] starts the first line of a cache file. a cache written by
+/// another version of rcss is ignored, since it may have minified differently.
+const HEADER: &str = concat!("rcss-cache ", env!("CARGO_PKG_VERSION"));
+
+/// [`Stamp`] is the modification time and length of a file, which are
+/// used to tell that it hasn't changed without reading it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Stamp {
+    pub modified: u128,
+    pub len: u64,
+}
+
+impl Stamp {
+    /// [`Stamp::new`] returns the stamp of the file described by `metadata`,
+    /// or `None` if its modification time isn't available.
+    pub fn new(metadata: &fs::Metadata) -> Option<Stamp> {
+        let modified = metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+        Some(Stamp {
+            modified: modified.as_nanos(),
+            len: metadata.len(),
+        })
+    }
+}
+
+/// [`Entry`] is the cached minified output of one file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Entry {
+    /// the stamp of the file when it was minified, or `None` if it can
+    /// only be matched by its content, like when imports were inlined.
+    pub stamp: Option<Stamp>,
+    pub content_hash: u64,
+    pub minified: String,
+    pub mappings: Vec<Mapping>,
+    pub license_comments: Vec<String>,
+}
+
+impl Entry {
+    /// [`Entry::new`] minifies `content`, which had the `stamp` when read,
+    /// with the rules for files with `extension`.
+    pub fn new(
+        extension: &str,
+        content: &str,
+        stamp: Option<Stamp>,
+        options: &crate::Options,
+    ) -> Entry {
+        let (minified, mappings, license_comments) =
+            crate::minify_source(extension, content, options);
+        Entry {
+            stamp,
+            content_hash: content_hash(content),
+            minified,
+            mappings,
+            license_comments: license_comments.into_iter().map(str::to_string).collect(),
+        }
+    }
+}
+
+/// [`Source`] is a file to be combined: either its cached [`Entry`],
+/// or its content that still needs to be minified.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Source {
+    Cached(Entry),
+    Read(Content, Option<Stamp>),
+}
+
+/// [`Cache`] maps the path of every file that has been minified to its
+/// cached [`Entry`].
+///
+/// # example
+/// [`Cache`] can be used to skip minifying a file that hasn't changed:
+/// ```rust
+/// use rcss::cache::{Cache, Entry};
+///
+/// let mut cache = Cache::default();
+/// let options = rcss::Options::default();
+/// cache.insert("a.css", Entry::new("css", "a { color: red }", None, &options));
+/// let entry = cache.matching("a.css", "a { color: red }").unwrap();
+/// assert_eq!(entry.minified, "a{color:red}");
+/// assert!(cache.matching("a.css", "a { color: blue }").is_none());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Cache {
+    /// the options that change how files are minified, since entries that
+    /// were minified with other options can't be used.
+    rules: String,
+    entries: HashMap<PathBuf, Entry>,
+}
+
+impl Cache {
+    /// [`Cache::new`] creates an empty cache, for files minified with the
+    /// options described by `rules`.
+    pub fn new(rules: impl Into<String>) -> Cache {
+        Cache {
+            rules: rules.into(),
+            ..Default::default()
+        }
+    }
+
+    /// [`Cache::load`] reads the cache file at `path`, or returns an empty
+    /// cache if it is missing, invalid, from another version of rcss, or was
+    /// written with other `rules`.
+    pub fn load(path: impl AsRef<Path>, rules: &str) -> Cache {
+        let content = fs::read_to_string(path).unwrap_or_default();
+        let mut lines = content.lines();
+        if lines.next() != Some(&format!("{HEADER}\t{}", escape(rules))) {
+            return Cache::new(rules);
+        }
+        let entries = lines.map(parse_entry).collect::<Option<_>>();
+        Cache {
+            rules: rules.to_string(),
+            entries: entries.unwrap_or_default(),
+        }
+    }
+
+    /// [`Cache::save`] writes the cache file to `path`.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut content = format!("{HEADER}\t{}\n", escape(&self.rules));
+        let mut entries = self.entries.iter().collect::<Vec<_>>();
+        entries.sort_by(|left, right| left.0.cmp(right.0));
+        for (path, entry) in entries {
+            content.push_str(&write_entry(path, entry));
+            content.push('\n');
+        }
+        output::write_atomically(path, |file| file.write_all(content.as_bytes()))
+    }
+
+    /// [`Cache::fresh`] returns the entry for `path` if the file still has
+    /// the `stamp` it had when it was minified.
+    pub fn fresh(&self, path: impl AsRef<Path>, stamp: Stamp) -> Option<&Entry> {
+        self.entries
+            .get(path.as_ref())
+            .filter(|entry| entry.stamp == Some(stamp))
+    }
+
+    /// [`Cache::matching`] returns the entry for `path` if it was minified
+    /// from exactly `content`.
+    pub fn matching(&self, path: impl AsRef<Path>, content: &str) -> Option<&Entry> {
+        self.entries
+            .get(path.as_ref())
+            .filter(|entry| entry.content_hash == content_hash(content))
+    }
+
+    /// [`Cache::insert`] caches `entry` for `path`.
+    pub fn insert(&mut self, path: impl Into<PathBuf>, entry: Entry) {
+        self.entries.insert(path.into(), entry);
+    }
+
+    /// [`Cache::retain_existing`] drops the entries of every file that no
+    /// longer exists.
+    pub fn retain_existing(&mut self) {
+        self.entries.retain(|path, _| path.is_file());
+    }
+}
+
+/// [`content_hash`] hashes `content`, for comparing it to the content a
+/// cached entry was minified from.
+fn content_hash(content: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// [`escape`] escapes the characters that separate fields and entries.
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
+/// [`unescape`] reverses [`escape`].
+fn unescape(value: &str) -> String {
+    let mut unescaped = String::new();
+    let mut characters = value.chars();
+    while let Some(character) = characters.next() {
+        if character != '\\' {
+            unescaped.push(character);
+            continue;
+        }
+        match characters.next() {
+            Some('t') => unescaped.push('\t'),
+            Some('n') => unescaped.push('\n'),
+            Some('r') => unescaped.push('\r'),
+            Some(escaped) => unescaped.push(escaped),
+            None => {}
+        }
+    }
+    unescaped
+}
+
+/// [`write_entry`] writes `entry` as a single tab separated line of the
+/// path, stamp, hash, output, mappings, and then every license comment.
+fn write_entry(path: &Path, entry: &Entry) -> String {
+    let (modified, len) = match entry.stamp {
+        Some(stamp) => (stamp.modified.to_string(), stamp.len.to_string()),
+        None => ("-".to_string(), "-".to_string()),
+    };
+    let mappings = entry
+        .mappings
+        .iter()
+        .map(|mapping| {
+            format!(
+                "{},{},{}",
+                mapping.generated_column, mapping.line, mapping.column
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    let mut fields = vec![
+        escape(&path.to_string_lossy()),
+        modified,
+        len,
+        entry.content_hash.to_string(),
+        escape(&entry.minified),
+        mappings,
+    ];
+    fields.extend(entry.license_comments.iter().map(|comment| escape(comment)));
+    fields.join("\t")
+}
+
+/// [`parse_entry`] reads a line written by [`write_entry`].
+fn parse_entry(line: &str) -> Option<(PathBuf, Entry)> {
+    let mut fields = line.split('\t');
+    let path = PathBuf::from(unescape(fields.next()?));
+    let stamp = match (fields.next()?, fields.next()?) {
+        ("-", "-") => None,
+        (modified, len) => Some(Stamp {
+            modified: modified.parse().ok()?,
+            len: len.parse().ok()?,
+        }),
+    };
+    let content_hash = fields.next()?.parse().ok()?;
+    let minified = unescape(fields.next()?);
+    let mappings = fields
+        .next()?
+        .split_whitespace()
+        .map(|mapping| {
+            let mut numbers = mapping.split(',').map(|number| number.parse().ok());
+            Some(Mapping {
+                generated_column: numbers.next()??,
+                line: numbers.next()??,
+                column: numbers.next()??,
+            })
+        })
+        .collect::<Option<_>>()?;
+    let license_comments = fields.map(unescape).collect();
+    Some((
+        path,
+        Entry {
+            stamp,
+            content_hash,
+            minified,
+            mappings,
+            license_comments,
+        },
+    ))
+}
@@ -0,0 +1,114 @@
+//! checks that an existing output is exactly what rcss would build now,
+//! so ci can verify that committed minified files are up to date without
+//! rewriting them.
+use std::fmt;
+
+/// [`CONTEXT`] is how many bytes an [`OutOfDate`] error shows on either
+/// side of the first difference.
+const CONTEXT: usize = 40;
+
+/// [`OutOfDate`] describes an existing output that differs from the one
+/// that was built, and where they first differ.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OutOfDate {
+    /// the path of the existing output.
+    pub output: String,
+    /// the size of the existing output, or `None` if it doesn't exist.
+    pub existing_bytes: Option<usize>,
+    pub built_bytes: usize,
+    /// the byte offset of the first difference.
+    pub offset: usize,
+    /// the existing and built outputs around the first difference.
+    pub existing: String,
+    pub built: String,
+}
+
+/// [`compare`] returns an [`OutOfDate`] describing how the `existing`
+/// content of `output` (or `None` if it doesn't exist) differs from the
+/// `built` one, or `None` if it is up to date.
+///
+/// # example
+/// [`compare`] can be used to check a stylesheet that is missing a rule:
+/// ```rust
+/// use rcss::check::compare;
+///
+/// assert_eq!(compare("style.css", Some("a{color:red}"), "a{color:red}"), None);
+/// let out_of_date = compare("style.css", Some("a{color:red}"), "a{color:blue}").unwrap();
+/// assert_eq!(out_of_date.offset, 8);
+/// assert_eq!(
+///     out_of_date.to_string(),
+///     "style.css is out of date: 12 B on disk, but 13 B built\n\
+///      first difference at byte 8:\n  - a{color:red}\n  + a{color:blue}"
+/// );
+/// ```
+pub fn compare(
+    output: impl Into<String>,
+    existing: Option<&str>,
+    built: &str,
+) -> Option<OutOfDate> {
+    if existing == Some(built) {
+        return None;
+    }
+    let existing_content = existing.unwrap_or_default();
+    let mut offset = existing_content
+        .bytes()
+        .zip(built.bytes())
+        .position(|(left, right)| left != right)
+        .unwrap_or(existing_content.len().min(built.len()));
+    // everything before the offset is the same in both, so a character
+    // boundary of one is a character boundary of the other
+    while !built.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    Some(OutOfDate {
+        output: output.into(),
+        existing_bytes: existing.map(str::len),
+        built_bytes: built.len(),
+        offset,
+        existing: excerpt(existing_content, offset),
+        built: excerpt(built, offset),
+    })
+}
+
+/// [`excerpt`] returns the [`CONTEXT`] bytes of `content` on either side of
+/// `offset`, with `...` where it was cut off, on a single line.
+fn excerpt(content: &str, offset: usize) -> String {
+    let mut start = offset.saturating_sub(CONTEXT);
+    while !content.is_char_boundary(start) {
+        start -= 1;
+    }
+    let mut end = (offset + CONTEXT).min(content.len());
+    while !content.is_char_boundary(end) {
+        end += 1;
+    }
+    let mut excerpt = content[start..end].escape_debug().to_string();
+    if start > 0 {
+        excerpt.insert_str(0, "...");
+    }
+    if end < content.len() {
+        excerpt.push_str("...");
+    }
+    excerpt
+}
+
+impl fmt::Display for OutOfDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some(existing_bytes) = self.existing_bytes else {
+            return write!(
+                f,
+                "{} doesn't exist, but {} B built",
+                self.output, self.built_bytes
+            );
+        };
+        writeln!(
+            f,
+            "{} is out of date: {existing_bytes} B on disk, but {} B built",
+            self.output, self.built_bytes
+        )?;
+        write!(
+            f,
+            "first difference at byte {}:\n  - {}\n  + {}",
+            self.offset, self.existing, self.built
+        )
+    }
+}
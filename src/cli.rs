@@ -0,0 +1,707 @@
+//! command line argument parsing for the rcss binary.
+use std::path::Path;
+
+/// [`USAGE`] is printed by `--help`, and whenever the arguments
+/// can't be parsed.
+pub const USAGE: &str = "\
+usage: rcss <directory> [directory ...] [output] [options]
+       rcss <file> [file ...] [output] [options]
+       rcss --bundle <directory>:<output> [--bundle ...] [options]
+       rcss --stdin < input.css > output.css
+       rcss critical --html <file> <directory> [output] [options]
+       rcss stats <directory> [output] [options]
+       rcss format <directory> [output] [options]
+       rcss format --stdin < input.css > output.css
+       rcss lint <directory> [output] [options]
+       rcss analyze specificity <directory> [output] [options]
+       rcss build [bundle-name] [options]
+
+combines and minifies every file in <directory>, and then in every other
+directory given, in that order, into a single file. files are combined
+in the order given, into <file>.min.<ext> within the directory of the first
+file if only one is given. a last [output] that is an existing file is
+combined like the others, rather than overwritten.
+`critical` writes only the rules that could match an element of the
+--html page, to critical.css by default, leaving the full stylesheet alone.
+`stats` prints the rules, selectors, specificity, duplicate declarations,
+and largest files of <directory>, and how much each pass would save,
+without writing anything.
+`format` pretty-prints every css file in <directory> in place, except the
+output, or stdin to stdout.
+`lint` reports duplicate selectors, unknown properties, invalid colors,
+empty rules, and !important overuse in every css file in <directory>
+(except the output), or in stdin, and fails if it finds any.
+`analyze specificity` lists every selector of <directory> from the most
+specific to the least, flagging ids and long chains of compound selectors.
+`build` builds the [bundles.<name>] table of rcss.toml named
+[bundle-name], with its own directories, output, extension, and options,
+or every one of them if no name is given.
+
+exit codes: 0 on success, 1 for usage errors and failed checks, 2 for files
+that can't be read or written, and 3 for files that can't be parsed.
+
+options:
+  -o, --output <file>       the file to write, within <directory> or --out-dir (default: style.<ext>, or bundle.<ext> for js, html, svg, and json)
+      --out-dir <dir>       write the output to <dir> instead of <directory>, creating it if needed
+      --out-file <path>     write the output to <path>, like dist/style.css, instead of within <directory>
+  -b, --bundle <dir>:<file> also combine <dir> into <file>, and can be repeated
+  -e, --extension <ext>     the type of file to combine: css, js, html, svg, or json (default: css)
+  -w, --watch               rebuild whenever a file in <directory> changes
+  -q, --quiet               only print errors
+  -v, --verbose             print every file rcss writes, or every directory it walks with -vv
+      --progress            draw a progress bar of the files and bytes minified, if stderr is a terminal
+      --format <text|json>  print errors and lint warnings as text, or as a json object per line
+  -O0, -O1, -O2             only concatenate, minify (default), or also merge and collapse rules
+      --preset <name>       also turn on the safe, default, or aggressive set of passes
+      --each                minify every file into its own <file>.min.<ext> next to it, instead of combining them
+      --in-place            minify every file into itself, overwriting it, like --each
+      --permissions-from <file> give the output the permissions and owner of <file>
+      --mode <octal>        give the output the mode <octal>, like 644
+      --preserve-mtime      set the modification time of the output to that of the newest file combined into it
+      --clean               remove the output, its source map, and its compressed and hashed copies first
+      --dry-run             print a report of what would be written, without writing anything
+      --check               fail, showing the first difference, if the output isn't already up to date, without writing anything
+      --report-conflicts    list every property that a later file sets differently for the same selector, instead of building
+      --report <file>       write a json summary of every build, with its sizes and how long each phase took, to <file>
+      --profile             also print how long the slowest files and every structural pass took to minify
+      --strict              fail if a css file has an unbalanced brace, or an unterminated string or comment
+      --max-size <size>     fail, listing the largest files, if the output is bigger than <size>, like 50kb
+      --skip-minified       combine files that look minified already, like *.min.css, as they are
+      --cache               skip minifying unchanged files, using a .rcss-cache file
+      --hash                add a content hash to the output name, and write manifest.json
+      --compress <format>   also write the output compressed with gzip or brotli
+      --source-map          write a source map next to the output
+      --inline-imports      inline @import statements in dependency order
+      --banner <text|file>  put <text> or the content of <file> at the top of the output, filling in {version}, {date}, and {commit}
+      --footer <text|file>  put <text> or the content of <file> at the end of the output, like --banner
+      --placeholders        replace ${NAME} in every file with the environment variable or --define NAME
+      --define <name=value> the value of the ${name} placeholder, if it isn't an environment variable
+      --allow-undefined     leave placeholders without a value as they are, instead of failing
+      --requires            combine every file after those named in its /* @requires <file> */ comments
+      --layers              wrap the rules of every file in an @layer named after its path
+      --layer-name <name>   wrap the rules of the whole output in an @layer called <name>, like theme
+      --append              add the output onto the end of the existing output file, instead of replacing it
+      --rewrite-urls        rewrite relative url()s to be relative to the output, rather than to each file
+      --inline-assets <size> inline images and fonts no bigger than <size>, like 4kb, as data uris
+      --filelist <file>     combine the files listed in <file>, one per line, in that order, instead of walking <directory>
+      --include <glob>      only combine files matching <glob>, like 'components/**'
+      --exclude <glob>      skip files matching <glob>, like '**/vendor/**'
+      --max-depth <n>       only walk <n> levels of subdirectories deep
+      --gitignore           skip files ignored by .gitignore files, as well as by .rcssignore files
+      --follow-symlinks     walk into symbolic links, which are skipped by default
+      --hidden              walk into hidden files and directories, like .git, which are skipped by default
+  -j, --jobs <n>            minify files on <n> threads (default: one per core)
+      --merge-duplicates    merge rules with identical selectors
+      --remove-overridden   remove declarations that a later one of the same property overrides
+      --keep-fallbacks      keep overridden declarations with other values, like display:block before display:flex
+      --merge-selectors     merge rules with identical declarations into a selector list
+      --collapse-shorthands collapse longhands like margin-top into shorthands like margin
+      --remove-empty-rules  remove rules and at-rules with empty blocks, like .a{}
+      --remove-unused-at-rules remove @keyframes and @font-face rules that no animation or font-family uses
+      --merge-media         merge @media blocks with identical queries
+      --add-prefixes        add the vendor prefixes that --browsers need
+      --strip-prefixes      remove the vendor prefixes that --browsers don't need
+      --normalize-case      lowercase property names, hex colors, units, and function names
+      --strip-hacks         remove old internet explorer hacks, like *zoom:1 or @media \\0screen
+      --downlevel-colors    write colors like rgb(0 0 0 / 50%) as rgba(0,0,0,.5) if --browsers need it
+      --browsers <query>    the browsers to prefix for, like 'last 2 versions, safari >= 15'
+      --purge <glob>        remove selectors whose classes and ids aren't in files matching <glob>
+      --flatten-nesting     flatten nested rules into plain css
+      --inline-variables    inline custom properties defined on :root
+      --remove-unused-variables remove custom properties that no var() uses, listing them with --verbose
+      --hash-classes        replace class names with short hashed ones, writing the mapping to <output>.json
+      --shorten-identifiers rename @keyframes and custom properties to short names, like --a
+      --keep-identifier <glob> leave @keyframes and custom properties matching <glob>, like '--theme-*', unshortened
+      --scope <selector>    scope every rule within <selector>, like '.my-widget', so it can't style the rest of a page
+      --preserve-comments   keep /*! license comments at the top of the output
+      --remove-key <glob>   remove json object keys matching <glob>, like '$schema'
+      --stdin               minify stdin to stdout, without touching any files
+      --indent <n|tab>      indent formatted blocks with <n> spaces or a tab (default: 2)
+  -h, --help                print this message
+  -V, --version             print the version of rcss";
+
+/// [`Args`] are the options for a single run of rcss.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Args {
+    pub directory: Option<String>,
+    /// the directories after `<directory>`, whose files are combined after
+    /// its files.
+    pub directories: Vec<String>,
+    /// the files to combine instead of walking `<directory>`, which is the
+    /// directory of the first of them.
+    pub files: Vec<String>,
+    pub output: Option<String>,
+    pub extension: String,
+    pub watch: bool,
+    pub log_level: rcss::log::Level,
+    pub diagnostics: crate::diagnostics::Format,
+    pub dry_run: bool,
+    pub check: bool,
+    /// the file to write a json summary of every build to.
+    pub report: Option<String>,
+    pub profile: bool,
+    pub permissions_from: Option<String>,
+    pub mode: Option<u32>,
+    pub preserve_mtime: bool,
+    pub skip_minified: bool,
+    pub gitignore: bool,
+    pub clean: bool,
+    pub each: bool,
+    pub in_place: bool,
+    pub progress: bool,
+    pub strict: bool,
+    pub level: Option<rcss::level::Level>,
+    pub preset: Option<rcss::preset::Preset>,
+    /// the name of every long flag that was given, without its dashes, like
+    /// `merge-selectors`.
+    pub given: Vec<String>,
+    pub rewrite_urls: bool,
+    pub inline_assets: Option<usize>,
+    pub max_size: Option<usize>,
+    pub source_map: bool,
+    pub inline_imports: bool,
+    pub layers: bool,
+    pub layer_name: Option<String>,
+    pub append: bool,
+    pub requires: bool,
+    pub banner: Option<String>,
+    pub placeholders: bool,
+    pub defines: Vec<(String, String)>,
+    pub allow_undefined: bool,
+    pub footer: Option<String>,
+    pub stdin: bool,
+    pub file_list: Option<String>,
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+    pub jobs: Option<usize>,
+    pub merge_duplicates: bool,
+    pub remove_overridden: bool,
+    pub keep_fallbacks: bool,
+    pub merge_selectors: bool,
+    pub collapse_shorthands: bool,
+    pub remove_empty_rules: bool,
+    pub remove_unused_at_rules: bool,
+    pub merge_media: bool,
+    pub add_prefixes: bool,
+    pub strip_prefixes: bool,
+    pub strip_hacks: bool,
+    pub downlevel_colors: bool,
+    pub normalize_case: bool,
+    pub browsers: Option<String>,
+    pub purge: Vec<String>,
+    /// the html page of the `critical` subcommand.
+    pub critical_html: Option<String>,
+    /// whether this is the `stats` subcommand.
+    pub stats: bool,
+    /// whether this is the `format` subcommand.
+    pub format: bool,
+    pub indent: Option<rcss::format::Indent>,
+    /// whether this is the `lint` subcommand.
+    pub lint: bool,
+    /// whether this is the `analyze specificity` subcommand.
+    pub specificity: bool,
+    /// whether to report the conflicts between files, instead of building.
+    pub report_conflicts: bool,
+    /// whether this is the `build` subcommand.
+    pub build: bool,
+    /// the `[bundles.<name>]` table that the `build` subcommand builds, or
+    /// `None` to build all of them.
+    pub bundle_name: Option<String>,
+    pub preserve_comments: bool,
+    pub flatten_nesting: bool,
+    pub inline_variables: bool,
+    pub remove_unused_variables: bool,
+    pub scope: Option<String>,
+    pub hash_classes: bool,
+    pub shorten_identifiers: bool,
+    pub keep_identifiers: Vec<String>,
+    pub bundles: Vec<rcss::bundle::Bundle>,
+    /// the directory to write every output to, from `--out-dir` or the
+    /// directory of `--out-file`.
+    pub out_dir: Option<String>,
+    pub hash: bool,
+    pub cache: bool,
+    pub remove_keys: Vec<String>,
+    pub follow_symlinks: bool,
+    pub hidden: bool,
+    pub max_depth: Option<usize>,
+    pub compress: Vec<rcss::compress::Compression>,
+}
+
+impl Args {
+    /// [`Args::output`] returns the file to write, defaulting to
+    /// `critical.<extension>` for the `critical` subcommand, `style.<extension>`
+    /// for css (and every extension without a rule set of its own), and
+    /// `bundle.<extension>` for javascript, html, svg, json, and any other
+    /// rule set.
+    pub fn output(&self) -> String {
+        self.output.clone().unwrap_or_else(|| {
+            if self.critical_html.is_some() {
+                return format!("critical.{}", self.extension);
+            }
+            let is_bundle = !rcss::rules::find(&self.extension).is_css();
+            match is_bundle {
+                true => format!("bundle.{}", self.extension),
+                false => format!("style.{}", self.extension),
+            }
+        })
+    }
+
+    /// [`Args::bundles`] returns the bundles to build: the `<directory>`
+    /// and its output (if a directory was given), then every `--bundle`.
+    pub fn bundles(&self) -> Vec<rcss::bundle::Bundle> {
+        let directory = self
+            .directory
+            .iter()
+            .map(|directory| rcss::bundle::Bundle::new(directory, self.output()));
+        directory.chain(self.bundles.iter().cloned()).collect()
+    }
+}
+
+/// [`Command`] is what the command line asked rcss to do.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Command {
+    Run(Box<Args>),
+    Help,
+    Version,
+}
+
+/// [`parse`] will turn the command line `arguments` (without the program
+/// name) into a [`Command`], or describe why they are invalid.
+///
+/// # example
+/// [`parse`] can be used to read the directory and output file:
+/// ```rust
+/// let command = parse(["assets/css", "--output", "site.css"].map(String::from));
+/// ```
+pub fn parse(arguments: impl IntoIterator<Item = String>) -> Result<Command, String> {
+    let mut args = Args {
+        extension: "css".to_string(),
+        ..Default::default()
+    };
+    let mut positional = vec![];
+    let (mut quiet, mut verbosity) = (false, 0);
+    let mut arguments = arguments.into_iter().peekable();
+    let subcommand = arguments.next_if(|argument| {
+        ["critical", "stats", "format", "lint", "analyze", "build"].contains(&argument.as_str())
+    });
+    let is_critical = subcommand.as_deref() == Some("critical");
+    args.stats = subcommand.as_deref() == Some("stats");
+    args.format = subcommand.as_deref() == Some("format");
+    args.lint = subcommand.as_deref() == Some("lint");
+    args.build = subcommand.as_deref() == Some("build");
+    if subcommand.as_deref() == Some("analyze") {
+        match arguments.next().as_deref() {
+            Some("specificity") => args.specificity = true,
+            Some(analysis) => {
+                return Err(format!("unknown analysis {analysis}, expected specificity"))
+            }
+            None => return Err("analyze requires an analysis, like specificity".to_string()),
+        }
+    }
+    let mut html = None;
+    let mut out_file = None;
+    let mut is_extension_given = false;
+    while let Some(argument) = arguments.next() {
+        // support both `--output style.css` and `--output=style.css`
+        let (flag, inline_value) = match argument.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => {
+                (flag.to_string(), Some(value.to_string()))
+            }
+            _ => (argument.clone(), None),
+        };
+        if let Some(name) = flag.strip_prefix("--") {
+            args.given.push(name.to_string());
+        }
+        let mut value = |name: &str| {
+            inline_value
+                .clone()
+                .or_else(|| arguments.next())
+                .filter(|value| !value.is_empty())
+                .ok_or_else(|| format!("{name} requires a value"))
+        };
+        match flag.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "-V" | "--version" => return Ok(Command::Version),
+            "-o" | "--output" => args.output = Some(value("--output")?),
+            "--out-dir" => args.out_dir = Some(value("--out-dir")?),
+            "--out-file" => out_file = Some(value("--out-file")?),
+            "-e" | "--extension" => {
+                args.extension = value("--extension")?.trim_start_matches('.').to_string();
+                is_extension_given = true;
+            }
+            "-w" | "--watch" => args.watch = true,
+            "-q" | "--quiet" => quiet = true,
+            "-v" | "--verbose" => verbosity += 1,
+            "-vv" => verbosity += 2,
+            "--format" => {
+                let format = value("--format")?;
+                args.diagnostics = crate::diagnostics::Format::parse(&format)
+                    .ok_or_else(|| format!("--format must be text or json, not {format}"))?;
+            }
+            "--dry-run" => args.dry_run = true,
+            "--check" => args.check = true,
+            "--report-conflicts" => args.report_conflicts = true,
+            "--report" => args.report = Some(value("--report")?),
+            "--profile" => args.profile = true,
+            "--permissions-from" => args.permissions_from = Some(value("--permissions-from")?),
+            "--mode" => {
+                let mode = value("--mode")?;
+                args.mode =
+                    Some(rcss::metadata::parse_mode(&mode).ok_or_else(|| {
+                        format!("--mode must be an octal mode like 644, not {mode}")
+                    })?);
+            }
+            "--preserve-mtime" => args.preserve_mtime = true,
+            "--skip-minified" => args.skip_minified = true,
+            "--gitignore" => args.gitignore = true,
+            "--clean" => args.clean = true,
+            "--each" => args.each = true,
+            "--in-place" => args.in_place = true,
+            "--progress" => args.progress = true,
+            "--strict" => args.strict = true,
+            "--max-size" => {
+                let max_size = value("--max-size")?;
+                args.max_size = Some(rcss::budget::parse_size(&max_size).ok_or_else(|| {
+                    format!("--max-size must be a size like 50kb, not {max_size}")
+                })?);
+            }
+            "--source-map" => args.source_map = true,
+            "--hash" => args.hash = true,
+            "--cache" => args.cache = true,
+            "--compress" => {
+                for name in value("--compress")?.split(',') {
+                    let compression = rcss::compress::Compression::parse(name)
+                        .ok_or_else(|| format!("--compress must be gzip or brotli, not {name}"))?;
+                    if !args.compress.contains(&compression) {
+                        args.compress.push(compression);
+                    }
+                }
+            }
+            "--inline-imports" => args.inline_imports = true,
+            "--layers" => args.layers = true,
+            "--layer-name" => args.layer_name = Some(value("--layer-name")?),
+            "--append" => args.append = true,
+            "--requires" => args.requires = true,
+            "--banner" => args.banner = Some(value("--banner")?),
+            "--placeholders" => args.placeholders = true,
+            "--allow-undefined" => args.allow_undefined = true,
+            "--define" => {
+                let define = value("--define")?;
+                let (name, value) = define
+                    .split_once('=')
+                    .ok_or_else(|| format!("--define must be a name=value pair, not {define}"))?;
+                args.defines.push((name.to_string(), value.to_string()));
+            }
+            "--footer" => args.footer = Some(value("--footer")?),
+            "--rewrite-urls" => args.rewrite_urls = true,
+            "--inline-assets" => {
+                let threshold = value("--inline-assets")?;
+                args.inline_assets =
+                    Some(rcss::budget::parse_size(&threshold).ok_or_else(|| {
+                        format!("--inline-assets must be a size like 4kb, not {threshold}")
+                    })?);
+            }
+            "--stdin" => args.stdin = true,
+            "--merge-duplicates" => args.merge_duplicates = true,
+            "--remove-overridden" => args.remove_overridden = true,
+            "--keep-fallbacks" => args.keep_fallbacks = true,
+            "--merge-selectors" => args.merge_selectors = true,
+            "--collapse-shorthands" => args.collapse_shorthands = true,
+            "--remove-empty-rules" => args.remove_empty_rules = true,
+            "--remove-unused-at-rules" => args.remove_unused_at_rules = true,
+            "--merge-media" => args.merge_media = true,
+            "--add-prefixes" => args.add_prefixes = true,
+            "--strip-prefixes" => args.strip_prefixes = true,
+            "--strip-hacks" => args.strip_hacks = true,
+            "--downlevel-colors" => args.downlevel_colors = true,
+            "--normalize-case" => args.normalize_case = true,
+            "--browsers" => args.browsers = Some(value("--browsers")?),
+            "--purge" => args.purge.push(value("--purge")?),
+            "--html" if is_critical => html = Some(value("--html")?),
+            "--indent" if args.format => {
+                let indent = value("--indent")?;
+                args.indent = Some(rcss::format::Indent::parse(&indent).ok_or_else(|| {
+                    format!("--indent must be a number of spaces or tab, not {indent}")
+                })?);
+            }
+            "--preserve-comments" => args.preserve_comments = true,
+            "--flatten-nesting" => args.flatten_nesting = true,
+            "--inline-variables" => args.inline_variables = true,
+            "--remove-unused-variables" => args.remove_unused_variables = true,
+            "--scope" => args.scope = Some(value("--scope")?),
+            "--hash-classes" => args.hash_classes = true,
+            "--shorten-identifiers" => args.shorten_identifiers = true,
+            "--keep-identifier" => args.keep_identifiers.push(value("--keep-identifier")?),
+            "--filelist" => args.file_list = Some(value("--filelist")?),
+            "--include" => args.include.push(value("--include")?),
+            "--exclude" => args.exclude.push(value("--exclude")?),
+            "--remove-key" => args.remove_keys.push(value("--remove-key")?),
+            "-b" | "--bundle" => args
+                .bundles
+                .push(rcss::bundle::Bundle::parse(&value("--bundle")?)?),
+            "--follow-symlinks" => args.follow_symlinks = true,
+            "--hidden" => args.hidden = true,
+            "--max-depth" => {
+                let max_depth = value("--max-depth")?;
+                args.max_depth = Some(
+                    max_depth
+                        .parse()
+                        .map_err(|_| format!("--max-depth must be a number, not {max_depth}"))?,
+                );
+            }
+            "-j" | "--jobs" => {
+                let jobs = value("--jobs")?;
+                args.jobs = Some(
+                    jobs.parse()
+                        .map_err(|_| format!("--jobs must be a number, not {jobs}"))?,
+                );
+            }
+            "--preset" => {
+                let preset = value("--preset")?;
+                args.preset = Some(rcss::preset::Preset::parse(&preset).ok_or_else(|| {
+                    format!("unknown preset {preset}, expected safe, default, or aggressive")
+                })?);
+            }
+            _ if flag.starts_with("-O") => {
+                args.level = Some(rcss::level::Level::parse(&flag[2..]).ok_or_else(|| {
+                    format!("unknown optimization level {flag}, expected -O0, -O1, or -O2")
+                })?)
+            }
+            _ if flag.starts_with('-') && flag.len() > 1 => {
+                return Err(format!("unknown option {flag}"))
+            }
+            _ => positional.push(argument),
+        }
+    }
+
+    if is_critical {
+        if args.stdin {
+            return Err("--stdin can't be used with critical".to_string());
+        }
+        if args.extension != "css" {
+            return Err("critical only extracts css".to_string());
+        }
+        args.critical_html = Some(html.ok_or("critical requires an --html <file> argument")?);
+    }
+    if args.stats {
+        if args.stdin || args.watch || args.dry_run {
+            return Err("--stdin, --watch, and --dry-run can't be used with stats".to_string());
+        }
+        if args.extension != "css" {
+            return Err("stats only analyzes css".to_string());
+        }
+    }
+    if args.format {
+        if args.watch || args.dry_run {
+            return Err("--watch and --dry-run can't be used with format".to_string());
+        }
+        if args.extension != "css" {
+            return Err("format only formats css".to_string());
+        }
+    }
+    if args.lint {
+        if args.watch || args.dry_run {
+            return Err("--watch and --dry-run can't be used with lint".to_string());
+        }
+        if args.extension != "css" {
+            return Err("lint only checks css".to_string());
+        }
+    }
+    if args.report_conflicts {
+        if args.stdin || args.watch || args.dry_run || args.check {
+            return Err(
+                "--stdin, --watch, --dry-run, and --check can't be used with --report-conflicts"
+                    .to_string(),
+            );
+        }
+        if args.stats || args.format || args.lint {
+            return Err("--report-conflicts can't be used with stats, format, or lint".to_string());
+        }
+        if args.report.is_some() || args.profile || args.each || args.in_place {
+            return Err(
+                "--report, --profile, --each, and --in-place can't be used with --report-conflicts"
+                    .to_string(),
+            );
+        }
+        if args.extension != "css" {
+            return Err("--report-conflicts only checks css".to_string());
+        }
+    }
+    if args.specificity {
+        if args.stdin || args.watch || args.dry_run || args.check {
+            return Err(
+                "--stdin, --watch, --dry-run, and --check can't be used with analyze".to_string(),
+            );
+        }
+        if args.report_conflicts {
+            return Err("--report-conflicts can't be used with analyze".to_string());
+        }
+        if args.report.is_some() || args.profile || args.each || args.in_place {
+            return Err(
+                "--report, --profile, --each, and --in-place can't be used with analyze"
+                    .to_string(),
+            );
+        }
+        if args.extension != "css" {
+            return Err("analyze only analyzes css".to_string());
+        }
+    }
+    if args.build {
+        if args.stdin || !args.bundles.is_empty() {
+            return Err("--stdin and --bundle can't be used with build".to_string());
+        }
+        if args.output.is_some() || out_file.is_some() {
+            return Err("--output and --out-file can't be used with build".to_string());
+        }
+        if let Some(extra) = positional.get(1) {
+            return Err(format!("unexpected argument {extra}"));
+        }
+        args.bundle_name = positional.pop();
+    }
+    args.log_level = match (quiet, verbosity) {
+        (true, 0) => rcss::log::Level::Quiet,
+        (true, _) => return Err("--quiet can't be used with --verbose".to_string()),
+        (false, 0) => rcss::log::Level::Normal,
+        (false, 1) => rcss::log::Level::Verbose,
+        (false, _) => rcss::log::Level::Debug,
+    };
+    if args.check && (args.stdin || args.watch || args.dry_run) {
+        return Err("--check can't be used with --stdin, --watch, or --dry-run".to_string());
+    }
+    if args.report.is_some()
+        && (args.stdin || args.dry_run || args.stats || args.format || args.lint)
+    {
+        return Err(
+            "--report can't be used with --stdin, --dry-run, stats, format, or lint".to_string(),
+        );
+    }
+    if args.profile && (args.stdin || args.stats || args.format || args.lint) {
+        return Err("--profile can't be used with --stdin, stats, format, or lint".to_string());
+    }
+    let is_each = args.each || args.in_place;
+    if args.append && (args.stdin || args.watch || is_each) {
+        return Err(
+            "--append can't be used with --stdin, --watch, --each, or --in-place".to_string(),
+        );
+    }
+    if is_each {
+        if args.stdin || args.stats || args.format || args.lint {
+            return Err(
+                "--each and --in-place can't be used with --stdin, stats, format, or lint"
+                    .to_string(),
+            );
+        }
+        if args.output.is_some() || out_file.is_some() {
+            return Err(
+                "--output and --out-file can't be used with --each and --in-place".to_string(),
+            );
+        }
+    }
+
+    if args.stdin {
+        return match positional.first() {
+            Some(extra) => Err(format!("unexpected argument {extra} with --stdin")),
+            None if args.watch => Err("--watch can't be used with --stdin".to_string()),
+            None if !args.bundles.is_empty() => {
+                Err("--bundle can't be used with --stdin".to_string())
+            }
+            None if args.out_dir.is_some() || out_file.is_some() => {
+                Err("--out-dir and --out-file can't be used with --stdin".to_string())
+            }
+            None => Ok(Command::Run(Box::new(args))),
+        };
+    }
+
+    let is_file = |path: &String| Path::new(path).is_file();
+    if positional.first().is_some_and(is_file) {
+        if args.stats || args.format || args.lint || args.specificity || args.report_conflicts {
+            return Err(
+                "stats, format, lint, analyze, and --report-conflicts take a <directory>, not a file"
+                    .to_string(),
+            );
+        }
+        // like a directory, the last argument is the output unless it was
+        // given as an option, or every file has its own, but an existing
+        // file is always an input, so a source is never overwritten
+        if positional.len() > 1
+            && args.output.is_none()
+            && out_file.is_none()
+            && !is_each
+            && positional.last().is_some_and(|last| !is_file(last))
+        {
+            args.output = positional.pop();
+        }
+        if let Some(argument) = positional.iter().find(|argument| !is_file(argument)) {
+            return Err(format!("{argument} is not a file"));
+        }
+        let first = Path::new(&positional[0]);
+        if !is_extension_given {
+            if let Some(extension) = first.extension() {
+                args.extension = extension.to_string_lossy().to_string();
+            }
+        }
+        if positional.len() == 1 && args.output.is_none() && out_file.is_none() {
+            let stem = first.file_stem().unwrap_or_default().to_string_lossy();
+            args.output = Some(format!("{stem}.min.{}", args.extension));
+        }
+        let directory = first.parent().unwrap_or(Path::new(""));
+        args.directory = Some(match directory.as_os_str().is_empty() {
+            true => ".".to_string(),
+            false => directory.to_string_lossy().to_string(),
+        });
+        args.files = positional;
+        positional = vec![];
+    }
+    let mut positional = positional.into_iter().peekable();
+    if args.files.is_empty() {
+        args.directory = positional.next();
+    }
+    // every argument after the directory is another directory, except for
+    // a last one that isn't a directory, which is the output
+    while let Some(argument) = positional.next() {
+        if Path::new(&argument).is_dir() {
+            args.directories.push(argument);
+            continue;
+        }
+        if positional.peek().is_some() || is_each {
+            return Err(format!("{argument} is not a directory"));
+        }
+        if args.output.is_some() {
+            return Err("the output file was given twice".to_string());
+        }
+        args.output = Some(argument);
+    }
+    if !args.directories.is_empty()
+        && (args.stats || args.format || args.lint || args.specificity || args.report_conflicts)
+    {
+        return Err(
+            "stats, format, lint, analyze, and --report-conflicts only take one <directory>"
+                .to_string(),
+        );
+    }
+    if args.directory.is_none() && args.output.is_some() {
+        return Err("--output requires a <directory> argument".to_string());
+    }
+    if let Some(out_file) = out_file {
+        if args.output.is_some() {
+            return Err("the output file was given twice".to_string());
+        }
+        if args.out_dir.is_some() {
+            return Err("--out-file can't be used with --out-dir".to_string());
+        }
+        if !args.bundles.is_empty() {
+            return Err("--out-file can't be used with --bundle".to_string());
+        }
+        let (out_dir, output) = match out_file.rsplit_once(['/', '\\']) {
+            Some((out_dir, output)) => (out_dir.to_string(), output.to_string()),
+            None => (".".to_string(), out_file),
+        };
+        if output.is_empty() {
+            return Err("--out-file must be a file, not a directory".to_string());
+        }
+        args.out_dir = Some(out_dir);
+        args.output = Some(output);
+    }
+    Ok(Command::Run(Box::new(args)))
+}
@@ -0,0 +1,466 @@
+//! shortening of color values, like `#ffffff` to `#fff` and
+//! `rgb(255,0,0)` to `red`.
+
+/// [`SHORT_NAMES`] are the named colors that are shorter than the
+/// shortest hex form of their value.
+const SHORT_NAMES: &[(&str, &str)] = &[
+    ("#f00", "red"),
+    ("#d2b48c", "tan"),
+    ("#f0ffff", "azure"),
+    ("#f5f5dc", "beige"),
+    ("#ffe4c4", "bisque"),
+    ("#a52a2a", "brown"),
+    ("#ff7f50", "coral"),
+    ("#ffd700", "gold"),
+    ("#808080", "gray"),
+    ("#008000", "green"),
+    ("#4b0082", "indigo"),
+    ("#fffff0", "ivory"),
+    ("#f0e68c", "khaki"),
+    ("#faf0e6", "linen"),
+    ("#800000", "maroon"),
+    ("#000080", "navy"),
+    ("#808000", "olive"),
+    ("#ffa500", "orange"),
+    ("#da70d6", "orchid"),
+    ("#cd853f", "peru"),
+    ("#ffc0cb", "pink"),
+    ("#dda0dd", "plum"),
+    ("#800080", "purple"),
+    ("#fa8072", "salmon"),
+    ("#a0522d", "sienna"),
+    ("#c0c0c0", "silver"),
+    ("#fffafa", "snow"),
+    ("#008080", "teal"),
+    ("#ff6347", "tomato"),
+    ("#ee82ee", "violet"),
+    ("#f5deb3", "wheat"),
+];
+
+/// [`shorten_hex`] returns the shortest way to write the hex color
+/// `hex` (including its `#`), or `None` if it isn't a valid hex color.
+///
+/// # example
+/// [`shorten_hex`] can be used to shorten a hex color, or swap it for a
+/// shorter color name:
+/// ```rust
+/// use rcss::colors::shorten_hex;
+///
+/// assert_eq!(shorten_hex("#FFFFFF").as_deref(), Some("#fff"));
+/// assert_eq!(shorten_hex("#ff0000").as_deref(), Some("red"));
+/// assert_eq!(shorten_hex("#123456").as_deref(), Some("#123456"));
+/// ```
+pub fn shorten_hex(hex: &str) -> Option<String> {
+    let digits = hex.strip_prefix('#')?.to_ascii_lowercase();
+    if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let digits = match digits.len() {
+        3 | 6 => digits,
+        // a fully opaque alpha channel can be dropped
+        4 if digits.ends_with('f') => digits[..3].to_string(),
+        8 if digits.ends_with("ff") => digits[..6].to_string(),
+        4 | 8 => digits,
+        _ => return None,
+    };
+    let bytes = digits.as_bytes();
+    let is_shortenable =
+        bytes.len() % 2 == 0 && bytes.chunks(2).all(|pair| pair[0] == pair[1]) && bytes.len() > 4;
+    let shortened = if is_shortenable {
+        let short = bytes
+            .chunks(2)
+            .map(|pair| pair[0] as char)
+            .collect::<String>();
+        format!("#{short}")
+    } else {
+        format!("#{digits}")
+    };
+    let name = SHORT_NAMES
+        .iter()
+        .find(|(value, _)| *value == shortened)
+        .map(|(_, name)| name.to_string());
+    Some(name.unwrap_or(shortened))
+}
+
+/// [`shorten_rgb`] returns the shortest way to write an `rgb()` or
+/// `rgba()` color, given each of its `channels` as written (like `255`
+/// or `100%`). it returns `None` for colors that aren't fully opaque,
+/// or that can't be parsed.
+///
+/// # example
+/// [`shorten_rgb`] can be used to swap `rgb(255,0,0)` for `red`:
+/// ```rust
+/// use rcss::colors::shorten_rgb;
+///
+/// assert_eq!(shorten_rgb(&["255", "0", "0"]).as_deref(), Some("red"));
+/// assert_eq!(shorten_rgb(&["0", "0", "0", "1"]).as_deref(), Some("#000"));
+/// assert_eq!(shorten_rgb(&["0", "0", "0", ".5"]), None);
+/// ```
+pub fn shorten_rgb(channels: &[&str]) -> Option<String> {
+    let (color, alpha) = match channels {
+        [red, green, blue] => ([red, green, blue], None),
+        [red, green, blue, alpha] => ([red, green, blue], Some(alpha)),
+        _ => return None,
+    };
+    if let Some(alpha) = alpha {
+        let opaque = match alpha.strip_suffix('%') {
+            Some(percentage) => percentage.parse::<f64>().ok()? >= 100.0,
+            None => alpha.parse::<f64>().ok()? >= 1.0,
+        };
+        if !opaque {
+            return None;
+        }
+    }
+    let mut hex = String::from("#");
+    for channel in color {
+        let value = match channel.strip_suffix('%') {
+            Some(percentage) => percentage.parse::<f64>().ok()? * 2.55,
+            None => channel.parse::<f64>().ok()?,
+        };
+        if value.fract() != 0.0 && !channel.ends_with('%') {
+            return None;
+        }
+        hex.push_str(&format!("{:02x}", value.round().clamp(0.0, 255.0) as u8));
+    }
+    shorten_hex(&hex)
+}
+
+/// [`NAMES`] are every named color, and the keywords that can be used
+/// wherever a color can.
+const NAMES: &[&str] = &[
+    "aliceblue",
+    "antiquewhite",
+    "aqua",
+    "aquamarine",
+    "azure",
+    "beige",
+    "bisque",
+    "black",
+    "blanchedalmond",
+    "blue",
+    "blueviolet",
+    "brown",
+    "burlywood",
+    "cadetblue",
+    "chartreuse",
+    "chocolate",
+    "coral",
+    "cornflowerblue",
+    "cornsilk",
+    "crimson",
+    "cyan",
+    "darkblue",
+    "darkcyan",
+    "darkgoldenrod",
+    "darkgray",
+    "darkgreen",
+    "darkgrey",
+    "darkkhaki",
+    "darkmagenta",
+    "darkolivegreen",
+    "darkorange",
+    "darkorchid",
+    "darkred",
+    "darksalmon",
+    "darkseagreen",
+    "darkslateblue",
+    "darkslategray",
+    "darkslategrey",
+    "darkturquoise",
+    "darkviolet",
+    "deeppink",
+    "deepskyblue",
+    "dimgray",
+    "dimgrey",
+    "dodgerblue",
+    "firebrick",
+    "floralwhite",
+    "forestgreen",
+    "fuchsia",
+    "gainsboro",
+    "ghostwhite",
+    "gold",
+    "goldenrod",
+    "gray",
+    "green",
+    "greenyellow",
+    "grey",
+    "honeydew",
+    "hotpink",
+    "indianred",
+    "indigo",
+    "ivory",
+    "khaki",
+    "lavender",
+    "lavenderblush",
+    "lawngreen",
+    "lemonchiffon",
+    "lightblue",
+    "lightcoral",
+    "lightcyan",
+    "lightgoldenrodyellow",
+    "lightgray",
+    "lightgreen",
+    "lightgrey",
+    "lightpink",
+    "lightsalmon",
+    "lightseagreen",
+    "lightskyblue",
+    "lightslategray",
+    "lightslategrey",
+    "lightsteelblue",
+    "lightyellow",
+    "lime",
+    "limegreen",
+    "linen",
+    "magenta",
+    "maroon",
+    "mediumaquamarine",
+    "mediumblue",
+    "mediumorchid",
+    "mediumpurple",
+    "mediumseagreen",
+    "mediumslateblue",
+    "mediumspringgreen",
+    "mediumturquoise",
+    "mediumvioletred",
+    "midnightblue",
+    "mintcream",
+    "mistyrose",
+    "moccasin",
+    "navajowhite",
+    "navy",
+    "oldlace",
+    "olive",
+    "olivedrab",
+    "orange",
+    "orangered",
+    "orchid",
+    "palegoldenrod",
+    "palegreen",
+    "paleturquoise",
+    "palevioletred",
+    "papayawhip",
+    "peachpuff",
+    "peru",
+    "pink",
+    "plum",
+    "powderblue",
+    "purple",
+    "rebeccapurple",
+    "red",
+    "rosybrown",
+    "royalblue",
+    "saddlebrown",
+    "salmon",
+    "sandybrown",
+    "seagreen",
+    "seashell",
+    "sienna",
+    "silver",
+    "skyblue",
+    "slateblue",
+    "slategray",
+    "slategrey",
+    "snow",
+    "springgreen",
+    "steelblue",
+    "tan",
+    "teal",
+    "thistle",
+    "tomato",
+    "turquoise",
+    "violet",
+    "wheat",
+    "white",
+    "whitesmoke",
+    "yellow",
+    "yellowgreen",
+    "transparent",
+    "currentcolor",
+    "inherit",
+    "initial",
+    "unset",
+    "revert",
+    "revert-layer",
+    "none",
+];
+
+/// [`is_valid_hex`] returns `true` if `hex` (including its `#`) is a
+/// hex color with 3, 4, 6, or 8 digits.
+///
+/// # example
+/// [`is_valid_hex`] can be used to catch a mistyped hex color:
+/// ```rust
+/// use rcss::colors::is_valid_hex;
+///
+/// assert!(is_valid_hex("#fffa"));
+/// assert!(!is_valid_hex("#fffff"));
+/// assert!(!is_valid_hex("#ggg"));
+/// ```
+pub fn is_valid_hex(hex: &str) -> bool {
+    match hex.strip_prefix('#') {
+        Some(digits) => {
+            matches!(digits.len(), 3 | 4 | 6 | 8) && digits.chars().all(|c| c.is_ascii_hexdigit())
+        }
+        None => false,
+    }
+}
+
+/// [`is_named`] returns `true` if `name` is a named color, like
+/// `rebeccapurple`, or a keyword like `currentcolor`, in any case.
+///
+/// # example
+/// [`is_named`] can be used to tell a color name from a typo:
+/// ```rust
+/// use rcss::colors::is_named;
+///
+/// assert!(is_named("RebeccaPurple"));
+/// assert!(!is_named("grren"));
+/// ```
+pub fn is_named(name: &str) -> bool {
+    NAMES.iter().any(|color| color.eq_ignore_ascii_case(name))
+}
+
+/// [`HUE_UNITS`] are the units a hue can be written in, and how many
+/// degrees one of each is.
+const HUE_UNITS: &[(&str, f64)] = &[
+    ("deg", 1.0),
+    ("grad", 0.9),
+    ("rad", 180.0 / std::f64::consts::PI),
+    ("turn", 360.0),
+];
+
+/// [`legacy_color`] returns the `rgb()`, `rgba()`, `hsl()`, or `hsla()`
+/// color `function` (without its `(`) with the space separated
+/// `arguments` of css color level 4, like `0 0 0 / 50%`, in the comma
+/// separated syntax that every browser understands. it returns `None` for
+/// other functions, arguments that are already comma separated, and
+/// arguments that can't be parsed, like a `var()`.
+///
+/// # notes
+/// a hue with a unit, like `120deg`, is written in degrees without one, a
+/// percentage alpha is written as a number, and `none` is written as `0`.
+/// an opaque `rgb()` color is written as its shortest hex or name.
+///
+/// # example
+/// [`legacy_color`] can be used to write colors for older browsers:
+/// ```rust
+/// use rcss::colors::legacy_color;
+///
+/// assert_eq!(legacy_color("rgb", "0 0 0 / 50%").as_deref(), Some("rgba(0,0,0,.5)"));
+/// assert_eq!(legacy_color("hsl", "120deg 50% 50%").as_deref(), Some("hsl(120,50%,50%)"));
+/// assert_eq!(legacy_color("RGB", "255 0 0").as_deref(), Some("red"));
+/// assert_eq!(legacy_color("rgb", "0,0,0"), None);
+/// assert_eq!(legacy_color("rgb", "var(--rgb) / .5"), None);
+/// ```
+pub fn legacy_color(function: &str, arguments: &str) -> Option<String> {
+    let function = function.to_ascii_lowercase();
+    let is_hsl = match function.as_str() {
+        "rgb" | "rgba" => false,
+        "hsl" | "hsla" => true,
+        _ => return None,
+    };
+    if arguments.contains(',') {
+        return None;
+    }
+    let (channels, alpha) = match arguments.split_once('/') {
+        Some((channels, alpha)) => (channels, Some(alpha.trim())),
+        None => (arguments, None),
+    };
+    let channels = channels
+        .split_whitespace()
+        .map(|channel| match channel.eq_ignore_ascii_case("none") {
+            true => "0",
+            false => channel,
+        })
+        .collect::<Vec<_>>();
+    let [first, second, third] = channels[..] else {
+        return None;
+    };
+    let alpha = match alpha {
+        Some(alpha) if alpha.eq_ignore_ascii_case("none") => Some(0.0),
+        Some(alpha) => Some(match alpha.strip_suffix('%') {
+            Some(percentage) => percentage.parse::<f64>().ok()? / 100.0,
+            None => alpha.parse::<f64>().ok()?,
+        }),
+        None => None,
+    }
+    .filter(|alpha| *alpha < 1.0)
+    .map(|alpha| alpha.max(0.0));
+    let channels = match is_hsl {
+        true => vec![hue(first)?, percentage(second)?, percentage(third)?],
+        false => rgb_channels([first, second, third])?,
+    };
+    let name = match is_hsl {
+        true => "hsl",
+        false => "rgb",
+    };
+    match alpha {
+        Some(alpha) => Some(format!(
+            "{name}a({},{})",
+            channels.join(","),
+            legacy_number(alpha)
+        )),
+        None if is_hsl => Some(format!("{name}({})", channels.join(","))),
+        None => {
+            let channels = channels.iter().map(String::as_str).collect::<Vec<_>>();
+            shorten_rgb(&channels).or_else(|| Some(format!("rgb({})", channels.join(","))))
+        }
+    }
+}
+
+/// [`rgb_channels`] returns the red, green, and blue `channels` of an
+/// `rgb()` color as the legacy syntax allows them: all numbers, or all
+/// percentages, so a mix of both is written as numbers.
+fn rgb_channels(channels: [&str; 3]) -> Option<Vec<String>> {
+    let is_percentage = channels.iter().all(|channel| channel.ends_with('%'));
+    channels
+        .iter()
+        .map(|channel| match (is_percentage, channel.strip_suffix('%')) {
+            (true, Some(percentage)) => percentage
+                .parse::<f64>()
+                .ok()
+                .map(|value| format!("{}%", legacy_number(value))),
+            (false, Some(percentage)) => percentage
+                .parse::<f64>()
+                .ok()
+                .map(|value| legacy_number((value * 2.55).round())),
+            (_, None) => channel
+                .parse::<f64>()
+                .ok()
+                .map(|value| legacy_number(value.round())),
+        })
+        .collect()
+}
+
+/// [`hue`] returns the `hue` of an `hsl()` color in degrees, without a
+/// unit.
+fn hue(hue: &str) -> Option<String> {
+    let lowercase = hue.to_ascii_lowercase();
+    let (value, degrees) = HUE_UNITS
+        .iter()
+        .find_map(|(unit, degrees)| Some((lowercase.strip_suffix(unit)?, *degrees)))
+        .unwrap_or((&lowercase, 1.0));
+    value
+        .parse::<f64>()
+        .ok()
+        .map(|value| legacy_number(value * degrees))
+}
+
+/// [`percentage`] returns the saturation or lightness `channel` of an
+/// `hsl()` color as a percentage, which the legacy syntax requires.
+fn percentage(channel: &str) -> Option<String> {
+    let value = channel.strip_suffix('%').unwrap_or(channel);
+    value
+        .parse::<f64>()
+        .ok()
+        .map(|value| format!("{}%", legacy_number(value)))
+}
+
+/// [`legacy_number`] returns `value` rounded to three decimals, in its
+/// shortest form, like `.5`.
+fn legacy_number(value: f64) -> String {
+    let value = format!("{value:.3}");
+    crate::numbers::shorten_number(&value, false).unwrap_or(value)
+}
@@ -0,0 +1,132 @@
+//! pre-compression of the combined output, so servers can send
+//! `style.css.gz` or `style.css.br` without compressing on the fly.
+use std::io;
+
+/// [`Compression`] is a format the combined output can be
+/// pre-compressed with.
+///
+/// # example
+/// [`Compression`] can be parsed from the name given to `--compress`:
+/// ```rust
+/// use rcss::compress::Compression;
+///
+/// assert_eq!(Compression::parse("gzip"), Some(Compression::Gzip));
+/// assert_eq!(Compression::parse("br"), Some(Compression::Brotli));
+/// assert_eq!(Compression::Brotli.extension(), "br");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Compression {
+    /// gzip, which is written to `<output>.gz` and needs the `gzip` feature.
+    Gzip,
+    /// brotli, which is written to `<output>.br` and needs the `brotli`
+    /// feature.
+    Brotli,
+}
+
+impl Compression {
+    /// [`Compression::parse`] returns the compression named `name`, which
+    /// is either its name or its file extension.
+    pub fn parse(name: &str) -> Option<Compression> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "gzip" | "gz" => Some(Compression::Gzip),
+            "brotli" | "br" => Some(Compression::Brotli),
+            _ => None,
+        }
+    }
+
+    /// [`Compression::extension`] returns the extension that is added to
+    /// the name of a compressed file.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Compression::Gzip => "gz",
+            Compression::Brotli => "br",
+        }
+    }
+
+    /// [`Compression::name`] returns the name of the compression, which is
+    /// also the name of the feature it needs.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Compression::Gzip => "gzip",
+            Compression::Brotli => "brotli",
+        }
+    }
+
+    /// [`Compression::is_supported`] returns `true` if rcss was built with
+    /// the feature this compression needs.
+    pub fn is_supported(&self) -> bool {
+        match self {
+            Compression::Gzip => cfg!(feature = "gzip"),
+            Compression::Brotli => cfg!(feature = "brotli"),
+        }
+    }
+
+    /// [`Compression::compress`] compresses `content` as much as possible,
+    /// or returns an [`io::ErrorKind::Unsupported`] error if the compression
+    /// [isn't supported](Compression::is_supported).
+    pub fn compress(&self, content: &[u8]) -> io::Result<Vec<u8>> {
+        let mut compressed = vec![];
+        self.compress_to(&mut compressed, [content])?;
+        Ok(compressed)
+    }
+
+    /// [`Compression::compress_to`] compresses the content made of `pieces`
+    /// like [`Compression::compress`], writing it to `writer` as it goes
+    /// rather than all at once.
+    #[cfg_attr(
+        not(any(feature = "gzip", feature = "brotli")),
+        allow(unused_variables)
+    )]
+    pub fn compress_to<'a>(
+        &self,
+        writer: impl io::Write,
+        pieces: impl IntoIterator<Item = &'a [u8]>,
+    ) -> io::Result<()> {
+        match self {
+            #[cfg(feature = "gzip")]
+            Compression::Gzip => gzip(writer, pieces),
+            #[cfg(feature = "brotli")]
+            Compression::Brotli => brotli(writer, pieces),
+            #[allow(unreachable_patterns)]
+            _ => Err(self.unsupported()),
+        }
+    }
+
+    /// [`Compression::unsupported`] returns the error for a compression that
+    /// [isn't supported](Compression::is_supported).
+    pub fn unsupported(&self) -> io::Error {
+        io::Error::new(
+            io::ErrorKind::Unsupported,
+            format!(
+                "rcss was built without {0} support, rebuild it with `--features {0}`",
+                self.name()
+            ),
+        )
+    }
+}
+
+#[cfg(feature = "gzip")]
+fn gzip<'a>(writer: impl io::Write, pieces: impl IntoIterator<Item = &'a [u8]>) -> io::Result<()> {
+    use io::Write;
+
+    let mut encoder = flate2::write::GzEncoder::new(writer, flate2::Compression::best());
+    for piece in pieces {
+        encoder.write_all(piece)?;
+    }
+    encoder.finish()?.flush()
+}
+
+#[cfg(feature = "brotli")]
+fn brotli<'a>(
+    writer: impl io::Write,
+    pieces: impl IntoIterator<Item = &'a [u8]>,
+) -> io::Result<()> {
+    use io::Write;
+
+    let mut encoder = brotli::CompressorWriter::new(writer, 4096, 11, 22);
+    for piece in pieces {
+        encoder.write_all(piece)?;
+    }
+    // the stream is only finished once the encoder is done with the writer
+    encoder.into_inner().flush()
+}
@@ -0,0 +1,246 @@
+//! a reader for `rcss.toml` configuration files, which support the
+//! subset of toml that rcss needs: comments, `[table]` headers, and
+//! `key = value` pairs where the value is a string, integer, boolean,
+//! or an array of those.
+use std::{collections::BTreeMap, fs, path::Path};
+
+use crate::error::{RcssError, Result};
+
+/// [`CONFIG_FILE_NAME`] is the name of the configuration file that
+/// rcss looks for in the current directory.
+pub const CONFIG_FILE_NAME: &str = "rcss.toml";
+
+/// [`Value`] is a single value from a configuration file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Value {
+    String(String),
+    Integer(i64),
+    Boolean(bool),
+    Array(Vec<Value>),
+}
+
+impl Value {
+    /// [`Value::as_str`] returns the value if it is a string.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// [`Value::as_integer`] returns the value if it is an integer.
+    pub fn as_integer(&self) -> Option<i64> {
+        match self {
+            Value::Integer(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// [`Value::as_bool`] returns the value if it is a boolean.
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Value::Boolean(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// [`Value::as_strings`] returns the value if it is an array
+    /// of strings.
+    pub fn as_strings(&self) -> Option<Vec<String>> {
+        match self {
+            Value::Array(values) => values
+                .iter()
+                .map(|value| value.as_str().map(str::to_string))
+                .collect(),
+            _ => None,
+        }
+    }
+}
+
+/// [`Config`] is a parsed configuration file, made up of tables of
+/// keys and [`Value`]s. keys before the first `[table]` header belong
+/// to the root table, which is named `""`.
+///
+/// # example
+/// [`Config`] can be used to read the `order` of files to combine:
+/// ```rust
+/// let config = rcss::config::Config::parse(r#"order = ["reset.css", "base.css"]"#).unwrap();
+/// let order = config.get("", "order").and_then(|value| value.as_strings());
+/// assert_eq!(order, Some(vec!["reset.css".to_string(), "base.css".to_string()]));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Config {
+    pub tables: BTreeMap<String, BTreeMap<String, Value>>,
+}
+
+impl Config {
+    /// [`Config::load`] reads and parses the configuration file at `path`,
+    /// returning an [`RcssError::Parse`] if it is invalid.
+    pub fn load(path: impl AsRef<Path>) -> Result<Config> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path).map_err(RcssError::io(path))?;
+        Config::parse(&content).map_err(|message| RcssError::Parse {
+            path: path.to_path_buf(),
+            message,
+        })
+    }
+
+    /// [`Config::parse`] parses the content of a configuration file,
+    /// returning a description of the problem if it is invalid.
+    pub fn parse(content: &str) -> std::result::Result<Config, String> {
+        let mut config = Config::default();
+        let mut table = String::new();
+        let mut lines = content.lines().enumerate();
+        while let Some((index, line)) = lines.next() {
+            let mut line = strip_comment(line).trim().to_string();
+            if line.is_empty() {
+                continue;
+            }
+            if let Some(header) = line.strip_prefix('[') {
+                let header = header
+                    .strip_suffix(']')
+                    .ok_or_else(|| format!("line {}: unterminated table header", index + 1))?;
+                table = header.trim().to_string();
+                config.tables.entry(table.clone()).or_default();
+                continue;
+            }
+            let (key, value) = line
+                .split_once('=')
+                .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+                .ok_or_else(|| format!("line {}: expected `key = value`", index + 1))?;
+            line = value;
+
+            // arrays can span multiple lines, until their brackets are balanced
+            while line.starts_with('[') && !is_balanced(&line) {
+                let (_, next) = lines
+                    .next()
+                    .ok_or_else(|| format!("line {}: unterminated array", index + 1))?;
+                line.push(' ');
+                line.push_str(strip_comment(next).trim());
+            }
+            let (value, rest) = parse_value(&line)
+                .ok_or_else(|| format!("line {}: invalid value for `{}`", index + 1, key))?;
+            if !rest.trim().is_empty() {
+                return Err(format!("line {}: unexpected `{}`", index + 1, rest.trim()));
+            }
+            let key = key.trim_matches('"').to_string();
+            config
+                .tables
+                .entry(table.clone())
+                .or_default()
+                .insert(key, value);
+        }
+        Ok(config)
+    }
+
+    /// [`Config::get`] returns the value of `key` within `table`.
+    pub fn get(&self, table: &str, key: &str) -> Option<&Value> {
+        self.tables.get(table)?.get(key)
+    }
+
+    /// [`Config::overlay`] returns a copy of this config whose root table
+    /// also has every key of `table`, replacing the root values of the keys
+    /// they share.
+    ///
+    /// # example
+    /// [`Config::overlay`] can be used to read the options of a bundle,
+    /// which default to the options of the root table:
+    /// ```rust
+    /// let config = rcss::config::Config::parse("hash = true\ncache = true\n[site]\nhash = false").unwrap();
+    /// let site = config.overlay("site");
+    /// assert_eq!(site.get("", "hash").and_then(|value| value.as_bool()), Some(false));
+    /// assert_eq!(site.get("", "cache").and_then(|value| value.as_bool()), Some(true));
+    /// ```
+    pub fn overlay(&self, table: &str) -> Config {
+        let mut config = self.clone();
+        if let Some(values) = self.tables.get(table) {
+            let root = config.tables.entry(String::new()).or_default();
+            root.extend(values.clone());
+        }
+        config
+    }
+}
+
+/// [`strip_comment`] removes a `#` comment from `line`, ignoring any
+/// `#` inside of a string.
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    for (index, character) in line.char_indices() {
+        match (quote, character) {
+            (None, '"' | '\'') => quote = Some(character),
+            (Some(open), _) if open == character => quote = None,
+            (None, '#') => return &line[..index],
+            _ => {}
+        }
+    }
+    line
+}
+
+/// [`is_balanced`] returns `true` if every `[` in `line` outside of a
+/// string has a matching `]`.
+fn is_balanced(line: &str) -> bool {
+    let mut quote = None;
+    let mut depth = 0;
+    for character in line.chars() {
+        match (quote, character) {
+            (None, '"' | '\'') => quote = Some(character),
+            (Some(open), _) if open == character => quote = None,
+            (None, '[') => depth += 1,
+            (None, ']') => depth -= 1,
+            _ => {}
+        }
+    }
+    depth <= 0
+}
+
+/// [`parse_value`] parses a single value from the start of `input`,
+/// returning it along with whatever input is left over.
+fn parse_value(input: &str) -> Option<(Value, &str)> {
+    let input = input.trim_start();
+    if let Some(rest) = input.strip_prefix('"') {
+        let mut value = String::new();
+        let mut characters = rest.char_indices();
+        while let Some((index, character)) = characters.next() {
+            match character {
+                '"' => return Some((Value::String(value), &rest[index + 1..])),
+                '\\' => match characters.next()?.1 {
+                    'n' => value.push('\n'),
+                    't' => value.push('\t'),
+                    escaped => value.push(escaped),
+                },
+                _ => value.push(character),
+            }
+        }
+        return None;
+    }
+    if let Some(rest) = input.strip_prefix('\'') {
+        let end = rest.find('\'')?;
+        return Some((Value::String(rest[..end].to_string()), &rest[end + 1..]));
+    }
+    if let Some(mut rest) = input.strip_prefix('[') {
+        let mut values = vec![];
+        loop {
+            rest = rest.trim_start();
+            if let Some(after) = rest.strip_prefix(']') {
+                return Some((Value::Array(values), after));
+            }
+            let (value, after) = parse_value(rest)?;
+            values.push(value);
+            rest = after.trim_start();
+            rest = rest.strip_prefix(',').unwrap_or(rest);
+        }
+    }
+    let end = input
+        .find(|c: char| c == ',' || c == ']' || c.is_whitespace())
+        .unwrap_or(input.len());
+    let (word, rest) = input.split_at(end);
+    match word {
+        "true" => Some((Value::Boolean(true), rest)),
+        "false" => Some((Value::Boolean(false), rest)),
+        _ => word
+            .replace('_', "")
+            .parse()
+            .ok()
+            .map(|value| (Value::Integer(value), rest)),
+    }
+}
@@ -0,0 +1,275 @@
+//! finding of the declarations that two files set differently for the same
+//! selector, where only the order the files are combined in decides which
+//! one wins, like a `.button{color:red}` that a later file overrides.
+use std::{collections::HashMap, fmt, path::Path};
+
+use crate::{
+    css,
+    error::Result,
+    glob, ordering, paths,
+    selectors::split_list,
+    tokenizer::{tokenize, Token, TokenKind},
+    Options,
+};
+
+/// [`Definition`] is where a declaration is set, and the value it is set
+/// to, as written.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Definition {
+    pub path: String,
+    pub line: usize,
+    pub column: usize,
+    pub value: String,
+}
+
+impl fmt::Display for Definition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.path, self.line, self.column)
+    }
+}
+
+/// [`Conflict`] is a `property` of a `selector` that a file sets to one
+/// value, and a later file overrides with another.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Conflict {
+    /// the selector, after the at-rules and rules it is nested within, like
+    /// `@media print .a`.
+    pub selector: String,
+    pub property: String,
+    /// the declaration that is overridden.
+    pub overridden: Definition,
+    /// the declaration of a later file that wins.
+    pub winner: Definition,
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} of {} is {}, overriding {} from {}",
+            self.winner,
+            self.property,
+            self.selector,
+            self.winner.value,
+            self.overridden.value,
+            self.overridden
+        )
+    }
+}
+
+/// [`Declared`] is the last declaration of a property of a selector so far,
+/// along with its minified value, and whether it is `!important`.
+struct Declared {
+    definition: Definition,
+    minified: String,
+    important: bool,
+}
+
+/// [`find_conflicts`] returns every [`Conflict`] between the stylesheets of
+/// `files`, which are paths and their content, in the order they are
+/// combined in.
+///
+/// # notes
+/// a declaration only conflicts with one of another file, since a file
+/// that sets the same property twice, like a fallback, does so on purpose.
+/// values are compared after they are minified, so `#FFFFFF` and `#fff` are
+/// the same, and an `!important` declaration only conflicts with another
+/// one, since it wins over the others no matter the order. selector lists
+/// are compared selector by selector, but a shorthand and its longhands,
+/// like `margin` and `margin-top`, aren't compared.
+///
+/// # example
+/// [`find_conflicts`] can be used to find a button color that a theme
+/// overrides:
+/// ```rust
+/// use rcss::conflicts::find_conflicts;
+///
+/// let files = [
+///     ("base.css".to_string(), ".button, .link { color: red; margin: 0 }".to_string()),
+///     ("theme.css".to_string(), ".button {\n  color: blue;\n  margin: 0px\n}".to_string()),
+/// ];
+/// let conflicts = find_conflicts(&files);
+/// assert_eq!(conflicts.len(), 1);
+/// assert_eq!(
+///     conflicts[0].to_string(),
+///     "theme.css:2:3: color of .button is blue, overriding red from base.css:1:18",
+/// );
+/// ```
+pub fn find_conflicts(files: &[(String, String)]) -> Vec<Conflict> {
+    let mut declared = HashMap::<(String, String), Declared>::new();
+    let mut conflicts = vec![];
+    for (path, content) in files {
+        for (selector, property, important, definition) in declarations(path, content) {
+            let minified = css::minify(&format!("a{{{property}:{}}}", definition.value));
+            let key = (selector, property);
+            if let Some(previous) = declared.get(&key) {
+                if previous.important && !important {
+                    continue;
+                }
+                if previous.definition.path != definition.path
+                    && previous.important == important
+                    && previous.minified != minified
+                {
+                    conflicts.push(Conflict {
+                        selector: key.0.clone(),
+                        property: key.1.clone(),
+                        overridden: previous.definition.clone(),
+                        winner: definition.clone(),
+                    });
+                }
+            }
+            declared.insert(
+                key,
+                Declared {
+                    definition,
+                    minified,
+                    important,
+                },
+            );
+        }
+    }
+    conflicts
+}
+
+/// [`declarations`] returns every declaration within a block of the
+/// stylesheet `css` of the file at `path`, once for every selector it is
+/// set for, with its lowercased property and whether it is `!important`.
+fn declarations(path: &str, css: &str) -> Vec<(String, String, bool, Definition)> {
+    let mut declarations = vec![];
+    // the minified selector or at-rule prelude of every open block
+    let mut blocks: Vec<String> = vec![];
+    let mut statement: Vec<Token> = vec![];
+    let mut nesting = 0usize;
+    for token in tokenize(css) {
+        match token.kind {
+            TokenKind::Comment => continue,
+            TokenKind::Function | TokenKind::OpenParen | TokenKind::OpenBracket => nesting += 1,
+            TokenKind::CloseParen | TokenKind::CloseBracket => nesting = nesting.saturating_sub(1),
+            _ => {}
+        }
+        if nesting > 0 {
+            statement.push(token);
+            continue;
+        }
+        match token.kind {
+            TokenKind::OpenBrace => {
+                let prelude = join(&statement);
+                let minified = css::minify(&format!("{prelude}{{}}"));
+                blocks.push(minified.trim_end_matches("{}").to_string());
+                statement.clear();
+            }
+            TokenKind::Semicolon | TokenKind::CloseBrace => {
+                if let Some((label, parents)) = blocks.split_last() {
+                    if let Some((property, important, definition)) = declaration(path, &statement) {
+                        let parents = parents.join(" ");
+                        let selectors = match label.starts_with('@') {
+                            true => vec![label.clone()],
+                            false => split_list(label),
+                        };
+                        for selector in selectors {
+                            let selector = match parents.is_empty() {
+                                true => selector,
+                                false => format!("{parents} {selector}"),
+                            };
+                            declarations.push((
+                                selector,
+                                property.clone(),
+                                important,
+                                definition.clone(),
+                            ));
+                        }
+                    }
+                }
+                statement.clear();
+                if token.kind == TokenKind::CloseBrace {
+                    blocks.pop();
+                }
+            }
+            _ => statement.push(token),
+        }
+    }
+    declarations
+}
+
+/// [`declaration`] returns the lowercased property of the declaration made
+/// of `tokens`, whether it is `!important`, and where it is set to what, or
+/// `None` if `tokens` aren't a declaration.
+fn declaration(path: &str, tokens: &[Token]) -> Option<(String, bool, Definition)> {
+    let mut tokens = tokens
+        .iter()
+        .filter(|token| token.kind != TokenKind::Comment)
+        .skip_while(|token| token.kind == TokenKind::Whitespace);
+    let property = tokens
+        .next()
+        .filter(|token| token.kind == TokenKind::Ident)?;
+    let rest = tokens
+        .skip_while(|token| token.kind == TokenKind::Whitespace)
+        .copied()
+        .collect::<Vec<_>>();
+    let [colon, value @ ..] = &rest[..] else {
+        return None;
+    };
+    if colon.kind != TokenKind::Colon {
+        return None;
+    }
+    let mut value = join(value);
+    let mut important = false;
+    if let Some(bang) = value.rfind('!') {
+        if value[bang + 1..].trim().eq_ignore_ascii_case("important") {
+            important = true;
+            value = value[..bang].trim_end().to_string();
+        }
+    }
+    let definition = Definition {
+        path: path.to_string(),
+        line: property.line,
+        column: property.column,
+        value,
+    };
+    Some((property.text.to_ascii_lowercase(), important, definition))
+}
+
+/// [`join`] joins `tokens`, collapsing every run of whitespace into a
+/// single space.
+fn join(tokens: &[Token]) -> String {
+    let mut joined = String::new();
+    for token in tokens {
+        match token.kind {
+            TokenKind::Whitespace => joined.push(' '),
+            _ => joined.push_str(token.text),
+        }
+    }
+    joined.trim().to_string()
+}
+
+/// [`conflicts_directory`] returns every [`Conflict`] between the css files
+/// within `directory`, except `output` (the minified file rcss writes
+/// there), in the order they would be combined in.
+///
+/// # example
+/// [`conflicts_directory`] can be used to print every conflict in
+/// `./assets/css`:
+/// ```rust,no_run
+/// let options = rcss::Options::default();
+/// for conflict in rcss::conflicts::conflicts_directory("./assets/css", "style.css", &options).unwrap() {
+///     println!("{conflict}");
+/// }
+/// ```
+pub fn conflicts_directory(
+    directory: &str,
+    output: &str,
+    options: &Options,
+) -> Result<Vec<Conflict>> {
+    let filter = glob::FileFilter::new(&options.include, &options.exclude);
+    let output_path = Path::new(directory).join(output);
+    let files = crate::recurse_files(directory, &filter, options)?
+        .into_iter()
+        .filter(|file| crate::has_extension(file, "css") && !paths::same_file(file, &output_path))
+        .collect();
+    let files = ordering::order_files(files, directory, &options.order, options.sort)?;
+    let sources = crate::read_sources(&files, options)?
+        .into_iter()
+        .map(|(file_path, content)| (file_path.display().to_string(), content))
+        .collect::<Vec<_>>();
+    Ok(find_conflicts(&sources))
+}
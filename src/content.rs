@@ -0,0 +1,86 @@
+//! the content of a source file, which is memory-mapped instead of copied
+//! into a string when it is large (with the `mmap` feature), so a multi
+//! megabyte generated stylesheet isn't held in memory twice.
+use std::{fmt, fs, io, ops::Deref, path::Path};
+
+/// [`MMAP_THRESHOLD`] is how many bytes long a file must be before it is
+/// memory-mapped. mapping a small file costs more than reading it.
+pub const MMAP_THRESHOLD: u64 = 1 << 20;
+
+/// [`Content`] is the text of a file, which can be used as a `&str`.
+pub enum Content {
+    /// the file, read into a string.
+    Read(String),
+    /// the file, mapped into memory, which is known to be valid utf-8.
+    #[cfg(feature = "mmap")]
+    Mapped(memmap2::Mmap),
+}
+
+/// [`read`] returns the content of the file at `path`, memory-mapped if it
+/// is at least [`MMAP_THRESHOLD`] bytes long and the `mmap` feature is
+/// turned on. like [`fs::read_to_string`], it fails if the file isn't utf-8.
+///
+/// # example
+/// [`read`] can be used like [`fs::read_to_string`]:
+/// ```rust,no_run
+/// let content = rcss::content::read("assets/css/generated.css").unwrap();
+/// let minified = rcss::minify_string(&content);
+/// ```
+pub fn read(path: impl AsRef<Path>) -> io::Result<Content> {
+    let path = path.as_ref();
+    #[cfg(feature = "mmap")]
+    {
+        let file = fs::File::open(path)?;
+        if file.metadata()?.len() >= MMAP_THRESHOLD {
+            // SAFETY: the map is only ever read. a file that is truncated by
+            // another process while it is minified is not supported, the same
+            // as a file that is rewritten halfway through being read.
+            let map = unsafe { memmap2::Mmap::map(&file)? };
+            std::str::from_utf8(&map)
+                .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+            return Ok(Content::Mapped(map));
+        }
+    }
+    fs::read_to_string(path).map(Content::Read)
+}
+
+impl Deref for Content {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        match self {
+            Content::Read(content) => content,
+            // SAFETY: a map is only created by `read`, after it is checked to
+            // be valid utf-8.
+            #[cfg(feature = "mmap")]
+            Content::Mapped(map) => unsafe { std::str::from_utf8_unchecked(map) },
+        }
+    }
+}
+
+impl From<String> for Content {
+    fn from(content: String) -> Content {
+        Content::Read(content)
+    }
+}
+
+// a map can't be cloned, so a clone is always read into a string
+impl Clone for Content {
+    fn clone(&self) -> Content {
+        Content::Read(self.to_string())
+    }
+}
+
+impl PartialEq for Content {
+    fn eq(&self, other: &Content) -> bool {
+        **self == **other
+    }
+}
+
+impl Eq for Content {}
+
+impl fmt::Debug for Content {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
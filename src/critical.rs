@@ -0,0 +1,92 @@
+//! critical css extraction, which keeps only the rules that match the
+//! elements of a single html page, so they can be inlined in its `<head>`
+//! while the full stylesheet loads asynchronously.
+use std::{collections::HashSet, fs, path::Path};
+
+use crate::{
+    error::{RcssError, Result},
+    html::{tag_end, Tag},
+    passes::purge::{purge_selectors, selector_names, Name},
+    stylesheet::Stylesheet,
+};
+
+/// [`Document`] is every element name, class name, and id used by the
+/// elements of an html page.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Document {
+    tags: HashSet<String>,
+    classes: HashSet<String>,
+    ids: HashSet<String>,
+}
+
+impl Document {
+    /// [`Document::parse`] reads the start tag of every element in `html`.
+    pub fn parse(html: &str) -> Document {
+        let mut document = Document::default();
+        let mut position = 0;
+        while let Some(offset) = html[position..].find('<') {
+            let start = position + offset;
+            position = start + 1;
+            if !html[position..].starts_with(|c: char| c.is_ascii_alphabetic()) {
+                continue;
+            }
+            let end = tag_end(html, start);
+            let tag = Tag::parse(&html[start..end]);
+            document.tags.insert(tag.name.to_ascii_lowercase());
+            for (attribute, value) in &tag.attributes {
+                let value = value.unwrap_or_default().trim_matches(['"', '\'']);
+                if attribute.eq_ignore_ascii_case("class") {
+                    document
+                        .classes
+                        .extend(value.split_ascii_whitespace().map(str::to_string));
+                } else if attribute.eq_ignore_ascii_case("id") {
+                    document.ids.insert(value.to_string());
+                }
+            }
+            position = end;
+        }
+        document
+    }
+
+    /// [`Document::read`] reads the html file at `path`.
+    pub fn read(path: impl AsRef<Path>) -> Result<Document> {
+        let path = path.as_ref();
+        Ok(Document::parse(
+            &fs::read_to_string(path).map_err(RcssError::io(path))?,
+        ))
+    }
+
+    /// [`Document::could_match`] returns `true` if every element name,
+    /// class name, and id in `selector` is used in the document, so the
+    /// selector could match one of its elements.
+    pub fn could_match(&self, selector: &str) -> bool {
+        selector_names(selector).iter().all(|name| match name {
+            Name::Type(name) => self.tags.contains(name),
+            Name::Class(name) => self.classes.contains(name),
+            Name::Id(name) => self.ids.contains(name),
+        })
+    }
+}
+
+/// [`extract_critical`] removes every selector in `stylesheet` that can't
+/// match an element of `document`, leaving only its critical css.
+///
+/// # notes
+/// whether an element is visible without scrolling isn't known, so every
+/// rule that could match an element of the page is kept. rules without
+/// any element names, class names, or ids, like `:root` or `*`, are kept
+/// too.
+///
+/// # example
+/// [`extract_critical`] can be used to find the rules a landing page needs:
+/// ```rust
+/// use rcss::{critical::{extract_critical, Document}, stylesheet::Stylesheet};
+///
+/// let document = Document::parse(r#"<header class="hero"><h1>rcss</h1></header>"#);
+/// let mut stylesheet = Stylesheet::parse(".hero h1{margin:0}table{width:100%}.modal{z-index:9}");
+/// extract_critical(&mut stylesheet, &document);
+/// assert_eq!(stylesheet.to_css(), ".hero h1{margin:0}");
+/// ```
+pub fn extract_critical(stylesheet: &mut Stylesheet, document: &Document) {
+    purge_selectors(stylesheet, &|selector| document.could_match(selector));
+}
@@ -0,0 +1,494 @@
+//! css minification rules, applied to the tokens produced by
+//! [`crate::tokenizer`] so that strings, urls, and other literals
+//! are never rewritten.
+use crate::{
+    colors, numbers, selectors,
+    source_map::Mapping,
+    tokenizer::{remove_comments, tokenize, Token, TokenKind},
+};
+use std::borrow::Cow;
+
+/// [`is_trimmable`] returns `true` for punctuation that never needs
+/// whitespace around it, including the comparisons of a range query like
+/// `(400px <= width < 800px)`.
+fn is_trimmable(token: &Token) -> bool {
+    match token.kind {
+        TokenKind::Colon
+        | TokenKind::Semicolon
+        | TokenKind::Comma
+        | TokenKind::OpenBrace
+        | TokenKind::CloseBrace => true,
+        TokenKind::Delim => matches!(token.text, ">" | "<" | "="),
+        _ => false,
+    }
+}
+
+/// [`starts_statement`] returns `true` if the token after `previous`
+/// is the first token of a rule, at-rule, or declaration.
+fn starts_statement(previous: Option<&Token>) -> bool {
+    previous.is_none_or(|token| {
+        matches!(
+            token.kind,
+            TokenKind::OpenBrace | TokenKind::CloseBrace | TokenKind::Semicolon
+        )
+    })
+}
+
+/// [`MATH_FUNCTIONS`] are the functions where a zero length can't lose
+/// its unit, since `calc(0 + 1px)` is invalid. `var()` is included since
+/// its fallback could be used within one.
+const MATH_FUNCTIONS: &[&str] = &["calc(", "-webkit-calc(", "min(", "max(", "clamp(", "var("];
+
+/// [`is_between_operands`] returns `true` if the tokens `previous` and
+/// `next` can be on either side of an operator within a math function,
+/// unlike the `,` and `)` of `var(--a,-)`.
+fn is_between_operands(previous: Option<&Token>, next: Option<&Token>) -> bool {
+    let ends_operand = previous.is_some_and(|previous| {
+        !matches!(
+            previous.kind,
+            TokenKind::Function | TokenKind::OpenParen | TokenKind::Comma | TokenKind::Colon
+        )
+    });
+    let starts_operand =
+        next.is_some_and(|next| !matches!(next.kind, TokenKind::CloseParen | TokenKind::Comma));
+    ends_operand && starts_operand
+}
+
+/// [`math_arguments`] returns a flag for every token in `tokens`, which is
+/// `true` if the token is within one of the [`MATH_FUNCTIONS`].
+fn math_arguments(tokens: &[Token]) -> Vec<bool> {
+    let mut functions = vec![];
+    let mut arguments = vec![false; tokens.len()];
+    for (index, token) in tokens.iter().enumerate() {
+        match token.kind {
+            TokenKind::Function => functions.push(token.text.to_ascii_lowercase()),
+            TokenKind::OpenParen => functions.push("(".to_string()),
+            TokenKind::CloseParen => {
+                functions.pop();
+            }
+            TokenKind::OpenBrace | TokenKind::CloseBrace | TokenKind::Semicolon => {
+                functions.clear()
+            }
+            _ => {}
+        }
+        arguments[index] = functions
+            .iter()
+            .any(|function| MATH_FUNCTIONS.contains(&function.as_str()));
+    }
+    arguments
+}
+
+/// [`keeps_numbers`] returns `true` if the numbers in the value of
+/// `property` must be kept as written, like the code points in
+/// `unicode-range:U+0025-00FF`, or a custom property that javascript
+/// could read.
+fn keeps_numbers(property: &str) -> bool {
+    property.starts_with("--") || property.eq_ignore_ascii_case("unicode-range")
+}
+
+/// [`keeps_zero_units`] returns `true` if a zero in the value of `property`
+/// can't lose its unit, like `flex:1 1 0px`, where a unitless zero would
+/// be read as `flex-shrink` instead of `flex-basis`.
+fn keeps_zero_units(property: &str) -> bool {
+    crate::passes::property_name(property) == "flex"
+}
+
+/// [`keeps_hex_colors`] returns `true` if the hex colors in the value of
+/// `property` must be kept as written, like the `#80000000` of an old IE
+/// `filter:progid:DXImageTransform.Microsoft.gradient(startColorstr=#80000000)`,
+/// which only reads `#aarrggbb`, and would read `#8000` as another color.
+fn keeps_hex_colors(property: &str) -> bool {
+    crate::passes::property_name(property) == "filter"
+}
+
+/// [`declaration_values`] returns a flag for every token in `tokens`,
+/// which is `true` if the token is part of a declaration value (like
+/// `red` in `color: red`), as opposed to a selector or at-rule prelude.
+pub fn declaration_values(tokens: &[Token]) -> Vec<bool> {
+    let mut values = vec![false; tokens.len()];
+    let mut depth = 0;
+    let mut statement_start = 0;
+    let mut index = 0;
+    while index < tokens.len() {
+        match tokens[index].kind {
+            TokenKind::OpenBrace => {
+                depth += 1;
+                statement_start = index + 1;
+            }
+            TokenKind::CloseBrace => {
+                depth -= 1;
+                statement_start = index + 1;
+            }
+            TokenKind::Semicolon => statement_start = index + 1,
+            TokenKind::Colon if depth > 0 => {
+                // a declaration is a property name, followed by a colon, that
+                // ends at a `;` or `}` rather than opening a nested rule.
+                let mut property = tokens[statement_start..index].iter().filter(|token| {
+                    !matches!(token.kind, TokenKind::Whitespace | TokenKind::Comment)
+                });
+                let is_property = property
+                    .next()
+                    .is_some_and(|token| token.kind == TokenKind::Ident)
+                    && property.next().is_none();
+                let end = tokens[index..]
+                    .iter()
+                    .position(|token| {
+                        matches!(
+                            token.kind,
+                            TokenKind::Semicolon | TokenKind::OpenBrace | TokenKind::CloseBrace
+                        )
+                    })
+                    .map_or(tokens.len(), |position| index + position);
+                let opens_rule = tokens
+                    .get(end)
+                    .is_some_and(|token| token.kind == TokenKind::OpenBrace);
+                if is_property && !opens_rule {
+                    values[index + 1..end].fill(true);
+                    index = end;
+                    continue;
+                }
+            }
+            _ => {}
+        }
+        index += 1;
+    }
+    values
+}
+
+/// [`rule_selectors`] returns a flag for every token in `tokens`, which is
+/// `true` if the token is part of the selector of a rule (like `a b` in
+/// `a b{color:red}`), where whitespace can be a descendant combinator.
+fn rule_selectors(tokens: &[Token]) -> Vec<bool> {
+    let mut selectors = vec![false; tokens.len()];
+    let mut statement_start = 0;
+    for (index, token) in tokens.iter().enumerate() {
+        match token.kind {
+            TokenKind::OpenBrace => {
+                let is_at_rule = tokens[statement_start..index]
+                    .iter()
+                    .find(|token| token.kind != TokenKind::Whitespace)
+                    .is_some_and(|token| token.kind == TokenKind::AtKeyword);
+                if !is_at_rule {
+                    selectors[statement_start..index].fill(true);
+                }
+                statement_start = index + 1;
+            }
+            TokenKind::CloseBrace | TokenKind::Semicolon => statement_start = index + 1,
+            _ => {}
+        }
+    }
+    selectors
+}
+
+/// [`color_function`] returns the index of the `)` that closes the color
+/// function starting at `tokens[index]`, along with its shortest form, if
+/// it is an `rgb()` or `rgba()` color that can be shortened.
+fn color_function(tokens: &[Token], index: usize) -> Option<(usize, String)> {
+    let name = tokens[index].text.to_ascii_lowercase();
+    if name != "rgb(" && name != "rgba(" {
+        return None;
+    }
+    let mut channels = vec![];
+    for (offset, token) in tokens[index + 1..].iter().enumerate() {
+        match token.kind {
+            TokenKind::Number => channels.push(token.text),
+            TokenKind::Whitespace | TokenKind::Comma => {}
+            TokenKind::CloseParen => {
+                return colors::shorten_rgb(&channels).map(|color| (index + offset + 1, color))
+            }
+            _ => return None,
+        }
+    }
+    None
+}
+
+/// [`license_comments`] returns every comment in `content` that starts
+/// with `/*!`, which is how libraries mark license banners that must be
+/// kept in minified output.
+///
+/// # example
+/// [`license_comments`] can be used to find the license of a library:
+/// ```rust
+/// let comments = rcss::css::license_comments("/*! mit */\n/* note */ a{}");
+/// assert_eq!(comments, vec!["/*! mit */"]);
+/// ```
+pub fn license_comments(content: &str) -> Vec<&str> {
+    tokenize(content)
+        .into_iter()
+        .filter(|token| token.kind == TokenKind::Comment && token.text.starts_with("/*!"))
+        .map(|token| token.text)
+        .collect()
+}
+
+/// [`HOISTED_AT_RULES`] are the statements that are only valid before
+/// every other rule of a stylesheet.
+const HOISTED_AT_RULES: &[&str] = &["@charset", "@import", "@layer"];
+
+/// [`split_hoisted`] splits the `@charset`, `@import`, and `@layer`
+/// statements (without a block) off the start of the minified `css`,
+/// returning each of them and the rest of `css`.
+///
+/// # example
+/// [`split_hoisted`] can be used to move the imports of a file to the top
+/// of combined output:
+/// ```rust
+/// use rcss::css::split_hoisted;
+///
+/// let (statements, rest) = split_hoisted(r#"@charset "UTF-8";@layer a,b;@import url(x.css);a{b:c}"#);
+/// assert_eq!(statements, [r#"@charset "UTF-8";"#, "@layer a,b;", "@import url(x.css);"]);
+/// assert_eq!(rest, "a{b:c}");
+/// assert_eq!(split_hoisted("@layer a{b{c:d}}"), (vec![], "@layer a{b{c:d}}"));
+/// ```
+pub fn split_hoisted(css: &str) -> (Vec<&str>, &str) {
+    let mut statements = vec![];
+    let mut start = 0;
+    let mut is_statement = false;
+    for token in tokenize(css) {
+        match token.kind {
+            TokenKind::Whitespace | TokenKind::Comment if !is_statement => {}
+            TokenKind::AtKeyword
+                if !is_statement
+                    && HOISTED_AT_RULES
+                        .iter()
+                        .any(|name| token.text.eq_ignore_ascii_case(name)) =>
+            {
+                is_statement = true;
+            }
+            TokenKind::Semicolon if is_statement => {
+                let end = token.offset + token.text.len();
+                statements.push(css[start..end].trim());
+                start = end;
+                is_statement = false;
+            }
+            TokenKind::OpenBrace | TokenKind::CloseBrace => break,
+            _ if !is_statement => break,
+            _ => {}
+        }
+    }
+    (statements, &css[start..])
+}
+
+/// [`minify`] will remove comments, collapse whitespace, remove
+/// unnecessary semi-colons, trim spaces around punctuation, and shorten
+/// colors and numbers in `content`. strings, urls, and at-rule keywords are kept
+/// as-is, including any punctuation within them, like the `,` of an attribute
+/// selector or the `;` and `{` of a `content` value.
+///
+/// # example
+/// [`minify`] can be used to minify css without collapsing the
+/// whitespace inside of a string:
+/// ```rust
+/// let minified = rcss::css::minify(r#"a::after { content: "a  b"; }"#);
+/// assert_eq!(minified, r#"a::after{content:"a  b"}"#);
+///
+/// let minified = rcss::css::minify(r#"[data-label="a, b"] > b::after { content: "; { } : " }"#);
+/// assert_eq!(minified, r#"[data-label="a, b"]>b::after{content:"; { } : "}"#);
+/// ```
+pub fn minify(content: &str) -> String {
+    minify_with_mappings(content).0
+}
+
+/// [`minify_with_mappings`] will minify `content` the same way as
+/// [`minify`], and also return a [`Mapping`] for the start of every
+/// rule, at-rule, and declaration, pointing back to where it started
+/// in `content`.
+///
+/// # example
+/// [`minify_with_mappings`] can be used to find where the second
+/// rule of a stylesheet came from:
+/// ```rust
+/// let (minified, mappings) = rcss::css::minify_with_mappings("a {}\nb {}");
+/// assert_eq!(minified, "a{}b{}");
+/// assert_eq!((mappings[1].line, mappings[1].column), (2, 1));
+/// ```
+pub fn minify_with_mappings(content: &str) -> (String, Vec<Mapping>) {
+    if content.len() < CHUNK_SIZE * 2 {
+        return minify_chunk(content);
+    }
+    // only the tokens of one chunk are ever held at once, since there are
+    // far more of them than there are bytes in the minified output
+    let mut output = String::new();
+    let mut output_column = 0;
+    let mut mappings = vec![];
+    let (mut line, mut column) = (1, 1);
+    for chunk in split_chunks(content, CHUNK_SIZE) {
+        let (minified, chunk_mappings) = minify_chunk(chunk);
+        mappings.extend(chunk_mappings.into_iter().map(|mapping| Mapping {
+            generated_column: mapping.generated_column + output_column,
+            line: mapping.line + line - 1,
+            column: match mapping.line {
+                1 => mapping.column + column - 1,
+                _ => mapping.column,
+            },
+        }));
+        output_column += minified.encode_utf16().count();
+        output.push_str(&minified);
+        match chunk.rsplit_once('\n') {
+            Some((_, last_line)) => {
+                line += chunk.matches('\n').count();
+                column = last_line.chars().count() + 1;
+            }
+            None => column += chunk.chars().count(),
+        }
+    }
+    (output, mappings)
+}
+
+/// [`CHUNK_SIZE`] is roughly how many bytes of a large stylesheet are
+/// minified at a time by [`minify_with_mappings`].
+pub const CHUNK_SIZE: usize = 1 << 20;
+
+/// [`split_chunks`] splits `css` into chunks of at least `size` bytes (but
+/// the last), each ending with the close brace of a top-level rule, so
+/// every chunk minifies the same on its own as it does within `css`.
+///
+/// # example
+/// [`split_chunks`] never splits inside of a rule, string, or comment:
+/// ```rust
+/// let css = "a { content: \"}\" }\n@media print { b {} }\n/* } */ c {}";
+/// let chunks = rcss::css::split_chunks(css, 1);
+/// assert_eq!(chunks, ["a { content: \"}\" }", "\n@media print { b {} }", "\n/* } */ c {}"]);
+/// ```
+pub fn split_chunks(css: &str, size: usize) -> Vec<&str> {
+    let bytes = css.as_bytes();
+    let mut chunks = vec![];
+    let mut start = 0;
+    let mut depth = 0_usize;
+    let mut index = 0;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'\\' => index += 1,
+            b'/' if bytes.get(index + 1) == Some(&b'*') => {
+                index = css[index + 2..]
+                    .find("*/")
+                    .map_or(bytes.len(), |end| index + end + 3);
+            }
+            quote @ (b'"' | b'\'') => {
+                index += 1;
+                // a string also ends at a line break, like the tokenizer's
+                while index < bytes.len() && !matches!(bytes[index], b'\n') && bytes[index] != quote
+                {
+                    if bytes[index] == b'\\' {
+                        index += 1;
+                    }
+                    index += 1;
+                }
+            }
+            b'{' => depth += 1,
+            b'}' if depth > 0 => {
+                depth -= 1;
+                if depth == 0 && index + 1 - start >= size {
+                    chunks.push(&css[start..=index]);
+                    start = index + 1;
+                }
+            }
+            _ => {}
+        }
+        index += 1;
+    }
+    if start < css.len() {
+        chunks.push(&css[start..]);
+    }
+    chunks
+}
+
+/// [`minify_chunk`] minifies `content` for [`minify_with_mappings`], all
+/// at once.
+fn minify_chunk(content: &str) -> (String, Vec<Mapping>) {
+    // comments are dropped, so the tokens on either side of a comment are
+    // treated as neighbours, unless they would be read as one
+    let tokens = remove_comments(tokenize(content));
+    let values = declaration_values(&tokens);
+    let math_arguments = math_arguments(&tokens);
+    let selectors = rule_selectors(&tokens);
+    let mut attribute_depth = 0;
+    let mut property = "";
+    let mut output = String::new();
+    let mut output_column = 0;
+    let mut mappings = vec![];
+    let mut previous: Option<&Token> = None;
+    let mut index = 0;
+    while index < tokens.len() {
+        let token = &tokens[index];
+        let next = tokens[index + 1..]
+            .iter()
+            .find(|token| token.kind != TokenKind::Whitespace);
+        let mut text = Cow::Borrowed(token.text);
+        let mut last_index = index;
+        match token.kind {
+            TokenKind::Whitespace => {
+                let is_edge = previous.is_none() || next.is_none();
+                // the space after a math operator is already written
+                let is_repeated = previous.is_some_and(|token| token.kind == TokenKind::Whitespace)
+                    || (math_arguments[index] && output.ends_with(' '));
+                let is_trimmed = match selectors[index] {
+                    true => !previous.zip(next).is_some_and(|(previous, next)| {
+                        selectors::is_significant_whitespace(previous, next, attribute_depth > 0)
+                    }),
+                    false => previous.is_some_and(is_trimmable) || next.is_some_and(is_trimmable),
+                };
+                if !is_edge && !is_repeated && !is_trimmed {
+                    output.push(' ');
+                    output_column += 1;
+                    previous = Some(token);
+                }
+                index += 1;
+                continue;
+            }
+            TokenKind::Semicolon if next.is_some_and(|next| next.kind == TokenKind::CloseBrace) => {
+                index += 1;
+                continue;
+            }
+            TokenKind::OpenBracket if selectors[index] => attribute_depth += 1,
+            TokenKind::CloseBracket if selectors[index] => attribute_depth -= 1,
+            TokenKind::Ident if !values[index] => property = token.text,
+            // a number right after another, like the `.0` of `1.5.0`, is
+            // kept as it is, since shortening it to `0` would join the two
+            TokenKind::Number
+                if values[index]
+                    && !keeps_numbers(property)
+                    && !previous.is_some_and(|previous| previous.kind == TokenKind::Number) =>
+            {
+                let drop_zero_unit = !math_arguments[index] && !keeps_zero_units(property);
+                if let Some(number) = numbers::shorten_number(token.text, drop_zero_unit) {
+                    text = Cow::Owned(number);
+                }
+            }
+            TokenKind::Hash if values[index] && !keeps_hex_colors(property) => {
+                if let Some(color) = colors::shorten_hex(token.text) {
+                    text = Cow::Owned(color);
+                }
+            }
+            // `calc(1px+2px)` is invalid, so `+` and `-` always have a space
+            // on either side within a math function, even if it was a comment
+            TokenKind::Delim
+                if math_arguments[index]
+                    && matches!(token.text, "+" | "-")
+                    && is_between_operands(previous, next) =>
+            {
+                let before = if output.ends_with(' ') { "" } else { " " };
+                text = Cow::Owned(format!("{before}{} ", token.text));
+            }
+            TokenKind::Function if values[index] => {
+                if let Some((close, color)) = color_function(&tokens, index) {
+                    text = Cow::Owned(color);
+                    last_index = close;
+                }
+            }
+            _ => {}
+        }
+        let is_closing = matches!(token.kind, TokenKind::CloseBrace | TokenKind::Semicolon);
+        if !is_closing && starts_statement(previous) {
+            mappings.push(Mapping {
+                generated_column: output_column,
+                line: token.line,
+                column: token.column,
+            });
+        }
+        output.push_str(&text);
+        output_column += text.encode_utf16().count();
+        previous = Some(&tokens[last_index]);
+        index = last_index + 1;
+    }
+    (output, mappings)
+}
@@ -0,0 +1,163 @@
+//! how the rcss binary reports errors and lint warnings, as text or as one
+//! json object per line for ci systems and editors, and the exit code it
+//! stops with for each kind of error.
+use std::{
+    fmt, process,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use rcss::{conflicts::Conflict, json::quote, lint::Warning, RcssError};
+
+/// [`EXIT_USAGE`] is the exit code of invalid arguments or configuration,
+/// and of checks that fail, like lint warnings or `--max-size`.
+pub const EXIT_USAGE: i32 = 1;
+/// [`EXIT_IO`] is the exit code of a file that couldn't be read or written.
+pub const EXIT_IO: i32 = 2;
+/// [`EXIT_PARSE`] is the exit code of a file that couldn't be parsed, like
+/// a malformed stylesheet with `--strict`.
+pub const EXIT_PARSE: i32 = 3;
+
+static IS_JSON: AtomicBool = AtomicBool::new(false);
+
+/// [`Format`] is how diagnostics are printed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Format {
+    /// `error: ...` lines, and lint warnings like `a.css:1:1: ...`.
+    #[default]
+    Text,
+    /// a json object per diagnostic, per line.
+    Json,
+}
+
+impl Format {
+    /// [`Format::parse`] reads a format from its name, like `"json"`.
+    pub fn parse(name: &str) -> Option<Format> {
+        match name {
+            "text" => Some(Format::Text),
+            "json" => Some(Format::Json),
+            _ => None,
+        }
+    }
+}
+
+/// [`set_format`] changes the [`Format`] of every diagnostic printed after
+/// it.
+pub fn set_format(format: Format) {
+    IS_JSON.store(format == Format::Json, Ordering::Relaxed);
+}
+
+/// [`exit_code`] returns the exit code that `error` stops rcss with.
+pub fn exit_code(error: &RcssError) -> i32 {
+    match error {
+        RcssError::Io { .. } | RcssError::InvalidPath(_) => EXIT_IO,
+        RcssError::Parse { .. } => EXIT_PARSE,
+        RcssError::Config(_) | RcssError::OverBudget(_) | RcssError::OutOfDate(_) => EXIT_USAGE,
+    }
+}
+
+/// [`kind`] returns the name of the kind of `error`, for json diagnostics.
+fn kind(error: &RcssError) -> &'static str {
+    match error {
+        RcssError::Io { .. } | RcssError::InvalidPath(_) => "io",
+        RcssError::Parse { .. } => "parse",
+        RcssError::Config(_) => "config",
+        RcssError::OverBudget(_) => "over-budget",
+        RcssError::OutOfDate(_) => "out-of-date",
+    }
+}
+
+/// [`fail`] prints `message` as an error, and exits with `code`.
+pub fn fail(message: impl fmt::Display, code: i32) -> ! {
+    match IS_JSON.load(Ordering::Relaxed) {
+        true => eprintln!(
+            "{{\"severity\":\"error\",\"kind\":\"usage\",\"message\":{}}}",
+            quote(&message.to_string())
+        ),
+        false => eprintln!("error: {message}"),
+    }
+    process::exit(code);
+}
+
+/// [`fail_with`] prints `error`, after the `context` it happened in, and
+/// exits with its [`exit_code`].
+pub fn fail_with(context: impl fmt::Display, error: &RcssError) -> ! {
+    if !IS_JSON.load(Ordering::Relaxed) {
+        eprintln!("error: {context}: {error}");
+        process::exit(exit_code(error));
+    }
+    let path = match error {
+        RcssError::Io {
+            path: Some(path), ..
+        }
+        | RcssError::InvalidPath(path)
+        | RcssError::Parse { path, .. } => {
+            format!(",\"path\":{}", quote(&path.to_string_lossy()))
+        }
+        _ => String::new(),
+    };
+    eprintln!(
+        "{{\"severity\":\"error\",\"kind\":\"{}\"{path},\"message\":{}}}",
+        kind(error),
+        quote(&format!("{context}: {error}"))
+    );
+    process::exit(exit_code(error));
+}
+
+/// [`report_warnings`] prints every lint warning to stdout, and fails if
+/// there were any.
+pub fn report_warnings(warnings: &[Warning]) {
+    let is_json = IS_JSON.load(Ordering::Relaxed);
+    for warning in warnings {
+        match is_json {
+            true => println!(
+                "{{\"severity\":\"warning\",\"check\":\"{}\",\"path\":{},\"line\":{},\"column\":{},\"message\":{}}}",
+                warning.check.name(),
+                quote(&warning.path),
+                warning.line,
+                warning.column,
+                quote(&warning.message)
+            ),
+            false => println!("{warning}"),
+        }
+    }
+    if is_json && !warnings.is_empty() {
+        process::exit(EXIT_USAGE);
+    }
+    match warnings.len() {
+        0 => {}
+        1 => fail("1 problem found", EXIT_USAGE),
+        count => fail(format_args!("{count} problems found"), EXIT_USAGE),
+    }
+}
+
+/// [`report_conflicts`] prints every conflict between files to stdout, and
+/// fails if there were any.
+pub fn report_conflicts(conflicts: &[Conflict]) {
+    let is_json = IS_JSON.load(Ordering::Relaxed);
+    for conflict in conflicts {
+        match is_json {
+            true => println!(
+                "{{\"severity\":\"warning\",\"check\":\"conflict\",\"path\":{},\"line\":{},\"column\":{},\"overridden\":{{\"path\":{},\"line\":{},\"column\":{}}},\"message\":{}}}",
+                quote(&conflict.winner.path),
+                conflict.winner.line,
+                conflict.winner.column,
+                quote(&conflict.overridden.path),
+                conflict.overridden.line,
+                conflict.overridden.column,
+                quote(&format!(
+                    "{} of {} is {}, overriding {}",
+                    conflict.property, conflict.selector, conflict.winner.value, conflict.overridden.value
+                ))
+            ),
+            false => println!("{conflict}"),
+        }
+    }
+    if is_json && !conflicts.is_empty() {
+        process::exit(EXIT_USAGE);
+    }
+    match conflicts.len() {
+        0 => {}
+        1 => fail("1 conflict found", EXIT_USAGE),
+        count => fail(format_args!("{count} conflicts found"), EXIT_USAGE),
+    }
+}
@@ -0,0 +1,92 @@
+//! the error type returned by everything in rcss that can fail.
+use std::{
+    error, fmt, io,
+    path::{Path, PathBuf},
+};
+
+/// [`RcssError`] describes why rcss couldn't minify something.
+///
+/// # example
+/// [`RcssError`] can be matched on to tell a missing directory apart from
+/// an invalid configuration:
+/// ```rust
+/// use rcss::RcssError;
+///
+/// match rcss::minify_directory("css", "./does/not/exist", "style.css") {
+///     Err(RcssError::Io { path, .. }) => assert!(path.is_some()),
+///     other => panic!("unexpected result {other:?}"),
+/// }
+/// ```
+#[derive(Debug)]
+pub enum RcssError {
+    /// reading or writing a file (at `path`, if it is known) failed.
+    Io {
+        path: Option<PathBuf>,
+        error: io::Error,
+    },
+    /// a path that isn't valid utf-8, like a directory argument, which
+    /// can't be used as a bundle directory or output name.
+    InvalidPath(PathBuf),
+    /// the file at `path` couldn't be parsed, like an `rcss.toml` with a
+    /// syntax error.
+    Parse { path: PathBuf, message: String },
+    /// an option or configuration value is invalid, or two options can't
+    /// be used together.
+    Config(String),
+    /// the output is bigger than [`crate::Options::max_size`].
+    OverBudget(crate::budget::OverBudget),
+    /// the existing output isn't what was built, with
+    /// [`crate::Options::check`].
+    OutOfDate(crate::check::OutOfDate),
+}
+
+/// [`Result`] is the result of everything in rcss that can fail.
+pub type Result<T> = std::result::Result<T, RcssError>;
+
+impl RcssError {
+    /// [`RcssError::io`] returns a function that wraps an [`io::Error`]
+    /// from reading or writing `path`, for use with `map_err`.
+    pub fn io(path: impl AsRef<Path>) -> impl FnOnce(io::Error) -> RcssError {
+        let path = path.as_ref().to_path_buf();
+        move |error| RcssError::Io {
+            path: Some(path),
+            error,
+        }
+    }
+}
+
+impl fmt::Display for RcssError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RcssError::Io {
+                path: Some(path),
+                error,
+            } => write!(f, "{}: {}", path.display(), error),
+            RcssError::Io { path: None, error } => write!(f, "{error}"),
+            RcssError::InvalidPath(path) => {
+                write!(f, "{} is not a valid utf-8 path", path.display())
+            }
+            RcssError::Parse { path, message } => {
+                write!(f, "could not parse {}: {}", path.display(), message)
+            }
+            RcssError::Config(message) => write!(f, "{message}"),
+            RcssError::OverBudget(over_budget) => write!(f, "{over_budget}"),
+            RcssError::OutOfDate(out_of_date) => write!(f, "{out_of_date}"),
+        }
+    }
+}
+
+impl error::Error for RcssError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            RcssError::Io { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for RcssError {
+    fn from(error: io::Error) -> RcssError {
+        RcssError::Io { path: None, error }
+    }
+}
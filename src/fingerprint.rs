@@ -0,0 +1,97 @@
+//! content-hash fingerprinting of output file names, like
+//! `style.0a1b2c3d.css`, so they can be cached forever.
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::{self, Write},
+    path::Path,
+};
+
+use crate::{json, output};
+
+/// [`MANIFEST_FILE_NAME`] is the name of the manifest that maps each
+/// output file name to its fingerprinted name, within the output directory.
+pub const MANIFEST_FILE_NAME: &str = "manifest.json";
+
+/// [`content_hash`] returns an 8 character hex hash of `content`, using
+/// 32-bit fnv-1a.
+///
+/// # example
+/// [`content_hash`] can be used to fingerprint some css:
+/// ```rust
+/// assert_eq!(rcss::fingerprint::content_hash(b"a{color:red}").len(), 8);
+/// ```
+pub fn content_hash(content: &[u8]) -> String {
+    content_hash_of([content])
+}
+
+/// [`content_hash_of`] returns the [`content_hash`] of the content made of
+/// `pieces`, without joining them.
+pub fn content_hash_of<'a>(pieces: impl IntoIterator<Item = &'a [u8]>) -> String {
+    let mut hash: u32 = 0x811c9dc5;
+    for byte in pieces.into_iter().flatten() {
+        hash ^= u32::from(*byte);
+        hash = hash.wrapping_mul(0x01000193);
+    }
+    format!("{hash:08x}")
+}
+
+/// [`hashed_name`] inserts `hash` before the extension of `file_name`.
+///
+/// # example
+/// [`hashed_name`] can be used to fingerprint `style.css`:
+/// ```rust
+/// assert_eq!(rcss::fingerprint::hashed_name("style.css", "0a1b2c3d"), "style.0a1b2c3d.css");
+/// ```
+pub fn hashed_name(file_name: &str, hash: &str) -> String {
+    match file_name.rsplit_once('.') {
+        Some((stem, extension)) if !stem.is_empty() => format!("{stem}.{hash}.{extension}"),
+        _ => format!("{file_name}.{hash}"),
+    }
+}
+
+/// [`is_hashed_name`] returns `true` if `candidate` is `file_name` with
+/// any hash inserted by [`hashed_name`].
+///
+/// # example
+/// [`is_hashed_name`] can be used to find old fingerprinted output:
+/// ```rust
+/// use rcss::fingerprint::is_hashed_name;
+///
+/// assert!(is_hashed_name("style.0a1b2c3d.css", "style.css"));
+/// assert!(!is_hashed_name("style.theme.css", "style.css"));
+/// ```
+pub fn is_hashed_name(candidate: &str, file_name: &str) -> bool {
+    let hash = match file_name.rsplit_once('.') {
+        Some((stem, extension)) if !stem.is_empty() => candidate
+            .strip_prefix(&format!("{stem}."))
+            .and_then(|rest| rest.strip_suffix(&format!(".{extension}"))),
+        _ => candidate.strip_prefix(&format!("{file_name}.")),
+    };
+    hash.is_some_and(|hash| hash.len() == 8 && hash.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// [`read_manifest`] returns the entries of the [`MANIFEST_FILE_NAME`]
+/// file within `directory`, or no entries if it is missing or invalid.
+pub fn read_manifest(directory: impl AsRef<Path>) -> BTreeMap<String, String> {
+    fs::read_to_string(directory.as_ref().join(MANIFEST_FILE_NAME))
+        .ok()
+        .and_then(|content| json::parse_string_object(&content))
+        .unwrap_or_default()
+}
+
+/// [`update_manifest`] records that `file_name` was written as
+/// `hashed_file_name` in the [`MANIFEST_FILE_NAME`] file within `directory`,
+/// keeping the entries of any other files already in it.
+pub fn update_manifest(
+    directory: impl AsRef<Path>,
+    file_name: &str,
+    hashed_file_name: &str,
+) -> io::Result<()> {
+    let mut entries = read_manifest(directory.as_ref());
+    entries.insert(file_name.to_string(), hashed_file_name.to_string());
+    let content = json::write_string_object(&entries);
+    output::write_atomically(directory.as_ref().join(MANIFEST_FILE_NAME), |file| {
+        file.write_all(content.as_bytes())
+    })
+}
@@ -0,0 +1,271 @@
+//! pretty-printing of css, the inverse of minification, which puts every
+//! declaration on its own line and normalizes spacing with the same
+//! tokenizer, so strings, urls, and comments are kept as they are.
+use std::{fmt, fs, io::Write, path::Path};
+
+use crate::{
+    error::{RcssError, Result},
+    glob, log, paths,
+    tokenizer::{tokenize, Token, TokenKind},
+    Options,
+};
+
+/// [`Indent`] is what every level of a formatted block is indented with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Indent {
+    /// this many spaces.
+    Spaces(usize),
+    /// a single tab.
+    Tab,
+}
+
+impl Default for Indent {
+    fn default() -> Indent {
+        Indent::Spaces(2)
+    }
+}
+
+impl Indent {
+    /// [`Indent::parse`] reads an indent from a number of spaces, like
+    /// `"4"`, or `"tab"`.
+    pub fn parse(indent: &str) -> Option<Indent> {
+        match indent.trim() {
+            indent if indent.eq_ignore_ascii_case("tab") => Some(Indent::Tab),
+            spaces => spaces.parse().ok().map(Indent::Spaces),
+        }
+    }
+}
+
+impl fmt::Display for Indent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Indent::Spaces(spaces) => write!(f, "{}", " ".repeat(*spaces)),
+            Indent::Tab => write!(f, "\t"),
+        }
+    }
+}
+
+/// [`Context`] is the kind of statement whose tokens are being joined.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Context {
+    Selector,
+    Prelude,
+    Value,
+}
+
+/// [`format()`] pretty-prints `css`, with one selector and one declaration
+/// per line, every block indented by `indent`, and a blank line between
+/// top-level rules.
+///
+/// # example
+/// [`format()`] can be used to make minified css readable again:
+/// ```rust
+/// use rcss::format::{format, Indent};
+///
+/// let formatted = format("a,b>c{color:red;margin:0 auto!important}", Indent::default());
+/// assert_eq!(formatted, "a,\nb > c {\n  color: red;\n  margin: 0 auto !important;\n}\n");
+/// ```
+pub fn format(css: &str, indent: Indent) -> String {
+    let mut formatter = Formatter {
+        output: String::new(),
+        indent: indent.to_string(),
+        depth: 0,
+        needs_blank_line: false,
+    };
+    let mut statement: Vec<Token> = vec![];
+    let mut nesting = 0usize;
+    for token in tokenize(css) {
+        match token.kind {
+            TokenKind::Function | TokenKind::OpenParen | TokenKind::OpenBracket => nesting += 1,
+            TokenKind::CloseParen | TokenKind::CloseBracket => nesting = nesting.saturating_sub(1),
+            _ => {}
+        }
+        if nesting > 0 {
+            statement.push(token);
+            continue;
+        }
+        let is_blank = statement
+            .iter()
+            .all(|token| token.kind == TokenKind::Whitespace);
+        match token.kind {
+            TokenKind::Comment if is_blank => {
+                formatter.line(token.text);
+                statement.clear();
+            }
+            TokenKind::OpenBrace => {
+                let context = match statement
+                    .iter()
+                    .find(|token| token.kind != TokenKind::Whitespace)
+                {
+                    Some(first) if first.kind == TokenKind::AtKeyword => Context::Prelude,
+                    _ => Context::Selector,
+                };
+                let prelude = formatter.join(&statement, context);
+                formatter.line(&format!("{prelude} {{"));
+                formatter.depth += 1;
+                statement.clear();
+            }
+            TokenKind::Semicolon => {
+                formatter.statement(&statement);
+                statement.clear();
+            }
+            TokenKind::CloseBrace => {
+                formatter.statement(&statement);
+                formatter.depth = formatter.depth.saturating_sub(1);
+                formatter.line("}");
+                formatter.needs_blank_line = formatter.depth == 0;
+                statement.clear();
+            }
+            _ => statement.push(token),
+        }
+    }
+    formatter.statement(&statement);
+    formatter.output
+}
+
+/// [`Formatter`] writes formatted lines, keeping track of how deeply they
+/// are nested.
+struct Formatter {
+    output: String,
+    indent: String,
+    depth: usize,
+    needs_blank_line: bool,
+}
+
+impl Formatter {
+    /// [`Formatter::line`] writes `text` on its own, indented, line.
+    fn line(&mut self, text: &str) {
+        if self.needs_blank_line && self.depth == 0 {
+            self.output.push('\n');
+        }
+        self.needs_blank_line = false;
+        self.output.push_str(&self.indent.repeat(self.depth));
+        self.output.push_str(text);
+        self.output.push('\n');
+    }
+
+    /// [`Formatter::statement`] writes a declaration, or an at-rule without
+    /// a block like `@import`, followed by a `;`.
+    fn statement(&mut self, tokens: &[Token]) {
+        let Some(start) = tokens
+            .iter()
+            .position(|token| token.kind != TokenKind::Whitespace)
+        else {
+            return;
+        };
+        let tokens = &tokens[start..];
+        let colon = tokens
+            .iter()
+            .position(|token| token.kind == TokenKind::Colon);
+        let statement = match colon {
+            Some(colon) if tokens[0].kind != TokenKind::AtKeyword => {
+                let property = self.join(&tokens[..colon], Context::Value);
+                let value = match property.starts_with("--") {
+                    // whitespace within a custom property is part of its value
+                    true => tokens[colon + 1..]
+                        .iter()
+                        .map(|token| token.text)
+                        .collect::<String>()
+                        .trim()
+                        .to_string(),
+                    false => self.join(&tokens[colon + 1..], Context::Value),
+                };
+                format!("{property}: {value};")
+            }
+            _ => format!("{};", self.join(tokens, Context::Prelude)),
+        };
+        self.line(&statement);
+    }
+
+    /// [`Formatter::join`] joins `tokens` with normalized spacing: single
+    /// spaces between words, after commas, and around selector combinators
+    /// (with a line break after every selector in a list).
+    fn join(&self, tokens: &[Token], context: Context) -> String {
+        let line_indent = self.indent.repeat(self.depth);
+        let mut joined = String::new();
+        let mut pending_space = false;
+        let mut nesting = 0usize;
+        for token in tokens {
+            let text = token.text;
+            match token.kind {
+                TokenKind::Whitespace => {
+                    pending_space = true;
+                    continue;
+                }
+                TokenKind::CloseParen | TokenKind::CloseBracket => {
+                    nesting = nesting.saturating_sub(1);
+                    joined.push_str(text);
+                    pending_space = false;
+                    continue;
+                }
+                TokenKind::Comma if context == Context::Selector && nesting == 0 => {
+                    joined.push_str(",\n");
+                    joined.push_str(&line_indent);
+                    pending_space = false;
+                    continue;
+                }
+                TokenKind::Comma => {
+                    joined.push(',');
+                    pending_space = true;
+                    continue;
+                }
+                _ => {}
+            }
+            let is_combinator = context == Context::Selector
+                && nesting == 0
+                && token.kind == TokenKind::Delim
+                && matches!(text, ">" | "+" | "~");
+            let is_important = token.kind == TokenKind::Delim && text == "!";
+            if (pending_space || is_combinator || is_important)
+                && !joined.is_empty()
+                && !joined.ends_with([' ', '\n', '(', '['])
+            {
+                joined.push(' ');
+            }
+            joined.push_str(text);
+            pending_space = is_combinator
+                || (context == Context::Prelude && nesting > 0 && token.kind == TokenKind::Colon);
+            if matches!(
+                token.kind,
+                TokenKind::Function | TokenKind::OpenParen | TokenKind::OpenBracket
+            ) {
+                nesting += 1;
+            }
+        }
+        joined.trim_end().to_string()
+    }
+}
+
+/// [`format_directory`] formats every css file within `directory` in
+/// place, except `output` (the minified file rcss writes there), and
+/// returns how many files were changed.
+///
+/// # example
+/// [`format_directory`] can be used to format `./assets/css` with four
+/// space indents:
+/// ```rust,no_run
+/// let options = rcss::Options {
+///     indent: rcss::format::Indent::Spaces(4),
+///     ..Default::default()
+/// };
+/// rcss::format::format_directory("./assets/css", "style.css", &options).unwrap();
+/// ```
+pub fn format_directory(directory: &str, output: &str, options: &Options) -> Result<usize> {
+    let filter = glob::FileFilter::new(&options.include, &options.exclude);
+    let output_path = Path::new(directory).join(output);
+    let mut changed = 0;
+    for file in crate::recurse_files(directory, &filter, options)? {
+        if !crate::has_extension(&file, "css") || paths::same_file(&file, &output_path) {
+            continue;
+        }
+        let content = fs::read_to_string(&file).map_err(RcssError::io(&file))?;
+        let formatted = format(&content, options.indent);
+        if formatted != content {
+            crate::output::write_atomically(&file, |writer| writer.write_all(formatted.as_bytes()))
+                .map_err(RcssError::io(&file))?;
+            log::verbose(format_args!("formatted {}", file.display()));
+            changed += 1;
+        }
+    }
+    Ok(changed)
+}
@@ -0,0 +1,169 @@
+//! glob patterns, used to include or exclude files relative to the
+//! directory being minified.
+
+/// [`Part`] is a single piece of a compiled [`Glob`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Part {
+    /// a character that must match exactly.
+    Literal(char),
+    /// `?`, which matches any character except `/`.
+    AnyCharacter,
+    /// `*`, which matches any run of characters except `/`.
+    AnyCharacters,
+    /// `**`, which matches any run of characters, including `/`.
+    AnyPath,
+    /// `[abc]` or `[a-z]`, which match one character from the set,
+    /// or `[!abc]`, which matches one character outside of the set.
+    Set(Vec<(char, char)>, bool),
+}
+
+/// [`Glob`] is a compiled glob pattern, like `**/vendor/**` or
+/// `components/*.css`, which is matched against `/` separated paths.
+///
+/// # example
+/// [`Glob`] can be used to check if a file is a vendor file:
+/// ```rust
+/// let vendor = rcss::glob::Glob::new("**/vendor/**");
+/// assert!(vendor.matches("vendor/normalize.css"));
+/// assert!(vendor.matches("pages/vendor/slider.css"));
+/// assert!(!vendor.matches("pages/vendors.css"));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Glob {
+    pattern: String,
+    parts: Vec<Part>,
+}
+
+impl Glob {
+    /// [`Glob::new`] compiles `pattern`. a `**/` matches zero or more
+    /// whole directories, and a leading `./` is ignored.
+    pub fn new(pattern: &str) -> Glob {
+        let normalized = pattern.replace('\\', "/");
+        let normalized = normalized.trim_start_matches("./");
+        let mut parts = vec![];
+        let mut characters = normalized.chars().peekable();
+        while let Some(character) = characters.next() {
+            match character {
+                '?' => parts.push(Part::AnyCharacter),
+                '*' if characters.peek() == Some(&'*') => {
+                    characters.next();
+                    // `**/` also matches no directories at all
+                    if characters.peek() == Some(&'/') {
+                        characters.next();
+                    }
+                    parts.push(Part::AnyPath);
+                }
+                '*' => parts.push(Part::AnyCharacters),
+                '[' => {
+                    let negated = characters.next_if_eq(&'!').is_some();
+                    let mut ranges = vec![];
+                    while let Some(start) = characters.next_if(|c| *c != ']') {
+                        let end = match characters.next_if_eq(&'-') {
+                            Some(_) => characters.next().unwrap_or(start),
+                            None => start,
+                        };
+                        ranges.push((start, end));
+                    }
+                    characters.next();
+                    parts.push(Part::Set(ranges, negated));
+                }
+                literal => parts.push(Part::Literal(literal)),
+            }
+        }
+        Glob {
+            pattern: pattern.to_string(),
+            parts,
+        }
+    }
+
+    /// [`Glob::pattern`] returns the pattern this glob was compiled from.
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    /// [`Glob::matches`] returns `true` if the whole of `path` matches.
+    pub fn matches(&self, path: &str) -> bool {
+        let path = path.replace('\\', "/").chars().collect::<Vec<_>>();
+        matches_parts(&self.parts, &path)
+    }
+}
+
+fn matches_parts(parts: &[Part], path: &[char]) -> bool {
+    let Some((part, rest)) = parts.split_first() else {
+        return path.is_empty();
+    };
+    match part {
+        Part::AnyPath => (0..=path.len()).any(|skip| matches_parts(rest, &path[skip..])),
+        Part::AnyCharacters => {
+            let next_separator = path.iter().position(|c| *c == '/').unwrap_or(path.len());
+            (0..=next_separator).any(|skip| matches_parts(rest, &path[skip..]))
+        }
+        _ => match path.split_first() {
+            Some((character, path_rest)) if matches_character(part, *character) => {
+                matches_parts(rest, path_rest)
+            }
+            _ => false,
+        },
+    }
+}
+
+fn matches_character(part: &Part, character: char) -> bool {
+    match part {
+        Part::Literal(literal) => *literal == character,
+        Part::AnyCharacter => character != '/',
+        Part::Set(ranges, negated) => {
+            let in_set = ranges
+                .iter()
+                .any(|(start, end)| (*start..=*end).contains(&character));
+            character != '/' && in_set != *negated
+        }
+        Part::AnyCharacters | Part::AnyPath => true,
+    }
+}
+
+/// [`FileFilter`] decides which files and directories are walked,
+/// based on `include` and `exclude` [`Glob`]s.
+///
+/// # example
+/// [`FileFilter`] can be used to skip vendor and already minified files:
+/// ```rust
+/// let filter = rcss::glob::FileFilter::new(&[], &["**/vendor/**".to_string(), "**/*.min.css".to_string()]);
+/// assert!(filter.allows_file("components/button.css"));
+/// assert!(!filter.allows_file("components/button.min.css"));
+/// assert!(!filter.allows_directory("vendor"));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FileFilter {
+    include: Vec<Glob>,
+    exclude: Vec<Glob>,
+}
+
+impl FileFilter {
+    /// [`FileFilter::new`] compiles the `include` and `exclude` patterns.
+    pub fn new(include: &[String], exclude: &[String]) -> FileFilter {
+        FileFilter {
+            include: include.iter().map(|pattern| Glob::new(pattern)).collect(),
+            exclude: exclude.iter().map(|pattern| Glob::new(pattern)).collect(),
+        }
+    }
+
+    /// [`FileFilter::allows_file`] returns `true` if the file at the
+    /// relative `path` matches an include pattern (or there are none),
+    /// and doesn't match any exclude pattern.
+    pub fn allows_file(&self, path: &str) -> bool {
+        let included =
+            self.include.is_empty() || self.include.iter().any(|glob| glob.matches(path));
+        included && !self.exclude.iter().any(|glob| glob.matches(path))
+    }
+
+    /// [`FileFilter::allows_directory`] returns `false` if everything in
+    /// the directory at the relative `path` is excluded, so it doesn't
+    /// need to be walked at all.
+    pub fn allows_directory(&self, path: &str) -> bool {
+        let contents = format!("{}/", path.trim_end_matches('/'));
+        !self
+            .exclude
+            .iter()
+            .any(|glob| glob.parts.last() == Some(&Part::AnyPath) && glob.matches(&contents))
+    }
+}
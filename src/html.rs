@@ -0,0 +1,370 @@
+//! the html rule set, which strips comments and collapses whitespace
+//! between tags and attributes, without touching the content of
+//! elements like `<pre>` where whitespace matters.
+
+/// [`EXTENSIONS`] are the file extensions that are minified as html.
+pub const EXTENSIONS: &[&str] = &["html", "htm"];
+
+/// [`RAW_TEXT_ELEMENTS`] are the elements whose content is kept exactly as
+/// written, since it either isn't html or its whitespace matters.
+const RAW_TEXT_ELEMENTS: &[&str] = &["script", "style", "pre", "textarea"];
+
+/// [`BLOCK_ELEMENTS`] are the elements that whitespace can be removed from
+/// around, since it is never rendered next to them.
+const BLOCK_ELEMENTS: &[&str] = &[
+    "html",
+    "head",
+    "body",
+    "title",
+    "meta",
+    "link",
+    "base",
+    "script",
+    "style",
+    "noscript",
+    "template",
+    "div",
+    "p",
+    "section",
+    "article",
+    "aside",
+    "header",
+    "footer",
+    "main",
+    "nav",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "ul",
+    "ol",
+    "li",
+    "dl",
+    "dt",
+    "dd",
+    "table",
+    "thead",
+    "tbody",
+    "tfoot",
+    "tr",
+    "th",
+    "td",
+    "caption",
+    "colgroup",
+    "col",
+    "form",
+    "fieldset",
+    "legend",
+    "figure",
+    "figcaption",
+    "blockquote",
+    "hr",
+    "br",
+    "pre",
+    "address",
+    "details",
+    "summary",
+    "dialog",
+    "option",
+    "optgroup",
+    "select",
+    "source",
+    "track",
+    "picture",
+    "video",
+    "audio",
+    "iframe",
+    "canvas",
+    "svg",
+];
+
+/// [`Piece`] is a part of an html document.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Piece<'a> {
+    /// text between tags, which is where whitespace is collapsed.
+    Text(&'a str),
+    /// a tag, already minified, along with its lowercase element name.
+    Tag(String, String),
+    /// something kept exactly as written, like a conditional comment, a
+    /// doctype, or the content of a `<pre>`.
+    Verbatim(&'a str),
+}
+
+/// [`is_extension`] returns `true` if files with `extension` are minified
+/// as html.
+///
+/// # example
+/// [`is_extension`] can be used to pick a rule set for a file:
+/// ```rust
+/// assert!(rcss::html::is_extension("htm"));
+/// assert!(!rcss::html::is_extension("css"));
+/// ```
+pub fn is_extension(extension: &str) -> bool {
+    EXTENSIONS
+        .iter()
+        .any(|known| known.eq_ignore_ascii_case(extension.trim_start_matches('.')))
+}
+
+/// [`minify`] will minify the html in `source`. it removes comments (except
+/// conditional comments), collapses whitespace within text to a single
+/// space, removes whitespace next to block elements, and removes extra
+/// whitespace within tags.
+///
+/// # example
+/// [`minify`] can be used to minify a small page:
+/// ```rust
+/// let minified = rcss::html::minify("<ul>\n  <!-- items -->\n  <li class=\" a  b \" >one  <b>two</b></li>\n</ul>");
+/// assert_eq!(minified, r#"<ul><li class="a b">one <b>two</b></li></ul>"#);
+/// ```
+pub fn minify(source: &str) -> String {
+    let pieces = parse(source);
+    let is_block = |piece: Option<&Piece>| match piece {
+        None | Some(Piece::Verbatim(_)) => true,
+        Some(Piece::Tag(_, name)) => BLOCK_ELEMENTS.contains(&name.as_str()),
+        Some(Piece::Text(_)) => false,
+    };
+    let mut minified = String::new();
+    for (index, piece) in pieces.iter().enumerate() {
+        match piece {
+            Piece::Text(text) => {
+                let mut text = collapse_whitespace(text);
+                if is_block(index.checked_sub(1).and_then(|index| pieces.get(index))) {
+                    text = text.trim_start().to_string();
+                }
+                if is_block(pieces.get(index + 1)) {
+                    text = text.trim_end().to_string();
+                }
+                // a comment between two pieces of text was removed
+                if minified.ends_with(' ') && text.starts_with(' ') {
+                    text.remove(0);
+                }
+                minified.push_str(&text);
+            }
+            Piece::Tag(tag, _) => minified.push_str(tag),
+            Piece::Verbatim(text) => minified.push_str(text),
+        }
+    }
+    minified
+}
+
+/// [`parse`] splits `source` into [`Piece`]s, dropping comments that
+/// aren't conditional comments.
+fn parse(source: &str) -> Vec<Piece<'_>> {
+    let mut pieces = vec![];
+    let mut position = 0;
+    let mut text_start = 0;
+    while let Some(offset) = source[position..].find('<') {
+        let start = position + offset;
+        let rest = &source[start..];
+        let end = if rest.starts_with("<!--") {
+            rest.find("-->")
+                .map_or(source.len(), |index| start + index + 3)
+        } else if rest.starts_with("<!") || rest.starts_with("<?") {
+            rest.find('>')
+                .map_or(source.len(), |index| start + index + 1)
+        } else if rest[1..].starts_with(|c: char| c.is_ascii_alphabetic() || c == '/') {
+            tag_end(source, start)
+        } else {
+            position = start + 1;
+            continue;
+        };
+        if text_start < start {
+            pieces.push(Piece::Text(&source[text_start..start]));
+        }
+        let markup = &source[start..end];
+        position = end;
+        text_start = end;
+        if markup.starts_with("<!--") {
+            if is_conditional_comment(markup) {
+                pieces.push(Piece::Verbatim(markup));
+            }
+            continue;
+        }
+        if markup.starts_with("<!") || markup.starts_with("<?") {
+            pieces.push(Piece::Verbatim(markup));
+            continue;
+        }
+        let (tag, name) = minify_tag(markup);
+        let is_raw_text = !markup.starts_with("</") && RAW_TEXT_ELEMENTS.contains(&name.as_str());
+        pieces.push(Piece::Tag(tag, name.clone()));
+        if is_raw_text {
+            let closing = format!("</{name}");
+            let content_end = source[end..]
+                .to_ascii_lowercase()
+                .find(&closing)
+                .map_or(source.len(), |index| end + index);
+            if end < content_end {
+                pieces.push(Piece::Verbatim(&source[end..content_end]));
+            }
+            position = content_end;
+            text_start = content_end;
+        }
+    }
+    if text_start < source.len() {
+        pieces.push(Piece::Text(&source[text_start..]));
+    }
+    pieces
+}
+
+/// [`is_conditional_comment`] returns `true` for the comments that old
+/// versions of internet explorer read, like `<!--[if IE]>...<![endif]-->`.
+fn is_conditional_comment(comment: &str) -> bool {
+    let content = &comment[4..];
+    content.starts_with("[if")
+        || content.starts_with("<![endif]")
+        || content.ends_with("<![endif]-->")
+}
+
+/// [`tag_end`] returns the index just after the `>` that ends the tag
+/// starting at `start`, skipping any `>` within quoted attribute values.
+pub(crate) fn tag_end(source: &str, start: usize) -> usize {
+    let mut quote = None;
+    for (index, character) in source[start..].char_indices() {
+        match (quote, character) {
+            (None, '"' | '\'') => quote = Some(character),
+            (Some(open), c) if c == open => quote = None,
+            (None, '>') => return start + index + 1,
+            _ => {}
+        }
+    }
+    source.len()
+}
+
+/// [`Tag`] is a start or end tag, split into its name and attributes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Tag<'a> {
+    pub is_closing: bool,
+    pub name: &'a str,
+    /// every attribute name, and its value as written (including quotes).
+    pub attributes: Vec<(&'a str, Option<&'a str>)>,
+    pub is_self_closing: bool,
+}
+
+impl<'a> Tag<'a> {
+    /// [`Tag::parse`] splits `tag`, like `<a href="/">`, into its parts.
+    pub fn parse(tag: &'a str) -> Tag<'a> {
+        let inner = tag.strip_prefix('<').unwrap_or(tag);
+        let inner = inner.strip_suffix('>').unwrap_or(inner);
+        let (is_closing, inner) = match inner.strip_prefix('/') {
+            Some(inner) => (true, inner),
+            None => (false, inner),
+        };
+        let name_end = inner
+            .find(|c: char| c.is_ascii_whitespace() || c == '/')
+            .unwrap_or(inner.len());
+        let mut parsed = Tag {
+            is_closing,
+            name: &inner[..name_end],
+            attributes: vec![],
+            is_self_closing: false,
+        };
+        let mut rest = inner[name_end..].trim_start();
+        while !rest.is_empty() {
+            if rest.trim_end() == "/" {
+                parsed.is_self_closing = true;
+                break;
+            }
+            let attribute_end = rest
+                .find(|c: char| c.is_ascii_whitespace() || c == '=')
+                .unwrap_or(rest.len());
+            let attribute = &rest[..attribute_end];
+            rest = rest[attribute_end..].trim_start();
+            let Some(value) = rest.strip_prefix('=') else {
+                parsed.attributes.push((attribute, None));
+                continue;
+            };
+            let value = value.trim_start();
+            let value_end = match value.chars().next() {
+                Some(quote @ ('"' | '\'')) => value[1..]
+                    .find(quote)
+                    .map_or(value.len(), |index| index + 2),
+                _ => value
+                    .find(|c: char| c.is_ascii_whitespace())
+                    .unwrap_or(value.len()),
+            };
+            parsed
+                .attributes
+                .push((attribute, Some(&value[..value_end])));
+            rest = value[value_end..].trim_start();
+        }
+        parsed
+    }
+
+    /// [`Tag::to_html`] writes the tag back out, with a single space before
+    /// each attribute and no other whitespace.
+    pub fn to_html(&self) -> String {
+        let mut html = String::from("<");
+        if self.is_closing {
+            html.push('/');
+        }
+        html.push_str(self.name);
+        for (attribute, value) in &self.attributes {
+            html.push(' ');
+            html.push_str(attribute);
+            if let Some(value) = value {
+                html.push('=');
+                html.push_str(value);
+            }
+        }
+        if self.is_self_closing {
+            html.push('/');
+        }
+        html.push('>');
+        html
+    }
+}
+
+/// [`minify_tag`] removes extra whitespace from `tag`, and from within its
+/// `class` attribute, returning it along with its lowercase element name.
+fn minify_tag(tag: &str) -> (String, String) {
+    let parsed = Tag::parse(tag);
+    let classes = parsed
+        .attributes
+        .iter()
+        .map(|(attribute, value)| match value {
+            Some(value) if attribute.eq_ignore_ascii_case("class") => Some(collapse_class(value)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let mut minified = parsed.clone();
+    for ((_, value), class) in minified.attributes.iter_mut().zip(&classes) {
+        if let Some(class) = class {
+            *value = Some(class);
+        }
+    }
+    (minified.to_html(), parsed.name.to_ascii_lowercase())
+}
+
+/// [`collapse_class`] collapses the whitespace within a `class` value, like
+/// `" a  b "`, keeping its quotes.
+fn collapse_class(value: &str) -> String {
+    let quote = value.chars().next().filter(|c| matches!(c, '"' | '\''));
+    let Some(quote) = quote.filter(|quote| value.len() > 1 && value.ends_with(*quote)) else {
+        return value.to_string();
+    };
+    let classes = value[1..value.len() - 1]
+        .split_ascii_whitespace()
+        .collect::<Vec<_>>();
+    format!("{quote}{}{quote}", classes.join(" "))
+}
+
+/// [`collapse_whitespace`] replaces every run of whitespace in `text` with
+/// a single space.
+pub(crate) fn collapse_whitespace(text: &str) -> String {
+    let mut collapsed = String::with_capacity(text.len());
+    let mut in_whitespace = false;
+    for character in text.chars() {
+        if character.is_ascii_whitespace() {
+            if !in_whitespace {
+                collapsed.push(' ');
+            }
+            in_whitespace = true;
+        } else {
+            collapsed.push(character);
+            in_whitespace = false;
+        }
+    }
+    collapsed
+}
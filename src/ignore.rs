@@ -0,0 +1,244 @@
+//! ignore files, like `.rcssignore` and `.gitignore`, whose gitignore
+//! patterns skip files and directories while walking, like `node_modules/`
+//! or `*.bak`.
+use std::{env, fs, io, path::Path, sync::Arc};
+
+use crate::glob::Glob;
+
+/// [`IGNORE_FILE_NAME`] is the name of the ignore file that every walk
+/// honors, in the directory being walked, any directory within it, and the
+/// directories above it within the project.
+pub const IGNORE_FILE_NAME: &str = ".rcssignore";
+
+/// [`GITIGNORE_FILE_NAME`] is the name of the ignore file that is honored
+/// like [`IGNORE_FILE_NAME`] when [`crate::Options::gitignore`] is set.
+pub const GITIGNORE_FILE_NAME: &str = ".gitignore";
+
+/// [`Pattern`] is one line of an ignore file.
+#[derive(Clone, Debug)]
+struct Pattern {
+    glob: Glob,
+    /// a `!` pattern, which includes what an earlier pattern ignored.
+    is_negated: bool,
+    /// a pattern ending in `/`, which only matches directories.
+    is_directory_only: bool,
+}
+
+/// [`IgnoreFile`] is the patterns of an ignore file, and where it is
+/// relative to the directory being walked.
+#[derive(Clone, Debug)]
+pub struct IgnoreFile {
+    patterns: Vec<Pattern>,
+    /// the directory of the ignore file relative to the directory being
+    /// walked, like `components/`, if it is within it.
+    within: String,
+    /// the directory being walked relative to the directory of the ignore
+    /// file, like `assets/css/`, if it is above it.
+    above: String,
+}
+
+impl IgnoreFile {
+    /// [`IgnoreFile::parse`] reads the gitignore patterns of `content`,
+    /// for an ignore file within the directory `within` of the directory
+    /// being walked (or `""` for the directory itself).
+    ///
+    /// # example
+    /// [`IgnoreFile::parse`] can be used to skip dependencies and backups,
+    /// except for one:
+    /// ```rust
+    /// use rcss::ignore::IgnoreFile;
+    ///
+    /// let ignore = IgnoreFile::parse("# dependencies\nnode_modules/\n*.bak\n!keep.bak\n/build", "");
+    /// assert_eq!(ignore.matches("pages/node_modules", true), Some(true));
+    /// assert_eq!(ignore.matches("pages/node_modules", false), None);
+    /// assert_eq!(ignore.matches("theme.css.bak", false), Some(true));
+    /// assert_eq!(ignore.matches("a/keep.bak", false), Some(false));
+    /// assert_eq!(ignore.matches("build", true), Some(true));
+    /// assert_eq!(ignore.matches("pages/build", true), None);
+    /// ```
+    pub fn parse(content: &str, within: &str) -> IgnoreFile {
+        let patterns = content
+            .lines()
+            .filter_map(|line| {
+                let line = line.trim_end();
+                if line.is_empty() || line.starts_with('#') {
+                    return None;
+                }
+                let (is_negated, line) = match line.strip_prefix('!') {
+                    Some(line) => (true, line),
+                    None => (false, line.strip_prefix('\\').unwrap_or(line)),
+                };
+                let (is_directory_only, line) = match line.strip_suffix('/') {
+                    Some(line) => (true, line),
+                    None => (false, line),
+                };
+                // a pattern with a slash is relative to the ignore file, and
+                // any other can match at any depth below it
+                let glob = match line.contains('/') {
+                    true => Glob::new(line.trim_start_matches('/')),
+                    false => Glob::new(&format!("**/{line}")),
+                };
+                Some(Pattern {
+                    glob,
+                    is_negated,
+                    is_directory_only,
+                })
+            })
+            .collect();
+        IgnoreFile {
+            patterns,
+            within: directory_prefix(within),
+            above: String::new(),
+        }
+    }
+
+    /// [`IgnoreFile::read`] reads the ignore file at `path` like
+    /// [`IgnoreFile::parse`], or returns `None` if it doesn't exist.
+    pub fn read(path: impl AsRef<Path>, within: &str) -> io::Result<Option<IgnoreFile>> {
+        match fs::read_to_string(path) {
+            Ok(content) => Ok(Some(IgnoreFile::parse(&content, within))),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// [`IgnoreFile::matches`] returns whether the last pattern that
+    /// matches `path`, relative to the directory being walked, ignores it
+    /// (`Some(true)`) or includes it again (`Some(false)`), or `None` if no
+    /// pattern does.
+    pub fn matches(&self, path: &str, is_directory: bool) -> Option<bool> {
+        let path = format!("{}{}", self.above, path.strip_prefix(&self.within)?);
+        self.patterns
+            .iter()
+            .rev()
+            .find(|pattern| {
+                (is_directory || !pattern.is_directory_only) && pattern.glob.matches(&path)
+            })
+            .map(|pattern| !pattern.is_negated)
+    }
+}
+
+/// [`directory_prefix`] returns `directory` ending with a `/`, or `""` for
+/// no directory at all.
+fn directory_prefix(directory: &str) -> String {
+    match directory.trim_end_matches('/') {
+        "" => String::new(),
+        directory => format!("{directory}/"),
+    }
+}
+
+/// [`Layer`] is an ignore file, and the ignore files of the directories
+/// around it.
+#[derive(Debug)]
+struct Layer {
+    file: IgnoreFile,
+    outer: Option<Arc<Layer>>,
+}
+
+/// [`Ignore`] is every ignore file that applies to a directory, which is
+/// cheap to clone for each of its subdirectories.
+#[derive(Clone, Debug, Default)]
+pub struct Ignore {
+    innermost: Option<Arc<Layer>>,
+}
+
+impl Ignore {
+    /// [`Ignore::with`] returns these ignore files, and then `file`, which
+    /// takes precedence over them.
+    pub fn with(&self, file: IgnoreFile) -> Ignore {
+        Ignore {
+            innermost: Some(Arc::new(Layer {
+                file,
+                outer: self.innermost.clone(),
+            })),
+        }
+    }
+
+    /// [`Ignore::is_ignored`] returns `true` if the innermost ignore file
+    /// with a pattern matching `path`, relative to the directory being
+    /// walked, ignores it.
+    ///
+    /// # example
+    /// [`Ignore::is_ignored`] can be used to include a file within a
+    /// directory again:
+    /// ```rust
+    /// use rcss::ignore::{Ignore, IgnoreFile};
+    ///
+    /// let ignore = Ignore::default()
+    ///     .with(IgnoreFile::parse("*.css", ""))
+    ///     .with(IgnoreFile::parse("!theme.css", "themes"));
+    /// assert!(ignore.is_ignored("button.css", false));
+    /// assert!(ignore.is_ignored("themes/dark.css", false));
+    /// assert!(!ignore.is_ignored("themes/theme.css", false));
+    /// ```
+    pub fn is_ignored(&self, path: &str, is_directory: bool) -> bool {
+        let mut layer = self.innermost.as_deref();
+        while let Some(Layer { file, outer }) = layer {
+            if let Some(is_ignored) = file.matches(path, is_directory) {
+                return is_ignored;
+            }
+            layer = outer.as_deref();
+        }
+        false
+    }
+
+    /// [`Ignore::read`] returns these ignore files, and then those within
+    /// `directory` named `names`, which is `within` the directory being
+    /// walked.
+    pub fn read(&self, directory: &Path, within: &str, names: &[&str]) -> io::Result<Ignore> {
+        let mut ignore = self.clone();
+        for name in names {
+            if let Some(file) = IgnoreFile::read(directory.join(name), within)? {
+                ignore = ignore.with(file);
+            }
+        }
+        Ok(ignore)
+    }
+
+    /// [`Ignore::above`] returns the ignore files named `names` within every
+    /// directory above `root` in its project, outermost first: up to the
+    /// root of its git repository, or else the current directory.
+    pub fn above(root: &Path, names: &[&str]) -> io::Result<Ignore> {
+        let root = fs::canonicalize(root)?;
+        let project = match root
+            .ancestors()
+            .find(|ancestor| ancestor.join(".git").exists())
+        {
+            Some(project) => Some(project.to_path_buf()),
+            None => env::current_dir()
+                .and_then(fs::canonicalize)
+                .ok()
+                .filter(|current| root.starts_with(current)),
+        };
+        let Some(project) = project else {
+            return Ok(Ignore::default());
+        };
+        let mut directories = root
+            .ancestors()
+            .skip(1)
+            .take_while(|ancestor| ancestor.starts_with(&project))
+            .collect::<Vec<_>>();
+        directories.reverse();
+        let mut ignore = Ignore::default();
+        for directory in directories {
+            let above = root.strip_prefix(directory).unwrap_or(&root);
+            let above = directory_prefix(&above.to_string_lossy().replace('\\', "/"));
+            for name in names {
+                if let Some(mut file) = IgnoreFile::read(directory.join(name), "")? {
+                    file.above = above.clone();
+                    ignore = ignore.with(file);
+                }
+            }
+        }
+        Ok(ignore)
+    }
+}
+
+/// [`ignore_file_names`] returns the names of the ignore files to honor,
+/// which include [`GITIGNORE_FILE_NAME`] if `gitignore` is set.
+pub fn ignore_file_names(gitignore: bool) -> &'static [&'static str] {
+    match gitignore {
+        true => &[GITIGNORE_FILE_NAME, IGNORE_FILE_NAME],
+        false => &[IGNORE_FILE_NAME],
+    }
+}
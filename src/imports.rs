@@ -0,0 +1,198 @@
+//! `@import` resolution, used to inline imported files in dependency
+//! order instead of concatenating files in directory-walk order.
+use crate::tokenizer::{tokenize, TokenKind};
+use std::{
+    collections::HashSet,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// [`Import`] is a single top-level `@import` statement.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Import {
+    /// the url being imported, without quotes or `url()`.
+    pub url: String,
+    /// any media query or other conditions after the url.
+    pub conditions: String,
+    /// the byte range of the whole statement, including the `;`.
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Import {
+    /// [`Import::is_remote`] returns `true` if the import points at
+    /// another host, and can't be inlined from disk.
+    pub fn is_remote(&self) -> bool {
+        let url = self.url.to_ascii_lowercase();
+        url.starts_with("http:") || url.starts_with("https:") || url.starts_with("//")
+    }
+
+    /// [`Import::is_inlinable`] returns `true` if the import is a local
+    /// file, and any conditions are a plain media query that the inlined
+    /// content can be wrapped in.
+    pub fn is_inlinable(&self) -> bool {
+        let conditions = self.conditions.to_ascii_lowercase();
+        !self.is_remote()
+            && !conditions.starts_with("layer")
+            && !conditions.starts_with("supports(")
+    }
+}
+
+/// [`find_imports`] will return every top-level `@import` statement
+/// in `content`, in the order they appear.
+///
+/// # example
+/// [`find_imports`] can be used to list the files a stylesheet imports:
+/// ```rust
+/// let imports = rcss::imports::find_imports(r#"@import "reset.css"; @import url(card.css) screen;"#);
+/// assert_eq!(imports[0].url, "reset.css");
+/// assert_eq!((imports[1].url.as_str(), imports[1].conditions.as_str()), ("card.css", "screen"));
+/// ```
+pub fn find_imports(content: &str) -> Vec<Import> {
+    let tokens = tokenize(content);
+    let mut imports = vec![];
+    let mut depth = 0;
+    let mut index = 0;
+    while index < tokens.len() {
+        let token = &tokens[index];
+        match token.kind {
+            TokenKind::OpenBrace => depth += 1,
+            TokenKind::CloseBrace => depth -= 1,
+            TokenKind::AtKeyword if depth == 0 && token.text.eq_ignore_ascii_case("@import") => {
+                let Some(semicolon) = tokens[index..]
+                    .iter()
+                    .position(|token| token.kind == TokenKind::Semicolon)
+                    .map(|position| index + position)
+                else {
+                    break;
+                };
+                let statement = tokens[index + 1..semicolon]
+                    .iter()
+                    .filter(|token| {
+                        !matches!(token.kind, TokenKind::Whitespace | TokenKind::Comment)
+                    })
+                    .collect::<Vec<_>>();
+                let (url, rest) = match statement.as_slice() {
+                    [url, rest @ ..] if url.kind == TokenKind::String => (unquote(url.text), rest),
+                    [url, rest @ ..] if url.kind == TokenKind::Url => (unwrap_url(url.text), rest),
+                    [function, url, close, rest @ ..]
+                        if function.text.eq_ignore_ascii_case("url(")
+                            && url.kind == TokenKind::String
+                            && close.kind == TokenKind::CloseParen =>
+                    {
+                        (unquote(url.text), rest)
+                    }
+                    _ => {
+                        index = semicolon + 1;
+                        continue;
+                    }
+                };
+                let conditions = match (rest.first(), rest.last()) {
+                    (Some(first), Some(last)) => {
+                        content[first.offset..last.offset + last.text.len()].to_string()
+                    }
+                    _ => String::new(),
+                };
+                imports.push(Import {
+                    url,
+                    conditions,
+                    start: token.offset,
+                    end: tokens[semicolon].offset + 1,
+                });
+                index = semicolon;
+            }
+            _ => {}
+        }
+        index += 1;
+    }
+    imports
+}
+
+/// [`inline_imports`] will push `file_path` onto `sources`, after first
+/// pushing every file it imports (recursively), so that each file comes
+/// after its dependencies. files in `visited` are skipped, which means
+/// every file is only added once and import cycles are broken.
+///
+/// # notes
+/// local `@import` statements are replaced with newlines, so line numbers
+/// in each source are unchanged. imports with a media query are wrapped in
+/// an `@media` block, and remote, `layer`, or `supports` imports are kept
+/// as-is.
+///
+/// # example
+/// [`inline_imports`] can be used to order `main.css` and everything it
+/// imports:
+/// ```rust,no_run
+/// let mut sources = vec![];
+/// rcss::imports::inline_imports(
+///     std::path::Path::new("./assets/css/main.css"),
+///     &mut std::collections::HashSet::new(),
+///     &mut sources,
+/// )
+/// .unwrap();
+/// ```
+pub fn inline_imports(
+    file_path: &Path,
+    visited: &mut HashSet<PathBuf>,
+    sources: &mut Vec<(PathBuf, String)>,
+) -> io::Result<()> {
+    if !visited.insert(fs::canonicalize(file_path)?) {
+        return Ok(());
+    }
+    let content = fs::read_to_string(file_path)?;
+    // a byte order mark would end up in the middle of the combined output
+    let content = content
+        .strip_prefix('\u{feff}')
+        .map(str::to_string)
+        .unwrap_or(content);
+    let directory = file_path.parent().unwrap_or(Path::new(""));
+    let imports = find_imports(&content)
+        .into_iter()
+        .filter(Import::is_inlinable)
+        .collect::<Vec<_>>();
+    let mut inlined_content = content.clone();
+    for import in imports.iter().rev() {
+        let newlines = content[import.start..import.end].matches('\n').count();
+        inlined_content.replace_range(import.start..import.end, &"\n".repeat(newlines));
+    }
+    for import in imports {
+        let import_path = directory.join(&import.url);
+        if !import_path.is_file() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!(
+                    "could not resolve @import \"{}\" in {}",
+                    import.url,
+                    file_path.display()
+                ),
+            ));
+        }
+        if import.conditions.is_empty() {
+            inline_imports(&import_path, visited, sources)?;
+            continue;
+        }
+        let mut conditional_sources = vec![];
+        inline_imports(&import_path, visited, &mut conditional_sources)?;
+        for (path, conditional_content) in conditional_sources {
+            let wrapped = format!("@media {}{{{}}}", import.conditions, conditional_content);
+            sources.push((path, wrapped));
+        }
+    }
+    sources.push((file_path.to_path_buf(), inlined_content));
+    Ok(())
+}
+
+/// [`unquote`] removes the quotes around a string token.
+fn unquote(text: &str) -> String {
+    let quote = &text[..1];
+    text[1..]
+        .strip_suffix(quote)
+        .unwrap_or(&text[1..])
+        .to_string()
+}
+
+/// [`unwrap_url`] removes the `url(` and `)` around a url token.
+fn unwrap_url(text: &str) -> String {
+    let url = &text[4..];
+    url.strip_suffix(')').unwrap_or(url).trim().to_string()
+}
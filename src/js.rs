@@ -0,0 +1,379 @@
+//! the javascript rule set, which strips comments and collapses
+//! whitespace without touching strings, template literals, or regular
+//! expression literals, or changing where semicolons are inserted.
+
+/// [`EXTENSIONS`] are the file extensions that are minified as javascript.
+pub const EXTENSIONS: &[&str] = &["js", "mjs", "cjs"];
+
+/// [`REGEX_KEYWORDS`] are the keywords that can be followed by a regular
+/// expression literal, rather than a division.
+const REGEX_KEYWORDS: &[&str] = &[
+    "return",
+    "typeof",
+    "instanceof",
+    "in",
+    "of",
+    "new",
+    "delete",
+    "void",
+    "throw",
+    "case",
+    "do",
+    "else",
+    "yield",
+    "await",
+];
+
+/// [`Kind`] is the type of a javascript [`Token`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Kind {
+    /// an identifier or keyword, like `return` or `$el`.
+    Word,
+    /// a number, like `1.5e-3` or `0xff`.
+    Number,
+    /// a string, template literal, or regular expression literal, which
+    /// are kept exactly as written.
+    Literal,
+    /// a punctuator, like `(` or `++`.
+    Punctuator,
+}
+
+/// [`Token`] is a piece of javascript, along with the whitespace and
+/// comments that came before it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Token<'a> {
+    kind: Kind,
+    text: &'a str,
+    /// whitespace or a comment came before this token.
+    has_space_before: bool,
+    /// a line break (including within a comment) came before this token.
+    has_newline_before: bool,
+}
+
+/// [`is_extension`] returns `true` if files with `extension` are minified
+/// as javascript.
+///
+/// # example
+/// [`is_extension`] can be used to pick a rule set for a file:
+/// ```rust
+/// assert!(rcss::js::is_extension("mjs"));
+/// assert!(!rcss::js::is_extension("css"));
+/// ```
+pub fn is_extension(extension: &str) -> bool {
+    EXTENSIONS
+        .iter()
+        .any(|known| known.eq_ignore_ascii_case(extension.trim_start_matches('.')))
+}
+
+/// [`minify`] will minify the javascript in `source`. it removes comments,
+/// and removes whitespace except where it separates two words, or where a
+/// line break could end a statement.
+///
+/// # example
+/// [`minify`] can be used to minify a small function:
+/// ```rust
+/// let minified = rcss::js::minify("function add ( a, b ) {\n  // add them\n  return a + b;\n}");
+/// assert_eq!(minified, "function add(a,b){return a+b;}");
+/// ```
+pub fn minify(source: &str) -> String {
+    let (hashbang, source) = split_hashbang(source);
+    let mut minified = hashbang.to_string();
+    let mut previous: Option<Token> = None;
+    for token in tokenize(source) {
+        if let Some(previous) = &previous {
+            if token.has_newline_before && !is_continuation(previous, &token) {
+                minified.push('\n');
+            } else if token.has_space_before && needs_space(previous, &token) {
+                minified.push(' ');
+            }
+        }
+        minified.push_str(token.text);
+        previous = Some(token);
+    }
+    minified
+}
+
+/// [`license_comments`] returns every `/*!` comment in `source`, which
+/// usually hold a license that must be kept.
+///
+/// # example
+/// [`license_comments`] can be used to find the license of a library:
+/// ```rust
+/// let comments = rcss::js::license_comments("/*! lib v1 | MIT */\nvar a = '/*! not me */';");
+/// assert_eq!(comments, vec!["/*! lib v1 | MIT */"]);
+/// ```
+pub fn license_comments(source: &str) -> Vec<&str> {
+    let mut comments = vec![];
+    let mut lexer = Lexer::new(split_hashbang(source).1);
+    while let Some(token) = lexer.next_token(&mut |comment| {
+        if comment.starts_with("/*!") {
+            comments.push(comment);
+        }
+    }) {
+        lexer.previous = Some((token.kind, token.text));
+    }
+    comments
+}
+
+/// [`split_hashbang`] splits a leading `#!` line, which must be kept as-is,
+/// from the rest of `source`.
+fn split_hashbang(source: &str) -> (&str, &str) {
+    if !source.starts_with("#!") {
+        return ("", source);
+    }
+    let end = source.find('\n').map_or(source.len(), |index| index + 1);
+    source.split_at(end)
+}
+
+/// [`needs_space`] returns `true` if `previous` and `next` would run
+/// together, or form a different token, without a space between them.
+fn needs_space(previous: &Token, next: &Token) -> bool {
+    let (Some(last), Some(first)) = (previous.text.chars().last(), next.text.chars().next()) else {
+        return false;
+    };
+    (is_word_character(last) && is_word_character(first))
+        || (last == first && matches!(last, '+' | '-' | '/'))
+        || (previous.kind == Kind::Number && first == '.')
+}
+
+/// [`is_continuation`] returns `true` if a line break between `previous`
+/// and `next` can be removed, because a semicolon could never be inserted
+/// there.
+fn is_continuation(previous: &Token, next: &Token) -> bool {
+    let continues_previous = previous.kind == Kind::Punctuator
+        && !matches!(previous.text, ")" | "]" | "}" | "++" | "--");
+    let continues_next = next.kind == Kind::Punctuator
+        && !matches!(
+            next.text,
+            "(" | "[" | "{" | "+" | "-" | "/" | "!" | "~" | "++" | "--"
+        );
+    continues_previous || continues_next
+}
+
+fn is_word_character(character: char) -> bool {
+    character.is_alphanumeric() || matches!(character, '_' | '$' | '\\') || !character.is_ascii()
+}
+
+/// [`tokenize`] splits `source` into [`Token`]s, dropping whitespace and
+/// comments.
+fn tokenize(source: &str) -> Vec<Token<'_>> {
+    let mut lexer = Lexer::new(source);
+    let mut tokens = vec![];
+    while let Some(token) = lexer.next_token(&mut |_| {}) {
+        lexer.previous = Some((token.kind, token.text));
+        tokens.push(token);
+    }
+    tokens
+}
+
+/// [`Lexer`] reads [`Token`]s from javascript source.
+struct Lexer<'a> {
+    source: &'a str,
+    position: usize,
+    /// the kind and text of the last token, which decides whether a `/`
+    /// starts a regular expression literal or is a division.
+    previous: Option<(Kind, &'a str)>,
+}
+
+impl<'a> Lexer<'a> {
+    fn new(source: &'a str) -> Lexer<'a> {
+        Lexer {
+            source,
+            position: 0,
+            previous: None,
+        }
+    }
+
+    fn peek(&self, offset: usize) -> Option<char> {
+        self.source[self.position..].chars().nth(offset)
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let character = self.peek(0)?;
+        self.position += character.len_utf8();
+        Some(character)
+    }
+
+    /// [`Lexer::next_token`] reads the next token, calling `on_comment` with
+    /// every comment that is skipped before it.
+    fn next_token(&mut self, on_comment: &mut impl FnMut(&'a str)) -> Option<Token<'a>> {
+        let mut has_space_before = false;
+        let mut has_newline_before = false;
+        loop {
+            match (self.peek(0)?, self.peek(1)) {
+                (character, _) if character.is_whitespace() => {
+                    has_newline_before |=
+                        matches!(character, '\n' | '\r' | '\u{2028}' | '\u{2029}');
+                    self.bump();
+                }
+                ('/', Some('/')) => {
+                    let start = self.position;
+                    while self.peek(0).is_some_and(|c| !matches!(c, '\n' | '\r')) {
+                        self.bump();
+                    }
+                    on_comment(&self.source[start..self.position]);
+                }
+                ('/', Some('*')) => {
+                    let start = self.position;
+                    let end = self.source[start + 2..]
+                        .find("*/")
+                        .map_or(self.source.len(), |index| start + 2 + index + 2);
+                    self.position = end;
+                    let comment = &self.source[start..end];
+                    has_newline_before |= comment.contains(['\n', '\r']);
+                    on_comment(comment);
+                }
+                _ => break,
+            }
+            has_space_before = true;
+        }
+
+        let start = self.position;
+        let character = self.bump()?;
+        let kind = match character {
+            '"' | '\'' => {
+                self.skip_string(character);
+                Kind::Literal
+            }
+            '`' => {
+                self.skip_template();
+                Kind::Literal
+            }
+            '/' if self.allows_regex() => {
+                self.skip_regex();
+                Kind::Literal
+            }
+            '0'..='9' => {
+                self.skip_number(start);
+                Kind::Number
+            }
+            '.' if self.peek(0).is_some_and(|c| c.is_ascii_digit()) => {
+                self.skip_number(start);
+                Kind::Number
+            }
+            c if is_word_character(c) => {
+                if c == '\\' {
+                    self.bump();
+                }
+                while let Some(c) = self.peek(0).filter(|c| is_word_character(*c)) {
+                    self.bump();
+                    if c == '\\' {
+                        self.bump();
+                    }
+                }
+                Kind::Word
+            }
+            '+' | '-' if self.peek(0) == Some(character) => {
+                self.bump();
+                Kind::Punctuator
+            }
+            _ => Kind::Punctuator,
+        };
+        Some(Token {
+            kind,
+            text: &self.source[start..self.position],
+            has_space_before,
+            has_newline_before,
+        })
+    }
+
+    /// [`Lexer::allows_regex`] returns `true` if a `/` after the previous
+    /// token starts a regular expression literal.
+    fn allows_regex(&self) -> bool {
+        match self.previous {
+            None => true,
+            Some((Kind::Word, word)) => REGEX_KEYWORDS.contains(&word),
+            Some((Kind::Number | Kind::Literal, _)) => false,
+            Some((Kind::Punctuator, punctuator)) => {
+                !matches!(punctuator, ")" | "]" | "}" | "++" | "--")
+            }
+        }
+    }
+
+    fn skip_string(&mut self, quote: char) {
+        while let Some(character) = self.bump() {
+            match character {
+                '\\' => {
+                    self.bump();
+                }
+                '\n' => return,
+                c if c == quote => return,
+                _ => {}
+            }
+        }
+    }
+
+    /// [`Lexer::skip_template`] skips a template literal, including any
+    /// `${}` substitutions, which may hold strings or templates of their own.
+    fn skip_template(&mut self) {
+        while let Some(character) = self.bump() {
+            match character {
+                '\\' => {
+                    self.bump();
+                }
+                '`' => return,
+                '$' if self.peek(0) == Some('{') => {
+                    self.bump();
+                    self.skip_substitution();
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn skip_substitution(&mut self) {
+        let mut depth = 1;
+        while let Some(character) = self.bump() {
+            match character {
+                '"' | '\'' => self.skip_string(character),
+                '`' => self.skip_template(),
+                '{' => depth += 1,
+                '}' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn skip_regex(&mut self) {
+        let mut in_class = false;
+        while let Some(character) = self.bump() {
+            match character {
+                '\\' => {
+                    self.bump();
+                }
+                '[' => in_class = true,
+                ']' => in_class = false,
+                '/' if !in_class => break,
+                '\n' => return,
+                _ => {}
+            }
+        }
+        while self.peek(0).is_some_and(is_word_character) {
+            self.bump();
+        }
+    }
+
+    /// [`Lexer::skip_number`] skips the rest of the number that began at
+    /// `start`, including the sign of an exponent like `1e-3`.
+    fn skip_number(&mut self, start: usize) {
+        while let Some(character) = self.peek(0) {
+            let number = &self.source[start..self.position];
+            let is_exponent_sign = matches!(character, '+' | '-')
+                && number.ends_with(['e', 'E'])
+                && !number.contains(['x', 'X']);
+            if character.is_ascii_alphanumeric()
+                || matches!(character, '.' | '_')
+                || is_exponent_sign
+            {
+                self.bump();
+            } else {
+                break;
+            }
+        }
+    }
+}
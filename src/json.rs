@@ -0,0 +1,301 @@
+//! the small amount of json that rcss reads and writes, for source
+//! maps and manifests, and the json rule set.
+use std::{collections::BTreeMap, iter::Peekable, str::Chars};
+
+/// [`quote`] quotes `value` as a json string.
+///
+/// # example
+/// [`quote`] can be used to write a file name into json:
+/// ```rust
+/// assert_eq!(rcss::json::quote(r#"a "b".css"#), r#""a \"b\".css""#);
+/// ```
+pub fn quote(value: &str) -> String {
+    let mut escaped = String::from("\"");
+    for character in value.chars() {
+        match character {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+/// [`write_string_object`] writes `entries` as a json object, with one
+/// entry per line.
+///
+/// # example
+/// [`write_string_object`] can be used to write a manifest:
+/// ```rust
+/// let entries = std::collections::BTreeMap::from([("a".to_string(), "b".to_string())]);
+/// assert_eq!(rcss::json::write_string_object(&entries), "{\n  \"a\": \"b\"\n}\n");
+/// ```
+pub fn write_string_object(entries: &BTreeMap<String, String>) -> String {
+    let entries = entries
+        .iter()
+        .map(|(key, value)| format!("  {}: {}", quote(key), quote(value)))
+        .collect::<Vec<_>>();
+    if entries.is_empty() {
+        return "{}\n".to_string();
+    }
+    format!("{{\n{}\n}}\n", entries.join(",\n"))
+}
+
+/// [`parse_string_object`] parses a json object whose values are all
+/// strings, like the ones written by [`write_string_object`]. it returns
+/// `None` for any other json.
+///
+/// # example
+/// [`parse_string_object`] can be used to read a manifest:
+/// ```rust
+/// let entries = rcss::json::parse_string_object(r#"{"style.css": "style.0a1b2c3d.css"}"#).unwrap();
+/// assert_eq!(entries["style.css"], "style.0a1b2c3d.css");
+/// ```
+pub fn parse_string_object(content: &str) -> Option<BTreeMap<String, String>> {
+    let mut characters = content.chars().peekable();
+    let mut entries = BTreeMap::new();
+    expect(&mut characters, '{')?;
+    if skip_whitespace(&mut characters) == Some('}') {
+        characters.next();
+    } else {
+        loop {
+            let key = parse_string(&mut characters)?;
+            expect(&mut characters, ':')?;
+            let value = parse_string(&mut characters)?;
+            entries.insert(key, value);
+            match skip_whitespace(&mut characters) {
+                Some(',') => characters.next(),
+                Some('}') => {
+                    characters.next();
+                    break;
+                }
+                _ => return None,
+            };
+        }
+    }
+    skip_whitespace(&mut characters)
+        .is_none()
+        .then_some(entries)
+}
+
+/// [`skip_whitespace`] skips whitespace, and peeks at the next character.
+fn skip_whitespace(characters: &mut Peekable<Chars>) -> Option<char> {
+    while characters.next_if(|c| c.is_whitespace()).is_some() {}
+    characters.peek().copied()
+}
+
+/// [`expect`] skips whitespace, and then consumes `expected`.
+fn expect(characters: &mut Peekable<Chars>, expected: char) -> Option<()> {
+    skip_whitespace(characters);
+    characters.next_if_eq(&expected).map(|_| ())
+}
+
+/// [`parse_string`] skips whitespace, and then parses a json string.
+fn parse_string(characters: &mut Peekable<Chars>) -> Option<String> {
+    expect(characters, '"')?;
+    let mut value = String::new();
+    loop {
+        match characters.next()? {
+            '"' => return Some(value),
+            '\\' => value.push(match characters.next()? {
+                'n' => '\n',
+                'r' => '\r',
+                't' => '\t',
+                'b' => '\u{8}',
+                'f' => '\u{c}',
+                'u' => {
+                    let hex = (0..4)
+                        .map(|_| characters.next())
+                        .collect::<Option<String>>()?;
+                    char::from_u32(u32::from_str_radix(&hex, 16).ok()?)?
+                }
+                escaped => escaped,
+            }),
+            character => value.push(character),
+        }
+    }
+}
+
+/// [`EXTENSIONS`] are the file extensions that are minified as json.
+pub const EXTENSIONS: &[&str] = &["json", "webmanifest"];
+
+/// [`is_extension`] returns `true` if files with `extension` are minified
+/// as json.
+///
+/// # example
+/// [`is_extension`] can be used to pick a rule set for a file:
+/// ```rust
+/// assert!(rcss::json::is_extension("webmanifest"));
+/// assert!(!rcss::json::is_extension("css"));
+/// ```
+pub fn is_extension(extension: &str) -> bool {
+    EXTENSIONS
+        .iter()
+        .any(|known| known.eq_ignore_ascii_case(extension.trim_start_matches('.')))
+}
+
+/// [`minify`] will remove the whitespace from the json in `source`, along
+/// with every object entry whose key matches one of `remove_keys`. json that
+/// can't be parsed only has the whitespace outside of strings removed.
+///
+/// # example
+/// [`minify`] can be used to drop the `$schema` and `//` comment keys from a
+/// translation file:
+/// ```rust
+/// use rcss::glob::Glob;
+///
+/// let json = r#"{ "$schema": "./schema.json", "//": "greetings", "hello": [ "hi", "hey" ] }"#;
+/// let remove_keys = [Glob::new("$schema"), Glob::new("//*")];
+/// assert_eq!(rcss::json::minify(json, &remove_keys), r#"{"hello":["hi","hey"]}"#);
+/// ```
+pub fn minify(source: &str, remove_keys: &[crate::glob::Glob]) -> String {
+    let mut minifier = Minifier {
+        source,
+        position: 0,
+        remove_keys,
+    };
+    let mut minified = String::new();
+    match minifier.document(&mut minified) {
+        Some(()) => minified,
+        None => strip_whitespace(source),
+    }
+}
+
+/// [`strip_whitespace`] removes all whitespace outside of strings.
+fn strip_whitespace(source: &str) -> String {
+    let mut stripped = String::new();
+    let mut in_string = false;
+    let mut is_escaped = false;
+    for character in source.chars() {
+        match character {
+            _ if in_string && is_escaped => is_escaped = false,
+            '\\' if in_string => is_escaped = true,
+            '"' => in_string = !in_string,
+            c if c.is_whitespace() && !in_string => continue,
+            _ => {}
+        }
+        stripped.push(character);
+    }
+    stripped
+}
+
+/// [`Minifier`] copies json without its whitespace, or any entry whose key
+/// matches one of `remove_keys`.
+struct Minifier<'a> {
+    source: &'a str,
+    position: usize,
+    remove_keys: &'a [crate::glob::Glob],
+}
+
+impl Minifier<'_> {
+    fn peek(&mut self) -> Option<u8> {
+        let rest = &self.source[self.position..];
+        self.position += rest.len() - rest.trim_start().len();
+        self.source.as_bytes().get(self.position).copied()
+    }
+
+    fn expect(&mut self, expected: u8) -> Option<()> {
+        (self.peek()? == expected).then(|| self.position += 1)
+    }
+
+    fn document(&mut self, output: &mut String) -> Option<()> {
+        self.source = self.source.trim_start_matches('\u{feff}');
+        self.value(output)?;
+        self.peek().is_none().then_some(())
+    }
+
+    fn value(&mut self, output: &mut String) -> Option<()> {
+        match self.peek()? {
+            b'{' => self.object(output),
+            b'[' => self.array(output),
+            b'"' => {
+                output.push_str(self.string()?);
+                Some(())
+            }
+            _ => {
+                let rest = &self.source[self.position..];
+                let end = rest
+                    .find(|c: char| c.is_whitespace() || matches!(c, ',' | ']' | '}' | ':'))
+                    .unwrap_or(rest.len());
+                if end == 0 {
+                    return None;
+                }
+                output.push_str(&rest[..end]);
+                self.position += end;
+                Some(())
+            }
+        }
+    }
+
+    /// [`Minifier::string`] returns the next string, including its quotes.
+    fn string(&mut self) -> Option<&str> {
+        self.peek()?;
+        let start = self.position;
+        self.expect(b'"')?;
+        let mut is_escaped = false;
+        for (index, character) in self.source[self.position..].char_indices() {
+            match character {
+                _ if is_escaped => is_escaped = false,
+                '\\' => is_escaped = true,
+                '"' => {
+                    self.position += index + 1;
+                    return Some(&self.source[start..self.position]);
+                }
+                _ => {}
+            }
+        }
+        None
+    }
+
+    fn object(&mut self, output: &mut String) -> Option<()> {
+        self.expect(b'{')?;
+        output.push('{');
+        let mut is_first = true;
+        if self.peek()? != b'}' {
+            loop {
+                let key = self.string()?.to_string();
+                self.expect(b':')?;
+                let name = parse_string(&mut key.chars().peekable())?;
+                if self.remove_keys.iter().any(|glob| glob.matches(&name)) {
+                    self.value(&mut String::new())?;
+                } else {
+                    if !is_first {
+                        output.push(',');
+                    }
+                    is_first = false;
+                    output.push_str(&key);
+                    output.push(':');
+                    self.value(output)?;
+                }
+                if self.expect(b',').is_none() {
+                    break;
+                }
+            }
+        }
+        self.expect(b'}')?;
+        output.push('}');
+        Some(())
+    }
+
+    fn array(&mut self, output: &mut String) -> Option<()> {
+        self.expect(b'[')?;
+        output.push('[');
+        if self.peek()? != b']' {
+            loop {
+                self.value(output)?;
+                if self.expect(b',').is_none() {
+                    break;
+                }
+                output.push(',');
+            }
+        }
+        self.expect(b']')?;
+        output.push(']');
+        Some(())
+    }
+}
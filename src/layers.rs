@@ -0,0 +1,74 @@
+//! cascade layers for combined files, so each file's rules can be wrapped
+//! in an `@layer` named after its path, and the order of the combined
+//! output is spelled out in the cascade rather than left to concatenation.
+
+/// [`layer_name`] returns the name of the layer for the css file at the
+/// relative `path`: its path without the extension, with every separator
+/// and every character that can't be in an identifier replaced with `-`.
+///
+/// # example
+/// [`layer_name`] can be used to name the layer of a component:
+/// ```rust
+/// use rcss::layers::layer_name;
+///
+/// assert_eq!(layer_name("components/button.css"), "components-button");
+/// assert_eq!(layer_name("base/reset.min.css"), "base-reset-min");
+/// assert_eq!(layer_name("01 vendor/grid.css"), "_01-vendor-grid");
+/// ```
+pub fn layer_name(path: &str) -> String {
+    let path = path.replace('\\', "/");
+    let path = path.trim_start_matches("./");
+    let path = match path.rsplit_once('.') {
+        Some((stem, _)) if !stem.ends_with('/') && !stem.is_empty() => stem,
+        _ => path,
+    };
+    let mut name = String::new();
+    for character in path.chars() {
+        match character.is_alphanumeric() || character == '_' || character == '-' {
+            true => name.push(character),
+            // collapse `a / b` into `a-b` rather than `a---b`
+            false if !name.ends_with('-') => name.push('-'),
+            false => {}
+        }
+    }
+    let name = name.trim_matches('-');
+    // an identifier can't start with a digit
+    match name.starts_with(|character: char| character.is_ascii_digit()) || name.is_empty() {
+        true => format!("_{name}"),
+        false => name.to_string(),
+    }
+}
+
+/// [`wrap`] returns what goes before and after css to put it in the layer
+/// called `name`, like `@layer components-button{` and `}`.
+pub fn wrap(name: &str) -> (String, &'static str) {
+    (format!("@layer {name}{{"), "}")
+}
+
+/// [`is_valid_name`] returns `true` if `name` can be used as the name of a
+/// layer as it is: identifiers joined by dots, like `themes.acme`.
+///
+/// # example
+/// [`is_valid_name`] can be used to check a layer name before wrapping css
+/// in it:
+/// ```rust
+/// use rcss::layers::is_valid_name;
+///
+/// assert!(is_valid_name("tenant-acme"));
+/// assert!(is_valid_name("themes.acme"));
+/// assert!(!is_valid_name("01-theme"));
+/// assert!(!is_valid_name("theme{"));
+/// assert!(!is_valid_name("themes..acme"));
+/// ```
+pub fn is_valid_name(name: &str) -> bool {
+    let is_identifier =
+        |character: char| character.is_alphanumeric() || character == '_' || character == '-';
+    name.split('.').all(|part| {
+        // an identifier can't start with a digit, even after a `-`
+        let start = part.strip_prefix('-').unwrap_or(part);
+        part.chars().all(is_identifier)
+            && start.starts_with(|character: char| {
+                character == '_' || character == '-' || character.is_alphabetic()
+            })
+    })
+}
@@ -0,0 +1,63 @@
+//! optimization levels, which trade how safe the combined output is for
+//! how small it is.
+use std::fmt;
+
+use crate::{
+    config::{Config, Value},
+    error::{RcssError, Result},
+};
+
+/// [`Level`] is how much combined files are optimized, from `-O0` to `-O2`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    /// `-O0`: files are only concatenated, exactly as they are.
+    Concatenate,
+    /// `-O1`: whitespace and comments are removed from every file.
+    #[default]
+    Minify,
+    /// `-O2`: like [`Level::Minify`], and the passes that restructure the
+    /// output without any other input are turned on: merging duplicates,
+    /// selectors, and media queries, collapsing shorthands, and removing
+    /// empty rules.
+    Restructure,
+}
+
+impl Level {
+    /// [`Level::parse`] reads a level from its number, like `"2"`.
+    pub fn parse(level: &str) -> Option<Level> {
+        match level.trim() {
+            "0" => Some(Level::Concatenate),
+            "1" => Some(Level::Minify),
+            "2" => Some(Level::Restructure),
+            _ => None,
+        }
+    }
+
+    /// [`Level::from_config`] reads the `level` key from the root table of
+    /// `config`, like `level = 2`, using [`Level::Minify`] if it isn't set.
+    pub fn from_config(config: &Config) -> Result<Level> {
+        let level = match config.get("", "level") {
+            Some(Value::Integer(level)) => Level::parse(&level.to_string()),
+            Some(value) => value.as_str().and_then(Level::parse),
+            None => return Ok(Level::default()),
+        };
+        level.ok_or_else(|| RcssError::Config("level must be 0, 1, or 2".to_string()))
+    }
+
+    /// [`Level::restructures`] returns `true` if this level turns the
+    /// structural passes on.
+    pub fn restructures(&self) -> bool {
+        *self == Level::Restructure
+    }
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let level = match self {
+            Level::Concatenate => 0,
+            Level::Minify => 1,
+            Level::Restructure => 2,
+        };
+        write!(f, "-O{level}")
+    }
+}
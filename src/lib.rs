@@ -0,0 +1,1959 @@
+//! rcss is a small minifier that combines and minifies every css file
+//! within a directory into a single destination file.
+//!
+//! the binary is a thin wrapper over this library, so the same minification
+//! can be driven from build scripts or other rust projects.
+//!
+//! minifying a string never touches the filesystem, so with the `wasm`
+//! feature, `rcss::wasm::minify` can run in a browser or node as well.
+//!
+//! minifying never panics, whatever the input: css that can't be parsed is
+//! kept as it is, and only [`minifier::Minifier::strict`] turns it into an
+//! error. the `cargo fuzz` targets in `fuzz/` check this, with
+//! `cargo +nightly fuzz run minify` or `cargo +nightly fuzz run tokenize`.
+//! minifying is also idempotent: minifying the output of rcss again, with
+//! the same options, writes exactly the same output.
+pub mod analyze;
+pub mod banner;
+pub mod browsers;
+pub mod budget;
+pub mod bundle;
+pub mod cache;
+pub mod check;
+pub mod colors;
+pub mod compress;
+pub mod config;
+pub mod conflicts;
+pub mod content;
+pub mod critical;
+pub mod css;
+pub mod error;
+pub mod fingerprint;
+pub mod format;
+pub mod glob;
+pub mod html;
+pub mod ignore;
+pub mod imports;
+pub mod js;
+pub mod json;
+pub mod layers;
+pub mod level;
+pub mod lint;
+pub mod log;
+pub mod metadata;
+#[cfg(feature = "tower")]
+pub mod middleware;
+pub mod minifier;
+pub mod numbers;
+pub mod ordering;
+pub mod output;
+pub mod parallel;
+pub mod passes;
+pub mod paths;
+pub mod placeholders;
+pub mod preset;
+pub mod profile;
+pub mod progress;
+pub mod report;
+pub mod rules;
+pub mod selectors;
+pub mod source_map;
+pub mod stats;
+pub mod stylesheet;
+pub mod svg;
+pub mod tokenizer;
+pub mod transform;
+pub mod urls;
+pub mod walk;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+pub mod watch;
+
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, HashSet},
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+pub use error::RcssError;
+use error::Result;
+
+/// [`recurse_files`] will traverse a directory and find all
+/// file paths within that directory that are allowed by `filter`,
+/// then return the resulting file paths as a
+/// [`error::Result<Vec<PathBuf>>`].
+///
+/// # notes
+/// the directory is walked by [`walk::walk_parallel`] on up to
+/// `options.jobs` threads, skipping whatever can't be read, and the paths
+/// are sorted so they don't depend on the file system or the threads.
+fn recurse_files(
+    user_path: impl AsRef<Path>,
+    filter: &glob::FileFilter,
+    options: &Options,
+) -> Result<Vec<PathBuf>> {
+    let mut files = walk::walk_parallel(user_path, filter, options, options.jobs)?;
+    files.sort();
+    Ok(files)
+}
+
+/// [`minify_string`] will minify the css in `content` using the
+/// rules in [`css::minify`]. it will normalize spaces, remove comments,
+/// remove unnecessary semi-colons, and trim spaces where appropriate,
+/// without touching the content of strings or urls. to minify with any
+/// other [`Options`], use a [`minifier::Minifier`].
+///
+/// # example
+/// [`minify_string`] can be used to minify css that is already
+/// in memory:
+/// ```rust
+/// let minified = rcss::minify_string("a {  color: red; }");
+/// assert_eq!(minified, "a{color:red}");
+/// ```
+pub fn minify_string(content: &str) -> String {
+    css::minify(content)
+}
+
+/// [`minify_source`] will minify `content` with the [`rules::RuleSet`]
+/// registered for files with `extension`: [`js::minify`] for javascript,
+/// [`html::minify`] for html, [`svg::minify`] for svg, [`json::minify`] for
+/// json, any rule set added with [`rules::register`], and
+/// [`css::minify_with_mappings`] for everything else. it returns the minified
+/// content, its source map mappings (only css has any), and the license
+/// comments within `content`. a leading byte order mark is removed, since
+/// it would be invalid in the middle of combined output. at
+/// [`level::Level::Concatenate`], `content` is returned as it is instead,
+/// ending with a newline so the next file starts on its own line, without
+/// any mappings or license comments (which it already has).
+///
+/// # example
+/// [`minify_source`] can be used to minify javascript:
+/// ```rust
+/// let (minified, _, _) = rcss::minify_source("js", "let a = 1 ;", &rcss::Options::default());
+/// assert_eq!(minified, "let a=1;");
+/// ```
+pub fn minify_source<'a>(
+    extension: &str,
+    content: &'a str,
+    options: &Options,
+) -> (String, Vec<source_map::Mapping>, Vec<&'a str>) {
+    let rule_set = rules::find(extension);
+    let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+    if options.level == level::Level::Concatenate {
+        let mut concatenated = content.to_string();
+        if !concatenated.is_empty() && !concatenated.ends_with('\n') {
+            concatenated.push('\n');
+        }
+        return (concatenated, vec![], vec![]);
+    }
+    let (minified, mappings) = rule_set.minify_with_mappings(content, options);
+    (minified, mappings, rule_set.license_comments(content))
+}
+
+/// [`minify_file`] will read the file at `file_path` and return
+/// its content minified by [`minify_string`], like
+/// [`minifier::Minifier::minify_file`] with the default [`Options`].
+///
+/// # example
+/// [`minify_file`] can be used to combine and minify the content
+/// from a `Vec<String>` `file_paths_to_minify`, then return
+/// that content as a [`String`]:
+/// ```rust,no_run
+/// fn minify_file_paths(file_paths_to_minify: Vec<String>) -> String {
+///     file_paths_to_minify
+///         .iter()
+///         .map(|file_path| rcss::minify_file(file_path).unwrap())
+///         .collect::<String>()
+/// }
+/// ```
+pub fn minify_file(file_path: impl AsRef<Path>) -> Result<String> {
+    let file_path = file_path.as_ref();
+    let content = fs::read_to_string(file_path).map_err(RcssError::io(file_path))?;
+    Ok(minify_string(&content))
+}
+
+/// [`read_sources`] reads every file in `file_paths`, returning each
+/// path with its content. when [`Options::inline_imports`] is set, only
+/// files that aren't imported by another file are read, with their
+/// imports inlined ahead of them.
+fn read_sources(file_paths: &[PathBuf], options: &Options) -> Result<Vec<(PathBuf, String)>> {
+    let read =
+        |file_path: &PathBuf| fs::read_to_string(file_path).map_err(RcssError::io(file_path));
+    if !options.inline_imports {
+        return file_paths
+            .iter()
+            .map(|file_path| Ok((file_path.clone(), read(file_path)?)))
+            .collect();
+    }
+
+    let mut imported_files = HashSet::new();
+    for file_path in file_paths {
+        let directory = file_path.parent().unwrap_or(Path::new(""));
+        for import in imports::find_imports(&read(file_path)?) {
+            if let Ok(import_path) = fs::canonicalize(directory.join(&import.url)) {
+                imported_files.insert(import_path);
+            }
+        }
+    }
+    let mut sources = vec![];
+    let mut visited = HashSet::new();
+    for file_path in file_paths {
+        if !imported_files.contains(&fs::canonicalize(file_path).map_err(RcssError::io(file_path))?)
+        {
+            imports::inline_imports(file_path, &mut visited, &mut sources)?;
+        }
+    }
+    Ok(sources)
+}
+
+/// [`read_cached_sources`] returns every file in `file_paths` to combine,
+/// like [`read_sources`]. when caching, files that haven't changed since
+/// they were minified aren't read at all, unless imports are being inlined
+/// (since an imported file may have changed), or placeholders replaced.
+/// otherwise, large files are memory-mapped by [`content::read`].
+fn read_cached_sources(
+    file_paths: &[PathBuf],
+    options: &Options,
+    cache: &cache::Cache,
+) -> Result<Vec<(PathBuf, cache::Source)>> {
+    let read = |file_path: &PathBuf| content::read(file_path).map_err(RcssError::io(file_path));
+    if options.placeholders {
+        // a file can't be skipped even if it hasn't changed, since the
+        // values of its placeholders may have
+        let mut sources = vec![];
+        for (file_path, content) in read_sources(file_paths, options)? {
+            let lookup = |name: &str| placeholders::lookup(name, &options.defines);
+            let content = placeholders::substitute(&content, lookup, options.allow_undefined)
+                .map_err(|error| RcssError::Parse {
+                    path: file_path.clone(),
+                    message: error.to_string(),
+                })?
+                .into_owned();
+            sources.push((file_path, cache::Source::Read(content.into(), None)));
+        }
+        return Ok(sources);
+    }
+    if options.inline_imports {
+        let sources = read_sources(file_paths, options)?;
+        return Ok(sources
+            .into_iter()
+            .map(|(file_path, content)| (file_path, cache::Source::Read(content.into(), None)))
+            .collect());
+    }
+    if !options.cache {
+        return file_paths
+            .iter()
+            .map(|file_path| {
+                Ok((
+                    file_path.clone(),
+                    cache::Source::Read(read(file_path)?, None),
+                ))
+            })
+            .collect();
+    }
+    let mut sources = vec![];
+    for file_path in file_paths {
+        let stamp = cache::Stamp::new(&fs::metadata(file_path).map_err(RcssError::io(file_path))?);
+        let cached = stamp.and_then(|stamp| cache.fresh(file_path, stamp));
+        let source = match cached {
+            Some(entry) => cache::Source::Cached(entry.clone()),
+            None => cache::Source::Read(read(file_path)?, stamp),
+        };
+        sources.push((file_path.clone(), source));
+    }
+    Ok(sources)
+}
+
+/// [`MAX_ROUNDS`] is how many times [`apply_passes`] runs the passes that
+/// remove or merge, at most, while they keep finding more to remove or merge.
+const MAX_ROUNDS: usize = 8;
+
+/// [`apply_passes`] runs every structural pass that is turned on in
+/// `options` over the minified `css`, adding or stripping vendor prefixes
+/// for `targets`, purging selectors that aren't in `used_names`, and
+/// extracting the critical css of the `critical` document. it returns the
+/// restructured css, and the hashed name of every class name, if they were
+/// hashed, and pushes how long parsing, every pass, and printing took to
+/// `times`.
+fn apply_passes(
+    css: &str,
+    options: &Options,
+    targets: &browsers::Targets,
+    used_names: &HashSet<String>,
+    critical: Option<&critical::Document>,
+    times: &mut Vec<(&'static str, Duration)>,
+) -> (String, BTreeMap<String, String>) {
+    let mut last = Instant::now();
+    // a pass that runs more than once adds up to a single time
+    let mut lap = |name| {
+        let now = Instant::now();
+        let time = now - std::mem::replace(&mut last, now);
+        match times.iter_mut().find(|(pass, _)| *pass == name) {
+            Some((_, total)) => *total += time,
+            None => times.push((name, time)),
+        }
+    };
+    let mut stylesheet = stylesheet::Stylesheet::parse(css);
+    lap("parse");
+    for transform in &options.transforms {
+        transform.apply(&mut stylesheet);
+        lap(transform.name());
+    }
+    if options.flatten_nesting {
+        passes::nesting::flatten_nesting(&mut stylesheet);
+        lap("--flatten-nesting");
+    }
+    if options.strip_hacks {
+        passes::hacks::strip_hacks(&mut stylesheet);
+        lap("--strip-hacks");
+    }
+    if options.normalize_case {
+        passes::case::normalize_case(&mut stylesheet);
+        lap("--normalize-case");
+    }
+    if !options.purge.is_empty() {
+        passes::purge::purge_unused(&mut stylesheet, used_names);
+        lap("--purge");
+    }
+    if let Some(document) = critical {
+        critical::extract_critical(&mut stylesheet, document);
+        lap("critical");
+    }
+    if options.downlevel_colors {
+        passes::color_syntax::downlevel_colors(&mut stylesheet, targets);
+        lap("--downlevel-colors");
+    }
+    // removing or merging one thing can leave another to remove or merge,
+    // like an `@keyframes` only used by a custom property that is removed,
+    // or identical `@media` blocks left next to each other, so these passes
+    // run until none of them changes anything, for rcss to write the same
+    // output when run on its own output again
+    for _ in 0..MAX_ROUNDS {
+        let before = stylesheet.clone();
+        if options.inline_variables {
+            passes::variables::inline_variables(&mut stylesheet);
+            lap("--inline-variables");
+        }
+        if options.remove_unused_at_rules {
+            passes::unused::remove_unused_at_rules(&mut stylesheet);
+            lap("--remove-unused-at-rules");
+        }
+        if options.remove_unused_variables {
+            for name in passes::variables::remove_unused_variables(&mut stylesheet) {
+                log::verbose(format_args!("removed unused custom property {name}"));
+            }
+            lap("--remove-unused-variables");
+        }
+        if options.strip_prefixes {
+            passes::prefixes::strip_prefixes(&mut stylesheet, targets);
+            lap("--strip-prefixes");
+        }
+        if options.remove_empty_rules {
+            passes::empty::remove_empty_rules(&mut stylesheet);
+            lap("--remove-empty-rules");
+        }
+        if options.merge_media {
+            passes::media::merge_media_queries(&mut stylesheet);
+            lap("--merge-media");
+        }
+        if options.merge_duplicates {
+            passes::duplicates::merge_duplicate_rules(&mut stylesheet);
+            lap("--merge-duplicates");
+        }
+        if options.remove_overridden {
+            passes::overrides::remove_overridden_declarations(
+                &mut stylesheet,
+                options.keep_fallbacks,
+            );
+            lap("--remove-overridden");
+        }
+        if options.collapse_shorthands {
+            passes::shorthands::collapse_shorthands(&mut stylesheet);
+            lap("--collapse-shorthands");
+        }
+        if options.merge_selectors {
+            passes::selector_lists::merge_identical_rules(&mut stylesheet);
+            lap("--merge-selectors");
+        }
+        if stylesheet == before {
+            break;
+        }
+    }
+    if options.add_prefixes {
+        passes::prefixes::add_prefixes(&mut stylesheet, targets);
+        lap("--add-prefixes");
+    }
+    if let Some(scope) = &options.scope {
+        passes::scope::scope_selectors(&mut stylesheet, scope);
+        lap("--scope");
+    }
+    if options.shorten_identifiers {
+        let keep = options
+            .keep_identifiers
+            .iter()
+            .map(|pattern| glob::Glob::new(pattern))
+            .collect::<Vec<_>>();
+        passes::identifiers::shorten_identifiers(&mut stylesheet, &keep);
+        lap("--shorten-identifiers");
+    }
+    let class_names = match options.hash_classes {
+        true => {
+            let class_names = passes::class_names::hash_class_names(&mut stylesheet);
+            lap("--hash-classes");
+            class_names
+        }
+        false => BTreeMap::new(),
+    };
+    let css = stylesheet.to_css();
+    lap("print");
+    (css, class_names)
+}
+
+/// [`Options`] controls how [`minify_directory_with`] combines files.
+///
+/// # example
+/// [`Options`] can be used to turn on source map generation, leaving
+/// every other option as its default:
+/// ```rust
+/// let options = rcss::Options {
+///     source_map: true,
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Options {
+    /// write a `.map` file next to the destination file, and link
+    /// to it with a `/*# sourceMappingURL= */` comment.
+    pub source_map: bool,
+    /// inline local `@import` statements, and order files so that
+    /// every file comes after the files it imports.
+    pub inline_imports: bool,
+    /// files (relative to the source directory) to combine first,
+    /// in this order.
+    pub order: Vec<String>,
+    /// how to sort every file that isn't listed in `order`.
+    pub sort: ordering::SortOrder,
+    /// a file that lists the files to combine, one per line and relative to
+    /// the list, like `files.txt`, read by [`ordering::read_file_list`]. they
+    /// are combined in that order instead of walking the source directory,
+    /// skipping any that aren't of the type being combined, and aren't
+    /// otherwise filtered, sorted, or reordered.
+    pub file_list: Option<String>,
+    /// the files to combine, in this order, instead of walking the source
+    /// directory, like the files of a `file_list`.
+    pub files: Vec<String>,
+    /// glob patterns (relative to the source directory) of files to
+    /// combine. every file is combined if this is empty.
+    pub include: Vec<String>,
+    /// glob patterns (relative to the source directory) of files to
+    /// skip, like `**/vendor/**`.
+    pub exclude: Vec<String>,
+    /// how many threads to minify files with, or `0` to use one
+    /// thread per core.
+    pub jobs: usize,
+    /// merge rules with identical selectors, and drop duplicate
+    /// declarations and `@font-face` blocks, across the combined output.
+    pub merge_duplicates: bool,
+    /// remove declarations that a later declaration of the same property in
+    /// the same block overrides, like the `color:red` of `color:red;color:blue`.
+    pub remove_overridden: bool,
+    /// keep overridden declarations with a different value than the one that
+    /// overrides them, like the `display:block` of `display:block;display:flex`,
+    /// as fallbacks for older browsers.
+    pub keep_fallbacks: bool,
+    /// merge rules with identical declarations into one rule with a
+    /// selector list, like `.a,.b{color:red}`.
+    pub merge_selectors: bool,
+    /// collapse complete sets of longhands into their shorthand, like
+    /// `margin-top`, `margin-right`, `margin-bottom`, and `margin-left`
+    /// into `margin`.
+    pub collapse_shorthands: bool,
+    /// remove rules and at-rules left with empty blocks, like `.a{}`, after
+    /// every other pass.
+    pub remove_empty_rules: bool,
+    /// remove the `@keyframes` and `@font-face` rules that no `animation`
+    /// or `font-family` of the combined output uses, with
+    /// [`passes::unused::remove_unused_at_rules`].
+    pub remove_unused_at_rules: bool,
+    /// merge `@media` blocks with identical queries into the first of
+    /// them.
+    pub merge_media: bool,
+    /// add the vendor prefixed copies of declarations that the `browsers`
+    /// need, like `-webkit-backdrop-filter`.
+    pub add_prefixes: bool,
+    /// remove vendor prefixed declarations that none of the `browsers`
+    /// need, when the unprefixed declaration is also set.
+    pub strip_prefixes: bool,
+    /// remove the hacks that only old versions of internet explorer read,
+    /// like `*zoom:1` or `@media \0screen`, with
+    /// [`passes::hacks::strip_hacks`].
+    pub strip_hacks: bool,
+    /// rewrite space separated colors, like `rgb(0 0 0 / 50%)`, in the
+    /// comma separated syntax, like `rgba(0,0,0,.5)`, when one of the
+    /// `browsers` doesn't support them.
+    pub downlevel_colors: bool,
+    /// lowercase property names, and the hex colors, units, and function
+    /// names of values, with [`passes::case::normalize_case`].
+    pub normalize_case: bool,
+    /// the [`browsers::Targets`] query that decides which vendor prefixes
+    /// are needed, like `"last 2 versions"`. an empty query is the same as
+    /// [`browsers::DEFAULT_QUERY`].
+    pub browsers: String,
+    /// glob patterns (relative to the current directory) of html,
+    /// javascript, and template files. when set, selectors with a class
+    /// name or id that isn't used in any of them are removed.
+    pub purge: Vec<String>,
+    /// the html file to extract critical css for. when set, only the rules
+    /// that could match one of its elements are kept.
+    pub critical: Option<String>,
+    /// keep `/*!` license comments, at the top of the combined output.
+    pub preserve_comments: bool,
+    /// flatten nested rules, like `.card{.title{...}}`, into plain css.
+    pub flatten_nesting: bool,
+    /// inline custom properties defined on `:root`, like `var(--gap)`.
+    pub inline_variables: bool,
+    /// remove the custom properties that nothing within the combined output
+    /// uses, with [`passes::variables::remove_unused_variables`].
+    pub remove_unused_variables: bool,
+    /// a selector, like `.my-widget`, to scope every rule within with
+    /// [`passes::scope::scope_selectors`], so the output only styles
+    /// elements inside of it.
+    pub scope: Option<String>,
+    /// replace every class name with a short hashed one, like css modules,
+    /// and write the mapping from each original name to its hashed name to
+    /// a json file named after the destination file, like `style.css.json`.
+    pub hash_classes: bool,
+    /// rename every `@keyframes` and custom property to a short name with
+    /// [`passes::identifiers::shorten_identifiers`].
+    pub shorten_identifiers: bool,
+    /// glob patterns of `@keyframes` and custom property names to keep when
+    /// shortening identifiers, like `--theme-*`, since javascript that refers
+    /// to them can't be rewritten.
+    pub keep_identifiers: Vec<String>,
+    /// custom passes to run over the combined css, in order, before every
+    /// built in pass. they can only be given from rust, not `rcss.toml`.
+    pub transforms: Vec<Arc<dyn transform::Transform>>,
+    /// add a content hash to the destination file name, like
+    /// `style.0a1b2c3d.css`, and record it in a [`fingerprint::MANIFEST_FILE_NAME`]
+    /// file. fingerprinted copies from earlier runs are removed.
+    pub hash: bool,
+    /// also write a pre-compressed copy of the destination file in each of
+    /// these formats, like `style.css.gz`.
+    pub compress: Vec<compress::Compression>,
+    /// keep the minified output of every file in a [`cache::CACHE_FILE_NAME`]
+    /// file, so files that haven't changed aren't minified again.
+    pub cache: bool,
+    /// globs of keys to remove from json objects, like `$schema`, compiled
+    /// once rather than for every file.
+    pub remove_keys: Vec<glob::Glob>,
+    /// walk into symbolic links to files and directories, which are
+    /// skipped otherwise.
+    pub follow_symlinks: bool,
+    /// walk into hidden files and directories, whose names start with a
+    /// `.`, like `.git` or `.cache`, which are skipped otherwise.
+    pub hidden: bool,
+    /// how many levels of subdirectories to walk into, or `None` for no
+    /// limit. `Some(0)` only combines the files directly in the directory.
+    pub max_depth: Option<usize>,
+    /// the directory to write the output (and its source map, compressed
+    /// copies, and manifest) to, instead of the source directory. it is
+    /// created if it doesn't exist.
+    pub out_dir: Option<String>,
+    /// the most bytes the output may be, like `51200` for `50kb`. a bigger
+    /// output is an [`RcssError::OverBudget`], and isn't written.
+    pub max_size: Option<usize>,
+    /// what [`format::format`] indents every level of a block with.
+    pub indent: format::Indent,
+    /// fail with an [`RcssError::Parse`] when a css file is malformed, like
+    /// with an unbalanced brace, rather than combining it anyway.
+    pub strict: bool,
+    /// rewrite the relative `url()`s of every css file, so they are
+    /// relative to the destination file rather than to the file they were
+    /// in, like `url(../img/a.png)` in `components/button.css` to
+    /// `url(img/a.png)`.
+    pub rewrite_urls: bool,
+    /// inline the images and fonts that css files reference with relative
+    /// `url()`s as base64 data uris, if they are this many bytes or fewer.
+    pub inline_assets: Option<usize>,
+    /// how much files are optimized. at [`level::Level::Concatenate`] they
+    /// are combined exactly as they are, and [`level::Level::Restructure`]
+    /// turns on the passes it implies.
+    pub level: level::Level,
+    /// remove the outputs of earlier runs before combining: the destination
+    /// file, its source map and compressed copies, and every fingerprinted
+    /// copy of it.
+    pub clean: bool,
+    /// draw a [`progress::Progress`] bar on stderr while files are
+    /// minified, if it is a terminal.
+    pub progress: bool,
+    /// wrap the rules of every css file in an `@layer` named after its
+    /// path by [`layers::layer_name`], like `@layer components-button`.
+    pub layers: bool,
+    /// wrap the rules of the whole output in an `@layer` of this name,
+    /// like `tenant-acme`, after any [`Options::layers`] of its files.
+    pub layer_name: Option<String>,
+    /// add the output onto the end of the existing destination file,
+    /// rather than replacing it, like a theme onto its base stylesheet.
+    /// only the `@charset` of the existing file is kept, and the `@import`
+    /// statements of the appended files end up after its rules, where they
+    /// are ignored.
+    pub append: bool,
+    /// combine every file after the files named by the `@requires` in its
+    /// header comment, like `/* @requires base/reset.css */`, as sorted by
+    /// [`ordering::sort_by_requires`].
+    pub requires: bool,
+    /// text, or the path to a file of it, to put at the top of the output
+    /// as it is (so it should be a comment), after any `@charset`. its
+    /// placeholders are replaced by [`banner::expand`].
+    pub banner: Option<String>,
+    /// text, or the path to a file of it, to put at the end of the output,
+    /// like [`Options::banner`].
+    pub footer: Option<String>,
+    /// replace every `${NAME}` placeholder within the source files with
+    /// the environment variable `NAME`, or else its value in `defines`, as
+    /// [`placeholders::substitute`] does.
+    pub placeholders: bool,
+    /// the values of placeholders that aren't environment variables, from
+    /// the `[define]` table of the configuration.
+    pub defines: BTreeMap<String, String>,
+    /// leave placeholders without a value as they are, instead of failing.
+    pub allow_undefined: bool,
+    /// more source directories, whose files are combined after the files
+    /// of the source directory, in this order.
+    pub directories: Vec<String>,
+    /// minify every file into its own file next to it, like `button.css`
+    /// into `button.min.css`, instead of combining them, as
+    /// [`minify_each_with`] does.
+    pub each: bool,
+    /// minify every file into itself, overwriting it, like `each` does.
+    pub in_place: bool,
+    /// build the output without writing anything, and fail with an
+    /// [`RcssError::OutOfDate`] if it isn't exactly the existing output.
+    pub check: bool,
+    /// time minifying every file and running every structural pass, into
+    /// the [`report::Summary::profile`] of a build.
+    pub profile: bool,
+    /// a file to copy the permissions, and ownership where there is any,
+    /// of every output from.
+    pub permissions_from: Option<String>,
+    /// the unix mode to give every output, like `0o644`, after any
+    /// `permissions_from`, as read by [`metadata::parse_mode`].
+    pub mode: Option<u32>,
+    /// set the modification time of every output to that of the newest
+    /// file combined into it, rather than when it was written.
+    pub preserve_mtime: bool,
+    /// combine files that already look minified, like `bootstrap.min.css`
+    /// or a file of one long line, as they are instead of minifying them
+    /// again.
+    pub skip_minified: bool,
+    /// skip whatever `.gitignore` files ignore while walking, as well as
+    /// what [`ignore::IGNORE_FILE_NAME`] files do.
+    pub gitignore: bool,
+}
+
+impl Options {
+    /// [`Options::has_structural_passes`] returns `true` if any of the
+    /// [`passes`] that restructure the combined output are turned on.
+    pub fn has_structural_passes(&self) -> bool {
+        self.merge_duplicates
+            || self.remove_overridden
+            || self.merge_selectors
+            || self.collapse_shorthands
+            || self.remove_empty_rules
+            || self.remove_unused_at_rules
+            || self.merge_media
+            || self.add_prefixes
+            || self.strip_prefixes
+            || self.strip_hacks
+            || self.downlevel_colors
+            || self.normalize_case
+            || !self.purge.is_empty()
+            || self.critical.is_some()
+            || self.flatten_nesting
+            || self.inline_variables
+            || self.remove_unused_variables
+            || self.scope.is_some()
+            || self.hash_classes
+            || self.shorten_identifiers
+            || !self.transforms.is_empty()
+    }
+
+    /// [`Options::for_rule_set`] returns these options as they apply to the
+    /// files of `rule_set`: the css-only options are turned off for every
+    /// other rule set, and [`level::Level::Restructure`] turns on the
+    /// structural passes it implies for css.
+    pub fn for_rule_set(&self, rule_set: &dyn rules::RuleSet) -> Options {
+        let restructures = self.level.restructures();
+        match rule_set.is_css() {
+            true => Options {
+                merge_duplicates: self.merge_duplicates || restructures,
+                merge_selectors: self.merge_selectors || restructures,
+                collapse_shorthands: self.collapse_shorthands || restructures,
+                remove_empty_rules: self.remove_empty_rules || restructures,
+                merge_media: self.merge_media || restructures,
+                ..self.clone()
+            },
+            false => Options {
+                inline_imports: false,
+                merge_duplicates: false,
+                remove_overridden: false,
+                merge_selectors: false,
+                collapse_shorthands: false,
+                remove_empty_rules: false,
+                remove_unused_at_rules: false,
+                merge_media: false,
+                add_prefixes: false,
+                strip_prefixes: false,
+                strip_hacks: false,
+                downlevel_colors: false,
+                normalize_case: false,
+                purge: vec![],
+                critical: None,
+                flatten_nesting: false,
+                inline_variables: false,
+                remove_unused_variables: false,
+                scope: None,
+                hash_classes: false,
+                shorten_identifiers: false,
+                transforms: vec![],
+                strict: false,
+                rewrite_urls: false,
+                inline_assets: None,
+                layers: false,
+                layer_name: None,
+                ..self.clone()
+            },
+        }
+    }
+
+    /// [`Options::from_config`] reads options from the root table of a
+    /// [`config::Config`], like an `rcss.toml` file containing:
+    /// ```toml
+    /// source-map = true
+    /// inline-imports = false
+    /// merge-duplicates = true
+    /// remove-overridden = true
+    /// keep-fallbacks = true
+    /// merge-selectors = true
+    /// collapse-shorthands = true
+    /// remove-empty-rules = true
+    /// remove-unused-at-rules = true
+    /// merge-media = true
+    /// add-prefixes = true
+    /// strip-prefixes = true
+    /// strip-hacks = true
+    /// downlevel-colors = true
+    /// normalize-case = true
+    /// browsers = "last 2 versions, safari >= 15"
+    /// purge = ["templates/**/*.html", "src/**/*.js"]
+    /// preserve-comments = true
+    /// flatten-nesting = true
+    /// inline-variables = true
+    /// remove-unused-variables = true
+    /// scope = ".my-widget"
+    /// hash-classes = true
+    /// shorten-identifiers = true
+    /// keep-identifiers = ["--theme-*"]
+    /// hash = true
+    /// cache = true
+    /// remove-keys = ["$schema", "//*"]
+    /// follow-symlinks = true
+    /// hidden = true
+    /// max-depth = 3
+    /// out-dir = "dist"
+    /// max-size = "50kb"
+    /// indent = 4
+    /// strict = true
+    /// clean = true
+    /// progress = true
+    /// layers = true
+    /// layer-name = "theme"
+    /// append = true
+    /// requires = true
+    /// banner = "/*! my-site {commit}, built {date} */"
+    /// footer = "assets/footer.css"
+    /// placeholders = true
+    /// allow-undefined = true
+    /// rewrite-urls = true
+    /// inline-assets = "4kb"
+    /// level = 2
+    /// preset = "default"
+    /// compress = ["gzip", "brotli"]
+    /// sort = "numeric-prefix"
+    /// order = ["base/reset.css", "base/typography.css"]
+    /// directories = ["vendor/css"]
+    /// each = true
+    /// in-place = false
+    /// permissions-from = "assets/template.css"
+    /// mode = "644"
+    /// preserve-mtime = true
+    /// skip-minified = true
+    /// gitignore = true
+    /// file-list = "css/files.txt"
+    /// include = ["**/*.css"]
+    /// exclude = ["**/vendor/**"]
+    /// jobs = 4
+    ///
+    /// [define]
+    /// CDN = "https://cdn.example.com"
+    /// ```
+    pub fn from_config(config: &config::Config) -> Result<Options> {
+        let flag = |key: &str| config.get("", key).and_then(config::Value::as_bool);
+        let strings = |key: &str| match config.get("", key) {
+            Some(value) => value
+                .as_strings()
+                .ok_or_else(|| RcssError::Config(format!("{key} must be an array of strings"))),
+            None => Ok(vec![]),
+        };
+        let text = |key: &str| match config.get("", key) {
+            Some(value) => Ok::<_, RcssError>(Some(
+                value
+                    .as_str()
+                    .map(str::to_string)
+                    .ok_or_else(|| RcssError::Config(format!("{key} must be a string")))?,
+            )),
+            None => Ok(None),
+        };
+        let size = |key: &str| match config.get("", key) {
+            Some(value) => value
+                .as_str()
+                .and_then(budget::parse_size)
+                .or_else(|| {
+                    value
+                        .as_integer()
+                        .and_then(|size| usize::try_from(size).ok())
+                })
+                .map(Some)
+                .ok_or_else(|| RcssError::Config(format!("{key} must be a size, like \"50kb\""))),
+            None => Ok(None),
+        };
+        let mut options = Options {
+            source_map: flag("source-map").unwrap_or_default(),
+            inline_imports: flag("inline-imports").unwrap_or_default(),
+            merge_duplicates: flag("merge-duplicates").unwrap_or_default(),
+            remove_overridden: flag("remove-overridden").unwrap_or_default(),
+            keep_fallbacks: flag("keep-fallbacks").unwrap_or_default(),
+            merge_selectors: flag("merge-selectors").unwrap_or_default(),
+            collapse_shorthands: flag("collapse-shorthands").unwrap_or_default(),
+            remove_empty_rules: flag("remove-empty-rules").unwrap_or_default(),
+            remove_unused_at_rules: flag("remove-unused-at-rules").unwrap_or_default(),
+            merge_media: flag("merge-media").unwrap_or_default(),
+            add_prefixes: flag("add-prefixes").unwrap_or_default(),
+            strip_prefixes: flag("strip-prefixes").unwrap_or_default(),
+            strip_hacks: flag("strip-hacks").unwrap_or_default(),
+            downlevel_colors: flag("downlevel-colors").unwrap_or_default(),
+            normalize_case: flag("normalize-case").unwrap_or_default(),
+            browsers: match config.get("", "browsers") {
+                Some(value) => value.as_str().map(str::to_string).ok_or_else(|| {
+                    RcssError::Config(
+                        "browsers must be a string, like \"last 2 versions\"".to_string(),
+                    )
+                })?,
+                None => String::new(),
+            },
+            preserve_comments: flag("preserve-comments").unwrap_or_default(),
+            flatten_nesting: flag("flatten-nesting").unwrap_or_default(),
+            inline_variables: flag("inline-variables").unwrap_or_default(),
+            remove_unused_variables: flag("remove-unused-variables").unwrap_or_default(),
+            hash: flag("hash").unwrap_or_default(),
+            cache: flag("cache").unwrap_or_default(),
+            remove_keys: strings("remove-keys")?
+                .iter()
+                .map(|pattern| glob::Glob::new(pattern))
+                .collect(),
+            purge: strings("purge")?,
+            critical: None,
+            follow_symlinks: flag("follow-symlinks").unwrap_or_default(),
+            hidden: flag("hidden").unwrap_or_default(),
+            max_depth: match config.get("", "max-depth") {
+                Some(value) => Some(
+                    value
+                        .as_integer()
+                        .and_then(|max_depth| usize::try_from(max_depth).ok())
+                        .ok_or_else(|| {
+                            RcssError::Config("max-depth must be a positive number".to_string())
+                        })?,
+                ),
+                None => None,
+            },
+            placeholders: flag("placeholders").unwrap_or_default(),
+            defines: match config.tables.get("define") {
+                Some(defines) => defines
+                    .iter()
+                    .map(|(name, value)| match value.as_str() {
+                        Some(value) => Ok((name.clone(), value.to_string())),
+                        None => Err(RcssError::Config(format!("define.{name} must be a string"))),
+                    })
+                    .collect::<Result<_>>()?,
+                None => BTreeMap::new(),
+            },
+            allow_undefined: flag("allow-undefined").unwrap_or_default(),
+            scope: text("scope")?,
+            hash_classes: flag("hash-classes").unwrap_or_default(),
+            shorten_identifiers: flag("shorten-identifiers").unwrap_or_default(),
+            keep_identifiers: strings("keep-identifiers")?,
+            transforms: vec![],
+            banner: text("banner")?,
+            footer: text("footer")?,
+            out_dir: match config.get("", "out-dir") {
+                Some(value) => Some(value.as_str().map(str::to_string).ok_or_else(|| {
+                    RcssError::Config("out-dir must be a string, like \"dist\"".to_string())
+                })?),
+                None => None,
+            },
+            max_size: size("max-size")?,
+            indent: match config.get("", "indent") {
+                Some(config::Value::Integer(spaces)) => {
+                    format::Indent::Spaces(usize::try_from(*spaces).map_err(|_| {
+                        RcssError::Config("indent must be a positive number".to_string())
+                    })?)
+                }
+                Some(value) => value
+                    .as_str()
+                    .and_then(format::Indent::parse)
+                    .ok_or_else(|| {
+                        RcssError::Config(
+                            "indent must be a number of spaces, or \"tab\"".to_string(),
+                        )
+                    })?,
+                None => format::Indent::default(),
+            },
+            strict: flag("strict").unwrap_or_default(),
+            rewrite_urls: flag("rewrite-urls").unwrap_or_default(),
+            inline_assets: size("inline-assets")?,
+            compress: strings("compress")?
+                .iter()
+                .map(|name| {
+                    compress::Compression::parse(name).ok_or_else(|| {
+                        RcssError::Config(format!("compress must be gzip or brotli, not {name}"))
+                    })
+                })
+                .collect::<Result<_>>()?,
+            order: strings("order")?,
+            sort: ordering::SortOrder::from_config(config)?,
+            file_list: text("file-list")?,
+            files: vec![],
+            include: strings("include")?,
+            exclude: strings("exclude")?,
+            jobs: match config.get("", "jobs") {
+                Some(value) => value
+                    .as_integer()
+                    .and_then(|jobs| usize::try_from(jobs).ok())
+                    .ok_or_else(|| {
+                        RcssError::Config("jobs must be a positive number".to_string())
+                    })?,
+                None => 0,
+            },
+            level: level::Level::from_config(config)?,
+            clean: flag("clean").unwrap_or_default(),
+            progress: flag("progress").unwrap_or_default(),
+            layers: flag("layers").unwrap_or_default(),
+            layer_name: text("layer-name")?,
+            append: flag("append").unwrap_or_default(),
+            requires: flag("requires").unwrap_or_default(),
+            directories: strings("directories")?,
+            each: flag("each").unwrap_or_default(),
+            in_place: flag("in-place").unwrap_or_default(),
+            check: false,
+            profile: false,
+            permissions_from: text("permissions-from")?,
+            mode: match text("mode")? {
+                Some(mode) => Some(metadata::parse_mode(&mode).ok_or_else(|| {
+                    RcssError::Config(format!(
+                        "mode must be an octal mode like \"644\", not {mode}"
+                    ))
+                })?),
+                None => None,
+            },
+            preserve_mtime: flag("preserve-mtime").unwrap_or_default(),
+            skip_minified: flag("skip-minified").unwrap_or_default(),
+            gitignore: flag("gitignore").unwrap_or_default(),
+        };
+        // an option set on its own overrides the preset
+        if let Some(preset) = preset::Preset::from_config(config)? {
+            preset.apply(&mut options, |key| config.get("", key).is_some());
+        }
+        Ok(options)
+    }
+}
+
+/// [`minify_directory`] combines all files of type `extension`
+/// within the `destination_folder_path` directory in the
+/// `destination_file_name` file **in-place**, using the default
+/// [`Options`].
+///
+/// # notes
+/// the destination file is created if it doesn't exist, and is
+/// excluded from the files being combined.
+///
+/// # example
+/// [`minify_directory`] can be used to combine and minify
+/// the content from all css files, and put their contents
+/// into `./assets/css/style.css`:
+/// ```rust,no_run
+/// fn minify_function() {
+///     rcss::minify_directory("css", "./assets/css", "style.css").unwrap();
+/// }
+/// ```
+pub fn minify_directory(
+    extension: &str,
+    destination_folder_path: &str,
+    destination_file_name: &str,
+) -> Result<report::Summary> {
+    minify_directory_with(
+        extension,
+        destination_folder_path,
+        destination_file_name,
+        &Options::default(),
+    )
+}
+
+/// [`minify_directory_with`] combines all files of type `extension`
+/// within the `destination_folder_path` directory in the
+/// `destination_file_name` file **in-place**, as configured by `options`,
+/// and returns a [`report::Summary`] of what it did.
+///
+/// # example
+/// [`minify_directory_with`] can be used to combine all css files into
+/// `./assets/css/style.css`, and write `./assets/css/style.css.map`:
+/// ```rust,no_run
+/// let options = rcss::Options {
+///     source_map: true,
+///     ..Default::default()
+/// };
+/// let summary = rcss::minify_directory_with("css", "./assets/css", "style.css", &options).unwrap();
+/// println!("{summary}");
+/// ```
+pub fn minify_directory_with(
+    extension: &str,
+    destination_folder_path: &str,
+    destination_file_name: &str,
+    options: &Options,
+) -> Result<report::Summary> {
+    let report = combine_directory(
+        extension,
+        destination_folder_path,
+        destination_file_name,
+        options,
+        false,
+    )?;
+    Ok(report.summary)
+}
+
+/// [`build`] combines and minifies the files of type `extension` within
+/// `directory` into `output`, written to the `OUT_DIR` of a cargo build
+/// script (or the [`Options::out_dir`] within it), and returns the path it
+/// was written to. a `cargo:rerun-if-changed` line is printed for
+/// `directory` and every file within it, so the build script only runs
+/// again once one of them changes.
+///
+/// # example
+/// [`build`] can be called from the `main` of a `build.rs` file, to bundle
+/// `assets/css` into the `OUT_DIR` of the package:
+/// ```rust,no_run
+/// rcss::build("css", "assets/css", "style.css", &Default::default()).unwrap();
+/// ```
+/// which the package can then embed with
+/// `include_str!(concat!(env!("OUT_DIR"), "/style.css"))`.
+pub fn build(extension: &str, directory: &str, output: &str, options: &Options) -> Result<PathBuf> {
+    let out_dir = std::env::var_os("OUT_DIR").ok_or_else(|| {
+        RcssError::Config(
+            "OUT_DIR isn't set, so rcss::build must run in a build script".to_string(),
+        )
+    })?;
+    let out_dir = match &options.out_dir {
+        Some(subdirectory) => Path::new(&out_dir).join(subdirectory),
+        None => PathBuf::from(out_dir),
+    };
+    let out_dir = out_dir
+        .into_os_string()
+        .into_string()
+        .map_err(|out_dir| RcssError::InvalidPath(out_dir.into()))?;
+    let filter = glob::FileFilter::new(&options.include, &options.exclude);
+    for directory in
+        std::iter::once(directory).chain(options.directories.iter().map(String::as_str))
+    {
+        println!("cargo:rerun-if-changed={directory}");
+        for file in recurse_files(directory, &filter, options)? {
+            if has_extension(&file, extension) {
+                println!("cargo:rerun-if-changed={}", file.display());
+            }
+        }
+    }
+    let options = Options {
+        out_dir: Some(out_dir),
+        ..options.clone()
+    };
+    let report = combine_directory(extension, directory, output, &options, false)?;
+    Ok(PathBuf::from(report.output))
+}
+
+/// [`dry_run_directory`] combines and minifies files exactly like
+/// [`minify_directory_with`], but doesn't write anything (not even the
+/// cache or manifest), and instead returns a [`report::Report`] of what
+/// would have been written.
+///
+/// # example
+/// [`dry_run_directory`] can be used to check the savings of minifying
+/// `./assets/css`, without overwriting `./assets/css/style.css`:
+/// ```rust,no_run
+/// let report = rcss::dry_run_directory("css", "./assets/css", "style.css", &Default::default()).unwrap();
+/// println!("{report}");
+/// ```
+pub fn dry_run_directory(
+    extension: &str,
+    destination_folder_path: &str,
+    destination_file_name: &str,
+    options: &Options,
+) -> Result<report::Report> {
+    combine_directory(
+        extension,
+        destination_folder_path,
+        destination_file_name,
+        options,
+        true,
+    )
+}
+
+/// [`minify_each_with`] minifies every file of type `extension` within
+/// `directory`, and within [`Options::directories`], into its own file
+/// next to it instead of combining them: `button.css` into `button.min.css`,
+/// or into `button.css` itself if [`Options::in_place`] is set. it returns
+/// a [`report::Summary`] of every file it wrote.
+///
+/// # notes
+/// files that are already named like `button.min.css` are skipped, unless
+/// files are minified in place. with [`Options::out_dir`], every file is
+/// written to the same path within it as within its source directory.
+///
+/// # example
+/// [`minify_each_with`] can be used to minify every css file within
+/// `./assets/css` on its own, so each page only loads the files it needs:
+/// ```rust,no_run
+/// let summaries = rcss::minify_each_with("css", "./assets/css", &Default::default()).unwrap();
+/// for summary in summaries {
+///     println!("wrote {}", summary.output);
+/// }
+/// ```
+pub fn minify_each_with(
+    extension: &str,
+    directory: &str,
+    options: &Options,
+) -> Result<Vec<report::Summary>> {
+    let reports = minify_each_file(extension, directory, options, false)?;
+    Ok(reports.into_iter().map(|report| report.summary).collect())
+}
+
+/// [`dry_run_each`] minifies every file like [`minify_each_with`], but
+/// doesn't write anything, and instead returns a [`report::Report`] of
+/// every file that would have been written.
+pub fn dry_run_each(
+    extension: &str,
+    directory: &str,
+    options: &Options,
+) -> Result<Vec<report::Report>> {
+    minify_each_file(extension, directory, options, true)
+}
+
+/// [`minify_each_file`] does the work of [`minify_each_with`], by combining
+/// every file on its own, and only writes files if `dry_run` isn't set.
+fn minify_each_file(
+    extension: &str,
+    directory: &str,
+    options: &Options,
+    dry_run: bool,
+) -> Result<Vec<report::Report>> {
+    let filter = glob::FileFilter::new(&options.include, &options.exclude);
+    let mut files = match &options.file_list {
+        Some(file_list) => ordering::read_file_list(file_list)?,
+        None if !options.files.is_empty() => options.files.iter().map(PathBuf::from).collect(),
+        None => recurse_files(directory, &filter, options)?,
+    };
+    for directory in &options.directories {
+        files.extend(recurse_files(directory, &filter, options)?);
+    }
+    let minified_suffix = format!(".min.{extension}");
+    let mut reports = vec![];
+    for file in files {
+        let (Some(path), Some(file_name), Some(stem)) = (
+            file.to_str(),
+            file.file_name().and_then(|name| name.to_str()),
+            file.file_stem().and_then(|stem| stem.to_str()),
+        ) else {
+            return Err(RcssError::InvalidPath(file));
+        };
+        if !has_extension(&file, extension)
+            || (!options.in_place && file_name.ends_with(&minified_suffix))
+        {
+            continue;
+        }
+        let output_name = match options.in_place {
+            true => file_name.to_string(),
+            false => format!("{stem}{minified_suffix}"),
+        };
+        let source_directory = match file.parent().and_then(Path::to_str) {
+            Some("") | None => ".",
+            Some(parent) => parent,
+        };
+        let out_dir = options.out_dir.as_ref().map(|out_dir| {
+            let root = layer_root(&file, directory, options);
+            match ordering::relative_path(source_directory, root) {
+                relative_directory if relative_directory.is_empty() => out_dir.clone(),
+                relative_directory => format!("{out_dir}/{relative_directory}"),
+            }
+        });
+        let file_options = Options {
+            files: vec![path.to_string()],
+            file_list: None,
+            directories: vec![],
+            each: false,
+            // cleaning a file that is minified in place would delete it
+            clean: options.clean && !options.in_place,
+            out_dir,
+            ..options.clone()
+        };
+        let mut report = combine_directory(
+            extension,
+            source_directory,
+            &output_name,
+            &file_options,
+            dry_run,
+        )?;
+        // every other file of the source directory has its own report
+        report.discovered = report.files.len();
+        report.excluded.clear();
+        reports.push(report);
+    }
+    Ok(reports)
+}
+
+/// [`combine_directory`] does the work of [`minify_directory_with`], and
+/// only writes files if `dry_run` isn't set.
+fn combine_directory(
+    extension: &str,
+    destination_folder_path: &str,
+    destination_file_name: &str,
+    options: &Options,
+    dry_run: bool,
+) -> Result<report::Report> {
+    // a check builds everything a dry run does, and compares it at the end
+    let dry_run = dry_run || options.check;
+    let mut phases = report::Phases::default();
+    let mut lap = Instant::now();
+    let mut elapsed = || {
+        let now = Instant::now();
+        std::mem::replace(&mut lap, now).elapsed()
+    };
+    let rule_set = rules::find(extension);
+    let options = &options.for_rule_set(&*rule_set);
+    if options.source_map && options.has_structural_passes() {
+        return Err(RcssError::Config(
+            "source maps can't be generated when rules are restructured".to_string(),
+        ));
+    }
+    check_level(options)?;
+    check_append(options)?;
+    let is_concatenated = options.level == level::Level::Concatenate;
+    if let Some(compression) = options.compress.iter().find(|c| !c.is_supported()) {
+        return Err(compression.unsupported().into());
+    }
+    let targets = browsers::Targets::parse(&options.browsers)?;
+    let used_names = match options.purge.is_empty() {
+        true => HashSet::new(),
+        false => passes::purge::read_used_names(&options.purge, options)?,
+    };
+    let critical = match &options.critical {
+        Some(path) => Some(critical::Document::read(path)?),
+        None => None,
+    };
+    let output_directory = options
+        .out_dir
+        .as_deref()
+        .unwrap_or(destination_folder_path);
+    // the output only needs to be kept out of the inputs when it is written
+    // within the source directory, however the paths to it are spelled
+    let destination_file_path = Path::new(output_directory).join(destination_file_name);
+    if options.clean && !dry_run {
+        clean_outputs(output_directory, destination_file_name)?;
+    }
+    let filter = glob::FileFilter::new(&options.include, &options.exclude);
+    let is_listed = options.file_list.is_some() || !options.files.is_empty();
+    let files_to_minify = match &options.file_list {
+        Some(file_list) => ordering::read_file_list(file_list)?,
+        None if is_listed => options.files.iter().map(PathBuf::from).collect(),
+        None => recurse_files(destination_folder_path, &filter, options)?,
+    };
+    // fingerprinted output from earlier runs, of this or any other bundle
+    // in the same directory, must not be combined into the new output, but
+    // files named like it elsewhere, like `old/style.0a1b2c3d.css`, are
+    // sources
+    let fingerprinted_outputs = match options.hash {
+        true => fingerprint::read_manifest(output_directory),
+        false => Default::default(),
+    };
+    let is_fingerprinted_output = |file: &Path| {
+        let file_name = file.file_name().unwrap_or_default();
+        let file_name = file_name.to_string_lossy();
+        let directory = match file.parent() {
+            Some(directory) if !directory.as_os_str().is_empty() => directory,
+            _ => Path::new("."),
+        };
+        options.hash
+            && paths::same_file(directory, output_directory)
+            && (fingerprint::is_hashed_name(&file_name, destination_file_name)
+                || fingerprinted_outputs
+                    .values()
+                    .any(|output| *output == file_name))
+    };
+    // a file that was given on its own is a source, which can't also be
+    // overwritten as the output
+    if !options.in_place {
+        let given = options.files.iter().map(PathBuf::from);
+        if let Some(file) = given
+            .into_iter()
+            .find(|file| paths::same_file(file, &destination_file_path))
+        {
+            return Err(RcssError::Config(format!(
+                "{} is both a file to combine and the output, give another with --output or --out-file",
+                file.display()
+            )));
+        }
+    }
+    let is_input = |file: &PathBuf| {
+        has_extension(file, extension)
+            && (options.in_place || !paths::same_file(file, &destination_file_path))
+            && !is_fingerprinted_output(file)
+    };
+    let files_without_destination_file = files_to_minify
+        .into_iter()
+        .filter(is_input)
+        .collect::<Vec<_>>();
+    let order = |files, root: &str| {
+        let files = ordering::order_files(files, root, &options.order, options.sort)?;
+        match options.requires {
+            true => ordering::sort_by_requires(files, root),
+            false => Ok(files),
+        }
+    };
+    let mut files_without_destination_file = match is_listed {
+        true => files_without_destination_file,
+        false => order(files_without_destination_file, destination_folder_path)?,
+    };
+    for directory in &options.directories {
+        let files = recurse_files(directory, &filter, options)?
+            .into_iter()
+            .filter(is_input)
+            .collect();
+        files_without_destination_file.extend(order(files, directory)?);
+    }
+    phases.walk += elapsed();
+    if options.strict {
+        for file_path in &files_without_destination_file {
+            let content = fs::read_to_string(file_path).map_err(RcssError::io(file_path))?;
+            tokenizer::validate(&content).map_err(|error| RcssError::Parse {
+                path: file_path.clone(),
+                message: error.to_string(),
+            })?;
+        }
+    }
+    let mut source_map = source_map::SourceMap::new(destination_file_name);
+    let cache_path = format!("{destination_folder_path}/{}", cache::CACHE_FILE_NAME);
+    let mut cache = match options.cache {
+        true => {
+            // only concatenated and skipped files are cached differently than
+            // minified ones
+            let mut rules = options
+                .remove_keys
+                .iter()
+                .map(glob::Glob::pattern)
+                .collect::<Vec<_>>()
+                .join(",");
+            if is_concatenated {
+                rules.insert_str(0, &format!("{} ", options.level));
+            }
+            if options.skip_minified {
+                rules.insert_str(0, "skip-minified ");
+            }
+            cache::Cache::load(&cache_path, &rules)
+        }
+        false => cache::Cache::default(),
+    };
+    let sources = read_cached_sources(&files_without_destination_file, options, &cache)?;
+    let source_bytes = |source: &cache::Source| match source {
+        cache::Source::Cached(entry) => entry.stamp.map_or(0, |stamp| stamp.len as usize),
+        cache::Source::Read(content, _) => content.len(),
+    };
+    let input_bytes = sources.iter().map(|(_, source)| source_bytes(source)).sum();
+    phases.read += elapsed();
+    let progress = progress::Progress::start(sources.len(), input_bytes, options.progress);
+    // files that look minified already are combined as they are
+    let verbatim_options = Options {
+        level: level::Level::Concatenate,
+        ..options.clone()
+    };
+    let timed_entries = parallel::map_ordered(&sources, options.jobs, |(file_path, source)| {
+        let started = Instant::now();
+        let entry = match source {
+            cache::Source::Cached(entry) => entry.clone(),
+            cache::Source::Read(content, stamp) => cache
+                .matching(file_path, content)
+                .cloned()
+                .unwrap_or_else(|| {
+                    match options.skip_minified && looks_minified(file_path, content) {
+                        true => {
+                            log::verbose(format_args!(
+                                "skipped minifying {}, which looks minified already",
+                                file_path.display()
+                            ));
+                            cache::Entry::new(extension, content, *stamp, &verbatim_options)
+                        }
+                        false => cache::Entry::new(extension, content, *stamp, options),
+                    }
+                }),
+        };
+        progress.advance(source_bytes(source));
+        (entry, started.elapsed())
+    });
+    progress.finish();
+    let (entries, file_times): (Vec<_>, Vec<_>) = timed_entries.into_iter().unzip();
+    let mut license_banner = String::new();
+    if options.preserve_comments {
+        let mut license_comments = vec![];
+        for entry in &entries {
+            for comment in &entry.license_comments {
+                if !license_comments.contains(comment) {
+                    license_comments.push(comment.clone());
+                }
+            }
+        }
+        license_banner = license_comments.concat();
+        source_map.add_generated(&license_banner);
+    }
+    // @charset, @import, and @layer statements are ignored anywhere but the
+    // top of the output, where only one @charset is allowed
+    let mut charset = String::new();
+    let mut hoisted = vec![];
+    for ((file_path, _), entry) in sources.iter().zip(&entries) {
+        if !rule_set.is_css() || is_concatenated {
+            break;
+        }
+        for statement in css::split_hoisted(&entry.minified).0 {
+            let is_charset = statement
+                .get(..8)
+                .is_some_and(|name| name.eq_ignore_ascii_case("@charset"));
+            let statement = match replace_urls(statement, file_path, output_directory, options)? {
+                Some((rewritten, _)) => rewritten,
+                None => statement.to_string(),
+            };
+            if is_charset && charset.is_empty() {
+                charset = statement;
+            } else if !is_charset && !hoisted.contains(&statement) {
+                hoisted.push(statement);
+            }
+        }
+    }
+    let hoisted = hoisted.concat();
+    let render = |value: &Option<String>| match value {
+        Some(value) => banner::render(value, destination_folder_path),
+        None => Ok(String::new()),
+    };
+    let (banner, footer) = (render(&options.banner)?, render(&options.footer)?);
+    source_map.add_generated(&banner);
+    source_map.add_generated(&charset);
+    source_map.add_generated(&hoisted);
+    // like the layer of a file, the layer of the whole output leaves out
+    // its hoisted statements
+    let output_layer = options.layer_name.as_deref().map(layers::wrap);
+    if let Some((prefix, _)) = &output_layer {
+        source_map.add_generated(prefix);
+    }
+    // the minified files are borrowed rather than copied into the output,
+    // which is streamed to the destination file
+    let mut output = output::Output::default();
+    for ((file_path, _), entry) in sources.iter().zip(&entries) {
+        let separator = rule_set.separator(output.last());
+        output.push(separator);
+        source_map.add_generated(separator);
+        let (minified, mut mappings) =
+            without_hoisted(entry, rule_set.is_css() && !is_concatenated);
+        let mut minified = Cow::Borrowed(minified);
+        if let Some((css, edits)) = replace_urls(&minified, file_path, output_directory, options)? {
+            minified = Cow::Owned(css);
+            mappings = Cow::Owned(urls::shift(&mappings, &edits));
+        }
+        // the hoisted statements of a file stay outside of its layer
+        let layer = match options.layers && !minified.is_empty() {
+            true => {
+                let root = layer_root(file_path, destination_folder_path, options);
+                Some(layers::wrap(&layers::layer_name(&ordering::relative_path(
+                    file_path, root,
+                ))))
+            }
+            false => None,
+        };
+        if let Some((prefix, _)) = &layer {
+            source_map.add_generated(prefix);
+            output.push(prefix.clone());
+        }
+        if options.source_map {
+            let source = relative_to(file_path, Path::new(output_directory))?;
+            let source = source.to_string_lossy().replace('\\', "/");
+            source_map.add_source(source, &minified, &mappings);
+        }
+        output.push(minified);
+        if let Some((_, suffix)) = layer {
+            source_map.add_generated(suffix);
+            output.push(suffix);
+        }
+    }
+    phases.minify += elapsed();
+    let mut report = report::Report::default();
+    if dry_run {
+        let mut every_file = recurse_files(destination_folder_path, &Default::default(), options)?;
+        for directory in &options.directories {
+            every_file.extend(recurse_files(directory, &Default::default(), options)?);
+        }
+        let every_file = every_file
+            .into_iter()
+            .filter(|file| has_extension(file, extension))
+            .collect::<Vec<_>>();
+        report.discovered = every_file.len();
+        report.excluded = every_file
+            .iter()
+            .filter(|file| !files_without_destination_file.contains(file))
+            .map(|file| ordering::relative_path(file, destination_folder_path))
+            .collect();
+        report.excluded.sort();
+        for ((file_path, _), entry) in sources.iter().zip(&entries) {
+            report.files.push(report::FileReport {
+                path: ordering::relative_path(file_path, destination_folder_path),
+                original_bytes: fs::metadata(file_path)
+                    .map_err(RcssError::io(file_path))?
+                    .len() as usize,
+                minified_bytes: entry.minified.len(),
+            });
+        }
+        phases.walk += elapsed();
+    }
+    if options.cache && !dry_run {
+        for ((file_path, _), entry) in sources.iter().zip(&entries) {
+            cache.insert(file_path, entry.clone());
+        }
+        cache.retain_existing();
+        cache
+            .save(&cache_path)
+            .map_err(RcssError::io(&cache_path))?;
+        phases.write += elapsed();
+    }
+    let output_metadata = metadata::OutputMetadata::new(options, &files_without_destination_file)?;
+    let mut pass_times = vec![];
+    if options.has_structural_passes() {
+        // the passes restructure the whole output at once
+        let (restructured, class_names) = apply_passes(
+            &output.to_string(),
+            options,
+            &targets,
+            &used_names,
+            critical.as_ref(),
+            &mut pass_times,
+        );
+        output = output::Output::default();
+        output.push(restructured);
+        if options.hash_classes && !dry_run {
+            let class_names_path = format!("{output_directory}/{destination_file_name}.json");
+            output::write_atomically(&class_names_path, |file| {
+                file.write_all(json::write_string_object(&class_names).as_bytes())
+            })
+            .and_then(|()| output_metadata.apply(&class_names_path))
+            .map_err(RcssError::io(&class_names_path))?;
+            log::verbose(format_args!("wrote {class_names_path}"));
+        }
+    }
+    phases.minify += elapsed();
+    if let Some((prefix, suffix)) = output_layer {
+        output.prepend(prefix);
+        output.push(suffix);
+    }
+    // appended output goes after the existing destination file as it is,
+    // which keeps the only @charset allowed at the top of the file
+    let existing = match options.append {
+        true => match fs::read_to_string(&destination_file_path) {
+            Ok(existing) if !existing.is_empty() => Some(existing),
+            Ok(_) => None,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => None,
+            Err(error) => return Err(RcssError::io(&destination_file_path)(error)),
+        },
+        false => None,
+    };
+    output.prepend(hoisted);
+    output.prepend(license_banner);
+    output.prepend(banner);
+    if existing.is_none() {
+        output.prepend(charset);
+    }
+    output.push(footer);
+    // the summary is of what this run adds, rather than of the whole file
+    let appended_bytes = output.len();
+    if let Some(existing) = existing {
+        output.prepend(rule_set.separator(&existing));
+        output.prepend(existing);
+    }
+    if let Some(budget) = options.max_size {
+        if output.len() > budget {
+            let files = sources
+                .iter()
+                .zip(&entries)
+                .map(|((file_path, _), entry)| {
+                    let path = ordering::relative_path(file_path, destination_folder_path);
+                    (path, entry.minified.len())
+                })
+                .collect();
+            return Err(RcssError::OverBudget(budget::OverBudget::new(
+                destination_file_path.to_string_lossy(),
+                output.len(),
+                budget,
+                files,
+            )));
+        }
+    }
+    if options.out_dir.is_some() && !dry_run {
+        fs::create_dir_all(output_directory).map_err(RcssError::io(output_directory))?;
+    }
+    let mut output_file_name = destination_file_name.to_string();
+    if options.hash {
+        let hash = fingerprint::content_hash_of(output.bytes());
+        output_file_name = fingerprint::hashed_name(destination_file_name, &hash);
+        if !dry_run {
+            remove_fingerprinted_outputs(output_directory, destination_file_name)?;
+            fingerprint::update_manifest(
+                output_directory,
+                destination_file_name,
+                &output_file_name,
+            )?;
+        }
+        source_map.set_file(&output_file_name);
+    }
+    if options.source_map {
+        let map_file_name = format!("{output_file_name}.map");
+        output.push(source_map::source_mapping_url(&map_file_name));
+        if !dry_run {
+            let map_file_path = format!("{output_directory}/{map_file_name}");
+            output::write_atomically(&map_file_path, |file| {
+                file.write_all(source_map.to_json().as_bytes())
+            })
+            .and_then(|()| output_metadata.apply(&map_file_path))
+            .map_err(RcssError::io(&map_file_path))?;
+            log::verbose(format_args!("wrote {map_file_path}"));
+        }
+    }
+    phases.write += elapsed();
+    report.output = format!("{output_directory}/{output_file_name}");
+    report.summary = report::Summary {
+        output: report.output.clone(),
+        files: sources.len(),
+        input_bytes,
+        output_bytes: appended_bytes,
+        phases,
+        profile: None,
+    };
+    if options.profile {
+        let files = sources
+            .iter()
+            .zip(file_times)
+            .map(|((file_path, source), time)| profile::FileTime {
+                path: ordering::relative_path(file_path, destination_folder_path),
+                bytes: source_bytes(source),
+                time,
+            })
+            .collect();
+        report.summary.profile = Some(profile::Profile {
+            files,
+            passes: pass_times,
+        });
+    }
+    if options.check {
+        let existing = match fs::read_to_string(&report.output) {
+            Ok(existing) => Some(existing),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => None,
+            Err(error) => return Err(RcssError::io(&report.output)(error)),
+        };
+        let built = output.to_string();
+        if let Some(out_of_date) = check::compare(&report.output, existing.as_deref(), &built) {
+            return Err(RcssError::OutOfDate(out_of_date));
+        }
+    }
+    if dry_run {
+        report.output_bytes = appended_bytes;
+        report.summary.phases.minify += elapsed();
+        return Ok(report);
+    }
+    for compression in &options.compress {
+        let compressed_file_path = format!(
+            "{output_directory}/{output_file_name}.{}",
+            compression.extension()
+        );
+        output::write_atomically(&compressed_file_path, |file| {
+            compression.compress_to(file, output.bytes())
+        })
+        .and_then(|()| output_metadata.apply(&compressed_file_path))
+        .map_err(RcssError::io(&compressed_file_path))?;
+        log::verbose(format_args!("wrote {compressed_file_path}"));
+    }
+    let output_file_path = format!("{output_directory}/{output_file_name}");
+    // files combined as they are can span several lines themselves
+    if rule_set.is_css()
+        && !is_concatenated
+        && !options.in_place
+        && !options.skip_minified
+        && !options.append
+    {
+        if let Ok(existing) = fs::read_to_string(&output_file_path) {
+            if looks_like_source(&existing) {
+                log::warn(format_args!(
+                    "overwriting {output_file_path}, which looks like a source file rather than \
+                     minified output"
+                ));
+            }
+        }
+    }
+    output
+        .write_file(&output_file_path)
+        .and_then(|()| output_metadata.apply(&output_file_path))
+        .map_err(RcssError::io(&output_file_path))?;
+    log::verbose(format_args!("wrote {output_file_path}"));
+    report.summary.phases.write += elapsed();
+    Ok(report)
+}
+
+/// [`layer_root`] returns the directory that the layer name of `file_path`,
+/// and its path within [`Options::out_dir`], are relative to: whichever of
+/// `directory` and the extra [`Options::directories`] it is within.
+fn layer_root<'a>(file_path: &Path, directory: &'a str, options: &'a Options) -> &'a str {
+    options
+        .directories
+        .iter()
+        .find(|extra| file_path.starts_with(extra))
+        .map_or(directory, String::as_str)
+}
+
+/// [`check_level`] fails if `options` turn on something that can't be done
+/// at their [`Options::level`], like passes at [`level::Level::Concatenate`].
+fn check_level(options: &Options) -> Result<()> {
+    if options.level == level::Level::Concatenate
+        && (options.source_map
+            || options.has_structural_passes()
+            || options.layers
+            || options.layer_name.is_some())
+    {
+        return Err(RcssError::Config(
+            "-O0 only concatenates files, so it can't be used with source maps, passes, or layers"
+                .to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// [`check_append`] fails if [`Options::layer_name`] isn't a valid layer
+/// name, or if [`Options::append`] is used with anything that needs the
+/// whole output to be built at once, like a source map or a content hash.
+fn check_append(options: &Options) -> Result<()> {
+    if let Some(name) = &options.layer_name {
+        if !layers::is_valid_name(name) {
+            return Err(RcssError::Config(format!(
+                "{name} isn't a valid layer name, like theme or themes.acme"
+            )));
+        }
+    }
+    if options.append
+        && (options.source_map
+            || options.hash
+            || options.clean
+            || options.check
+            || options.in_place)
+    {
+        return Err(RcssError::Config(
+            "--append can't be used with --source-map, --hash, --clean, --check, or --in-place"
+                .to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// [`clean_outputs`] removes the `file_name` output of earlier runs from
+/// `directory`, along with its source map, its class name mapping, its
+/// compressed copies, and its fingerprinted copies.
+fn clean_outputs(directory: &str, file_name: &str) -> Result<()> {
+    for suffix in ["", ".map", ".json", ".gz", ".br"] {
+        let path = format!("{directory}/{file_name}{suffix}");
+        match fs::remove_file(&path) {
+            Ok(()) => log::verbose(format_args!("removed {path}")),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {}
+            Err(error) => return Err(RcssError::io(&path)(error)),
+        }
+    }
+    match Path::new(directory).is_dir() {
+        true => remove_fingerprinted_outputs(directory, file_name),
+        false => Ok(()),
+    }
+}
+
+/// [`MINIFIED_LINE`] is how long a line must be, in bytes, for a file made
+/// mostly of that line to look minified already.
+const MINIFIED_LINE: usize = 500;
+
+/// [`looks_minified`] returns `true` if the file at `file_path` with
+/// `content` looks minified already: if it is named like `bootstrap.min.css`,
+/// or is almost all one long line, like a vendor bundle after its license
+/// comment.
+fn looks_minified(file_path: &Path, content: &str) -> bool {
+    let file_name = file_path.file_name().unwrap_or_default().to_string_lossy();
+    let longest_line = content.lines().map(str::len).max().unwrap_or_default();
+    file_name.contains(".min.")
+        || (longest_line >= MINIFIED_LINE && longest_line * 10 >= content.len() * 9)
+}
+
+/// [`looks_like_source`] returns `true` if the css `content` of an existing
+/// destination file spans several lines outside of its license comments,
+/// which the single line that rcss writes never does.
+fn looks_like_source(content: &str) -> bool {
+    let content = css::license_comments(content)
+        .into_iter()
+        .fold(content.to_string(), |rest, comment| {
+            rest.replacen(comment, "", 1)
+        });
+    content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .count()
+        > 1
+}
+
+/// [`relative_to`] returns the path to `path` from `directory`, like
+/// `../src/a.css` for `src/a.css` from `dist`, so a source map can point
+/// at its sources from wherever it is written.
+fn relative_to(path: &Path, directory: &Path) -> Result<PathBuf> {
+    let path = std::path::absolute(path).map_err(RcssError::io(path))?;
+    let directory = std::path::absolute(directory).map_err(RcssError::io(directory))?;
+    let path_components = path.components().collect::<Vec<_>>();
+    let directory_components = directory.components().collect::<Vec<_>>();
+    let shared = path_components
+        .iter()
+        .zip(&directory_components)
+        .take_while(|(left, right)| left == right)
+        .count();
+    let mut relative = PathBuf::new();
+    for _ in shared..directory_components.len() {
+        relative.push("..");
+    }
+    relative.extend(&path_components[shared..]);
+    Ok(relative)
+}
+
+/// [`replace_urls`] inlines the small assets of the minified `css` of the
+/// file at `file_path` (if [`Options::inline_assets`] is set), and rewrites
+/// the rest of its relative urls to be relative to `output_directory` (if
+/// [`Options::rewrite_urls`] is set), or returns `None` if neither is.
+fn replace_urls(
+    css: &str,
+    file_path: &Path,
+    output_directory: &str,
+    options: &Options,
+) -> Result<Option<(String, Vec<urls::Edit>)>> {
+    if !options.rewrite_urls && options.inline_assets.is_none() {
+        return Ok(None);
+    }
+    let directory = file_path.parent().unwrap_or(Path::new(""));
+    let prefix = match options.rewrite_urls {
+        true => {
+            let prefix = relative_to(directory, Path::new(output_directory))?;
+            Some(prefix.to_string_lossy().replace('\\', "/"))
+        }
+        false => None,
+    };
+    Ok(Some(urls::rewrite(css, |url| {
+        let inlined = options
+            .inline_assets
+            .and_then(|threshold| urls::data_uri(directory, url, threshold));
+        inlined.or_else(|| prefix.as_deref().map(|prefix| urls::join(prefix, url)))
+    })))
+}
+
+/// [`without_hoisted`] returns the minified output of `entry`, and its
+/// source map mappings, without the statements that [`css::split_hoisted`]
+/// moves to the top of the combined output (if it is `is_css`).
+fn without_hoisted(entry: &cache::Entry, is_css: bool) -> (&str, Cow<'_, [source_map::Mapping]>) {
+    let (_, rest) = css::split_hoisted(&entry.minified);
+    if !is_css || rest.len() == entry.minified.len() {
+        return (&entry.minified, Cow::Borrowed(&entry.mappings));
+    }
+    let removed = entry.minified[..entry.minified.len() - rest.len()]
+        .encode_utf16()
+        .count();
+    let mappings = entry
+        .mappings
+        .iter()
+        .filter(|mapping| mapping.generated_column >= removed)
+        .map(|mapping| source_map::Mapping {
+            generated_column: mapping.generated_column - removed,
+            ..*mapping
+        })
+        .collect();
+    (rest, Cow::Owned(mappings))
+}
+
+/// [`has_extension`] returns `true` if the name of the file at `path` ends
+/// with `extension`, even if the rest of it isn't valid utf-8.
+fn has_extension(path: &Path, extension: &str) -> bool {
+    path.as_os_str()
+        .as_encoded_bytes()
+        .ends_with(extension.as_bytes())
+}
+
+/// [`remove_fingerprinted_outputs`] removes every fingerprinted copy of
+/// `file_name` (and its source map and compressed copies) written to
+/// `directory` by an earlier run.
+fn remove_fingerprinted_outputs(directory: &str, file_name: &str) -> Result<()> {
+    for entry in fs::read_dir(directory).map_err(RcssError::io(directory))? {
+        let path = entry.map_err(RcssError::io(directory))?.path();
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        let name = [".map", ".gz", ".br"]
+            .iter()
+            .find_map(|suffix| name.strip_suffix(suffix))
+            .unwrap_or(&name);
+        if fingerprint::is_hashed_name(name, file_name) {
+            fs::remove_file(&path).map_err(RcssError::io(&path))?;
+        }
+    }
+    Ok(())
+}
+
+/// [`minify_bundles`] combines the files of type `extension` within the
+/// directory of each of `bundles` into its output, as configured by
+/// `options`. it stops at the first bundle that can't be built.
+///
+/// # example
+/// [`minify_bundles`] can be used to build a critical and a main stylesheet
+/// in one go:
+/// ```rust,no_run
+/// use rcss::bundle::Bundle;
+///
+/// let bundles = [
+///     Bundle::new("./assets/css/critical", "critical.css"),
+///     Bundle::new("./assets/css/main", "main.css"),
+/// ];
+/// rcss::minify_bundles("css", &bundles, &rcss::Options::default()).unwrap();
+/// ```
+pub fn minify_bundles(
+    extension: &str,
+    bundles: &[bundle::Bundle],
+    options: &Options,
+) -> Result<()> {
+    for bundle in bundles {
+        minify_directory_with(extension, &bundle.directory, &bundle.output, options)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,774 @@
+//! linting of css, which walks the same tokens that minification does to
+//! find likely mistakes, like duplicate selectors, unknown properties,
+//! invalid colors, empty rules, and too many `!important`s.
+use std::{collections::HashMap, fmt, fs, path::Path};
+
+use crate::{
+    colors,
+    error::{RcssError, Result},
+    glob, ordering, paths,
+    tokenizer::{tokenize, Token, TokenKind},
+    Options,
+};
+
+/// [`MAX_IMPORTANT`] is how many declarations of a single file can use
+/// `!important` before it is reported.
+pub const MAX_IMPORTANT: usize = 10;
+
+/// [`PROPERTIES`] are the standard css properties, and the descriptors of
+/// at-rules like `@font-face`. vendor prefixed and custom properties are
+/// never reported, so they aren't listed.
+const PROPERTIES: &[&str] = &[
+    "accent-color",
+    "additive-symbols",
+    "align-content",
+    "align-items",
+    "align-self",
+    "alignment-baseline",
+    "all",
+    "animation",
+    "animation-composition",
+    "animation-delay",
+    "animation-direction",
+    "animation-duration",
+    "animation-fill-mode",
+    "animation-iteration-count",
+    "animation-name",
+    "animation-play-state",
+    "animation-range",
+    "animation-timeline",
+    "animation-timing-function",
+    "appearance",
+    "ascent-override",
+    "aspect-ratio",
+    "backdrop-filter",
+    "backface-visibility",
+    "background",
+    "background-attachment",
+    "background-blend-mode",
+    "background-clip",
+    "background-color",
+    "background-image",
+    "background-origin",
+    "background-position",
+    "background-position-x",
+    "background-position-y",
+    "background-repeat",
+    "background-size",
+    "baseline-shift",
+    "block-size",
+    "border",
+    "border-block",
+    "border-block-color",
+    "border-block-end",
+    "border-block-end-color",
+    "border-block-end-style",
+    "border-block-end-width",
+    "border-block-start",
+    "border-block-start-color",
+    "border-block-start-style",
+    "border-block-start-width",
+    "border-block-style",
+    "border-block-width",
+    "border-bottom",
+    "border-bottom-color",
+    "border-bottom-left-radius",
+    "border-bottom-right-radius",
+    "border-bottom-style",
+    "border-bottom-width",
+    "border-collapse",
+    "border-color",
+    "border-end-end-radius",
+    "border-end-start-radius",
+    "border-image",
+    "border-image-outset",
+    "border-image-repeat",
+    "border-image-slice",
+    "border-image-source",
+    "border-image-width",
+    "border-inline",
+    "border-inline-color",
+    "border-inline-end",
+    "border-inline-end-color",
+    "border-inline-end-style",
+    "border-inline-end-width",
+    "border-inline-start",
+    "border-inline-start-color",
+    "border-inline-start-style",
+    "border-inline-start-width",
+    "border-inline-style",
+    "border-inline-width",
+    "border-left",
+    "border-left-color",
+    "border-left-style",
+    "border-left-width",
+    "border-radius",
+    "border-right",
+    "border-right-color",
+    "border-right-style",
+    "border-right-width",
+    "border-spacing",
+    "border-start-end-radius",
+    "border-start-start-radius",
+    "border-style",
+    "border-top",
+    "border-top-color",
+    "border-top-left-radius",
+    "border-top-right-radius",
+    "border-top-style",
+    "border-top-width",
+    "border-width",
+    "bottom",
+    "box-decoration-break",
+    "box-shadow",
+    "box-sizing",
+    "break-after",
+    "break-before",
+    "break-inside",
+    "caption-side",
+    "caret-color",
+    "clear",
+    "clip",
+    "clip-path",
+    "clip-rule",
+    "color",
+    "color-interpolation",
+    "color-interpolation-filters",
+    "color-scheme",
+    "column-count",
+    "column-fill",
+    "column-gap",
+    "column-rule",
+    "column-rule-color",
+    "column-rule-style",
+    "column-rule-width",
+    "column-span",
+    "column-width",
+    "columns",
+    "contain",
+    "contain-intrinsic-block-size",
+    "contain-intrinsic-height",
+    "contain-intrinsic-inline-size",
+    "contain-intrinsic-size",
+    "contain-intrinsic-width",
+    "container",
+    "container-name",
+    "container-type",
+    "content",
+    "content-visibility",
+    "counter-increment",
+    "counter-reset",
+    "counter-set",
+    "cursor",
+    "cx",
+    "cy",
+    "d",
+    "descent-override",
+    "direction",
+    "display",
+    "dominant-baseline",
+    "empty-cells",
+    "fallback",
+    "fill",
+    "fill-opacity",
+    "fill-rule",
+    "filter",
+    "flex",
+    "flex-basis",
+    "flex-direction",
+    "flex-flow",
+    "flex-grow",
+    "flex-shrink",
+    "flex-wrap",
+    "float",
+    "flood-color",
+    "flood-opacity",
+    "font",
+    "font-display",
+    "font-family",
+    "font-feature-settings",
+    "font-kerning",
+    "font-language-override",
+    "font-optical-sizing",
+    "font-palette",
+    "font-size",
+    "font-size-adjust",
+    "font-stretch",
+    "font-style",
+    "font-synthesis",
+    "font-synthesis-small-caps",
+    "font-synthesis-style",
+    "font-synthesis-weight",
+    "font-variant",
+    "font-variant-alternates",
+    "font-variant-caps",
+    "font-variant-east-asian",
+    "font-variant-emoji",
+    "font-variant-ligatures",
+    "font-variant-numeric",
+    "font-variant-position",
+    "font-variation-settings",
+    "font-weight",
+    "forced-color-adjust",
+    "gap",
+    "grid",
+    "grid-area",
+    "grid-auto-columns",
+    "grid-auto-flow",
+    "grid-auto-rows",
+    "grid-column",
+    "grid-column-end",
+    "grid-column-gap",
+    "grid-column-start",
+    "grid-gap",
+    "grid-row",
+    "grid-row-end",
+    "grid-row-gap",
+    "grid-row-start",
+    "grid-template",
+    "grid-template-areas",
+    "grid-template-columns",
+    "grid-template-rows",
+    "hanging-punctuation",
+    "height",
+    "hyphenate-character",
+    "hyphens",
+    "image-orientation",
+    "image-rendering",
+    "inherits",
+    "initial-letter",
+    "initial-value",
+    "inline-size",
+    "inset",
+    "inset-block",
+    "inset-block-end",
+    "inset-block-start",
+    "inset-inline",
+    "inset-inline-end",
+    "inset-inline-start",
+    "isolation",
+    "justify-content",
+    "justify-items",
+    "justify-self",
+    "left",
+    "letter-spacing",
+    "lighting-color",
+    "line-break",
+    "line-gap-override",
+    "line-height",
+    "list-style",
+    "list-style-image",
+    "list-style-position",
+    "list-style-type",
+    "margin",
+    "margin-block",
+    "margin-block-end",
+    "margin-block-start",
+    "margin-bottom",
+    "margin-inline",
+    "margin-inline-end",
+    "margin-inline-start",
+    "margin-left",
+    "margin-right",
+    "margin-top",
+    "marker",
+    "marker-end",
+    "marker-mid",
+    "marker-start",
+    "mask",
+    "mask-border",
+    "mask-clip",
+    "mask-composite",
+    "mask-image",
+    "mask-mode",
+    "mask-origin",
+    "mask-position",
+    "mask-repeat",
+    "mask-size",
+    "mask-type",
+    "math-depth",
+    "math-shift",
+    "math-style",
+    "max-block-size",
+    "max-height",
+    "max-inline-size",
+    "max-width",
+    "min-block-size",
+    "min-height",
+    "min-inline-size",
+    "min-width",
+    "mix-blend-mode",
+    "negative",
+    "object-fit",
+    "object-position",
+    "offset",
+    "offset-anchor",
+    "offset-distance",
+    "offset-path",
+    "offset-position",
+    "offset-rotate",
+    "opacity",
+    "order",
+    "orphans",
+    "outline",
+    "outline-color",
+    "outline-offset",
+    "outline-style",
+    "outline-width",
+    "overflow",
+    "overflow-anchor",
+    "overflow-block",
+    "overflow-clip-margin",
+    "overflow-inline",
+    "overflow-wrap",
+    "overflow-x",
+    "overflow-y",
+    "overscroll-behavior",
+    "overscroll-behavior-block",
+    "overscroll-behavior-inline",
+    "overscroll-behavior-x",
+    "overscroll-behavior-y",
+    "pad",
+    "padding",
+    "padding-block",
+    "padding-block-end",
+    "padding-block-start",
+    "padding-bottom",
+    "padding-inline",
+    "padding-inline-end",
+    "padding-inline-start",
+    "padding-left",
+    "padding-right",
+    "padding-top",
+    "page",
+    "page-break-after",
+    "page-break-before",
+    "page-break-inside",
+    "paint-order",
+    "perspective",
+    "perspective-origin",
+    "place-content",
+    "place-items",
+    "place-self",
+    "pointer-events",
+    "position",
+    "prefix",
+    "print-color-adjust",
+    "quotes",
+    "r",
+    "range",
+    "resize",
+    "right",
+    "rotate",
+    "row-gap",
+    "ruby-align",
+    "ruby-position",
+    "rx",
+    "ry",
+    "scale",
+    "scroll-behavior",
+    "scroll-margin",
+    "scroll-margin-block",
+    "scroll-margin-block-end",
+    "scroll-margin-block-start",
+    "scroll-margin-bottom",
+    "scroll-margin-inline",
+    "scroll-margin-inline-end",
+    "scroll-margin-inline-start",
+    "scroll-margin-left",
+    "scroll-margin-right",
+    "scroll-margin-top",
+    "scroll-padding",
+    "scroll-padding-block",
+    "scroll-padding-block-end",
+    "scroll-padding-block-start",
+    "scroll-padding-bottom",
+    "scroll-padding-inline",
+    "scroll-padding-inline-end",
+    "scroll-padding-inline-start",
+    "scroll-padding-left",
+    "scroll-padding-right",
+    "scroll-padding-top",
+    "scroll-snap-align",
+    "scroll-snap-stop",
+    "scroll-snap-type",
+    "scroll-timeline",
+    "scroll-timeline-axis",
+    "scroll-timeline-name",
+    "scrollbar-color",
+    "scrollbar-gutter",
+    "scrollbar-width",
+    "shape-image-threshold",
+    "shape-margin",
+    "shape-outside",
+    "shape-rendering",
+    "size",
+    "size-adjust",
+    "speak-as",
+    "src",
+    "stop-color",
+    "stop-opacity",
+    "stroke",
+    "stroke-dasharray",
+    "stroke-dashoffset",
+    "stroke-linecap",
+    "stroke-linejoin",
+    "stroke-miterlimit",
+    "stroke-opacity",
+    "stroke-width",
+    "suffix",
+    "symbols",
+    "syntax",
+    "system",
+    "tab-size",
+    "table-layout",
+    "text-align",
+    "text-align-last",
+    "text-anchor",
+    "text-combine-upright",
+    "text-decoration",
+    "text-decoration-color",
+    "text-decoration-line",
+    "text-decoration-skip-ink",
+    "text-decoration-style",
+    "text-decoration-thickness",
+    "text-emphasis",
+    "text-emphasis-color",
+    "text-emphasis-position",
+    "text-emphasis-style",
+    "text-indent",
+    "text-justify",
+    "text-orientation",
+    "text-overflow",
+    "text-rendering",
+    "text-shadow",
+    "text-size-adjust",
+    "text-transform",
+    "text-underline-offset",
+    "text-underline-position",
+    "text-wrap",
+    "top",
+    "touch-action",
+    "transform",
+    "transform-box",
+    "transform-origin",
+    "transform-style",
+    "transition",
+    "transition-behavior",
+    "transition-delay",
+    "transition-duration",
+    "transition-property",
+    "transition-timing-function",
+    "translate",
+    "unicode-bidi",
+    "unicode-range",
+    "user-select",
+    "vector-effect",
+    "vertical-align",
+    "view-timeline",
+    "view-timeline-axis",
+    "view-timeline-inset",
+    "view-timeline-name",
+    "view-transition-name",
+    "visibility",
+    "white-space",
+    "white-space-collapse",
+    "widows",
+    "width",
+    "will-change",
+    "word-break",
+    "word-spacing",
+    "word-wrap",
+    "writing-mode",
+    "x",
+    "y",
+    "z-index",
+    "zoom",
+];
+
+/// [`Check`] is the kind of mistake a [`Warning`] reports.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Check {
+    /// a selector that already has a rule within the same block.
+    DuplicateSelector,
+    /// a property that isn't a standard css property.
+    UnknownProperty,
+    /// a hex color with the wrong number of digits, or a misspelled color
+    /// name.
+    InvalidColor,
+    /// a rule or at-rule with nothing in its block, like `.a{}`.
+    EmptyRule,
+    /// a file with more than [`MAX_IMPORTANT`] `!important` declarations.
+    ImportantOveruse,
+}
+
+impl Check {
+    /// [`Check::name`] returns the name printed after every warning of
+    /// this check, like `duplicate-selector`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Check::DuplicateSelector => "duplicate-selector",
+            Check::UnknownProperty => "unknown-property",
+            Check::InvalidColor => "invalid-color",
+            Check::EmptyRule => "empty-rule",
+            Check::ImportantOveruse => "important-overuse",
+        }
+    }
+}
+
+/// [`Warning`] is a likely mistake, and the 1-based line and column of
+/// the file it was found at.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Warning {
+    pub path: String,
+    pub line: usize,
+    pub column: usize,
+    pub check: Check,
+    pub message: String,
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}: {} ({})",
+            self.path,
+            self.line,
+            self.column,
+            self.message,
+            self.check.name()
+        )
+    }
+}
+
+/// [`Block`] is a `{ ... }` block that is still open.
+struct Block<'a> {
+    /// the selector or at-rule prelude that opened the block, and where it
+    /// starts.
+    label: String,
+    start: Option<Token<'a>>,
+    is_empty: bool,
+    /// the line of every selector with a rule directly within the block.
+    selectors: HashMap<String, usize>,
+}
+
+/// [`lint`] returns every [`Warning`] within the stylesheet `css`, in the
+/// order they appear, reporting them as found in the file at `path`.
+///
+/// # example
+/// [`lint`] can be used to check a stylesheet before it is minified:
+/// ```rust
+/// use rcss::lint::{lint, Check};
+///
+/// let warnings = lint("style.css", ".a{colr:red}\n.a{color:#ff}");
+/// let checks = warnings.iter().map(|warning| warning.check).collect::<Vec<_>>();
+/// assert_eq!(checks, [Check::UnknownProperty, Check::DuplicateSelector, Check::InvalidColor]);
+/// assert_eq!(warnings[1].to_string(), "style.css:2:1: selector .a was already used on line 1 (duplicate-selector)");
+/// ```
+pub fn lint(path: &str, css: &str) -> Vec<Warning> {
+    let mut warnings = vec![];
+    let mut warn = |token: &Token, check: Check, message: String| {
+        warnings.push(Warning {
+            path: path.to_string(),
+            line: token.line,
+            column: token.column,
+            check,
+            message,
+        })
+    };
+    let mut blocks = vec![Block {
+        label: String::new(),
+        start: None,
+        is_empty: true,
+        selectors: HashMap::new(),
+    }];
+    let mut statement: Vec<Token> = vec![];
+    let mut importants = vec![];
+    let mut nesting = 0usize;
+    for token in tokenize(css) {
+        match token.kind {
+            TokenKind::Comment => continue,
+            TokenKind::Function | TokenKind::OpenParen | TokenKind::OpenBracket => nesting += 1,
+            TokenKind::CloseParen | TokenKind::CloseBracket => nesting = nesting.saturating_sub(1),
+            _ => {}
+        }
+        if nesting > 0 {
+            statement.push(token);
+            continue;
+        }
+        match token.kind {
+            TokenKind::OpenBrace => {
+                let prelude = statement
+                    .iter()
+                    .filter(|token| token.kind != TokenKind::Whitespace)
+                    .copied()
+                    .collect::<Vec<_>>();
+                let start = prelude.first().copied().unwrap_or(token);
+                let label = normalize(&statement);
+                // the top level block is never closed, so there is always a
+                // parent
+                if let Some(parent) = blocks.last_mut() {
+                    parent.is_empty = false;
+                    if start.kind != TokenKind::AtKeyword && !label.is_empty() {
+                        match parent.selectors.get(&label) {
+                            Some(line) => warn(
+                                &start,
+                                Check::DuplicateSelector,
+                                format!("selector {label} was already used on line {line}"),
+                            ),
+                            None => {
+                                parent.selectors.insert(label.clone(), start.line);
+                            }
+                        }
+                    }
+                }
+                blocks.push(Block {
+                    label,
+                    start: Some(start),
+                    is_empty: true,
+                    selectors: HashMap::new(),
+                });
+                statement.clear();
+            }
+            TokenKind::Semicolon | TokenKind::CloseBrace => {
+                let tokens = statement
+                    .iter()
+                    .filter(|token| token.kind != TokenKind::Whitespace)
+                    .copied()
+                    .collect::<Vec<_>>();
+                if let (false, Some(block)) = (tokens.is_empty(), blocks.last_mut()) {
+                    block.is_empty = false;
+                    if blocks.len() > 1 {
+                        lint_declaration(&tokens, &mut warn, &mut importants);
+                    }
+                }
+                statement.clear();
+                let closed = match token.kind == TokenKind::CloseBrace && blocks.len() > 1 {
+                    true => blocks.pop(),
+                    false => None,
+                };
+                if let Some(block) = closed {
+                    if let (true, Some(start)) = (block.is_empty, block.start) {
+                        let message = match block.label.starts_with('@') {
+                            true => format!("empty {} block", block.label),
+                            false => format!("empty rule {}", block.label),
+                        };
+                        warn(&start, Check::EmptyRule, message);
+                    }
+                }
+            }
+            _ => statement.push(token),
+        }
+    }
+    if let Some(token) = importants.get(MAX_IMPORTANT) {
+        warn(
+            token,
+            Check::ImportantOveruse,
+            format!(
+                "!important is used {} times, more than the {MAX_IMPORTANT} allowed",
+                importants.len()
+            ),
+        );
+    }
+    warnings.sort_by_key(|warning| (warning.line, warning.column));
+    warnings
+}
+
+/// [`lint_declaration`] checks the declaration made of `tokens` (without
+/// any whitespace), keeping track of where `!important` is used.
+fn lint_declaration<'a>(
+    tokens: &[Token<'a>],
+    warn: &mut impl FnMut(&Token, Check, String),
+    importants: &mut Vec<Token<'a>>,
+) {
+    let [property, TokenKind::Colon, ..] = tokens
+        .iter()
+        .take(2)
+        .map(|token| token.kind)
+        .collect::<Vec<_>>()[..]
+    else {
+        return;
+    };
+    if property != TokenKind::Ident {
+        return;
+    }
+    let name = tokens[0].text.to_ascii_lowercase();
+    // custom and vendor prefixed properties can be anything
+    if !name.starts_with('-') && !PROPERTIES.contains(&name.as_str()) {
+        warn(
+            &tokens[0],
+            Check::UnknownProperty,
+            format!("unknown property {}", tokens[0].text),
+        );
+    }
+    if name.starts_with("--") {
+        return;
+    }
+    let mut value = &tokens[2..];
+    if let [.., bang, important] = value {
+        if bang.text == "!" && important.text.eq_ignore_ascii_case("important") {
+            importants.push(*bang);
+            value = &value[..value.len() - 2];
+        }
+    }
+    for token in value.iter().filter(|token| token.kind == TokenKind::Hash) {
+        if !colors::is_valid_hex(token.text) {
+            warn(
+                token,
+                Check::InvalidColor,
+                format!("invalid color {}", token.text),
+            );
+        }
+    }
+    if let [color] = value {
+        if name.ends_with("color")
+            && color.kind == TokenKind::Ident
+            && !colors::is_named(color.text)
+        {
+            warn(
+                color,
+                Check::InvalidColor,
+                format!("invalid color {}", color.text),
+            );
+        }
+    }
+}
+
+/// [`normalize`] joins `tokens`, collapsing every run of whitespace into a
+/// single space, so the same selector is always written the same way.
+fn normalize(tokens: &[Token]) -> String {
+    let mut normalized = String::new();
+    for token in tokens {
+        match token.kind {
+            TokenKind::Whitespace => normalized.push(' '),
+            _ => normalized.push_str(token.text),
+        }
+    }
+    normalized.trim().to_string()
+}
+
+/// [`lint_directory`] lints every css file within `directory`, except
+/// `output` (the minified file rcss writes there), and returns their
+/// warnings, file by file.
+///
+/// # example
+/// [`lint_directory`] can be used to print every warning in `./assets/css`:
+/// ```rust,no_run
+/// for warning in rcss::lint::lint_directory("./assets/css", "style.css", &Default::default()).unwrap() {
+///     println!("{warning}");
+/// }
+/// ```
+pub fn lint_directory(directory: &str, output: &str, options: &Options) -> Result<Vec<Warning>> {
+    let filter = glob::FileFilter::new(&options.include, &options.exclude);
+    let output_path = Path::new(directory).join(output);
+    let files = crate::recurse_files(directory, &filter, options)?
+        .into_iter()
+        .filter(|file| crate::has_extension(file, "css") && !paths::same_file(file, &output_path))
+        .collect();
+    let mut warnings = vec![];
+    for file in ordering::order_files(files, directory, &options.order, options.sort)? {
+        let content = fs::read_to_string(&file).map_err(RcssError::io(&file))?;
+        warnings.extend(lint(&file.display().to_string(), &content));
+    }
+    Ok(warnings)
+}
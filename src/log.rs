@@ -0,0 +1,75 @@
+//! leveled diagnostics, which are always written to stderr so that stdout
+//! stays clean for minified output and reports.
+use std::{
+    fmt,
+    sync::atomic::{AtomicU8, Ordering},
+};
+
+static LEVEL: AtomicU8 = AtomicU8::new(Level::Normal as u8);
+
+/// [`Level`] is how much rcss prints while it works, from nothing (but
+/// errors) to every directory it walks.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    /// `--quiet`: only errors are printed.
+    Quiet,
+    /// the default: only a summary of every build, and what rcss is waiting
+    /// for, like watched directories.
+    #[default]
+    Normal,
+    /// `-v` or `--verbose`: also every file that is written.
+    Verbose,
+    /// `-vv`: also every directory that is walked.
+    Debug,
+}
+
+/// [`set_level`] changes the [`Level`] of every diagnostic printed after it.
+///
+/// # example
+/// [`set_level`] can be used to silence everything but errors:
+/// ```rust
+/// use rcss::log::{self, Level};
+///
+/// log::set_level(Level::Quiet);
+/// assert_eq!(log::level(), Level::Quiet);
+/// ```
+pub fn set_level(level: Level) {
+    LEVEL.store(level as u8, Ordering::Relaxed);
+}
+
+/// [`level`] returns the current [`Level`].
+pub fn level() -> Level {
+    match LEVEL.load(Ordering::Relaxed) {
+        0 => Level::Quiet,
+        1 => Level::Normal,
+        2 => Level::Verbose,
+        _ => Level::Debug,
+    }
+}
+
+/// [`info`] prints `message` unless the level is [`Level::Quiet`].
+pub fn info(message: impl fmt::Display) {
+    print(Level::Normal, message);
+}
+
+/// [`warn`] prints `message` as a warning unless the level is
+/// [`Level::Quiet`].
+pub fn warn(message: impl fmt::Display) {
+    print(Level::Normal, format_args!("warning: {message}"));
+}
+
+/// [`verbose`] prints `message` at [`Level::Verbose`] or above.
+pub fn verbose(message: impl fmt::Display) {
+    print(Level::Verbose, message);
+}
+
+/// [`debug`] prints `message` at [`Level::Debug`].
+pub fn debug(message: impl fmt::Display) {
+    print(Level::Debug, message);
+}
+
+fn print(minimum: Level, message: impl fmt::Display) {
+    if level() >= minimum {
+        eprintln!("{message}");
+    }
+}
@@ -1,152 +1,307 @@
-extern crate regex;
-use regex::Regex;
+mod cli;
+mod diagnostics;
+
 use std::{
     env,
-    fs::{self, read_dir},
-    io::{Read, Write},
-    path::{Path, PathBuf},
+    io::{self, Read, Write},
+    path::Path,
+    process,
+    time::Duration,
 };
 
-/// [`recurse_files`] will traverse a directory and find all
-/// file paths within that directory, then return the resulting
-/// file paths as a `std::io:Result<Vec<String>>`, after which
-/// [`core::option::Option::unwrap_or_else`] can be used to
-/// retrieve the `Vec<String>`.
-///
-/// # example
-/// [`recurse_files`] can be used to gather a list of all
-/// file paths in a directory, including subdirectories, and
-/// return that list of files:
-/// ```rust
-/// fn get_file_paths(path_to_recurse: impl AsRef<Path>) -> std::io::Result<Vec<String>> {
-///     recurse_files(path_to_recurse).unwrap_or_else(|_| {
-///         panic!("could not recurse through the {directory_to_recurse} directory")
-///     });
-/// }
-/// ```
-fn recurse_files(user_path: impl AsRef<Path>) -> std::io::Result<Vec<String>> {
-    let mut buf = vec![];
-    println!("{:#?}", user_path.as_ref());
-    let absolute_path = PathBuf::from(user_path.as_ref());
-    println!("{:#?}", absolute_path);
-    let entries = read_dir(absolute_path)?;
-    for entry in entries {
-        let entry = entry?;
-        let meta = entry.metadata()?;
-        if meta.is_dir() {
-            let mut subdir = recurse_files(entry.path())?;
-            buf.append(&mut subdir);
-        }
-        if meta.is_file() {
-            buf.push(entry.path().to_str().unwrap().to_string());
-        }
+use diagnostics::{fail, fail_with, EXIT_USAGE};
+use rcss::{
+    bundle::{Bundle, NamedBundle},
+    report::Summary,
+    RcssError,
+};
+
+/// [`Target`] is a bundle to build, with the extension and options to
+/// build it with.
+struct Target {
+    bundle: Bundle,
+    extension: String,
+    options: rcss::Options,
+}
+
+/// [`load_config`] reads [`rcss::Options`], any bundles, and any named
+/// bundles from the `rcss.toml` file in the current directory, along with
+/// the config itself, or uses the default options, no bundles, and an empty
+/// config if there isn't one.
+fn load_config() -> (
+    rcss::Options,
+    Vec<Bundle>,
+    Vec<NamedBundle>,
+    rcss::config::Config,
+) {
+    if !Path::new(rcss::config::CONFIG_FILE_NAME).is_file() {
+        let config = rcss::config::Config::default();
+        return (rcss::Options::default(), vec![], vec![], config);
     }
-    Ok(buf)
+    rcss::config::Config::load(rcss::config::CONFIG_FILE_NAME)
+        .and_then(|config| {
+            let options = rcss::Options::from_config(&config)?;
+            let bundles = Bundle::from_config(&config)?;
+            let named_bundles = NamedBundle::from_config(&config)?;
+            Ok((options, bundles, named_bundles, config))
+        })
+        .unwrap_or_else(|error| match error {
+            RcssError::Config(message) => fail(
+                format_args!("{}: {message}", rcss::config::CONFIG_FILE_NAME),
+                EXIT_USAGE,
+            ),
+            error => fail_with("could not read the config", &error),
+        })
 }
 
-/// [`minify`] will apply regex rules to files to reduce
-/// file size and enable multi-file development. it
-/// will normalize spaces, remove comments, remove
-/// unnecessary semi-colons, and trim spaces where
-/// appropriate.
-///
-/// # notes
-/// in the future, the `extension` parameter that is
-/// passed in [`minify_files`], which calls this, will
-/// be used to determine which rules to fill
-/// `patterns_and_replacement` with.
-///
-/// # example
-/// [`minify`] can be used to combine and minify the content
-/// from a `Vec<String>` `file_paths_to_minify`, then return
-///  that content as a [`String`]:
-/// ```rust
-/// fn minify_file_paths(file_paths_to_minify: Vec<String>) -> String {
-///     file_paths_to_minify
-///         .iter()
-///         .map(|file_path| {
-///             fs::OpenOptions::new()
-///                 .read(true)
-///                 .open(file_path)
-///                 .map(|mut file| minify(&mut file))
-///                 .unwrap()
-///         })
-///         .collect::<String>()
-/// }
-/// ```
-fn minify(file: &mut std::fs::File) -> String {
-    let mut string_buffer = String::new();
-    // note: these currently work best with CSS
-    let patterns_and_replacement = [
-        (Regex::new(r"\s+").unwrap(), " "),
-        (Regex::new(r"; }").unwrap(), "}"),
-        (Regex::new(r"([,:;\{\}>])\s").unwrap(), "${1}"),
-        (Regex::new(r"\s([,:;\{\}>])").unwrap(), "${1}"),
-        (Regex::new(r"0 0 0 0").unwrap(), "0"),
-        (Regex::new(r"/\*.*?\*/").unwrap(), ""),
-    ];
+/// [`print_stats`] prints the [`rcss::stats::Stats`] of the files in `bundle`.
+fn print_stats(bundle: &Bundle, options: &rcss::Options) -> rcss::error::Result<()> {
+    let stats = rcss::stats::stats_directory(&bundle.directory, &bundle.output, options)?;
+    println!("{stats}");
+    Ok(())
+}
 
-    let _ = file.read_to_string(&mut string_buffer);
-    for pattern in patterns_and_replacement {
-        string_buffer = pattern.0.replace_all(&string_buffer, pattern.1).to_string()
+/// [`build`] combines and minifies the files of `target`, and prints and
+/// returns a summary of what it did, or only prints a report of what would
+/// be written if `args.dry_run` is set.
+fn build(args: &cli::Args, target: &Target) -> rcss::error::Result<Vec<Summary>> {
+    let Target {
+        bundle,
+        extension,
+        options,
+    } = target;
+    if options.each || options.in_place {
+        return build_each(args, target);
+    }
+    if args.dry_run {
+        let report =
+            rcss::dry_run_directory(extension, &bundle.directory, &bundle.output, options)?;
+        println!("{report}");
+        print_profile(&report.summary);
+        return Ok(vec![]);
     }
-    string_buffer
+    let summary =
+        rcss::minify_directory_with(extension, &bundle.directory, &bundle.output, options)?;
+    rcss::log::info(&summary);
+    print_profile(&summary);
+    let output = match &options.out_dir {
+        Some(out_dir) => format!("{out_dir}/{}", bundle.output),
+        None => bundle.output.clone(),
+    };
+    match options.check {
+        true => rcss::log::verbose(format_args!("{output} is up to date")),
+        false => rcss::log::verbose(format_args!(
+            "minified {} files from {} into {}",
+            extension, bundle.directory, output
+        )),
+    }
+    Ok(vec![summary])
 }
 
-/// [`minify_files`] combines all files of type `extension`
-/// within the `./assets/` directory in the `destination_file`
-/// **in-place**, and doesn't return anything.
-///
-/// # notes
-/// the file referenced by `destination_file_path` must exist
-/// on-disk before the build process is started, otherwise this
-/// function won't be able to open the file in truncated/write
-/// mode.
-///
-/// # example
-/// [`minify_files`] can be used to combine and minify
-/// the content from all css files, and put their contents
-/// into `./assets/css/style.css`:
-/// ```rust
-/// fn minify_function() {
-///     minify_files("css", "./assets/css", "style.css");
-/// }
-/// ```
-fn minify_files(extension: &str, destination_folder_path: &str, destination_file_name: &str) {
-    let destination_file_path = &format!("{destination_folder_path}/{destination_file_name}");
-    println!("{}", destination_file_path);
-    let files_to_minify = recurse_files(destination_folder_path).unwrap_or_else(|_| {
-        panic!(
-            "could not open {} directory to minify {} files",
-            destination_folder_path, extension
-        )
-    });
-    let mut destination_file = fs::OpenOptions::new()
-        .create(true)
-        .write(true)
-        .truncate(true)
-        .open(destination_file_path)
-        .unwrap_or_else(|_| {
-            panic!(
-                "could not open destination file ({}).",
-                destination_file_path
-            )
+/// [`build_each`] minifies every file of `target` into its own file, and
+/// prints their total and returns a summary of each, or only prints a
+/// report of what would be written if `args.dry_run` is set.
+fn build_each(args: &cli::Args, target: &Target) -> rcss::error::Result<Vec<Summary>> {
+    let Target {
+        bundle,
+        extension,
+        options,
+    } = target;
+    if args.dry_run {
+        let reports = rcss::dry_run_each(extension, &bundle.directory, options)?;
+        for report in &reports {
+            println!("{report}");
+        }
+        let summaries = reports
+            .into_iter()
+            .map(|report| report.summary)
+            .collect::<Vec<_>>();
+        print_profile(&Summary::total(&bundle.directory, &summaries));
+        return Ok(vec![]);
+    }
+    let written = rcss::minify_each_with(extension, &bundle.directory, options)?;
+    let total = Summary::total(&bundle.directory, &written);
+    rcss::log::info(&total);
+    print_profile(&total);
+    match options.check {
+        true => rcss::log::verbose(format_args!(
+            "{} {} files from {} are up to date",
+            written.len(),
+            extension,
+            bundle.directory
+        )),
+        false => rcss::log::verbose(format_args!(
+            "minified {} {} files from {}",
+            written.len(),
+            extension,
+            bundle.directory
+        )),
+    }
+    Ok(written)
+}
+
+/// [`print_profile`] prints how long the slowest files and every pass of
+/// `summary` took, if it was profiled.
+fn print_profile(summary: &Summary) {
+    if let Some(profile) = &summary.profile {
+        rcss::log::info(profile);
+    }
+}
+
+/// [`write_report`] writes `summaries` as json to the `--report` file, if
+/// one was given.
+fn write_report(args: &cli::Args, summaries: &[Summary]) -> rcss::error::Result<()> {
+    let Some(report) = &args.report else {
+        return Ok(());
+    };
+    rcss::output::write_atomically(report, |file| {
+        file.write_all(rcss::report::write_summaries(summaries).as_bytes())
+    })
+    .map_err(RcssError::io(report))
+}
+
+/// [`apply_args`] turns on every option that was given on the command line,
+/// on top of the `options` from `rcss.toml`. a `--preset` leaves alone the
+/// options given on the command line, and those whose `rcss.toml` key
+/// `is_set`.
+fn apply_args(options: &mut rcss::Options, args: &cli::Args, is_set: impl Fn(&str) -> bool) {
+    options.source_map |= args.source_map;
+    options.inline_imports |= args.inline_imports;
+    options.layers |= args.layers;
+    options.layer_name = args.layer_name.clone().or(options.layer_name.clone());
+    options.append |= args.append;
+    options.requires |= args.requires;
+    options.banner = args.banner.clone().or(options.banner.clone());
+    options.footer = args.footer.clone().or(options.footer.clone());
+    options.placeholders |= args.placeholders;
+    options.defines.extend(args.defines.iter().cloned());
+    options.allow_undefined |= args.allow_undefined;
+    options.merge_duplicates |= args.merge_duplicates;
+    options.remove_overridden |= args.remove_overridden;
+    options.keep_fallbacks |= args.keep_fallbacks;
+    options.merge_selectors |= args.merge_selectors;
+    options.collapse_shorthands |= args.collapse_shorthands;
+    options.remove_empty_rules |= args.remove_empty_rules;
+    options.remove_unused_at_rules |= args.remove_unused_at_rules;
+    options.merge_media |= args.merge_media;
+    options.add_prefixes |= args.add_prefixes;
+    options.strip_prefixes |= args.strip_prefixes;
+    options.strip_hacks |= args.strip_hacks;
+    options.downlevel_colors |= args.downlevel_colors;
+    options.normalize_case |= args.normalize_case;
+    options.browsers = args.browsers.clone().unwrap_or(options.browsers.clone());
+    options.preserve_comments |= args.preserve_comments;
+    options.flatten_nesting |= args.flatten_nesting;
+    options.inline_variables |= args.inline_variables;
+    options.remove_unused_variables |= args.remove_unused_variables;
+    options.scope = args.scope.clone().or(options.scope.clone());
+    options.hash_classes |= args.hash_classes;
+    options.shorten_identifiers |= args.shorten_identifiers;
+    options
+        .keep_identifiers
+        .extend(args.keep_identifiers.iter().cloned());
+    options.hash |= args.hash;
+    options.cache |= args.cache;
+    for compression in &args.compress {
+        if !options.compress.contains(compression) {
+            options.compress.push(*compression);
+        }
+    }
+    options.file_list = args.file_list.clone().or(options.file_list.clone());
+    options.include.extend(args.include.iter().cloned());
+    options.exclude.extend(args.exclude.iter().cloned());
+    options.remove_keys.extend(
+        args.remove_keys
+            .iter()
+            .map(|pattern| rcss::glob::Glob::new(pattern)),
+    );
+    options.purge.extend(args.purge.iter().cloned());
+    options.critical = args.critical_html.clone();
+    options.jobs = args.jobs.unwrap_or(options.jobs);
+    options.follow_symlinks |= args.follow_symlinks;
+    options.hidden |= args.hidden;
+    options.max_depth = args.max_depth.or(options.max_depth);
+    options.out_dir = args.out_dir.clone().or(options.out_dir.clone());
+    options.max_size = args.max_size.or(options.max_size);
+    options.indent = args.indent.unwrap_or(options.indent);
+    options.strict |= args.strict;
+    options.rewrite_urls |= args.rewrite_urls;
+    options.inline_assets = args.inline_assets.or(options.inline_assets);
+    options.level = args.level.unwrap_or(options.level);
+    if let Some(preset) = args.preset {
+        preset.apply(options, |key| {
+            is_set(key) || args.given.iter().any(|flag| flag == key)
         });
-    let files_without_destination_file = files_to_minify
+    }
+    options.clean |= args.clean;
+    options.each |= args.each;
+    options.in_place |= args.in_place;
+    options.check |= args.check;
+    options.permissions_from = args
+        .permissions_from
+        .clone()
+        .or(options.permissions_from.clone());
+    options.mode = args.mode.or(options.mode);
+    options.preserve_mtime |= args.preserve_mtime;
+    options.skip_minified |= args.skip_minified;
+    options.gitignore |= args.gitignore;
+    options.profile |= args.profile;
+    options.progress |= args.progress;
+}
+
+/// [`find_named_bundles`] returns the named bundles that the `build`
+/// subcommand builds: the one called `name`, or all of them.
+fn find_named_bundles(named_bundles: Vec<NamedBundle>, name: Option<&str>) -> Vec<NamedBundle> {
+    if named_bundles.is_empty() {
+        fail(
+            format_args!(
+                "{} has no [bundles.<name>] tables to build",
+                rcss::config::CONFIG_FILE_NAME
+            ),
+            EXIT_USAGE,
+        );
+    }
+    let Some(name) = name else {
+        return named_bundles;
+    };
+    let names = named_bundles
         .iter()
-        .filter(|file| file.ends_with(extension) && !file.contains(destination_file_path))
+        .map(|named_bundle| named_bundle.name.clone())
         .collect::<Vec<_>>();
-    let minified_file_content: String = files_without_destination_file
-        .iter()
-        .map(|file_path| {
-            fs::OpenOptions::new()
-                .read(true)
-                .open(file_path)
-                .map(|mut file| minify(&mut file))
-                .unwrap()
-        })
-        .collect::<String>();
-    let _ = destination_file.write_all(minified_file_content.as_bytes());
+    let found = named_bundles
+        .into_iter()
+        .filter(|named_bundle| named_bundle.name == name)
+        .collect::<Vec<_>>();
+    if found.is_empty() {
+        fail(
+            format_args!(
+                "no bundle named {name}, expected one of {}",
+                names.join(", ")
+            ),
+            EXIT_USAGE,
+        );
+    }
+    found
+}
+
+/// [`minify_stdin`] reads all of stdin, and writes it minified with
+/// `options` (or formatted, for the `format` subcommand) to stdout. for the
+/// `lint` subcommand, it prints the warnings within it instead.
+fn minify_stdin(args: &cli::Args, options: rcss::Options) -> rcss::error::Result<()> {
+    let mut content = String::new();
+    io::stdin().read_to_string(&mut content)?;
+    if args.lint {
+        diagnostics::report_warnings(&rcss::lint::lint("<stdin>", &content));
+        return Ok(());
+    }
+    if args.format {
+        let formatted = rcss::format::format(&content, options.indent);
+        return Ok(io::stdout().write_all(formatted.as_bytes())?);
+    }
+    let minified = rcss::minifier::Minifier::with_options(options)
+        .extension(&args.extension)
+        .minify(&content)?;
+    Ok(io::stdout().write_all(minified.as_bytes())?)
 }
 
 /// [`main`] is the entry point for the rcss minification program.
@@ -155,22 +310,236 @@ fn minify_files(extension: &str, destination_folder_path: &str, destination_file
 /// `cargo run -- c:\some-dir\css`: will take all css files in the `c:\some-dir\css` path, and
 /// combine them into a new `c:\some-dir\css\style.css` file.
 ///
-/// `cargo run -- c:\some-dir\css new-style.css`: will take all css files in the  `c:\some-dir\css`
+/// `cargo run -- c:\some-dir\css new-style.css`: will take all css files in the `c:\some-dir\css`
 /// path, and combine them into a new `c:\some-dir\css\new-style.css` file.
+///
+/// every other subcommand and option is listed by `cargo run -- --help`, from [`cli::USAGE`].
 fn main() {
-    let args: Vec<String> = env::args().collect();
-    let css_folder = &args[1];
-    let destination_file = if args.len() > 2 { &args[2] } else { "" };
-    let default_destination_file = "style.css";
-
-    // throw if the directory argument is empty
-    assert!(!css_folder.is_empty());
+    // a non utf-8 argument would panic within `env::args`
+    let arguments = env::args_os().skip(1).map(|argument| {
+        argument.into_string().unwrap_or_else(|argument| {
+            fail_with("invalid argument", &RcssError::InvalidPath(argument.into()))
+        })
+    });
+    let args = match cli::parse(arguments) {
+        Ok(cli::Command::Run(args)) => *args,
+        Ok(cli::Command::Help) => {
+            println!("{}", cli::USAGE);
+            return;
+        }
+        Ok(cli::Command::Version) => {
+            println!("rcss {}", env!("CARGO_PKG_VERSION"));
+            return;
+        }
+        Err(error) => {
+            eprintln!("error: {error}\n\n{}", cli::USAGE);
+            process::exit(EXIT_USAGE);
+        }
+    };
+    rcss::log::set_level(args.log_level);
+    diagnostics::set_format(args.diagnostics);
+    let (mut options, config_bundles, named_bundles, config) = load_config();
+    apply_args(&mut options, &args, |key| config.get("", key).is_some());
+    if args.stdin {
+        minify_stdin(&args, options)
+            .unwrap_or_else(|error| fail_with("could not minify stdin", &error));
+        return;
+    }
+    let named_bundles = match args.build {
+        true => find_named_bundles(named_bundles, args.bundle_name.as_deref()),
+        false => named_bundles,
+    };
+    let named_targets = named_bundles.into_iter().map(|named_bundle| {
+        let mut options = named_bundle.options;
+        let table = format!("bundles.{}", named_bundle.name);
+        apply_args(&mut options, &args, |key| {
+            config.get(&table, key).is_some() || config.get("", key).is_some()
+        });
+        Target {
+            bundle: named_bundle.bundle,
+            extension: named_bundle.extension,
+            options,
+        }
+    });
+    // bundles from the command line replace every bundle of rcss.toml, and
+    // `build` only builds its named bundles
+    let mut bundles = args.bundles();
+    let is_from_config = bundles.is_empty();
+    if is_from_config && !args.build {
+        bundles = config_bundles;
+    }
+    let mut targets = bundles
+        .into_iter()
+        .enumerate()
+        .map(|(index, bundle)| {
+            let mut options = options.clone();
+            // the directories after <directory> only belong to its bundle
+            if index == 0 && !is_from_config && args.directory.is_some() {
+                options.directories.extend(args.directories.iter().cloned());
+                options.files.extend(args.files.iter().cloned());
+            }
+            Target {
+                bundle,
+                extension: args.extension.clone(),
+                options,
+            }
+        })
+        .collect::<Vec<_>>();
+    if is_from_config {
+        targets.extend(named_targets);
+    }
+    if targets.is_empty() {
+        eprintln!("error: missing <directory> argument\n\n{}", cli::USAGE);
+        process::exit(EXIT_USAGE);
+    }
+    // every rebuild would add the output onto the end of itself again
+    if args.watch && targets.iter().any(|target| target.options.append) {
+        fail("append can't be used with --watch", EXIT_USAGE);
+    }
 
-    // use our `default_destination_file` if no `destination_file` was provided
-    if destination_file.is_empty() {
-        minify_files("css", css_folder, default_destination_file);
+    if args.lint {
+        let mut warnings = vec![];
+        for Target {
+            bundle, options, ..
+        } in &targets
+        {
+            warnings.extend(
+                rcss::lint::lint_directory(&bundle.directory, &bundle.output, options)
+                    .unwrap_or_else(|error| {
+                        fail_with(format_args!("could not lint {}", bundle.directory), &error)
+                    }),
+            );
+        }
+        diagnostics::report_warnings(&warnings);
+        return;
+    }
+    if args.report_conflicts {
+        let mut conflicts = vec![];
+        for Target {
+            bundle, options, ..
+        } in &targets
+        {
+            conflicts.extend(
+                rcss::conflicts::conflicts_directory(&bundle.directory, &bundle.output, options)
+                    .unwrap_or_else(|error| {
+                        fail_with(
+                            format_args!("could not check {} for conflicts", bundle.directory),
+                            &error,
+                        )
+                    }),
+            );
+        }
+        diagnostics::report_conflicts(&conflicts);
+        return;
+    }
+    if args.specificity {
+        for Target {
+            bundle, options, ..
+        } in &targets
+        {
+            let selectors =
+                rcss::analyze::specificity_directory(&bundle.directory, &bundle.output, options)
+                    .unwrap_or_else(|error| {
+                        fail_with(
+                            format_args!("could not analyze {}", bundle.directory),
+                            &error,
+                        )
+                    });
+            for selector in selectors {
+                println!("{selector}");
+            }
+        }
+        return;
+    }
+    if args.format {
+        for Target {
+            bundle, options, ..
+        } in &targets
+        {
+            let changed =
+                rcss::format::format_directory(&bundle.directory, &bundle.output, options)
+                    .unwrap_or_else(|error| {
+                        fail_with(
+                            format_args!("could not format {}", bundle.directory),
+                            &error,
+                        )
+                    });
+            rcss::log::verbose(format_args!(
+                "formatted {changed} files in {}",
+                bundle.directory
+            ));
+        }
+        return;
+    }
+    if args.stats {
+        for Target {
+            bundle, options, ..
+        } in &targets
+        {
+            print_stats(bundle, options).unwrap_or_else(|error| {
+                fail_with(
+                    format_args!("could not analyze {}", bundle.directory),
+                    &error,
+                )
+            });
+        }
+        return;
+    }
+    let verb = match args.check {
+        true => "check",
+        false => "minify",
+    };
+    let mut summaries = vec![];
+    for target in &targets {
+        summaries.extend(build(&args, target).unwrap_or_else(|error| {
+            let into = match target.options.each || target.options.in_place {
+                true => String::new(),
+                false => format!(" into {}", target.bundle.output),
+            };
+            fail_with(
+                format_args!(
+                    "could not {verb} {} files from {}{into}",
+                    target.extension, target.bundle.directory
+                ),
+                &error,
+            )
+        }));
+    }
+    write_report(&args, &summaries)
+        .unwrap_or_else(|error| fail_with("could not write the report", &error));
+    if !args.watch {
         return;
     }
 
-    minify_files("css", css_folder, destination_file);
+    // every directory is watched with the options it is built with
+    let directories = targets
+        .iter()
+        .flat_map(|target| {
+            std::iter::once(&target.bundle.directory)
+                .chain(&target.options.directories)
+                .map(|directory| (directory.as_str(), &target.options))
+        })
+        .collect::<Vec<_>>();
+    let names = directories
+        .iter()
+        .map(|(directory, _)| *directory)
+        .collect::<Vec<_>>()
+        .join(", ");
+    rcss::log::info(format_args!("watching {names} for changes"));
+    rcss::watch::watch(&directories, Duration::from_millis(500), || {
+        let mut summaries = vec![];
+        for target in &targets {
+            match build(&args, target) {
+                Ok(target_summaries) => summaries.extend(target_summaries),
+                Err(error) => eprintln!(
+                    "error: could not rebuild {}: {}",
+                    target.bundle.output, error
+                ),
+            }
+        }
+        if let Err(error) = write_report(&args, &summaries) {
+            eprintln!("error: could not write the report: {error}");
+        }
+    })
+    .unwrap_or_else(|error| fail_with(format_args!("could not watch {names}"), &error));
 }
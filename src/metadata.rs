@@ -0,0 +1,115 @@
+//! the permissions, ownership, and modification time given to every file
+//! rcss writes, so outputs can be deployed as they are and `make`-style
+//! tools can tell when they are stale.
+use std::{
+    fs::{self, File, FileTimes, Metadata},
+    io,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use crate::{
+    error::{RcssError, Result},
+    Options,
+};
+
+/// [`parse_mode`] reads an octal file mode like `"644"`, `"0644"`, or
+/// `"0o755"`, or returns `None` if it isn't one.
+///
+/// # example
+/// [`parse_mode`] can be used to read the mode given to `--mode`:
+/// ```rust
+/// use rcss::metadata::parse_mode;
+///
+/// assert_eq!(parse_mode("644"), Some(0o644));
+/// assert_eq!(parse_mode("0o755"), Some(0o755));
+/// assert_eq!(parse_mode("rw-r--r--"), None);
+/// assert_eq!(parse_mode("10000"), None);
+/// ```
+pub fn parse_mode(mode: &str) -> Option<u32> {
+    let mode = mode.trim();
+    let digits = mode.strip_prefix("0o").unwrap_or(mode);
+    u32::from_str_radix(digits, 8)
+        .ok()
+        .filter(|mode| *mode <= 0o7777)
+}
+
+/// [`OutputMetadata`] is what every output is given once it is written:
+/// the modification time of the newest input, the permissions and
+/// ownership of a template file, and then an explicit mode.
+#[derive(Clone, Debug, Default)]
+pub struct OutputMetadata {
+    template: Option<Metadata>,
+    mode: Option<u32>,
+    modified: Option<SystemTime>,
+}
+
+impl OutputMetadata {
+    /// [`OutputMetadata::new`] reads the metadata that [`Options`] ask for,
+    /// with `inputs` being every file combined into the output.
+    pub fn new(options: &Options, inputs: &[PathBuf]) -> Result<OutputMetadata> {
+        let template = match &options.permissions_from {
+            Some(template) => Some(fs::metadata(template).map_err(RcssError::io(template))?),
+            None => None,
+        };
+        let mut modified = None;
+        if options.preserve_mtime {
+            for input in inputs {
+                let input_modified = fs::metadata(input)
+                    .and_then(|metadata| metadata.modified())
+                    .map_err(RcssError::io(input))?;
+                modified = modified.max(Some(input_modified));
+            }
+        }
+        Ok(OutputMetadata {
+            template,
+            mode: options.mode,
+            modified,
+        })
+    }
+
+    /// [`OutputMetadata::apply`] gives the file at `path` this metadata.
+    pub fn apply(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        // before the permissions, which could make the file read-only. on
+        // unix, its owner can set the times of a read-only file anyway
+        if let Some(modified) = self.modified {
+            let file = match cfg!(unix) {
+                true => File::open(path)?,
+                false => File::options().write(true).open(path)?,
+            };
+            file.set_times(FileTimes::new().set_modified(modified))?;
+        }
+        if let Some(template) = &self.template {
+            fs::set_permissions(path, template.permissions())?;
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::MetadataExt;
+                let metadata = fs::metadata(path)?;
+                if (metadata.uid(), metadata.gid()) != (template.uid(), template.gid()) {
+                    std::os::unix::fs::chown(path, Some(template.uid()), Some(template.gid()))?;
+                }
+            }
+        }
+        if let Some(mode) = self.mode {
+            set_mode(path, mode)?;
+        }
+        Ok(())
+    }
+}
+
+/// [`set_mode`] sets the permissions of the file at `path` to `mode`, or
+/// only whether it is read-only where there are no unix modes.
+fn set_mode(path: &Path, mode: u32) -> io::Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(mode))
+    }
+    #[cfg(not(unix))]
+    {
+        let mut permissions = fs::metadata(path)?.permissions();
+        permissions.set_readonly(mode & 0o222 == 0);
+        fs::set_permissions(path, permissions)
+    }
+}
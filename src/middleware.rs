@@ -0,0 +1,166 @@
+//! a tower middleware that minifies css responses as they are served, for
+//! axum and other tower based servers, behind the `tower` feature. small
+//! sites can skip a build step entirely, at the cost of minifying every
+//! stylesheet once.
+use std::{
+    collections::HashMap,
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+};
+
+use bytes::Bytes;
+use http::{header, HeaderValue, Request, Response};
+use http_body::Body;
+use http_body_util::{BodyExt, Either, Full};
+use tower_layer::Layer;
+use tower_service::Service;
+
+/// [`MAX_CACHED`] is how many minified responses are kept, before the
+/// cache is cleared, so stale versions of a stylesheet can't pile up.
+const MAX_CACHED: usize = 256;
+
+/// [`Cache`] is the minified body of every response, keyed by the path
+/// (and query) it was requested at along with its `ETag`, since an `ETag`
+/// is only unique within one resource.
+type Cache = Arc<Mutex<HashMap<(String, HeaderValue), Bytes>>>;
+
+/// [`MinifyBody`] is the body of a response from [`Minify`]: either the
+/// minified css, or the untouched body of any other response.
+pub type MinifyBody<B> = Either<Full<Bytes>, B>;
+
+/// [`MinifyLayer`] wraps a service in [`Minify`]. every service it wraps
+/// shares one cache of minified responses, keyed by their path and `ETag`.
+///
+/// # example
+/// [`MinifyLayer`] can be added to an axum router serving a directory of
+/// stylesheets:
+/// ```rust,ignore
+/// let app = axum::Router::new()
+///     .nest_service("/css", tower_http::services::ServeDir::new("assets/css"))
+///     .layer(rcss::middleware::MinifyLayer::new());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct MinifyLayer {
+    cache: Cache,
+}
+
+impl MinifyLayer {
+    /// [`MinifyLayer::new`] creates a layer with an empty cache.
+    pub fn new() -> MinifyLayer {
+        MinifyLayer::default()
+    }
+}
+
+impl<S> Layer<S> for MinifyLayer {
+    type Service = Minify<S>;
+
+    fn layer(&self, inner: S) -> Minify<S> {
+        Minify {
+            inner,
+            cache: self.cache.clone(),
+        }
+    }
+}
+
+/// [`Minify`] minifies the body of every `200 OK` `text/css` response to
+/// a `GET` request of the `inner` service, reusing the minified body of a
+/// response to the same path with the same `ETag`. compressed responses,
+/// and the empty or partial bodies of `HEAD` requests and
+/// `206 Partial Content` responses, are passed through untouched.
+#[derive(Clone, Debug)]
+pub struct Minify<S> {
+    inner: S,
+    cache: Cache,
+}
+
+impl<S, RequestBody, ResponseBody> Service<Request<RequestBody>> for Minify<S>
+where
+    S: Service<Request<RequestBody>, Response = Response<ResponseBody>>,
+    S::Future: Send + 'static,
+    ResponseBody: Body<Data = Bytes> + Send + 'static,
+{
+    type Response = Response<MinifyBody<ResponseBody>>;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, context: &mut Context<'_>) -> Poll<Result<(), S::Error>> {
+        self.inner.poll_ready(context)
+    }
+
+    fn call(&mut self, request: Request<RequestBody>) -> Self::Future {
+        let is_get = request.method() == http::Method::GET;
+        let path = request
+            .uri()
+            .path_and_query()
+            .map_or("/", |path| path.as_str())
+            .to_string();
+        let response = self.inner.call(request);
+        let cache = self.cache.clone();
+        Box::pin(async move {
+            let response = response.await?;
+            if !is_get || !is_minifiable(&response) {
+                return Ok(response.map(Either::Right));
+            }
+            let (mut parts, body) = response.into_parts();
+            parts.headers.remove(header::CONTENT_LENGTH);
+            let key = parts
+                .headers
+                .get(header::ETAG)
+                .map(|etag| (path, etag.clone()));
+            let cached = key.as_ref().and_then(|key| {
+                let cache = cache.lock().unwrap_or_else(|error| error.into_inner());
+                cache.get(key).cloned()
+            });
+            if let Some(minified) = cached {
+                return Ok(Response::from_parts(
+                    parts,
+                    Either::Left(Full::new(minified)),
+                ));
+            }
+            let css = match body.collect().await {
+                Ok(collected) => collected.to_bytes(),
+                // the body can't be minified, or even passed through, once
+                // reading it has failed
+                Err(_) => {
+                    parts.status = http::StatusCode::BAD_GATEWAY;
+                    return Ok(Response::from_parts(parts, Either::Left(Full::default())));
+                }
+            };
+            let minified = match std::str::from_utf8(&css) {
+                Ok(css) => Bytes::from(crate::minify_string(css)),
+                Err(_) => css,
+            };
+            if let Some(key) = key {
+                let mut cache = cache.lock().unwrap_or_else(|error| error.into_inner());
+                if cache.len() >= MAX_CACHED {
+                    cache.clear();
+                }
+                cache.insert(key, minified.clone());
+            }
+            Ok(Response::from_parts(
+                parts,
+                Either::Left(Full::new(minified)),
+            ))
+        })
+    }
+}
+
+/// [`is_minifiable`] returns `true` if `response` is a complete,
+/// uncompressed css response.
+fn is_minifiable<B>(response: &Response<B>) -> bool {
+    let is_css = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|content_type| content_type.to_str().ok())
+        .is_some_and(|content_type| {
+            content_type
+                .trim_start()
+                .to_ascii_lowercase()
+                .starts_with("text/css")
+        });
+    response.status() == http::StatusCode::OK
+        && is_css
+        && !response.headers().contains_key(header::CONTENT_ENCODING)
+}
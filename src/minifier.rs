@@ -0,0 +1,279 @@
+//! [`Minifier`], a configured minifier that is built once and then used to
+//! minify any number of strings, files, and directories the same way.
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use crate::{
+    browsers, content, critical, error::Result, level::Level, passes, report, rules, tokenizer,
+    transform::Transform, Options, RcssError,
+};
+
+/// [`Minifier`] minifies files of one extension with [`Options`], which are
+/// set with its builder methods. every method that isn't called leaves its
+/// option as the default.
+///
+/// # example
+/// [`Minifier`] can be used to minify every stylesheet of a project with
+/// the same options:
+/// ```rust
+/// let minifier = rcss::minifier::Minifier::new()
+///     .remove_comments(true)
+///     .merge_media_queries(true);
+/// let minified = minifier
+///     .minify("@media print { a { color: red } }\n@media print { b { color: blue } }")
+///     .unwrap();
+/// assert_eq!(minified, "@media print{a{color:red}b{color:blue}}");
+/// ```
+#[derive(Clone, Debug)]
+pub struct Minifier {
+    extension: String,
+    options: Options,
+}
+
+impl Default for Minifier {
+    fn default() -> Minifier {
+        Minifier::new()
+    }
+}
+
+impl Minifier {
+    /// [`Minifier::new`] returns a minifier for css files, with the default
+    /// [`Options`].
+    pub fn new() -> Minifier {
+        Minifier::with_options(Options::default())
+    }
+
+    /// [`Minifier::with_options`] returns a minifier for css files, with
+    /// `options`, like ones read by [`Options::from_config`].
+    pub fn with_options(options: Options) -> Minifier {
+        Minifier {
+            extension: "css".to_string(),
+            options,
+        }
+    }
+
+    /// [`Minifier::options`] returns the options the minifier was built with.
+    pub fn options(&self) -> &Options {
+        &self.options
+    }
+
+    /// [`Minifier::extension`] minifies files with the [`rules::RuleSet`]
+    /// registered for `extension`, like `js`, instead of css.
+    pub fn extension(mut self, extension: &str) -> Minifier {
+        self.extension = extension.to_string();
+        self
+    }
+
+    /// [`Minifier::level`] sets how much files are optimized.
+    pub fn level(mut self, level: Level) -> Minifier {
+        self.options.level = level;
+        self
+    }
+
+    /// [`Minifier::remove_comments`] removes `/*!` license comments, like
+    /// every other comment, when set. otherwise they are kept at the top of
+    /// the output.
+    pub fn remove_comments(mut self, is_removed: bool) -> Minifier {
+        self.options.preserve_comments = !is_removed;
+        self
+    }
+
+    /// [`Minifier::merge_media_queries`] sets [`Options::merge_media`].
+    pub fn merge_media_queries(mut self, is_merged: bool) -> Minifier {
+        self.options.merge_media = is_merged;
+        self
+    }
+
+    /// [`Minifier::merge_duplicates`] sets [`Options::merge_duplicates`].
+    pub fn merge_duplicates(mut self, is_merged: bool) -> Minifier {
+        self.options.merge_duplicates = is_merged;
+        self
+    }
+
+    /// [`Minifier::merge_selectors`] sets [`Options::merge_selectors`].
+    pub fn merge_selectors(mut self, is_merged: bool) -> Minifier {
+        self.options.merge_selectors = is_merged;
+        self
+    }
+
+    /// [`Minifier::collapse_shorthands`] sets [`Options::collapse_shorthands`].
+    pub fn collapse_shorthands(mut self, is_collapsed: bool) -> Minifier {
+        self.options.collapse_shorthands = is_collapsed;
+        self
+    }
+
+    /// [`Minifier::remove_empty_rules`] sets [`Options::remove_empty_rules`].
+    pub fn remove_empty_rules(mut self, is_removed: bool) -> Minifier {
+        self.options.remove_empty_rules = is_removed;
+        self
+    }
+
+    /// [`Minifier::remove_unused_at_rules`] sets
+    /// [`Options::remove_unused_at_rules`].
+    pub fn remove_unused_at_rules(mut self, is_removed: bool) -> Minifier {
+        self.options.remove_unused_at_rules = is_removed;
+        self
+    }
+
+    /// [`Minifier::remove_unused_variables`] sets
+    /// [`Options::remove_unused_variables`].
+    pub fn remove_unused_variables(mut self, is_removed: bool) -> Minifier {
+        self.options.remove_unused_variables = is_removed;
+        self
+    }
+
+    /// [`Minifier::flatten_nesting`] sets [`Options::flatten_nesting`].
+    pub fn flatten_nesting(mut self, is_flattened: bool) -> Minifier {
+        self.options.flatten_nesting = is_flattened;
+        self
+    }
+
+    /// [`Minifier::inline_variables`] sets [`Options::inline_variables`].
+    pub fn inline_variables(mut self, is_inlined: bool) -> Minifier {
+        self.options.inline_variables = is_inlined;
+        self
+    }
+
+    /// [`Minifier::add_prefixes`] sets [`Options::add_prefixes`].
+    pub fn add_prefixes(mut self, is_added: bool) -> Minifier {
+        self.options.add_prefixes = is_added;
+        self
+    }
+
+    /// [`Minifier::strip_prefixes`] sets [`Options::strip_prefixes`].
+    pub fn strip_prefixes(mut self, is_stripped: bool) -> Minifier {
+        self.options.strip_prefixes = is_stripped;
+        self
+    }
+
+    /// [`Minifier::strip_hacks`] sets [`Options::strip_hacks`].
+    pub fn strip_hacks(mut self, is_stripped: bool) -> Minifier {
+        self.options.strip_hacks = is_stripped;
+        self
+    }
+
+    /// [`Minifier::normalize_case`] sets [`Options::normalize_case`].
+    pub fn normalize_case(mut self, is_normalized: bool) -> Minifier {
+        self.options.normalize_case = is_normalized;
+        self
+    }
+
+    /// [`Minifier::downlevel_colors`] sets [`Options::downlevel_colors`].
+    pub fn downlevel_colors(mut self, is_downleveled: bool) -> Minifier {
+        self.options.downlevel_colors = is_downleveled;
+        self
+    }
+
+    /// [`Minifier::browsers`] sets the [`Options::browsers`] query that the
+    /// vendor prefixes are added or stripped for.
+    pub fn browsers(mut self, query: &str) -> Minifier {
+        self.options.browsers = query.to_string();
+        self
+    }
+
+    /// [`Minifier::strict`] sets [`Options::strict`].
+    pub fn strict(mut self, is_strict: bool) -> Minifier {
+        self.options.strict = is_strict;
+        self
+    }
+
+    /// [`Minifier::source_map`] sets [`Options::source_map`], which only
+    /// applies to [`Minifier::minify_directory`].
+    pub fn source_map(mut self, is_written: bool) -> Minifier {
+        self.options.source_map = is_written;
+        self
+    }
+
+    /// [`Minifier::transform`] adds `transform` to [`Options::transforms`],
+    /// to run after every transform added before it.
+    pub fn transform(mut self, transform: impl Transform + 'static) -> Minifier {
+        self.options.transforms.push(Arc::new(transform));
+        self
+    }
+
+    /// [`Minifier::jobs`] sets [`Options::jobs`].
+    pub fn jobs(mut self, jobs: usize) -> Minifier {
+        self.options.jobs = jobs;
+        self
+    }
+
+    /// [`Minifier::minify`] returns `content` minified, with every pass
+    /// that is turned on applied to it.
+    pub fn minify(&self, content: &str) -> Result<String> {
+        self.minify_source(Path::new("<input>"), content)
+    }
+
+    /// [`Minifier::minify_file`] reads the file at `file_path`, and returns
+    /// its content minified by [`Minifier::minify`].
+    ///
+    /// # example
+    /// [`Minifier::minify_file`] can be used to minify a single javascript
+    /// file:
+    /// ```rust,no_run
+    /// let minifier = rcss::minifier::Minifier::new().extension("js");
+    /// let minified = minifier.minify_file("assets/js/app.js").unwrap();
+    /// ```
+    pub fn minify_file(&self, file_path: impl AsRef<Path>) -> Result<String> {
+        let file_path = file_path.as_ref();
+        let content = content::read(file_path).map_err(RcssError::io(file_path))?;
+        self.minify_source(file_path, &content)
+    }
+
+    /// [`Minifier::minify_directory`] combines every file of the minifier's
+    /// extension within `directory` into `destination_file_name`, like
+    /// [`crate::minify_directory_with`], and returns a summary of what it did.
+    pub fn minify_directory(
+        &self,
+        directory: &str,
+        destination_file_name: &str,
+    ) -> Result<report::Summary> {
+        crate::minify_directory_with(
+            &self.extension,
+            directory,
+            destination_file_name,
+            &self.options,
+        )
+    }
+
+    /// [`Minifier::minify_source`] minifies the `content` of the file at
+    /// `file_path`, which is only used to report a malformed file.
+    fn minify_source(&self, file_path: &Path, content: &str) -> Result<String> {
+        let rule_set = rules::find(&self.extension);
+        let options = self.options.for_rule_set(&*rule_set);
+        crate::check_level(&options)?;
+        if options.strict {
+            tokenizer::validate(content).map_err(|error| RcssError::Parse {
+                path: PathBuf::from(file_path),
+                message: error.to_string(),
+            })?;
+        }
+        let (mut minified, _, license_comments) =
+            crate::minify_source(&self.extension, content, &options);
+        if options.has_structural_passes() {
+            let targets = browsers::Targets::parse(&options.browsers)?;
+            let used_names = match options.purge.is_empty() {
+                true => HashSet::new(),
+                false => passes::purge::read_used_names(&options.purge, &options)?,
+            };
+            let critical = match &options.critical {
+                Some(path) => Some(critical::Document::read(path)?),
+                None => None,
+            };
+            (minified, _) = crate::apply_passes(
+                &minified,
+                &options,
+                &targets,
+                &used_names,
+                critical.as_ref(),
+                &mut vec![],
+            );
+        }
+        if options.preserve_comments {
+            minified.insert_str(0, &license_comments.concat());
+        }
+        Ok(minified)
+    }
+}
@@ -0,0 +1,121 @@
+//! shortening of numbers and dimensions, like `0.50em` to `.5em` and
+//! `1000ms` to `1e3ms`.
+
+/// [`LENGTH_UNITS`] are the units of lengths, which are the only
+/// dimensions where a zero means the same thing without its unit.
+const LENGTH_UNITS: &[&str] = &[
+    "px", "em", "rem", "ex", "ch", "vw", "vh", "vmin", "vmax", "cm", "mm", "q", "in", "pt", "pc",
+];
+
+/// [`MAX_EXPONENT`] is the largest exponent a number is rewritten with,
+/// so something like `1e300` is never expanded into three hundred zeros.
+const MAX_EXPONENT: i32 = 64;
+
+/// [`shorten_number`] returns the shortest way to write the css `number`
+/// (a number token, with any unit or `%`), or `None` if it can't be read.
+/// leading and trailing zeros and `+` signs are removed, scientific
+/// notation is used when it is shorter, and the unit of a zero length is
+/// removed if `drop_zero_unit` is set.
+///
+/// # notes
+/// a unitless integer is never written in scientific notation, since
+/// properties like `z-index` only accept integers, and `1e3` isn't one.
+///
+/// # example
+/// [`shorten_number`] can be used to shorten a dimension:
+/// ```rust
+/// use rcss::numbers::shorten_number;
+///
+/// assert_eq!(shorten_number("0.50em", true).as_deref(), Some(".5em"));
+/// assert_eq!(shorten_number("1.0s", true).as_deref(), Some("1s"));
+/// assert_eq!(shorten_number("0px", true).as_deref(), Some("0"));
+/// assert_eq!(shorten_number("0px", false).as_deref(), Some("0px"));
+/// assert_eq!(shorten_number("1000ms", true).as_deref(), Some("1e3ms"));
+/// assert_eq!(shorten_number("1000", true).as_deref(), Some("1000"));
+/// ```
+pub fn shorten_number(number: &str, drop_zero_unit: bool) -> Option<String> {
+    let (sign, rest) = match number.as_bytes().first()? {
+        b'+' => ("", &number[1..]),
+        b'-' => ("-", &number[1..]),
+        _ => ("", number),
+    };
+    let (integer, rest) = rest.split_at(count_digits(rest));
+    let (fraction, rest) = match rest.strip_prefix('.') {
+        Some(after) if count_digits(after) > 0 => after.split_at(count_digits(after)),
+        _ => ("", rest),
+    };
+    if integer.is_empty() && fraction.is_empty() {
+        return None;
+    }
+    let (exponent, unit) = split_exponent(rest)?;
+    if !unit.is_empty() && unit != "%" && !unit.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+
+    let digits = format!("{integer}{fraction}");
+    let digits = digits.trim_start_matches('0');
+    let significant = digits.trim_end_matches('0');
+    if significant.is_empty() {
+        let is_length = LENGTH_UNITS.contains(&unit.to_ascii_lowercase().as_str());
+        return match drop_zero_unit && is_length {
+            true => Some("0".to_string()),
+            false => Some(format!("0{unit}")),
+        };
+    }
+    let exponent = exponent - fraction.len() as i32 + (digits.len() - significant.len()) as i32;
+    if exponent.abs() > MAX_EXPONENT {
+        return None;
+    }
+
+    let plain = plain_notation(significant, exponent);
+    let is_integer = unit.is_empty() && exponent >= 0;
+    let shortest = match exponent {
+        0 => plain,
+        _ if is_integer => plain,
+        _ => {
+            let scientific = format!("{significant}e{exponent}");
+            match scientific.len() < plain.len() {
+                true => scientific,
+                false => plain,
+            }
+        }
+    };
+    Some(format!("{sign}{shortest}{unit}"))
+}
+
+/// [`count_digits`] returns how many ascii digits `text` starts with.
+fn count_digits(text: &str) -> usize {
+    text.bytes().take_while(u8::is_ascii_digit).count()
+}
+
+/// [`split_exponent`] splits an optional exponent, like `e-3`, from the
+/// start of `text`, returning it (or `0`) and the rest of `text`.
+fn split_exponent(text: &str) -> Option<(i32, &str)> {
+    let Some(after) = text.strip_prefix(['e', 'E']) else {
+        return Some((0, text));
+    };
+    let sign_length = usize::from(after.starts_with(['+', '-']));
+    let digits = count_digits(&after[sign_length..]);
+    if digits == 0 {
+        // something like `1em`, where the `e` starts the unit
+        return Some((0, text));
+    }
+    let (exponent, rest) = after.split_at(sign_length + digits);
+    Some((exponent.parse().ok()?, rest))
+}
+
+/// [`plain_notation`] writes `significant` digits times ten to the power
+/// of `exponent`, without scientific notation.
+fn plain_notation(significant: &str, exponent: i32) -> String {
+    if exponent >= 0 {
+        return format!("{significant}{}", "0".repeat(exponent as usize));
+    }
+    let point = significant.len() as i32 + exponent;
+    match point > 0 {
+        true => {
+            let (integer, fraction) = significant.split_at(point as usize);
+            format!("{integer}.{fraction}")
+        }
+        false => format!(".{}{significant}", "0".repeat(-point as usize)),
+    }
+}
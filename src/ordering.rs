@@ -0,0 +1,297 @@
+//! ordering of the files that get combined, so the cascade order of
+//! the combined output is stable across platforms and controllable.
+use crate::{
+    config::Config,
+    content,
+    error::{RcssError, Result},
+    paths,
+};
+use std::{
+    cmp::Ordering,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// [`SortOrder`] decides how files that aren't listed in an explicit
+/// order are sorted.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SortOrder {
+    /// sort by path, one directory at a time, so `a/z.css` comes
+    /// before `b.css`.
+    #[default]
+    Alphabetical,
+    /// sort by path like [`SortOrder::Alphabetical`], except a leading
+    /// number in a file or directory name is compared numerically, so
+    /// `2-base.css` comes before `10-theme.css`.
+    NumericPrefix,
+}
+
+impl SortOrder {
+    /// [`SortOrder::parse`] reads a sort order from its name in a
+    /// configuration file, like `"numeric-prefix"`.
+    pub fn parse(name: &str) -> Option<SortOrder> {
+        match name {
+            "alphabetical" => Some(SortOrder::Alphabetical),
+            "numeric-prefix" => Some(SortOrder::NumericPrefix),
+            _ => None,
+        }
+    }
+
+    /// [`SortOrder::from_config`] reads the `sort` key from the root table
+    /// of `config`, using [`SortOrder::Alphabetical`] if it isn't set.
+    pub fn from_config(config: &Config) -> Result<SortOrder> {
+        let Some(value) = config.get("", "sort") else {
+            return Ok(SortOrder::default());
+        };
+        value.as_str().and_then(SortOrder::parse).ok_or_else(|| {
+            RcssError::Config(format!(
+                "unknown sort {value:?}, expected \"alphabetical\" or \"numeric-prefix\""
+            ))
+        })
+    }
+
+    fn compare(&self, left: &str, right: &str) -> Ordering {
+        let left_components = left.split('/');
+        let right_components = right.split('/');
+        if *self == SortOrder::Alphabetical {
+            return left_components.cmp(right_components);
+        }
+        left_components
+            .map(numeric_prefix)
+            .cmp(right_components.map(numeric_prefix))
+    }
+}
+
+/// [`numeric_prefix`] splits the leading digits off of `component`, so
+/// they can be compared as a number. components without a number sort
+/// after every numbered component.
+fn numeric_prefix(component: &str) -> (u64, &str) {
+    let digits = component
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(component.len());
+    let prefix = component[..digits].parse().unwrap_or(u64::MAX);
+    (prefix, &component[digits..])
+}
+
+/// [`relative_path`] returns `file_path` relative to `root`, always
+/// using `/` as the separator. anything that isn't valid utf-8 is replaced
+/// with the unicode replacement character, so it can still be matched and
+/// sorted.
+pub fn relative_path(file_path: impl AsRef<Path>, root: impl AsRef<Path>) -> String {
+    let path = file_path.as_ref();
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+/// [`order_files`] will put `files` from the `root` directory into the
+/// order they should be combined in: first every file listed in `order`
+/// (relative to `root`), in that order, then the rest sorted by `sort`.
+///
+/// # example
+/// [`order_files`] can be used to put a reset stylesheet first, and sort
+/// the rest by their numeric prefix:
+/// ```rust
+/// use rcss::ordering::{order_files, SortOrder};
+/// use std::path::PathBuf;
+///
+/// let files = ["css/10-theme.css", "css/2-base.css", "css/reset.css"].map(PathBuf::from);
+/// let ordered = order_files(files.to_vec(), "css", &["reset.css".to_string()], SortOrder::NumericPrefix).unwrap();
+/// assert_eq!(ordered, ["css/reset.css", "css/2-base.css", "css/10-theme.css"].map(PathBuf::from));
+/// ```
+pub fn order_files(
+    files: Vec<PathBuf>,
+    root: impl AsRef<Path>,
+    order: &[String],
+    sort: SortOrder,
+) -> Result<Vec<PathBuf>> {
+    let root = root.as_ref();
+    let mut remaining = files
+        .into_iter()
+        .map(|file| (relative_path(&file, root), file))
+        .collect::<Vec<_>>();
+    let mut ordered = vec![];
+    for entry in order {
+        let entry = entry.trim_start_matches("./").replace('\\', "/");
+        let position = remaining
+            .iter()
+            .position(|(relative, _)| *relative == entry)
+            .ok_or_else(|| {
+                RcssError::Config(format!(
+                    "order entry \"{entry}\" does not match any file in {}",
+                    root.display()
+                ))
+            })?;
+        ordered.push(remaining.remove(position).1);
+    }
+    remaining.sort_by(|(left, _), (right, _)| sort.compare(left, right));
+    ordered.extend(remaining.into_iter().map(|(_, file)| file));
+    Ok(ordered)
+}
+
+/// [`parse_file_list`] returns the paths listed in the file list `content`,
+/// one per line and in the order they are listed, relative to `base`. blank
+/// lines, and lines that start with `#`, are skipped.
+///
+/// # example
+/// [`parse_file_list`] can be used to read the files a build system lists:
+/// ```rust
+/// use std::path::PathBuf;
+///
+/// let list = "# generated by the build\nbase/reset.css\n\n  components/button.css  \n";
+/// assert_eq!(
+///     rcss::ordering::parse_file_list(list, "css"),
+///     ["css/base/reset.css", "css/components/button.css"].map(PathBuf::from)
+/// );
+/// ```
+pub fn parse_file_list(content: &str, base: impl AsRef<Path>) -> Vec<PathBuf> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| base.as_ref().join(line))
+        .collect()
+}
+
+/// [`read_file_list`] returns the paths listed in the file list at `path`,
+/// like [`parse_file_list`], relative to the directory the list is in. it
+/// fails if a listed path isn't a file.
+pub fn read_file_list(path: impl AsRef<Path>) -> Result<Vec<PathBuf>> {
+    let path = path.as_ref();
+    let content = fs::read_to_string(path).map_err(RcssError::io(path))?;
+    let files = parse_file_list(&content, path.parent().unwrap_or(Path::new("")));
+    if let Some(missing) = files.iter().find(|file| !file.is_file()) {
+        return Err(RcssError::Config(format!(
+            "{} lists {}, which is not a file",
+            path.display(),
+            missing.display()
+        )));
+    }
+    Ok(files)
+}
+
+/// [`REQUIRES`] starts a dependency annotation within a header comment.
+const REQUIRES: &str = "@requires";
+
+/// [`find_requires`] returns the paths named by every `@requires` in the
+/// comments at the top of `content`, before its first rule. a path that
+/// starts with `.` is relative to the file, and any other path is relative
+/// to the directory being combined.
+///
+/// # example
+/// [`find_requires`] can be used to find what a component depends on:
+/// ```rust
+/// let css = "/* @requires base/reset.css\n * @requires ./tokens.css, ./icons.css */\n.button {}";
+/// assert_eq!(
+///     rcss::ordering::find_requires(css),
+///     ["base/reset.css", "./tokens.css", "./icons.css"]
+/// );
+/// ```
+pub fn find_requires(content: &str) -> Vec<String> {
+    let mut rest = content.trim_start_matches('\u{feff}').trim_start();
+    // the only thing that may come before a header comment
+    if rest.starts_with("@charset") {
+        rest = rest
+            .find(';')
+            .map_or("", |end| rest[end + 1..].trim_start());
+    }
+    let mut requires = vec![];
+    while let Some(comment) = rest.strip_prefix("/*") {
+        let (body, after) = comment.split_once("*/").unwrap_or((comment, ""));
+        for annotation in body.split(REQUIRES).skip(1) {
+            let line = annotation.lines().next().unwrap_or_default();
+            requires.extend(
+                line.split([',', ' ', '\t'])
+                    .map(|path| path.trim_matches(['"', '\'', '*']))
+                    .filter(|path| !path.is_empty())
+                    .map(str::to_string),
+            );
+        }
+        rest = after.trim_start();
+    }
+    requires
+}
+
+/// [`sort_by_requires`] moves every one of `files` (from the `root`
+/// directory) after the files it requires by [`find_requires`], keeping
+/// the order of everything else. it fails if a file requires one that
+/// isn't in `files`, or if files require each other.
+pub fn sort_by_requires(files: Vec<PathBuf>, root: impl AsRef<Path>) -> Result<Vec<PathBuf>> {
+    let root = root.as_ref();
+    let relative_paths = files
+        .iter()
+        .map(|file| relative_path(file, root))
+        .collect::<Vec<_>>();
+    let mut requires = vec![];
+    for (file, relative) in files.iter().zip(&relative_paths) {
+        let content = content::read(file).map_err(RcssError::io(file))?;
+        let mut required = vec![];
+        for path in find_requires(&content) {
+            let path = match path.starts_with('.') {
+                true => {
+                    let directory = relative
+                        .rsplit_once('/')
+                        .map_or("", |(directory, _)| directory);
+                    paths::normalize(format!("{directory}/{path}"))
+                }
+                false => paths::normalize(&path),
+            };
+            let position = relative_paths
+                .iter()
+                .position(|other| paths::normalize(other) == path)
+                .ok_or_else(|| {
+                    RcssError::Config(format!(
+                        "{relative} requires \"{path}\", which is not a file being combined from {}",
+                        root.display()
+                    ))
+                })?;
+            required.push(position);
+        }
+        requires.push(required);
+    }
+    let mut visited = vec![false; files.len()];
+    let mut order = vec![];
+    for index in 0..files.len() {
+        visit(index, &requires, &mut visited, &mut vec![], &mut order).map_err(|cycle| {
+            let cycle = cycle
+                .iter()
+                .map(|index| relative_paths[*index].as_str())
+                .collect::<Vec<_>>();
+            RcssError::Config(format!("files require each other: {}", cycle.join(" -> ")))
+        })?;
+    }
+    let mut files = files.into_iter().map(Some).collect::<Vec<_>>();
+    Ok(order
+        .into_iter()
+        .filter_map(|index| files[index].take())
+        .collect())
+}
+
+/// [`visit`] adds the file at `index` to `order` after everything it
+/// requires, depth first. `path` is every file being visited on the way to
+/// it, and is returned as the cycle if the file is already among them.
+fn visit(
+    index: usize,
+    requires: &[Vec<usize>],
+    visited: &mut [bool],
+    path: &mut Vec<usize>,
+    order: &mut Vec<usize>,
+) -> std::result::Result<(), Vec<usize>> {
+    if let Some(start) = path.iter().position(|visiting| *visiting == index) {
+        let mut cycle = path[start..].to_vec();
+        cycle.push(index);
+        return Err(cycle);
+    }
+    if visited[index] {
+        return Ok(());
+    }
+    path.push(index);
+    for required in &requires[index] {
+        visit(*required, requires, visited, path, order)?;
+    }
+    path.pop();
+    visited[index] = true;
+    order.push(index);
+    Ok(())
+}
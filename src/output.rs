@@ -0,0 +1,139 @@
+//! the combined output, kept as the pieces it is made of (mostly borrowed
+//! from the minified files) so it can be streamed to the destination file
+//! without joining the whole bundle into one string first.
+use std::{
+    borrow::Cow,
+    ffi::OsString,
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    path::Path,
+    process,
+};
+
+/// [`Output`] is combined output, made of pieces in order.
+///
+/// # example
+/// [`Output`] can be built up from borrowed and owned pieces:
+/// ```rust
+/// let mut output = rcss::output::Output::default();
+/// output.push("a{color:red}");
+/// output.push(String::from("b{color:blue}"));
+/// output.prepend("@charset \"utf-8\";");
+/// assert_eq!(output.len(), 42);
+/// assert_eq!(output.to_string(), "@charset \"utf-8\";a{color:red}b{color:blue}");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Output<'a> {
+    pieces: Vec<Cow<'a, str>>,
+}
+
+impl<'a> Output<'a> {
+    /// [`Output::push`] appends `piece` to the output.
+    pub fn push(&mut self, piece: impl Into<Cow<'a, str>>) {
+        let piece = piece.into();
+        // an empty last piece would hide what the output really ends with
+        if !piece.is_empty() {
+            self.pieces.push(piece);
+        }
+    }
+
+    /// [`Output::prepend`] inserts `piece` at the start of the output.
+    pub fn prepend(&mut self, piece: impl Into<Cow<'a, str>>) {
+        let piece = piece.into();
+        if !piece.is_empty() {
+            self.pieces.insert(0, piece);
+        }
+    }
+
+    /// [`Output::last`] returns the last piece of the output, or `""` if it
+    /// is empty.
+    pub fn last(&self) -> &str {
+        self.pieces.last().map_or("", |piece| piece)
+    }
+
+    /// [`Output::len`] returns how many bytes long the output is.
+    pub fn len(&self) -> usize {
+        self.pieces.iter().map(|piece| piece.len()).sum()
+    }
+
+    /// [`Output::is_empty`] returns `true` if the output has nothing in it.
+    pub fn is_empty(&self) -> bool {
+        self.pieces.is_empty()
+    }
+
+    /// [`Output::bytes`] returns every piece of the output, as bytes.
+    pub fn bytes(&self) -> impl Iterator<Item = &[u8]> {
+        self.pieces.iter().map(|piece| piece.as_bytes())
+    }
+
+    /// [`Output::write_to`] writes every piece of the output to `writer`.
+    pub fn write_to(&self, mut writer: impl Write) -> io::Result<()> {
+        for piece in self.bytes() {
+            writer.write_all(piece)?;
+        }
+        writer.flush()
+    }
+
+    /// [`Output::write_file`] writes the output to the file at `path`
+    /// through a buffer, replacing it if it exists, with
+    /// [`write_atomically`].
+    pub fn write_file(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        write_atomically(path, |file| self.write_to(file))
+    }
+}
+
+/// [`write_atomically`] calls `write` with a buffered temporary file next to
+/// `path`, and then renames it over `path`, so that `path` is never left
+/// half written if rcss is stopped partway through. the temporary file is
+/// removed if `write` fails, and takes the permissions of the file it
+/// replaces.
+///
+/// # example
+/// [`write_atomically`] can be used to replace a stylesheet:
+/// ```rust,no_run
+/// use std::io::Write;
+///
+/// rcss::output::write_atomically("./assets/css/style.css", |file| {
+///     file.write_all(b"a{color:red}")
+/// })
+/// .unwrap();
+/// ```
+pub fn write_atomically(
+    path: impl AsRef<Path>,
+    write: impl FnOnce(&mut BufWriter<File>) -> io::Result<()>,
+) -> io::Result<()> {
+    let path = path.as_ref();
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "the path isn't a file"))?;
+    // hidden, and without the extension of the file, so a build running at
+    // the same time doesn't combine it
+    let mut temporary_name = OsString::from(".");
+    temporary_name.push(file_name);
+    temporary_name.push(format!(".{}.tmp", process::id()));
+    let temporary_path = path.with_file_name(temporary_name);
+    let result = File::create(&temporary_path)
+        .and_then(|file| {
+            let mut file = BufWriter::new(file);
+            write(&mut file)?;
+            file.flush()?;
+            if let Ok(metadata) = fs::metadata(path) {
+                fs::set_permissions(&temporary_path, metadata.permissions())?;
+            }
+            Ok(())
+        })
+        .and_then(|()| fs::rename(&temporary_path, path));
+    if result.is_err() {
+        let _ = fs::remove_file(&temporary_path);
+    }
+    result
+}
+
+impl std::fmt::Display for Output<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for piece in &self.pieces {
+            f.write_str(piece)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,51 @@
+//! a small fan-out helper, used to minify many files on every core
+//! while keeping their results in order.
+use std::{num::NonZeroUsize, thread};
+
+/// [`available_jobs`] returns how many threads to use when `jobs` is
+/// `0`, which is the number of cores on the machine.
+pub fn available_jobs(jobs: usize) -> usize {
+    if jobs > 0 {
+        return jobs;
+    }
+    thread::available_parallelism()
+        .map(NonZeroUsize::get)
+        .unwrap_or(1)
+}
+
+/// [`map_ordered`] calls `map` on every item in `items` using up to
+/// `jobs` threads (or one per core if `jobs` is `0`), and returns the
+/// results in the same order as `items`.
+///
+/// # example
+/// [`map_ordered`] can be used to minify several stylesheets at once:
+/// ```rust
+/// let stylesheets = ["a { color: red; }", "b { color: blue; }"];
+/// let minified = rcss::parallel::map_ordered(&stylesheets, 0, |css| rcss::minify_string(css));
+/// assert_eq!(minified, ["a{color:red}", "b{color:blue}"]);
+/// ```
+pub fn map_ordered<T, R>(items: &[T], jobs: usize, map: impl Fn(&T) -> R + Sync) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+{
+    let jobs = available_jobs(jobs).min(items.len());
+    if jobs <= 1 {
+        return items.iter().map(map).collect();
+    }
+    let chunk_size = items.len().div_ceil(jobs);
+    thread::scope(|scope| {
+        let handles = items
+            .chunks(chunk_size)
+            .map(|chunk| scope.spawn(|| chunk.iter().map(&map).collect::<Vec<_>>()))
+            .collect::<Vec<_>>();
+        handles
+            .into_iter()
+            .flat_map(|handle| {
+                handle
+                    .join()
+                    .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+            })
+            .collect()
+    })
+}
@@ -0,0 +1,83 @@
+//! structural optimization passes, which work on a parsed
+//! [`crate::stylesheet::Stylesheet`] rather than individual tokens.
+pub mod case;
+pub mod class_names;
+pub mod color_syntax;
+pub mod duplicates;
+pub mod empty;
+pub mod hacks;
+pub mod identifiers;
+pub mod media;
+pub mod nesting;
+pub mod overrides;
+pub mod prefixes;
+pub mod purge;
+pub mod scope;
+pub mod selector_lists;
+pub mod shorthands;
+pub mod unused;
+pub mod variables;
+
+use crate::stylesheet::{Node, Stylesheet};
+
+/// [`property_name`] returns `property` lowercased and without any
+/// vendor prefix, so `-webkit-Transform` and `transform` compare equal.
+pub fn property_name(property: &str) -> String {
+    let property = property.to_ascii_lowercase();
+    for prefix in ["-webkit-", "-moz-", "-ms-", "-o-"] {
+        if let Some(unprefixed) = property.strip_prefix(prefix) {
+            return unprefixed.to_string();
+        }
+    }
+    property
+}
+
+/// [`properties_overlap`] returns `true` if setting one property could
+/// change the other, like `margin` and `margin-top`.
+pub fn properties_overlap(left: &str, right: &str) -> bool {
+    let (left, right) = (property_name(left), property_name(right));
+    left == right
+        || left.starts_with(&format!("{right}-"))
+        || right.starts_with(&format!("{left}-"))
+}
+
+/// [`declared_properties`] returns the property of every declaration
+/// within `node`, including within nested blocks.
+pub fn declared_properties(node: &Node) -> Vec<&str> {
+    match node {
+        Node::Declaration(declaration) => vec![declaration.property.as_str()],
+        _ => node
+            .children()
+            .map(|children| children.iter().flat_map(declared_properties).collect())
+            .unwrap_or_default(),
+    }
+}
+
+/// [`can_move_past`] returns `true` if a rule that sets `properties` can
+/// be moved before (or after) every node in `between` without changing the
+/// cascade, since none of them set an overlapping property.
+pub fn can_move_past(between: &[Node], properties: &[&str]) -> bool {
+    !between.iter().any(|node| {
+        matches!(node, Node::Raw(_))
+            || declared_properties(node).iter().any(|property| {
+                properties
+                    .iter()
+                    .any(|other| properties_overlap(property, other))
+            })
+    })
+}
+
+/// [`for_each_block`] calls `pass` on the top-level nodes of `stylesheet`,
+/// and then on the children of every rule and at-rule, recursively.
+pub fn for_each_block(stylesheet: &mut Stylesheet, pass: &mut impl FnMut(&mut Vec<Node>)) {
+    visit_block(&mut stylesheet.nodes, pass);
+}
+
+fn visit_block(nodes: &mut Vec<Node>, pass: &mut impl FnMut(&mut Vec<Node>)) {
+    pass(nodes);
+    for node in nodes.iter_mut() {
+        if let Some(children) = node.children_mut() {
+            visit_block(children, pass);
+        }
+    }
+}
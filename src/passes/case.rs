@@ -0,0 +1,79 @@
+//! lowercasing of whatever css doesn't care the case of, like `COLOR` or
+//! `10PX`, so the output has fewer distinct strings and compresses better.
+use crate::{
+    colors::is_valid_hex,
+    stylesheet::{Node, Stylesheet},
+    tokenizer::{tokenize, TokenKind},
+};
+
+/// [`normalize_case`] lowercases the property name of every declaration
+/// within `stylesheet`, and the hex colors, units, and function names
+/// within their values, like `COLOR:RGBA(0,0,0,.5)` to
+/// `color:rgba(0,0,0,.5)`.
+///
+/// # notes
+/// strings, the contents of urls, and every other name, like a font family
+/// or an animation name, keep their case, since it could matter. so do
+/// custom properties, along with their values, and functions whose names
+/// start with `--`.
+///
+/// # example
+/// [`normalize_case`] can be used to lowercase the declarations of an old
+/// stylesheet:
+/// ```rust
+/// use rcss::{passes::case::normalize_case, stylesheet::Stylesheet};
+///
+/// let mut stylesheet = Stylesheet::parse(
+///     ".a{COLOR:#ABCDEF;Margin:10PX 1Em;background:URL(A.png) RGBA(0,0,0,.5);font-family:\"Open Sans\",Arial;--Gap:1PX}",
+/// );
+/// normalize_case(&mut stylesheet);
+/// assert_eq!(
+///     stylesheet.to_css(),
+///     ".a{color:#abcdef;margin:10px 1em;background:url(A.png) rgba(0,0,0,.5);font-family:\"Open Sans\",Arial;--Gap:1PX}"
+/// );
+/// ```
+pub fn normalize_case(stylesheet: &mut Stylesheet) {
+    normalize_nodes(&mut stylesheet.nodes);
+}
+
+/// [`normalize_nodes`] lowercases every declaration within `nodes`, and
+/// within every block nested in them.
+fn normalize_nodes(nodes: &mut [Node]) {
+    for node in nodes {
+        match node {
+            Node::Declaration(declaration) if !declaration.property.starts_with("--") => {
+                declaration.property.make_ascii_lowercase();
+                declaration.value = normalize_value(&declaration.value);
+            }
+            _ => {
+                if let Some(children) = node.children_mut() {
+                    normalize_nodes(children);
+                }
+            }
+        }
+    }
+}
+
+/// [`normalize_value`] returns `value` with its hex colors, units, and
+/// function names lowercased.
+fn normalize_value(value: &str) -> String {
+    let mut normalized = String::with_capacity(value.len());
+    for token in tokenize(value) {
+        match token.kind {
+            TokenKind::Number => normalized.push_str(&token.text.to_ascii_lowercase()),
+            TokenKind::Hash if is_valid_hex(token.text) => {
+                normalized.push_str(&token.text.to_ascii_lowercase())
+            }
+            TokenKind::Function if !token.text.starts_with("--") => {
+                normalized.push_str(&token.text.to_ascii_lowercase())
+            }
+            // only the `url(` of a url, rather than what it points to
+            TokenKind::Url => {
+                normalized.push_str("url(");
+                normalized.push_str(&token.text[4..]);
+            }
+            _ => normalized.push_str(token.text),
+        }
+    }
+    normalized
+}
@@ -0,0 +1,119 @@
+//! css modules style hashing of class names, like `.card-title` to
+//! `._3f1a9`, which also returns the mapping from every original name to
+//! its hashed one, so html and javascript can be rewritten to match.
+use std::collections::{BTreeMap, HashSet};
+
+use crate::{
+    fingerprint::content_hash,
+    passes::purge::unescape,
+    stylesheet::{Node, Stylesheet},
+    tokenizer::{tokenize, TokenKind},
+};
+
+/// [`HASH_LENGTH`] is how many characters of the hash of a class name its
+/// hashed name starts with, growing only when two names would collide.
+const HASH_LENGTH: usize = 5;
+
+/// [`hash_class_names`] replaces every class name within the selectors of
+/// `stylesheet`, including within functional pseudo-classes like `:not()`,
+/// and within the roots and limits of `@scope`, with a short name from the
+/// hash of the original name, and returns the
+/// mapping from every original name (without escapes) to its hashed name.
+///
+/// # example
+/// [`hash_class_names`] can be used to hash the class names of a component:
+/// ```rust
+/// use rcss::{passes::class_names::hash_class_names, stylesheet::Stylesheet};
+///
+/// let mut stylesheet = Stylesheet::parse(".card>.title:not(.hidden){margin:0}");
+/// let names = hash_class_names(&mut stylesheet);
+/// assert_eq!(
+///     stylesheet.to_css(),
+///     format!(".{}>.{}:not(.{}){{margin:0}}", names["card"], names["title"], names["hidden"])
+/// );
+/// assert_eq!(names["card"].len(), 6);
+/// ```
+pub fn hash_class_names(stylesheet: &mut Stylesheet) -> BTreeMap<String, String> {
+    let mut names = Names::default();
+    hash_block(&mut stylesheet.nodes, &mut names);
+    names.hashed
+}
+
+/// [`Names`] are the hashed names chosen so far, by original name, and
+/// every one of them, so a new one doesn't collide.
+#[derive(Default)]
+struct Names {
+    hashed: BTreeMap<String, String>,
+    taken: HashSet<String>,
+}
+
+/// [`hash_block`] hashes the class names of every rule within `nodes`,
+/// recording new ones in `names`.
+fn hash_block(nodes: &mut [Node], names: &mut Names) {
+    for node in nodes {
+        match node {
+            Node::Rule(rule) => rule.selector = hash_selector(&rule.selector, names),
+            Node::AtRule(at_rule) if at_rule.name.eq_ignore_ascii_case("scope") => {
+                at_rule.prelude = hash_selector(&at_rule.prelude, names)
+            }
+            // the `from` and `50%` of a keyframe aren't selectors
+            Node::AtRule(at_rule) if at_rule.name.to_ascii_lowercase().ends_with("keyframes") => {
+                continue
+            }
+            _ => {}
+        }
+        if let Some(children) = node.children_mut() {
+            hash_block(children, names);
+        }
+    }
+}
+
+/// [`hash_selector`] returns `selector` with every class name replaced by
+/// its hashed name.
+fn hash_selector(selector: &str, names: &mut Names) -> String {
+    let tokens = tokenize(selector);
+    let mut hashed = String::with_capacity(selector.len());
+    let mut depth = 0;
+    for (index, token) in tokens.iter().enumerate() {
+        let previous = index.checked_sub(1).map(|previous| &tokens[previous]);
+        match token.kind {
+            TokenKind::OpenBracket => depth += 1,
+            TokenKind::CloseBracket => depth -= 1,
+            // the values of attribute selectors, like `[class~=card]`, are
+            // left alone, since they aren't class selectors
+            TokenKind::Ident
+                if depth == 0
+                    && previous.is_some_and(|previous| {
+                        previous.kind == TokenKind::Delim && previous.text == "."
+                    }) =>
+            {
+                hashed.push_str(&hashed_name(&unescape(token.text), names));
+                continue;
+            }
+            _ => {}
+        }
+        hashed.push_str(token.text);
+    }
+    hashed
+}
+
+/// [`hashed_name`] returns the hashed name of the class `name`, choosing
+/// one that no other class has if it doesn't have one yet.
+fn hashed_name(name: &str, names: &mut Names) -> String {
+    if let Some(hashed) = names.hashed.get(name) {
+        return hashed.clone();
+    }
+    let hash = content_hash(name.as_bytes());
+    let mut hashed = (HASH_LENGTH..=hash.len())
+        .map(|length| format!("_{}", &hash[..length]))
+        .find(|candidate| !names.taken.contains(candidate))
+        .unwrap_or_else(|| format!("_{hash}"));
+    let mut suffix = 0;
+    while names.taken.contains(&hashed) {
+        suffix += 1;
+        hashed = format!("_{hash}{suffix}");
+    }
+    names.taken.insert(hashed.clone());
+    names.hashed.insert(name.to_string(), hashed.clone());
+    hashed
+}
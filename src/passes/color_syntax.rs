@@ -0,0 +1,118 @@
+//! downleveling of the space separated color syntax of css color level 4,
+//! like `rgb(0 0 0 / 50%)`, to the comma separated syntax, like
+//! `rgba(0,0,0,.5)`, for the targeted browsers that don't support it.
+use crate::{
+    browsers::{Browser, Targets, Version},
+    colors::legacy_color,
+    stylesheet::{Node, Stylesheet},
+    tokenizer::{tokenize, TokenKind},
+};
+
+/// [`SPACE_SEPARATED`] are the browsers that support space separated
+/// colors, and the version they were first supported in (or never, for
+/// `None`).
+const SPACE_SEPARATED: &[(Browser, Option<Version>)] = &[
+    (Browser::Chrome, Some(Version(65, 0))),
+    (Browser::Edge, Some(Version(79, 0))),
+    (Browser::Firefox, Some(Version(52, 0))),
+    (Browser::Safari, Some(Version(12, 1))),
+    (Browser::Ios, Some(Version(12, 2))),
+    (Browser::Ie, None),
+];
+
+/// [`is_needed`] returns `true` if any of the `targets` doesn't support
+/// space separated colors.
+pub fn is_needed(targets: &Targets) -> bool {
+    SPACE_SEPARATED.iter().any(
+        |(browser, supported)| match (targets.oldest(*browser), supported) {
+            (Some(_), None) => true,
+            (Some(oldest), Some(supported)) => oldest < *supported,
+            (None, _) => false,
+        },
+    )
+}
+
+/// [`downlevel_colors`] rewrites every `rgb()`, `rgba()`, `hsl()`, and
+/// `hsla()` color with space separated arguments within the declarations of
+/// `stylesheet` with [`legacy_color`], if one of the `targets` doesn't
+/// support them.
+///
+/// # example
+/// [`downlevel_colors`] can be used to support safari 11:
+/// ```rust
+/// use rcss::{browsers::Targets, passes::color_syntax::downlevel_colors, stylesheet::Stylesheet};
+///
+/// let mut stylesheet =
+///     Stylesheet::parse(".a{color:rgb(0 0 0/50%);background:hsl(120deg 50% 50%) url(a.png)}");
+/// downlevel_colors(&mut stylesheet, &Targets::parse("safari >= 11").unwrap());
+/// assert_eq!(
+///     stylesheet.to_css(),
+///     ".a{color:rgba(0,0,0,.5);background:hsl(120,50%,50%) url(a.png)}"
+/// );
+/// ```
+pub fn downlevel_colors(stylesheet: &mut Stylesheet, targets: &Targets) {
+    if is_needed(targets) {
+        downlevel_nodes(&mut stylesheet.nodes);
+    }
+}
+
+/// [`downlevel_nodes`] rewrites the colors of every declaration within
+/// `nodes`, and within every block nested in them.
+fn downlevel_nodes(nodes: &mut [Node]) {
+    for node in nodes {
+        match node {
+            Node::Declaration(declaration) => {
+                if let Some(value) = downlevel_value(&declaration.value) {
+                    declaration.value = value;
+                }
+            }
+            _ => {
+                if let Some(children) = node.children_mut() {
+                    downlevel_nodes(children);
+                }
+            }
+        }
+    }
+}
+
+/// [`downlevel_value`] returns `value` with every space separated color
+/// rewritten, or `None` if it doesn't have any.
+fn downlevel_value(value: &str) -> Option<String> {
+    let tokens = tokenize(value);
+    let mut downleveled = String::with_capacity(value.len());
+    let mut is_changed = false;
+    let mut index = 0;
+    while index < tokens.len() {
+        let token = &tokens[index];
+        index += 1;
+        if token.kind == TokenKind::Function {
+            // the arguments end at the close paren of the function, past
+            // any nested within them
+            let mut depth = 0;
+            let close = tokens[index..].iter().position(|token| {
+                match token.kind {
+                    TokenKind::Function | TokenKind::OpenParen => depth += 1,
+                    TokenKind::CloseParen if depth == 0 => return true,
+                    TokenKind::CloseParen => depth -= 1,
+                    _ => {}
+                }
+                false
+            });
+            let name = token.text.trim_end_matches('(');
+            if let Some(close) = close {
+                let arguments = tokens[index..index + close]
+                    .iter()
+                    .map(|token| token.text)
+                    .collect::<String>();
+                if let Some(color) = legacy_color(name, &arguments) {
+                    downleveled.push_str(&color);
+                    is_changed = true;
+                    index += close + 1;
+                    continue;
+                }
+            }
+        }
+        downleveled.push_str(token.text);
+    }
+    is_changed.then_some(downleveled)
+}
@@ -0,0 +1,144 @@
+//! merging of rules with identical selectors, and removal of the
+//! duplicate declarations and `@font-face` blocks that concatenating many
+//! files produces.
+use crate::{
+    passes::{can_move_past, declared_properties, for_each_block},
+    stylesheet::{Declaration, Node, Stylesheet},
+};
+
+/// [`merge_duplicate_rules`] merges every rule into an earlier rule in the
+/// same block with an identical selector, dropping declarations from the
+/// earlier rule that the later rule overrides. it also drops declarations
+/// that are exact duplicates of a later declaration in the same block, and
+/// `@font-face` blocks that are exact duplicates of a later one, like when
+/// two files both declare the same font.
+///
+/// # notes
+/// a rule is only merged when no rule between the two sets any of the
+/// properties it sets, so the cascade order of the result is unchanged.
+///
+/// # example
+/// [`merge_duplicate_rules`] can be used to combine two `.a` rules:
+/// ```rust
+/// use rcss::{passes::duplicates::merge_duplicate_rules, stylesheet::Stylesheet};
+///
+/// let mut stylesheet = Stylesheet::parse(".a{color:red;margin:0}.b{padding:0}.a{color:blue}");
+/// merge_duplicate_rules(&mut stylesheet);
+/// assert_eq!(stylesheet.to_css(), ".a{margin:0;color:blue}.b{padding:0}");
+///
+/// let font = "@font-face{font-family:a;src:url(a.woff2) format(\"woff2\")}";
+/// let mut stylesheet = Stylesheet::parse(&format!("{font}.a{{font-family:a}}{font}"));
+/// merge_duplicate_rules(&mut stylesheet);
+/// assert_eq!(stylesheet.to_css(), format!(".a{{font-family:a}}{font}"));
+/// ```
+pub fn merge_duplicate_rules(stylesheet: &mut Stylesheet) {
+    for_each_block(stylesheet, &mut |nodes| {
+        remove_duplicate_font_faces(nodes);
+        merge_rules_in_block(nodes);
+        for node in nodes.iter_mut() {
+            if let Some(children) = node.children_mut() {
+                remove_identical_declarations(children);
+            }
+        }
+    });
+}
+
+/// [`remove_duplicate_font_faces`] removes every `@font-face` in `nodes`
+/// that is identical to a later one. the last one is kept, since a later
+/// `@font-face` wins over an earlier one that matches the same font.
+fn remove_duplicate_font_faces(nodes: &mut Vec<Node>) {
+    let is_font_face = |node: &Node| matches!(node, Node::AtRule(at_rule) if at_rule.name.eq_ignore_ascii_case("font-face"));
+    let mut index = 0;
+    while index < nodes.len() {
+        let is_repeated = is_font_face(&nodes[index]) && nodes[index + 1..].contains(&nodes[index]);
+        if is_repeated {
+            nodes.remove(index);
+        } else {
+            index += 1;
+        }
+    }
+}
+
+/// [`merge_rules_in_block`] merges rules with identical selectors that are
+/// siblings within `nodes`.
+fn merge_rules_in_block(nodes: &mut Vec<Node>) {
+    let mut index = 0;
+    while index < nodes.len() {
+        let Some(selector) = mergeable_selector(&nodes[index]) else {
+            index += 1;
+            continue;
+        };
+        let earlier = (0..index)
+            .rev()
+            .find(|&earlier| mergeable_selector(&nodes[earlier]) == Some(selector));
+        let Some(earlier) = earlier else {
+            index += 1;
+            continue;
+        };
+        let properties = declared_properties(&nodes[index]);
+        if !can_move_past(&nodes[earlier + 1..index], &properties) {
+            index += 1;
+            continue;
+        }
+
+        let (before, after) = nodes.split_at_mut(index);
+        let (Node::Rule(rule), Node::Rule(later)) = (&mut before[earlier], &mut after[0]) else {
+            index += 1;
+            continue;
+        };
+        for child in std::mem::take(&mut later.children) {
+            let Node::Declaration(declaration) = child else {
+                continue;
+            };
+            merge_declaration(&mut rule.children, declaration);
+        }
+        nodes.remove(index);
+    }
+}
+
+/// [`mergeable_selector`] returns the selector of `node` if it is a rule
+/// that only contains declarations.
+fn mergeable_selector(node: &Node) -> Option<&str> {
+    match node {
+        Node::Rule(rule)
+            if rule
+                .children
+                .iter()
+                .all(|child| matches!(child, Node::Declaration(_))) =>
+        {
+            Some(&rule.selector)
+        }
+        _ => None,
+    }
+}
+
+/// [`merge_declaration`] appends `declaration` to `children`, removing any
+/// earlier declaration of the same property that it overrides. nothing is
+/// appended if an earlier `!important` declaration would win anyway.
+fn merge_declaration(children: &mut Vec<Node>, declaration: Declaration) {
+    let same_property = |node: &Node| matches!(node, Node::Declaration(existing) if existing.property.eq_ignore_ascii_case(&declaration.property));
+    let is_overridden = children.iter().any(|node| {
+        same_property(node)
+            && matches!(node, Node::Declaration(existing) if existing.important && !declaration.important)
+    });
+    if is_overridden {
+        return;
+    }
+    children.retain(|node| !same_property(node));
+    children.push(Node::Declaration(declaration));
+}
+
+/// [`remove_identical_declarations`] removes every declaration in `nodes`
+/// that is identical to a later declaration in the same block.
+fn remove_identical_declarations(nodes: &mut Vec<Node>) {
+    let mut index = 0;
+    while index < nodes.len() {
+        let is_repeated = matches!(&nodes[index], Node::Declaration(_))
+            && nodes[index + 1..].contains(&nodes[index]);
+        if is_repeated {
+            nodes.remove(index);
+        } else {
+            index += 1;
+        }
+    }
+}
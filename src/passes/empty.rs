@@ -0,0 +1,47 @@
+//! removal of rules and at-rules that are left without any content, like
+//! `.a{}` or `@media print{}`.
+use crate::stylesheet::{Node, Stylesheet};
+
+/// [`remove_empty_rules`] removes every rule with an empty block, and
+/// every at-rule whose block is empty (or only contained empty rules),
+/// from `stylesheet`.
+///
+/// # notes
+/// an empty `@layer` block is kept, since it still declares where the
+/// layer is in the layer order.
+///
+/// # example
+/// [`remove_empty_rules`] can be used to remove a media query that only
+/// contains an empty rule:
+/// ```rust
+/// use rcss::{passes::empty::remove_empty_rules, stylesheet::Stylesheet};
+///
+/// let mut stylesheet = Stylesheet::parse("a{color:red}@media print{.b{}}.c{}");
+/// remove_empty_rules(&mut stylesheet);
+/// assert_eq!(stylesheet.to_css(), "a{color:red}");
+/// ```
+pub fn remove_empty_rules(stylesheet: &mut Stylesheet) {
+    remove_empty_nodes(&mut stylesheet.nodes);
+}
+
+/// [`remove_empty_nodes`] removes the empty rules and at-rules within
+/// `nodes`, starting with the most deeply nested ones, so a block that
+/// only contained empty rules is removed too.
+fn remove_empty_nodes(nodes: &mut Vec<Node>) {
+    for node in nodes.iter_mut() {
+        if let Some(children) = node.children_mut() {
+            remove_empty_nodes(children);
+        }
+    }
+    nodes.retain(|node| match node {
+        Node::Rule(rule) => !rule.children.is_empty(),
+        Node::AtRule(at_rule) => {
+            at_rule.name.eq_ignore_ascii_case("layer")
+                || at_rule
+                    .children
+                    .as_ref()
+                    .is_none_or(|children| !children.is_empty())
+        }
+        _ => true,
+    });
+}
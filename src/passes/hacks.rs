@@ -0,0 +1,93 @@
+//! removal of the hacks that only old versions of internet explorer
+//! understand, like `*zoom:1` or `@media \0screen`, for projects that no
+//! longer support those browsers.
+use crate::{
+    selectors::split_list,
+    stylesheet::{Declaration, Node, Stylesheet},
+};
+
+/// [`HACK_SELECTORS`] are the starts of the selectors that only internet
+/// explorer 6 and 7 match, since they see an `html` inside of `*`.
+const HACK_SELECTORS: &[&str] = &["* html", "*html", "*+html", "*:first-child+html"];
+
+/// [`strip_hacks`] removes every legacy internet explorer hack from
+/// `stylesheet`: declarations whose property starts with `*` or `_`, or
+/// whose value ends with `!ie` or `\9`, `@media` blocks whose query contains
+/// `\0` or `\9`, like `@media \0screen`, and selectors that start with
+/// `* html` or `*+html`.
+///
+/// # notes
+/// a rule whose selectors were all hacks is removed along with them, but a
+/// rule left without declarations is kept for
+/// [`crate::passes::empty::remove_empty_rules`] to remove.
+///
+/// # example
+/// [`strip_hacks`] can be used to remove the hacks of an old stylesheet:
+/// ```rust
+/// use rcss::{passes::hacks::strip_hacks, stylesheet::Stylesheet};
+///
+/// let mut stylesheet = Stylesheet::parse(
+///     ".a{*zoom:1;_height:1px;color:red!ie;width:1px\\9;margin:0}@media \\0screen{.b{color:red}}*+html .c,.d{color:blue}",
+/// );
+/// strip_hacks(&mut stylesheet);
+/// assert_eq!(stylesheet.to_css(), ".a{margin:0}.d{color:blue}");
+/// ```
+pub fn strip_hacks(stylesheet: &mut Stylesheet) {
+    strip_nodes(&mut stylesheet.nodes);
+}
+
+/// [`strip_nodes`] removes the hacks within `nodes`, and within every block
+/// nested in them.
+fn strip_nodes(nodes: &mut Vec<Node>) {
+    nodes.retain_mut(|node| {
+        match node {
+            Node::Declaration(declaration) => return !is_hack_declaration(declaration),
+            Node::AtRule(at_rule)
+                if at_rule.name.eq_ignore_ascii_case("media")
+                    && (at_rule.prelude.contains("\\0") || at_rule.prelude.contains("\\9")) =>
+            {
+                return false
+            }
+            Node::Rule(rule) => {
+                let selectors = split_list(&rule.selector);
+                if selectors.iter().any(|selector| is_hack_selector(selector)) {
+                    let selectors = selectors
+                        .into_iter()
+                        .filter(|selector| !is_hack_selector(selector))
+                        .collect::<Vec<_>>();
+                    if selectors.is_empty() {
+                        return false;
+                    }
+                    rule.selector = selectors.join(",");
+                }
+            }
+            _ => {}
+        }
+        if let Some(children) = node.children_mut() {
+            strip_nodes(children);
+        }
+        true
+    });
+}
+
+/// [`is_hack_declaration`] returns `true` if `declaration` is only read by
+/// old versions of internet explorer, like `*zoom:1` or `color:red\9`.
+fn is_hack_declaration(declaration: &Declaration) -> bool {
+    let value = declaration.value.trim_end();
+    declaration.property.starts_with(['*', '_'])
+        || value.ends_with("\\9")
+        || value
+            .rfind('!')
+            .is_some_and(|bang| value[bang + 1..].trim().eq_ignore_ascii_case("ie"))
+}
+
+/// [`is_hack_selector`] returns `true` if `selector` only matches in old
+/// versions of internet explorer.
+fn is_hack_selector(selector: &str) -> bool {
+    let selector = selector.to_ascii_lowercase();
+    HACK_SELECTORS.iter().any(|hack| {
+        selector
+            .strip_prefix(hack)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with([' ', '>', '+', '~']))
+    })
+}
@@ -0,0 +1,260 @@
+//! shortening of the names of `@keyframes` and custom properties, like
+//! `@keyframes fade-in-from-left` to `@keyframes a` and `--brand-color` to
+//! `--a`, along with every use of them within the stylesheet.
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    glob::Glob,
+    passes::{for_each_block, property_name},
+    stylesheet::{Node, Stylesheet},
+    tokenizer::{tokenize, TokenKind},
+};
+
+/// [`RESERVED_NAMES`] are the keywords that a shortened `@keyframes` name
+/// can't be, since they mean something else within `animation`.
+const RESERVED_NAMES: [&str; 28] = [
+    "none",
+    "auto",
+    "initial",
+    "inherit",
+    "unset",
+    "revert",
+    "default",
+    "ease",
+    "ease-in",
+    "ease-out",
+    "linear",
+    "infinite",
+    "normal",
+    "reverse",
+    "alternate",
+    "forwards",
+    "backwards",
+    "both",
+    "running",
+    "paused",
+    "step",
+    "steps",
+    "start",
+    "end",
+    "jump",
+    "replace",
+    "add",
+    "accumulate",
+];
+
+/// [`shorten_identifiers`] renames every `@keyframes` and custom property
+/// that is declared within `stylesheet` to the shortest name that isn't
+/// already used, and rewrites every `animation`, `var()`, and `@property`
+/// that refers to them to match. names matching one of `keep` are left
+/// alone, since javascript that refers to them can't be rewritten.
+///
+/// # notes
+/// custom properties that are only referenced, and never declared, are
+/// left alone, since they are set from outside of the stylesheet, like by an
+/// inline `style` attribute. a `@keyframes` name that is also the value of a
+/// custom property is left alone too, since it can't be told apart from any
+/// other keyword.
+///
+/// # example
+/// [`shorten_identifiers`] can be used to shorten the names of an animation
+/// and a theme color, keeping the one javascript sets:
+/// ```rust
+/// use rcss::{glob::Glob, passes::identifiers::shorten_identifiers, stylesheet::Stylesheet};
+///
+/// let mut stylesheet = Stylesheet::parse(
+///     ":root{--brand-color:red;--theme-gap:4px}\
+///      @keyframes fade-in{from{opacity:0}}\
+///      .card{color:var(--brand-color);margin:var(--theme-gap);animation:fade-in 1s ease}",
+/// );
+/// shorten_identifiers(&mut stylesheet, &[Glob::new("--theme-*")]);
+/// assert_eq!(
+///     stylesheet.to_css(),
+///     ":root{--a:red;--theme-gap:4px}@keyframes a{from{opacity:0}}\
+///      .card{color:var(--a);margin:var(--theme-gap);animation:a 1s ease}"
+/// );
+/// ```
+pub fn shorten_identifiers(stylesheet: &mut Stylesheet, keep: &[Glob]) {
+    let mut names = Names::default();
+    collect_names(&stylesheet.nodes, &mut names);
+    let is_kept = |name: &str| keep.iter().any(|glob| glob.matches(name));
+    names
+        .keyframes
+        .retain(|name| !is_kept(name) && !names.keywords.contains(name));
+    names.properties.retain(|name| !is_kept(name));
+
+    let keyframes = shortened_names(&names.keyframes, &names.idents, "");
+    let properties = shortened_names(&names.properties, &names.idents, "--");
+    if keyframes.is_empty() && properties.is_empty() {
+        return;
+    }
+    for_each_block(stylesheet, &mut |nodes| {
+        for node in nodes.iter_mut() {
+            match node {
+                Node::AtRule(at_rule) if is_keyframes(&at_rule.name) => {
+                    if let Some(name) = keyframes.get(&at_rule.prelude) {
+                        at_rule.prelude = name.clone();
+                    }
+                }
+                Node::AtRule(at_rule) => {
+                    at_rule.prelude = rename(&at_rule.prelude, &properties, |_| true);
+                }
+                Node::Declaration(declaration) => {
+                    if let Some(name) = properties.get(&declaration.property) {
+                        declaration.property = name.clone();
+                    }
+                    declaration.value = rename(&declaration.value, &properties, |_| true);
+                    if matches!(
+                        property_name(&declaration.property).as_str(),
+                        "animation" | "animation-name"
+                    ) {
+                        declaration.value = rename(&declaration.value, &keyframes, |name| {
+                            !name.starts_with("--")
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+    });
+}
+
+/// [`Names`] are the identifiers found within a stylesheet: the declared
+/// `@keyframes` and custom properties, in the order they are first
+/// declared, every identifier, and the identifiers within the values of
+/// custom properties.
+#[derive(Default)]
+struct Names {
+    keyframes: Vec<String>,
+    properties: Vec<String>,
+    idents: HashSet<String>,
+    keywords: HashSet<String>,
+}
+
+impl Names {
+    /// [`Names::declare`] adds `name` to `declared`, unless it is already
+    /// there.
+    fn declare(declared: &mut Vec<String>, name: &str) {
+        if !declared.iter().any(|declared| declared == name) {
+            declared.push(name.to_string());
+        }
+    }
+
+    /// [`Names::add_idents`] adds every identifier within `text` to the
+    /// identifiers that are taken, and returns them.
+    fn add_idents(&mut self, text: &str) -> Vec<String> {
+        let idents = tokenize(text)
+            .into_iter()
+            .filter(|token| token.kind == TokenKind::Ident)
+            .map(|token| token.text.to_string())
+            .collect::<Vec<_>>();
+        self.idents.extend(idents.iter().cloned());
+        idents
+    }
+}
+
+/// [`collect_names`] adds the identifiers within `nodes`, and within every
+/// block within them, to `names`.
+fn collect_names(nodes: &[Node], names: &mut Names) {
+    for node in nodes {
+        match node {
+            Node::Rule(rule) => {
+                names.add_idents(&rule.selector);
+            }
+            Node::AtRule(at_rule) => {
+                let idents = names.add_idents(&at_rule.prelude);
+                let is_name = idents.len() == 1 && idents[0] == at_rule.prelude;
+                if is_keyframes(&at_rule.name) && is_name {
+                    Names::declare(&mut names.keyframes, &at_rule.prelude);
+                } else if at_rule.name.eq_ignore_ascii_case("property") && is_name {
+                    Names::declare(&mut names.properties, &at_rule.prelude);
+                }
+            }
+            Node::Declaration(declaration) => {
+                let idents = names.add_idents(&declaration.value);
+                if declaration.property.starts_with("--") {
+                    names.idents.insert(declaration.property.clone());
+                    Names::declare(&mut names.properties, &declaration.property);
+                    names.keywords.extend(idents);
+                }
+            }
+            Node::Raw(raw) => {
+                names.add_idents(raw);
+            }
+        }
+        if let Some(children) = node.children() {
+            collect_names(children, names);
+        }
+    }
+}
+
+/// [`shortened_names`] returns the shortest name, starting with `prefix`,
+/// for every one of `declared`, skipping the names that are already `taken`
+/// and leaving out the ones that wouldn't get any shorter.
+fn shortened_names(
+    declared: &[String],
+    taken: &HashSet<String>,
+    prefix: &str,
+) -> HashMap<String, String> {
+    let mut shortened = HashMap::new();
+    let mut index = 0;
+    for name in declared {
+        let short_name = loop {
+            let short_name = format!("{prefix}{}", short_name(index));
+            index += 1;
+            if !taken.contains(&short_name) && !RESERVED_NAMES.contains(&short_name.as_str()) {
+                break short_name;
+            }
+        };
+        if short_name.len() < name.len() {
+            shortened.insert(name.clone(), short_name);
+        } else {
+            // every later name is at least as long, so give this one back
+            index -= 1;
+        }
+    }
+    shortened
+}
+
+/// [`short_name`] returns the `index`th shortest lowercase name: `a` to
+/// `z`, then `aa` to `zz`, and so on.
+fn short_name(mut index: usize) -> String {
+    let mut name = vec![];
+    loop {
+        name.push(b'a' + (index % 26) as u8);
+        match index / 26 {
+            0 => break,
+            next => index = next - 1,
+        }
+    }
+    name.reverse();
+    String::from_utf8(name).unwrap_or_default()
+}
+
+/// [`rename`] returns `text` with every identifier in `names`, and allowed
+/// by `is_renamed`, replaced by its new name.
+fn rename(
+    text: &str,
+    names: &HashMap<String, String>,
+    is_renamed: impl Fn(&str) -> bool,
+) -> String {
+    if names.is_empty() {
+        return text.to_string();
+    }
+    let mut renamed = String::with_capacity(text.len());
+    for token in tokenize(text) {
+        match names.get(token.text) {
+            Some(name) if token.kind == TokenKind::Ident && is_renamed(token.text) => {
+                renamed.push_str(name)
+            }
+            _ => renamed.push_str(token.text),
+        }
+    }
+    renamed
+}
+
+/// [`is_keyframes`] returns `true` if the at-rule `name` is `keyframes`,
+/// with or without a vendor prefix.
+fn is_keyframes(name: &str) -> bool {
+    name.to_ascii_lowercase().ends_with("keyframes")
+}
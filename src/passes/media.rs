@@ -0,0 +1,87 @@
+//! merging of `@media` blocks with identical queries, which combining
+//! many component stylesheets produces plenty of.
+use crate::{
+    passes::{can_move_past, declared_properties, for_each_block},
+    stylesheet::{AtRule, Node, Stylesheet},
+};
+
+/// [`merge_media_queries`] moves the content of every `@media` block into
+/// an earlier `@media` block in the same block with an identical query,
+/// after the content it already has.
+///
+/// # notes
+/// a block is only merged when no rule between the two sets any of the
+/// properties it sets, so the cascade order of the result is unchanged.
+///
+/// # example
+/// [`merge_media_queries`] can be used to combine two `(min-width:768px)`
+/// queries:
+/// ```rust
+/// use rcss::{passes::media::merge_media_queries, stylesheet::Stylesheet};
+///
+/// let mut stylesheet = Stylesheet::parse(
+///     "@media (min-width:768px){.a{color:red}}.b{margin:0}@media (min-width:768px){.c{padding:0}}",
+/// );
+/// merge_media_queries(&mut stylesheet);
+/// assert_eq!(
+///     stylesheet.to_css(),
+///     "@media (min-width:768px){.a{color:red}.c{padding:0}}.b{margin:0}",
+/// );
+/// ```
+pub fn merge_media_queries(stylesheet: &mut Stylesheet) {
+    for_each_block(stylesheet, &mut merge_media_in_block);
+}
+
+/// [`merge_media_in_block`] merges `@media` blocks with identical queries
+/// that are siblings within `nodes`.
+fn merge_media_in_block(nodes: &mut Vec<Node>) {
+    let mut index = 0;
+    while index < nodes.len() {
+        let Some(query) = media_query(&nodes[index]) else {
+            index += 1;
+            continue;
+        };
+        let earlier = (0..index).rev().find(|&earlier| {
+            media_query(&nodes[earlier]).is_some_and(|other| other.eq_ignore_ascii_case(query))
+        });
+        let Some(earlier) = earlier else {
+            index += 1;
+            continue;
+        };
+        let properties = declared_properties(&nodes[index]);
+        if !can_move_past(&nodes[earlier + 1..index], &properties) {
+            index += 1;
+            continue;
+        }
+
+        let (before, after) = nodes.split_at_mut(index);
+        let (
+            Node::AtRule(AtRule {
+                children: Some(children),
+                ..
+            }),
+            Node::AtRule(AtRule {
+                children: Some(later),
+                ..
+            }),
+        ) = (&mut before[earlier], &mut after[0])
+        else {
+            index += 1;
+            continue;
+        };
+        children.append(later);
+        nodes.remove(index);
+    }
+}
+
+/// [`media_query`] returns the query of `node` if it is an `@media` block.
+fn media_query(node: &Node) -> Option<&str> {
+    match node {
+        Node::AtRule(at_rule)
+            if at_rule.name.eq_ignore_ascii_case("media") && at_rule.children.is_some() =>
+        {
+            Some(&at_rule.prelude)
+        }
+        _ => None,
+    }
+}
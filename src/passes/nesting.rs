@@ -0,0 +1,101 @@
+//! flattening of nested rules, like `.card{.title{...}}`, into
+//! plain css that every browser understands.
+use crate::{
+    selectors::{replace_nesting, split_list},
+    stylesheet::{Node, Rule, Stylesheet},
+};
+
+/// [`CONTEXT_FREE_AT_RULES`] are at-rules whose contents don't depend on
+/// the rule they are nested in.
+pub(crate) const CONTEXT_FREE_AT_RULES: &[&str] = &[
+    "keyframes",
+    "font-face",
+    "page",
+    "property",
+    "counter-style",
+];
+
+/// [`flatten_nesting`] will flatten every nested rule in `stylesheet`,
+/// resolving `&` against the parent selector (or joining them with a
+/// descendant combinator when there is no `&`), and bubbling nested
+/// at-rules like `@media` up around a copy of the parent rule.
+///
+/// # example
+/// [`flatten_nesting`] can be used to turn a nested card into plain css:
+/// ```rust
+/// use rcss::{passes::nesting::flatten_nesting, stylesheet::Stylesheet};
+///
+/// let mut stylesheet = Stylesheet::parse(".card{color:red;.title{margin:0}&:hover{color:blue}}");
+/// flatten_nesting(&mut stylesheet);
+/// assert_eq!(stylesheet.to_css(), ".card{color:red}.card .title{margin:0}.card:hover{color:blue}");
+/// ```
+pub fn flatten_nesting(stylesheet: &mut Stylesheet) {
+    stylesheet.nodes = flatten_block(std::mem::take(&mut stylesheet.nodes), None);
+}
+
+/// [`flatten_block`] flattens `nodes`, which are nested within a rule with
+/// the selectors `parents` (or aren't within any rule, when `None`).
+fn flatten_block(nodes: Vec<Node>, parents: Option<&[String]>) -> Vec<Node> {
+    let mut flattened = vec![];
+    let mut declarations = vec![];
+    for node in nodes {
+        match node {
+            Node::Declaration(_) | Node::Raw(_) if parents.is_some() => declarations.push(node),
+            Node::Declaration(_) | Node::Raw(_) => flattened.push(node),
+            Node::Rule(rule) => {
+                flush_declarations(&mut flattened, &mut declarations, parents);
+                let selectors = resolve_selectors(&rule.selector, parents);
+                flattened.extend(flatten_block(rule.children, Some(&selectors)));
+            }
+            Node::AtRule(mut at_rule) => {
+                flush_declarations(&mut flattened, &mut declarations, parents);
+                let is_context_free =
+                    CONTEXT_FREE_AT_RULES.contains(&at_rule.name.to_ascii_lowercase().as_str());
+                let context = if is_context_free { None } else { parents };
+                at_rule.children = at_rule
+                    .children
+                    .map(|children| flatten_block(children, context));
+                flattened.push(Node::AtRule(at_rule));
+            }
+        }
+    }
+    flush_declarations(&mut flattened, &mut declarations, parents);
+    flattened
+}
+
+/// [`flush_declarations`] moves `declarations` into a new rule for the
+/// `parents` selectors at the end of `flattened`.
+fn flush_declarations(
+    flattened: &mut Vec<Node>,
+    declarations: &mut Vec<Node>,
+    parents: Option<&[String]>,
+) {
+    let Some(parents) = parents.filter(|_| !declarations.is_empty()) else {
+        return;
+    };
+    flattened.push(Node::Rule(Rule {
+        selector: parents.join(","),
+        children: std::mem::take(declarations),
+    }));
+}
+
+/// [`resolve_selectors`] returns the selector list of a rule nested within
+/// `parents`, with every nested selector resolved against every parent.
+fn resolve_selectors(selector: &str, parents: Option<&[String]>) -> Vec<String> {
+    let selectors = split_list(selector);
+    let Some(parents) = parents else {
+        return selectors;
+    };
+    let mut resolved = vec![];
+    for parent in parents {
+        for selector in &selectors {
+            let is_relative = selector.starts_with(['>', '+', '~']);
+            resolved.push(match selector {
+                _ if selector.contains('&') => replace_nesting(selector, parent),
+                _ if is_relative => format!("{parent}{selector}"),
+                _ => format!("{parent} {selector}"),
+            });
+        }
+    }
+    resolved
+}
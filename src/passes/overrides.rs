@@ -0,0 +1,86 @@
+//! removal of declarations that a later declaration of the same property in
+//! the same block overrides, like the `color:red` of `color:red;color:blue`.
+use crate::{
+    passes::for_each_block,
+    stylesheet::{Declaration, Node, Stylesheet},
+};
+
+/// [`remove_overridden_declarations`] removes every declaration that is
+/// overridden by another declaration of the same property within the same
+/// block: an earlier one that a later one replaces, or a later one that an
+/// earlier `!important` one wins over anyway.
+///
+/// # notes
+/// a declaration with a different value than the one that overrides it can
+/// be a fallback for browsers that don't understand the other value, like the
+/// `display:block` of `display:block;display:flex`. those are kept when
+/// `keep_fallbacks` is set, except for custom properties, which every
+/// browser that understands them accepts any value of. blocks with anything
+/// that couldn't be parsed are left alone.
+///
+/// # example
+/// [`remove_overridden_declarations`] can be used to remove a color that is
+/// set twice, keeping a fallback:
+/// ```rust
+/// use rcss::{passes::overrides::remove_overridden_declarations, stylesheet::Stylesheet};
+///
+/// let css = ".a{color:red;display:block;color:blue;display:flex}";
+/// let mut stylesheet = Stylesheet::parse(css);
+/// remove_overridden_declarations(&mut stylesheet, false);
+/// assert_eq!(stylesheet.to_css(), ".a{color:blue;display:flex}");
+///
+/// let mut stylesheet = Stylesheet::parse(".a{display:block;display:flex;--gap:0;--gap:4px}");
+/// remove_overridden_declarations(&mut stylesheet, true);
+/// assert_eq!(stylesheet.to_css(), ".a{display:block;display:flex;--gap:4px}");
+/// ```
+pub fn remove_overridden_declarations(stylesheet: &mut Stylesheet, keep_fallbacks: bool) {
+    for_each_block(stylesheet, &mut |nodes| {
+        if nodes.iter().any(|node| matches!(node, Node::Raw(_))) {
+            return;
+        }
+        let mut index = 0;
+        while index < nodes.len() {
+            let is_overridden = match &nodes[index] {
+                Node::Declaration(declaration) => {
+                    nodes.iter().enumerate().any(|(other, node)| match node {
+                        Node::Declaration(overriding) if other != index => {
+                            overrides(overriding, other > index, declaration, keep_fallbacks)
+                        }
+                        _ => false,
+                    })
+                }
+                _ => false,
+            };
+            if is_overridden {
+                nodes.remove(index);
+            } else {
+                index += 1;
+            }
+        }
+    });
+}
+
+/// [`overrides`] returns `true` if `overriding`, which comes after
+/// `declaration` if `is_later` is set and before it otherwise, makes
+/// `declaration` unnecessary.
+fn overrides(
+    overriding: &Declaration,
+    is_later: bool,
+    declaration: &Declaration,
+    keep_fallbacks: bool,
+) -> bool {
+    if !overriding
+        .property
+        .eq_ignore_ascii_case(&declaration.property)
+    {
+        return false;
+    }
+    let wins = match is_later {
+        true => overriding.important || !declaration.important,
+        false => overriding.important && !declaration.important,
+    };
+    let is_fallback = keep_fallbacks
+        && !declaration.property.starts_with("--")
+        && overriding.value != declaration.value;
+    wins && !is_fallback
+}
@@ -0,0 +1,264 @@
+//! adding the vendor prefixes that the targeted browsers need, and
+//! stripping the ones that none of them need anymore.
+use crate::{
+    browsers::{Browser, Targets, Version},
+    passes::for_each_block,
+    stylesheet::{Declaration, Node, Stylesheet},
+};
+
+/// [`Prefixed`] is a property that some browsers only support with a
+/// vendor `prefix`, and which `browsers` need it before which version
+/// (or in every version, for `None`).
+struct Prefixed {
+    properties: &'static [&'static str],
+    prefix: &'static str,
+    browsers: &'static [(Browser, Option<Version>)],
+}
+
+const PREFIXED: &[Prefixed] = &[
+    Prefixed {
+        properties: &["user-select"],
+        prefix: "-webkit-",
+        browsers: &[
+            (Browser::Chrome, Some(Version(54, 0))),
+            (Browser::Safari, None),
+            (Browser::Ios, None),
+        ],
+    },
+    Prefixed {
+        properties: &["user-select"],
+        prefix: "-moz-",
+        browsers: &[(Browser::Firefox, Some(Version(69, 0)))],
+    },
+    Prefixed {
+        properties: &["user-select"],
+        prefix: "-ms-",
+        browsers: &[(Browser::Ie, None), (Browser::Edge, Some(Version(79, 0)))],
+    },
+    Prefixed {
+        properties: &["appearance"],
+        prefix: "-webkit-",
+        browsers: &[
+            (Browser::Chrome, Some(Version(84, 0))),
+            (Browser::Edge, Some(Version(84, 0))),
+            (Browser::Safari, Some(Version(15, 4))),
+            (Browser::Ios, Some(Version(15, 4))),
+        ],
+    },
+    Prefixed {
+        properties: &["appearance"],
+        prefix: "-moz-",
+        browsers: &[(Browser::Firefox, Some(Version(80, 0)))],
+    },
+    Prefixed {
+        properties: &["backdrop-filter"],
+        prefix: "-webkit-",
+        browsers: &[
+            (Browser::Safari, Some(Version(18, 0))),
+            (Browser::Ios, Some(Version(18, 0))),
+        ],
+    },
+    Prefixed {
+        properties: &["text-size-adjust"],
+        prefix: "-webkit-",
+        browsers: &[(Browser::Safari, None), (Browser::Ios, None)],
+    },
+    Prefixed {
+        properties: &[
+            "mask",
+            "mask-image",
+            "mask-size",
+            "mask-position",
+            "mask-repeat",
+        ],
+        prefix: "-webkit-",
+        browsers: &[
+            (Browser::Chrome, Some(Version(120, 0))),
+            (Browser::Edge, Some(Version(120, 0))),
+            (Browser::Safari, Some(Version(15, 4))),
+            (Browser::Ios, Some(Version(15, 4))),
+        ],
+    },
+    Prefixed {
+        properties: &["hyphens"],
+        prefix: "-webkit-",
+        browsers: &[
+            (Browser::Safari, Some(Version(17, 0))),
+            (Browser::Ios, Some(Version(17, 0))),
+        ],
+    },
+    Prefixed {
+        properties: &["hyphens"],
+        prefix: "-ms-",
+        browsers: &[(Browser::Ie, None)],
+    },
+    Prefixed {
+        properties: &["tab-size"],
+        prefix: "-moz-",
+        browsers: &[(Browser::Firefox, Some(Version(91, 0)))],
+    },
+    Prefixed {
+        properties: &["box-decoration-break"],
+        prefix: "-webkit-",
+        browsers: &[
+            (Browser::Chrome, Some(Version(130, 0))),
+            (Browser::Edge, Some(Version(130, 0))),
+            (Browser::Safari, None),
+            (Browser::Ios, None),
+        ],
+    },
+    Prefixed {
+        properties: &["clip-path"],
+        prefix: "-webkit-",
+        browsers: &[
+            (Browser::Chrome, Some(Version(55, 0))),
+            (Browser::Safari, Some(Version(13, 1))),
+            (Browser::Ios, Some(Version(13, 0))),
+        ],
+    },
+    Prefixed {
+        properties: &["columns", "column-count", "column-width"],
+        prefix: "-webkit-",
+        browsers: &[
+            (Browser::Chrome, Some(Version(50, 0))),
+            (Browser::Safari, Some(Version(9, 0))),
+            (Browser::Ios, Some(Version(9, 0))),
+        ],
+    },
+    Prefixed {
+        properties: &["columns", "column-count", "column-width"],
+        prefix: "-moz-",
+        browsers: &[(Browser::Firefox, Some(Version(52, 0)))],
+    },
+    Prefixed {
+        properties: &["transform", "transform-origin", "transition", "animation"],
+        prefix: "-webkit-",
+        browsers: &[
+            (Browser::Chrome, Some(Version(36, 0))),
+            (Browser::Safari, Some(Version(9, 0))),
+            (Browser::Ios, Some(Version(9, 0))),
+        ],
+    },
+    Prefixed {
+        properties: &["transform", "transform-origin"],
+        prefix: "-ms-",
+        browsers: &[(Browser::Ie, Some(Version(10, 0)))],
+    },
+];
+
+/// [`is_needed`] returns `true` if any of the `targets` needs `prefixed`.
+fn is_needed(prefixed: &Prefixed, targets: &Targets) -> bool {
+    prefixed.browsers.iter().any(|(browser, unprefixed)| {
+        match (targets.oldest(*browser), unprefixed) {
+            (Some(_), None) => true,
+            (Some(oldest), Some(unprefixed)) => oldest < *unprefixed,
+            (None, _) => false,
+        }
+    })
+}
+
+/// [`add_prefixes`] adds a prefixed copy of every declaration in
+/// `stylesheet` that one of the `targets` needs a vendor prefix for, right
+/// before the declaration, unless the block already has one.
+///
+/// # example
+/// [`add_prefixes`] can be used to support older versions of safari:
+/// ```rust
+/// use rcss::{browsers::Targets, passes::prefixes::add_prefixes, stylesheet::Stylesheet};
+///
+/// let mut stylesheet = Stylesheet::parse(".a{backdrop-filter:blur(4px)}");
+/// add_prefixes(&mut stylesheet, &Targets::parse("safari >= 16").unwrap());
+/// assert_eq!(
+///     stylesheet.to_css(),
+///     ".a{-webkit-backdrop-filter:blur(4px);backdrop-filter:blur(4px)}",
+/// );
+/// ```
+pub fn add_prefixes(stylesheet: &mut Stylesheet, targets: &Targets) {
+    for_each_block(stylesheet, &mut |nodes| {
+        let mut index = 0;
+        while index < nodes.len() {
+            let Node::Declaration(declaration) = &nodes[index] else {
+                index += 1;
+                continue;
+            };
+            let needed = PREFIXED
+                .iter()
+                .filter(|prefixed| {
+                    prefixed
+                        .properties
+                        .iter()
+                        .any(|property| declaration.property.eq_ignore_ascii_case(property))
+                        && is_needed(prefixed, targets)
+                })
+                .map(|prefixed| format!("{}{}", prefixed.prefix, declaration.property))
+                .filter(|property| !declares(nodes, property))
+                .collect::<Vec<_>>();
+            let declaration = declaration.clone();
+            for property in needed {
+                nodes.insert(
+                    index,
+                    Node::Declaration(Declaration {
+                        property,
+                        ..declaration.clone()
+                    }),
+                );
+                index += 1;
+            }
+            index += 1;
+        }
+    });
+}
+
+/// [`strip_prefixes`] removes every prefixed declaration in `stylesheet`
+/// that none of the `targets` need, as long as the same block also has
+/// the unprefixed declaration.
+///
+/// # notes
+/// prefixes that rcss doesn't know about are always kept.
+///
+/// # example
+/// [`strip_prefixes`] can be used to drop a prefix that safari no longer
+/// needs:
+/// ```rust
+/// use rcss::{browsers::Targets, passes::prefixes::strip_prefixes, stylesheet::Stylesheet};
+///
+/// let mut stylesheet = Stylesheet::parse(".a{-webkit-hyphens:auto;hyphens:auto}");
+/// strip_prefixes(&mut stylesheet, &Targets::parse("safari >= 17").unwrap());
+/// assert_eq!(stylesheet.to_css(), ".a{hyphens:auto}");
+/// ```
+pub fn strip_prefixes(stylesheet: &mut Stylesheet, targets: &Targets) {
+    for_each_block(stylesheet, &mut |nodes| {
+        let obsolete = nodes
+            .iter()
+            .map(|node| match node {
+                Node::Declaration(declaration) => is_obsolete(&declaration.property, targets)
+                    .is_some_and(|unprefixed| declares(nodes, unprefixed)),
+                _ => false,
+            })
+            .collect::<Vec<_>>();
+        let mut obsolete = obsolete.into_iter();
+        nodes.retain(|_| !obsolete.next().unwrap_or_default());
+    });
+}
+
+/// [`is_obsolete`] returns the unprefixed name of `property` if it has a
+/// known vendor prefix that none of the `targets` need.
+fn is_obsolete<'a>(property: &'a str, targets: &Targets) -> Option<&'a str> {
+    PREFIXED.iter().find_map(|prefixed| {
+        let unprefixed = property
+            .get(prefixed.prefix.len()..)
+            .filter(|_| property[..prefixed.prefix.len()].eq_ignore_ascii_case(prefixed.prefix))?;
+        let is_known = prefixed
+            .properties
+            .iter()
+            .any(|known| unprefixed.eq_ignore_ascii_case(known));
+        (is_known && !is_needed(prefixed, targets)).then_some(unprefixed)
+    })
+}
+
+/// [`declares`] returns `true` if `nodes` has a declaration of `property`.
+fn declares(nodes: &[Node], property: &str) -> bool {
+    nodes.iter().any(|node| {
+        matches!(node, Node::Declaration(declaration) if declaration.property.eq_ignore_ascii_case(property))
+    })
+}
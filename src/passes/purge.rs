@@ -0,0 +1,237 @@
+//! removal of selectors that never match, by checking their class names
+//! and ids against the html, javascript, and template files that use the
+//! stylesheet.
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    error::{RcssError, Result},
+    glob,
+    selectors::split_list,
+    stylesheet::{Node, Stylesheet},
+    tokenizer::{tokenize, TokenKind},
+    Options,
+};
+
+/// [`used_names`] returns every word in `content` that could be a class
+/// name or id, like `card` and `md:flex` in `<div class="card md:flex">`.
+///
+/// # example
+/// [`used_names`] can be used to find the classes used by a template:
+/// ```rust
+/// let used = rcss::passes::purge::used_names(r#"<a class="button md:wide" id="home">"#);
+/// assert!(used.contains("button"));
+/// assert!(used.contains("md:wide"));
+/// assert!(used.contains("home"));
+/// ```
+pub fn used_names(content: &str) -> HashSet<String> {
+    let mut names = HashSet::new();
+    let is_separator = |c: char| c.is_whitespace() || "\"'`<>=(){};,".contains(c);
+    for word in content.split(is_separator).filter(|word| !word.is_empty()) {
+        names.insert(word.to_string());
+        let is_part_separator = |c: char| !(c.is_alphanumeric() || c == '-' || c == '_');
+        for part in word
+            .split(is_part_separator)
+            .filter(|part| !part.is_empty())
+        {
+            names.insert(part.to_string());
+        }
+    }
+    names
+}
+
+/// [`read_used_names`] returns the [`used_names`] of every file matching
+/// one of the `patterns`, which are relative to the current directory.
+/// directories are walked with the same `options` as stylesheets.
+///
+/// # notes
+/// it is an error if no file matches any of the `patterns`, since every
+/// selector with a class name or id would be purged.
+pub fn read_used_names(patterns: &[String], options: &Options) -> Result<HashSet<String>> {
+    let mut names = HashSet::new();
+    let mut matched = false;
+    for pattern in patterns {
+        let pattern = pattern.trim_start_matches("./").replace('\\', "/");
+        let (base, relative_pattern) = split_base(&pattern);
+        let files = match Path::new(base).is_file() {
+            true => vec![PathBuf::from(base)],
+            false if Path::new(base).is_dir() => {
+                let filter = glob::FileFilter::new(&[relative_pattern.to_string()], &[]);
+                crate::recurse_files(base, &filter, options)?
+            }
+            false => vec![],
+        };
+        for file in files {
+            matched = true;
+            let content = fs::read_to_string(&file).map_err(RcssError::io(&file))?;
+            names.extend(used_names(&content));
+        }
+    }
+    if !matched {
+        return Err(RcssError::Config(format!(
+            "no files match the purge patterns {}",
+            patterns.join(", ")
+        )));
+    }
+    Ok(names)
+}
+
+/// [`split_base`] splits `pattern` into the directory before its first
+/// wildcard, which is walked, and the pattern for the paths within it.
+fn split_base(pattern: &str) -> (&str, &str) {
+    let wildcard = pattern.find(['*', '?', '[']).unwrap_or(pattern.len());
+    match pattern[..wildcard].rfind('/') {
+        Some(slash) => (&pattern[..slash], &pattern[slash + 1..]),
+        None if wildcard == pattern.len() => (pattern, ""),
+        None => (".", pattern),
+    }
+}
+
+/// [`purge_unused`] removes every selector in `stylesheet` with a class
+/// name or id that isn't in `used`, every rule left without any selectors,
+/// and every at-rule, like `@media`, left empty by removing them.
+///
+/// # notes
+/// selectors within functional pseudo-classes, like `.a` in `:not(.a)`,
+/// and attribute selectors aren't checked, since they don't need to match
+/// for the selector around them to match.
+///
+/// # example
+/// [`purge_unused`] can be used to drop an unused `.modal` rule:
+/// ```rust
+/// use rcss::{passes::purge::{purge_unused, used_names}, stylesheet::Stylesheet};
+///
+/// let mut stylesheet = Stylesheet::parse(
+///     ".card{margin:0}.modal,.card>h1{padding:0}@media print{#home{color:red}}",
+/// );
+/// purge_unused(&mut stylesheet, &used_names(r#"<div class="card"><h1>"#));
+/// assert_eq!(stylesheet.to_css(), ".card{margin:0}.card>h1{padding:0}");
+/// ```
+pub fn purge_unused(stylesheet: &mut Stylesheet, used: &HashSet<String>) {
+    purge_selectors(stylesheet, &|selector| {
+        selector_names(selector).iter().all(|name| match name {
+            Name::Class(name) | Name::Id(name) => used.contains(name),
+            Name::Type(_) => true,
+        })
+    });
+}
+
+/// [`purge_selectors`] removes every selector in `stylesheet` that `keep`
+/// returns `false` for, every rule left without any selectors, and every
+/// at-rule left empty by removing them. keyframes are always kept.
+pub fn purge_selectors(stylesheet: &mut Stylesheet, keep: &dyn Fn(&str) -> bool) {
+    purge_block(&mut stylesheet.nodes, keep);
+}
+
+/// [`purge_block`] purges the rules within `nodes`, starting with the most
+/// deeply nested ones, and returns `true` if it removed anything.
+fn purge_block(nodes: &mut Vec<Node>, keep: &dyn Fn(&str) -> bool) -> bool {
+    let mut purged = vec![false; nodes.len()];
+    for (node, purged) in nodes.iter_mut().zip(purged.iter_mut()) {
+        match node {
+            Node::Rule(rule) => {
+                let selectors = split_list(&rule.selector);
+                let count = selectors.len();
+                let selectors = selectors
+                    .into_iter()
+                    .filter(|selector| keep(selector))
+                    .collect::<Vec<_>>();
+                *purged = selectors.len() < count;
+                rule.selector = selectors.join(",");
+            }
+            // the `from` and `50%` of a keyframe aren't selectors
+            Node::AtRule(at_rule) if at_rule.name.to_ascii_lowercase().ends_with("keyframes") => {
+                continue
+            }
+            _ => {}
+        }
+        if let Some(children) = node.children_mut() {
+            *purged |= purge_block(children, keep);
+        }
+    }
+    let is_removed = |node: &Node, purged: bool| match node {
+        Node::Rule(rule) => rule.selector.is_empty(),
+        Node::AtRule(at_rule) => purged && at_rule.children.as_ref().is_some_and(Vec::is_empty),
+        _ => false,
+    };
+    let removed = nodes
+        .iter()
+        .zip(&purged)
+        .map(|(node, purged)| is_removed(node, *purged))
+        .collect::<Vec<_>>();
+    let mut removed_nodes = removed.iter();
+    nodes.retain(|_| !removed_nodes.next().copied().unwrap_or_default());
+    purged.contains(&true)
+}
+
+/// [`Name`] is a name that an element must have to match a selector.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Name {
+    /// an element name, like `h1`, which is lowercase.
+    Type(String),
+    /// a class name, like `card` in `.card`, without any escapes.
+    Class(String),
+    /// an id, like `home` in `#home`, without any escapes.
+    Id(String),
+}
+
+/// [`selector_names`] returns every [`Name`] an element must have to
+/// match the complex `selector`, or one of its ancestors or siblings.
+///
+/// # notes
+/// selectors within functional pseudo-classes, like `.a` in `:not(.a)`,
+/// and attribute selectors are left out, since they don't need to match
+/// for the selector around them to match.
+///
+/// # example
+/// [`selector_names`] can be used to find the classes in a selector:
+/// ```rust
+/// use rcss::passes::purge::{selector_names, Name};
+///
+/// let names = selector_names("nav>.md\\:flex:not(.hidden)");
+/// assert_eq!(names, [Name::Type("nav".to_string()), Name::Class("md:flex".to_string())]);
+/// ```
+pub fn selector_names(selector: &str) -> Vec<Name> {
+    let tokens = tokenize(selector);
+    let mut names = vec![];
+    let mut depth = 0;
+    for (index, token) in tokens.iter().enumerate() {
+        let previous = index.checked_sub(1).map(|previous| &tokens[previous]);
+        match token.kind {
+            TokenKind::Function | TokenKind::OpenParen | TokenKind::OpenBracket => depth += 1,
+            TokenKind::CloseParen | TokenKind::CloseBracket => depth -= 1,
+            _ if depth > 0 => {}
+            TokenKind::Hash => names.push(Name::Id(unescape(&token.text[1..]))),
+            TokenKind::Ident => match previous {
+                Some(previous) if previous.kind == TokenKind::Delim && previous.text == "." => {
+                    names.push(Name::Class(unescape(token.text)))
+                }
+                Some(previous)
+                    if previous.kind != TokenKind::Whitespace
+                        && previous.kind != TokenKind::Delim => {}
+                // the `*|` or `svg|` of a namespaced selector isn't a combinator
+                Some(previous) if previous.text == "|" => {}
+                _ => names.push(Name::Type(unescape(token.text).to_ascii_lowercase())),
+            },
+            _ => {}
+        }
+    }
+    names
+}
+
+/// [`unescape`] removes the backslashes from an escaped name, like
+/// `md\:flex`, so it can be compared with the names used in content.
+pub(crate) fn unescape(name: &str) -> String {
+    let mut unescaped = String::new();
+    let mut characters = name.chars();
+    while let Some(character) = characters.next() {
+        match character {
+            '\\' => unescaped.extend(characters.next()),
+            _ => unescaped.push(character),
+        }
+    }
+    unescaped
+}
@@ -0,0 +1,121 @@
+//! scoping of every selector under a prefix selector, like `.my-widget`, so
+//! the css of an embeddable widget doesn't leak into the page it is on.
+use crate::{
+    passes::nesting::CONTEXT_FREE_AT_RULES,
+    selectors::split_list,
+    stylesheet::{Node, Stylesheet},
+    tokenizer::{tokenize, TokenKind},
+};
+
+/// [`ROOT_SELECTORS`] match the root of a document, and are replaced with
+/// the scope itself, since the widget is the root of its own styles.
+const ROOT_SELECTORS: &[&str] = &[":root", "html", "body"];
+
+/// [`scope_selectors`] rewrites the selector of every rule in `stylesheet`
+/// to only match within `scope`, leaving the at-rules whose contents aren't
+/// selectors, like `@keyframes`, alone. a selector that starts with `:root`,
+/// `html`, or `body` matches the scope itself instead. the root of a
+/// `@scope` is scoped, rather than the rules within it, which are relative
+/// to that root.
+///
+/// # example
+/// [`scope_selectors`] can be used to scope a widget's css:
+/// ```rust
+/// use rcss::{passes::scope::scope_selectors, stylesheet::Stylesheet};
+///
+/// let mut stylesheet = Stylesheet::parse(
+///     ":root{--gap:1px}a,.b>p{color:red}@media print{body .c{margin:0}}@keyframes d{to{opacity:0}}",
+/// );
+/// scope_selectors(&mut stylesheet, ".my-widget");
+/// assert_eq!(
+///     stylesheet.to_css(),
+///     ".my-widget{--gap:1px}.my-widget a,.my-widget .b>p{color:red}\
+///      @media print{.my-widget .c{margin:0}}@keyframes d{to{opacity:0}}"
+/// );
+/// ```
+pub fn scope_selectors(stylesheet: &mut Stylesheet, scope: &str) {
+    scope_nodes(&mut stylesheet.nodes, scope);
+}
+
+/// [`scope_nodes`] scopes every rule within `nodes`, which aren't nested
+/// within another rule, so the selectors of nested rules stay relative to
+/// their scoped parent.
+fn scope_nodes(nodes: &mut [Node], scope: &str) {
+    for node in nodes {
+        match node {
+            Node::Rule(rule) => {
+                rule.selector = split_list(&rule.selector)
+                    .iter()
+                    .map(|selector| scope_selector(selector, scope))
+                    .collect::<Vec<_>>()
+                    .join(",");
+            }
+            Node::AtRule(at_rule) if at_rule.name.eq_ignore_ascii_case("scope") => {
+                at_rule.prelude = scope_prelude(&at_rule.prelude, scope);
+            }
+            Node::AtRule(at_rule)
+                if !CONTEXT_FREE_AT_RULES.contains(&at_rule.name.to_ascii_lowercase().as_str()) =>
+            {
+                if let Some(children) = &mut at_rule.children {
+                    scope_nodes(children, scope);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// [`scope_selector`] returns `selector` scoped within `scope`.
+fn scope_selector(selector: &str, scope: &str) -> String {
+    if selector == scope || has_prefix(selector, scope) {
+        return selector.to_string();
+    }
+    for root in ROOT_SELECTORS {
+        if selector.eq_ignore_ascii_case(root) {
+            return scope.to_string();
+        }
+        if has_prefix(&selector.to_ascii_lowercase(), root) {
+            return format!("{scope}{}", &selector[root.len()..]);
+        }
+    }
+    format!("{scope} {selector}")
+}
+
+/// [`scope_prelude`] returns the `prelude` of a `@scope`, like
+/// `(.card) to (.content)`, with its root scoped within `scope`, leaving its
+/// limit alone, since that is already within the root.
+fn scope_prelude(prelude: &str, scope: &str) -> String {
+    let Some(root) = prelude.strip_prefix('(') else {
+        return prelude.to_string();
+    };
+    // the root ends at its close paren, which a string can't contain
+    let mut depth = 0;
+    let mut end = 0;
+    for token in tokenize(root) {
+        match token.kind {
+            TokenKind::Function | TokenKind::OpenParen => depth += 1,
+            TokenKind::CloseParen if depth == 0 => break,
+            TokenKind::CloseParen => depth -= 1,
+            _ => {}
+        }
+        end += token.text.len();
+    }
+    if end == root.len() {
+        return prelude.to_string();
+    }
+    let selectors = split_list(&root[..end])
+        .iter()
+        .map(|selector| scope_selector(selector, scope))
+        .collect::<Vec<_>>()
+        .join(",");
+    format!("({selectors}{}", &root[end..])
+}
+
+/// [`has_prefix`] returns `true` if `selector` starts with the whole
+/// compound selector `prefix`, like `.a` in `.a .b` or `.a>.b`, but not in
+/// `.ab`.
+fn has_prefix(selector: &str, prefix: &str) -> bool {
+    selector
+        .strip_prefix(prefix)
+        .is_some_and(|rest| rest.starts_with([' ', '>', '+', '~', '.', ':', '[', '#']))
+}
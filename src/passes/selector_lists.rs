@@ -0,0 +1,88 @@
+//! merging of rules with identical declarations into a single rule with
+//! a selector list, which generated css tends to be full of.
+use crate::{
+    passes::{can_move_past, declared_properties, for_each_block},
+    selectors::split_list,
+    stylesheet::{Node, Stylesheet},
+};
+
+/// [`merge_identical_rules`] merges every rule into an earlier rule in the
+/// same block with identical declarations, by adding its selectors to the
+/// earlier rule's selector list.
+///
+/// # notes
+/// a rule is only merged when no rule between the two sets any of the
+/// properties it sets, so the cascade order of the result is unchanged.
+/// rules with vendor prefixed pseudo-classes or pseudo-elements (like
+/// `::-moz-selection`) are never merged, since a browser drops the whole
+/// selector list if it doesn't understand one of its selectors.
+///
+/// # example
+/// [`merge_identical_rules`] can be used to combine `.a` and `.b`:
+/// ```rust
+/// use rcss::{passes::selector_lists::merge_identical_rules, stylesheet::Stylesheet};
+///
+/// let mut stylesheet = Stylesheet::parse(".a{color:red}.c{margin:0}.b{color:red}");
+/// merge_identical_rules(&mut stylesheet);
+/// assert_eq!(stylesheet.to_css(), ".a,.b{color:red}.c{margin:0}");
+/// ```
+pub fn merge_identical_rules(stylesheet: &mut Stylesheet) {
+    for_each_block(stylesheet, &mut merge_rules_in_block);
+}
+
+/// [`merge_rules_in_block`] merges rules with identical declarations that
+/// are siblings within `nodes`.
+fn merge_rules_in_block(nodes: &mut Vec<Node>) {
+    let mut index = 0;
+    while index < nodes.len() {
+        let Some(declarations) = mergeable_declarations(&nodes[index]) else {
+            index += 1;
+            continue;
+        };
+        let earlier = (0..index)
+            .rev()
+            .find(|&earlier| mergeable_declarations(&nodes[earlier]) == Some(declarations));
+        let Some(earlier) = earlier else {
+            index += 1;
+            continue;
+        };
+        let properties = declared_properties(&nodes[index]);
+        if !can_move_past(&nodes[earlier + 1..index], &properties) {
+            index += 1;
+            continue;
+        }
+
+        let (before, after) = nodes.split_at_mut(index);
+        let (Node::Rule(rule), Node::Rule(later)) = (&mut before[earlier], &after[0]) else {
+            index += 1;
+            continue;
+        };
+        let mut selectors = split_list(&rule.selector);
+        for selector in split_list(&later.selector) {
+            if !selectors.contains(&selector) {
+                selectors.push(selector);
+            }
+        }
+        rule.selector = selectors.join(",");
+        nodes.remove(index);
+    }
+}
+
+/// [`mergeable_declarations`] returns the declarations of `node` if it is
+/// a rule that only contains declarations, and has no vendor prefixed
+/// pseudo selectors.
+fn mergeable_declarations(node: &Node) -> Option<&[Node]> {
+    match node {
+        Node::Rule(rule)
+            if !rule.children.is_empty()
+                && !rule.selector.contains(":-")
+                && rule
+                    .children
+                    .iter()
+                    .all(|child| matches!(child, Node::Declaration(_))) =>
+        {
+            Some(&rule.children)
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,314 @@
+//! collapsing of longhand properties into their shorthand, like replacing
+//! `margin-top`, `margin-right`, `margin-bottom`, and `margin-left` with
+//! a single `margin`.
+use crate::{
+    passes::{for_each_block, property_name},
+    stylesheet::{Declaration, Node, Stylesheet},
+};
+
+/// [`Shorthand`] describes a shorthand property, and how to collapse the
+/// values of its longhands into its value.
+struct Shorthand {
+    name: &'static str,
+    /// every longhand, in the order their values are passed to `collapse`.
+    longhands: &'static [&'static str],
+    /// every property that starts with one of these prefixes could set (or
+    /// depend on) one of the longhands, like the logical `margin-inline`.
+    related: &'static [&'static str],
+    /// whether the shorthand also resets related properties that aren't
+    /// longhands, like `font` resetting `font-kerning`.
+    resets_related: bool,
+    collapse: fn(&[&str]) -> Option<String>,
+}
+
+const SHORTHANDS: &[Shorthand] = &[
+    Shorthand {
+        name: "margin",
+        longhands: &["margin-top", "margin-right", "margin-bottom", "margin-left"],
+        related: &["margin"],
+        resets_related: false,
+        collapse: collapse_sides,
+    },
+    Shorthand {
+        name: "padding",
+        longhands: &[
+            "padding-top",
+            "padding-right",
+            "padding-bottom",
+            "padding-left",
+        ],
+        related: &["padding"],
+        resets_related: false,
+        collapse: collapse_sides,
+    },
+    Shorthand {
+        name: "border-width",
+        longhands: &[
+            "border-top-width",
+            "border-right-width",
+            "border-bottom-width",
+            "border-left-width",
+        ],
+        related: &["border"],
+        resets_related: false,
+        collapse: collapse_sides,
+    },
+    Shorthand {
+        name: "border-style",
+        longhands: &[
+            "border-top-style",
+            "border-right-style",
+            "border-bottom-style",
+            "border-left-style",
+        ],
+        related: &["border"],
+        resets_related: false,
+        collapse: collapse_sides,
+    },
+    Shorthand {
+        name: "border-color",
+        longhands: &[
+            "border-top-color",
+            "border-right-color",
+            "border-bottom-color",
+            "border-left-color",
+        ],
+        related: &["border"],
+        resets_related: false,
+        collapse: collapse_sides,
+    },
+    Shorthand {
+        name: "border-radius",
+        longhands: &[
+            "border-top-left-radius",
+            "border-top-right-radius",
+            "border-bottom-right-radius",
+            "border-bottom-left-radius",
+        ],
+        related: &["border"],
+        resets_related: false,
+        collapse: collapse_corners,
+    },
+    Shorthand {
+        name: "gap",
+        longhands: &["row-gap", "column-gap"],
+        related: &[
+            "gap",
+            "row-gap",
+            "column-gap",
+            "grid-gap",
+            "grid-row-gap",
+            "grid-column-gap",
+        ],
+        resets_related: false,
+        collapse: collapse_gap,
+    },
+    Shorthand {
+        name: "font",
+        longhands: &[
+            "font-style",
+            "font-variant",
+            "font-weight",
+            "font-stretch",
+            "font-size",
+            "line-height",
+            "font-family",
+        ],
+        related: &["font", "line-height"],
+        resets_related: true,
+        collapse: collapse_font,
+    },
+];
+
+/// [`collapse_shorthands`] replaces every complete set of longhands in
+/// each block of `stylesheet` with their shorthand, using the shortest
+/// form of its value.
+///
+/// # notes
+/// longhands are only collapsed when each is set exactly once, none use
+/// `var()`, they are all (or none) `!important`, and no related property
+/// (like `margin-inline-start` for `margin`) is set between them. the
+/// shorthand takes the place of the last longhand.
+///
+/// `font` also resets sub-properties that can't be set within it, like
+/// `font-kerning`, to their initial values. it is only collapsed when
+/// none of those are set before it in the same block, but could still
+/// override an inherited value.
+///
+/// # example
+/// [`collapse_shorthands`] can be used to collapse the sides of a margin:
+/// ```rust
+/// use rcss::{passes::shorthands::collapse_shorthands, stylesheet::Stylesheet};
+///
+/// let mut stylesheet = Stylesheet::parse(
+///     ".a{margin-top:0;margin-right:auto;margin-bottom:0;margin-left:auto}",
+/// );
+/// collapse_shorthands(&mut stylesheet);
+/// assert_eq!(stylesheet.to_css(), ".a{margin:0 auto}");
+/// ```
+pub fn collapse_shorthands(stylesheet: &mut Stylesheet) {
+    for_each_block(stylesheet, &mut |nodes| {
+        for shorthand in SHORTHANDS {
+            collapse_in_block(nodes, shorthand);
+        }
+    });
+}
+
+/// [`collapse_in_block`] collapses the longhands of `shorthand` that are
+/// declared within `nodes`, if they can be collapsed.
+fn collapse_in_block(nodes: &mut Vec<Node>, shorthand: &Shorthand) {
+    let mut positions = vec![];
+    for longhand in shorthand.longhands {
+        let mut matching = nodes.iter().enumerate().filter(|(_, node)| {
+            matches!(node, Node::Declaration(declaration) if declaration.property.eq_ignore_ascii_case(longhand))
+        });
+        match (matching.next(), matching.next()) {
+            (Some((position, _)), None) => positions.push(position),
+            _ => return,
+        }
+    }
+    let declarations = positions
+        .iter()
+        .filter_map(|&position| match &nodes[position] {
+            Node::Declaration(declaration) => Some(declaration),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let (Some(important), Some(&first), Some(&last)) = (
+        declarations
+            .first()
+            .map(|declaration| declaration.important),
+        positions.iter().min(),
+        positions.iter().max(),
+    ) else {
+        return;
+    };
+    let is_collapsible = declarations.iter().all(|declaration| {
+        declaration.important == important
+            && !declaration.value.to_ascii_lowercase().contains("var(")
+    });
+    let is_related = |node: &Node| match node {
+        Node::Declaration(declaration) => {
+            let property = property_name(&declaration.property);
+            shorthand
+                .related
+                .iter()
+                .any(|related| property.starts_with(related))
+        }
+        _ => false,
+    };
+    // a related property before the longhands is only changed by a
+    // shorthand that resets it
+    let start = match shorthand.resets_related {
+        true => 0,
+        false => first,
+    };
+    let is_interrupted = (start..=last)
+        .filter(|position| !positions.contains(position))
+        .any(|position| is_related(&nodes[position]));
+    if !is_collapsible || is_interrupted {
+        return;
+    }
+    let values = declarations
+        .iter()
+        .map(|declaration| declaration.value.as_str())
+        .collect::<Vec<_>>();
+    let Some(value) = (shorthand.collapse)(&values) else {
+        return;
+    };
+
+    nodes[last] = Node::Declaration(Declaration {
+        property: shorthand.name.to_string(),
+        value,
+        important,
+    });
+    positions.sort_unstable();
+    for &position in positions.iter().rev().filter(|&&position| position != last) {
+        nodes.remove(position);
+    }
+}
+
+/// [`collapse_sides`] returns the shortest value for the top, right,
+/// bottom, and left `values` of a box shorthand like `margin`.
+fn collapse_sides(values: &[&str]) -> Option<String> {
+    let [top, right, bottom, left] = values else {
+        return None;
+    };
+    if values.iter().any(|value| value.contains(' ')) {
+        return None;
+    }
+    let sides = match (top == bottom, right == left, top == right) {
+        (true, true, true) => vec![top],
+        (true, true, false) => vec![top, right],
+        (false, true, _) => vec![top, right, bottom],
+        (_, false, _) => vec![top, right, bottom, left],
+    };
+    Some(
+        sides
+            .iter()
+            .map(|side| side.to_string())
+            .collect::<Vec<_>>()
+            .join(" "),
+    )
+}
+
+/// [`collapse_corners`] returns the shortest `border-radius` for the top
+/// left, top right, bottom right, and bottom left `values`. elliptical
+/// corners, like `4px 8px`, aren't collapsed.
+fn collapse_corners(values: &[&str]) -> Option<String> {
+    collapse_sides(values)
+}
+
+/// [`collapse_gap`] returns the shortest `gap` for the row and column
+/// gap `values`.
+fn collapse_gap(values: &[&str]) -> Option<String> {
+    match values {
+        [row, column] if !row.contains(' ') && !column.contains(' ') => match row == column {
+            true => Some(row.to_string()),
+            false => Some(format!("{row} {column}")),
+        },
+        _ => None,
+    }
+}
+
+/// [`collapse_font`] returns the `font` for the style, variant, weight,
+/// stretch, size, line height, and family `values`, leaving out every
+/// value that is `normal`.
+fn collapse_font(values: &[&str]) -> Option<String> {
+    let [style, variant, weight, stretch, size, line_height, family] = values else {
+        return None;
+    };
+    // only the css 2.1 values of these can be set within `font`
+    let is_variant = ["normal", "small-caps"].contains(&variant.to_ascii_lowercase().as_str());
+    let is_stretch = [
+        "normal",
+        "ultra-condensed",
+        "extra-condensed",
+        "condensed",
+        "semi-condensed",
+        "semi-expanded",
+        "expanded",
+        "extra-expanded",
+        "ultra-expanded",
+    ]
+    .contains(&stretch.to_ascii_lowercase().as_str());
+    // a keyword like `inherit` can't be combined with other values
+    let is_keyword = values.iter().any(|value| {
+        ["inherit", "initial", "unset", "revert", "revert-layer"]
+            .contains(&value.to_ascii_lowercase().as_str())
+    });
+    if !is_variant || !is_stretch || is_keyword || size.contains(' ') {
+        return None;
+    }
+    let mut parts = [style, variant, weight, stretch]
+        .into_iter()
+        .filter(|value| !value.eq_ignore_ascii_case("normal"))
+        .map(|value| value.to_string())
+        .collect::<Vec<_>>();
+    match line_height.eq_ignore_ascii_case("normal") {
+        true => parts.push(size.to_string()),
+        false => parts.push(format!("{size}/{line_height}")),
+    }
+    parts.push(family.to_string());
+    Some(parts.join(" "))
+}
@@ -0,0 +1,171 @@
+//! removal of the `@keyframes` and `@font-face` rules that nothing within
+//! the stylesheet refers to, like the animations and fonts of a component
+//! that is no longer used.
+use std::collections::HashSet;
+
+use crate::{
+    passes::property_name,
+    selectors::split_list,
+    stylesheet::{Node, Stylesheet},
+    tokenizer::{tokenize, TokenKind},
+};
+
+/// [`References`] are the `@keyframes` names, and the lowercased font
+/// families, that a stylesheet refers to.
+#[derive(Default)]
+struct References {
+    animations: HashSet<String>,
+    fonts: HashSet<String>,
+}
+
+/// [`remove_unused_at_rules`] removes every `@keyframes` whose name isn't
+/// used by an `animation` or `animation-name` within `stylesheet`, and
+/// every `@font-face` whose family isn't used by a `font-family` or `font`.
+///
+/// # notes
+/// the values of custom properties, and whatever couldn't be parsed, are
+/// counted as uses of every name within them, since they can be used as
+/// either. a `@font-face` without a `font-family` is kept. names that are
+/// only used from outside of the stylesheet, like by an inline `style`
+/// attribute or javascript, can't be seen, so their rules are removed.
+///
+/// # example
+/// [`remove_unused_at_rules`] can be used to remove an animation and a font
+/// that nothing uses anymore:
+/// ```rust
+/// use rcss::{passes::unused::remove_unused_at_rules, stylesheet::Stylesheet};
+///
+/// let mut stylesheet = Stylesheet::parse(
+///     "@keyframes spin{to{rotate:1turn}}@keyframes fade{to{opacity:0}}\
+///      @font-face{font-family:\"Open Sans\";src:url(a.woff2)}@font-face{font-family:Lobster;src:url(b.woff2)}\
+///      .a{animation:spin 1s;font:12px/1.5 open sans,sans-serif}",
+/// );
+/// remove_unused_at_rules(&mut stylesheet);
+/// assert_eq!(
+///     stylesheet.to_css(),
+///     "@keyframes spin{to{rotate:1turn}}@font-face{font-family:\"Open Sans\";src:url(a.woff2)}\
+///      .a{animation:spin 1s;font:12px/1.5 open sans,sans-serif}"
+/// );
+/// ```
+pub fn remove_unused_at_rules(stylesheet: &mut Stylesheet) {
+    let mut references = References::default();
+    collect_references(&stylesheet.nodes, &mut references);
+    remove_unused(&mut stylesheet.nodes, &references);
+}
+
+/// [`collect_references`] adds the names used within `nodes`, and within
+/// every block within them, to `references`.
+fn collect_references(nodes: &[Node], references: &mut References) {
+    for node in nodes {
+        match node {
+            // the family of a `@font-face` declares a font, rather than
+            // using one
+            Node::AtRule(at_rule) if at_rule.name.eq_ignore_ascii_case("font-face") => continue,
+            Node::Declaration(declaration) => {
+                let property = property_name(&declaration.property);
+                let value = &declaration.value;
+                match property.as_str() {
+                    "animation" | "animation-name" => add_animations(value, references),
+                    "font" | "font-family" => add_fonts(value, references),
+                    _ if property.starts_with("--") => {
+                        add_animations(value, references);
+                        add_fonts(value, references);
+                    }
+                    _ => {}
+                }
+            }
+            Node::Raw(raw) => {
+                add_animations(raw, references);
+                add_fonts(raw, references);
+            }
+            _ => {}
+        }
+        if let Some(children) = node.children() {
+            collect_references(children, references);
+        }
+    }
+}
+
+/// [`add_animations`] adds every name and string within `value` to the
+/// animations of `references`.
+fn add_animations(value: &str, references: &mut References) {
+    for token in tokenize(value) {
+        match token.kind {
+            TokenKind::Ident => {
+                references.animations.insert(token.text.to_string());
+            }
+            TokenKind::String => {
+                references
+                    .animations
+                    .insert(unquote(token.text).to_string());
+            }
+            _ => {}
+        }
+    }
+}
+
+/// [`add_fonts`] adds every family that could be within the comma
+/// separated `value` to the fonts of `references`: the words at the end of
+/// every item, since the size and style of a `font` come before its first
+/// family, like `12px/1.5 open sans`.
+fn add_fonts(value: &str, references: &mut References) {
+    for item in split_list(value) {
+        let words = family(&item);
+        let words = words.split(' ').collect::<Vec<_>>();
+        for start in 0..words.len() {
+            references.fonts.insert(words[start..].join(" "));
+        }
+    }
+}
+
+/// [`remove_unused`] removes the `@keyframes` and `@font-face` rules within
+/// `nodes`, and within every block within them, that aren't in
+/// `references`.
+fn remove_unused(nodes: &mut Vec<Node>, references: &References) {
+    nodes.retain_mut(|node| {
+        match node {
+            Node::AtRule(at_rule) if at_rule.name.to_ascii_lowercase().ends_with("keyframes") => {
+                return references
+                    .animations
+                    .contains(unquote(at_rule.prelude.trim()));
+            }
+            Node::AtRule(at_rule) if at_rule.name.eq_ignore_ascii_case("font-face") => {
+                let declared = at_rule
+                    .children
+                    .iter()
+                    .flatten()
+                    .find_map(|child| match child {
+                        Node::Declaration(declaration)
+                            if declaration.property.eq_ignore_ascii_case("font-family") =>
+                        {
+                            Some(family(&declaration.value))
+                        }
+                        _ => None,
+                    });
+                return declared.is_none_or(|family| references.fonts.contains(&family));
+            }
+            _ => {}
+        }
+        if let Some(children) = node.children_mut() {
+            remove_unused(children, references);
+        }
+        true
+    });
+}
+
+/// [`family`] returns the font family `name` as it is compared: lowercased,
+/// without quotes, and with single spaces between its words.
+fn family(name: &str) -> String {
+    name.replace(['"', '\''], " ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_ascii_lowercase()
+}
+
+/// [`unquote`] returns `text` without the quotes around it, if it has any.
+fn unquote(text: &str) -> &str {
+    text.strip_prefix(['"', '\''])
+        .and_then(|text| text.strip_suffix(['"', '\'']))
+        .unwrap_or(text)
+}
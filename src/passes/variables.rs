@@ -0,0 +1,326 @@
+//! inlining of custom properties defined on `:root`, like replacing
+//! `var(--gap)` with `4px`.
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    css,
+    passes::for_each_block,
+    stylesheet::{Node, Stylesheet},
+    tokenizer::{tokenize, TokenKind},
+};
+
+/// [`inline_variables`] will replace every `var(--name)` in `stylesheet`
+/// with the value of `--name` from the top-level `:root` rules, and then
+/// remove the definitions (and any `:root` rules left empty) of the custom
+/// properties that are no longer used.
+///
+/// # notes
+/// a custom property is only inlined when it is only ever set in top-level
+/// `:root` rules, since one that is changed by another selector or within
+/// an at-rule like `@media` depends on where it is used. custom properties
+/// that are set from javascript can't be detected, which is why this pass
+/// is opt-in.
+///
+/// # example
+/// [`inline_variables`] can be used to inline a spacing variable:
+/// ```rust
+/// use rcss::{passes::variables::inline_variables, stylesheet::Stylesheet};
+///
+/// let mut stylesheet = Stylesheet::parse(":root{--gap:4px}.a{margin:var(--gap) var(--gap)}");
+/// inline_variables(&mut stylesheet);
+/// assert_eq!(stylesheet.to_css(), ".a{margin:4px 4px}");
+/// ```
+pub fn inline_variables(stylesheet: &mut Stylesheet) {
+    let definitions = inlinable_definitions(stylesheet);
+    if definitions.is_empty() {
+        return;
+    }
+    let mut resolved = HashMap::new();
+    for name in definitions.keys() {
+        resolve(name, &definitions, &mut resolved, &mut HashSet::new());
+    }
+    let resolved = resolved
+        .into_iter()
+        .filter_map(|(name, value)| Some((name, value?)))
+        .collect::<HashMap<_, _>>();
+
+    for_each_block(stylesheet, &mut |nodes| {
+        for node in nodes.iter_mut() {
+            if let Node::Declaration(declaration) = node {
+                let value = substitute(&declaration.value, &resolved);
+                if value != declaration.value {
+                    declaration.value = minify_value(&declaration.property, value);
+                }
+            }
+        }
+    });
+
+    let mut used = HashSet::new();
+    for_each_block(stylesheet, &mut |nodes| {
+        for node in nodes.iter() {
+            if let Node::Declaration(declaration) = node {
+                used.extend(referenced_variables(&declaration.value));
+            }
+        }
+    });
+    stylesheet.nodes.retain_mut(|node| {
+        let Node::Rule(rule) = node else {
+            return true;
+        };
+        if rule.selector != ":root" {
+            return true;
+        }
+        rule.children.retain(|child| match child {
+            Node::Declaration(declaration) => {
+                !resolved.contains_key(&declaration.property)
+                    || used.contains(&declaration.property)
+            }
+            _ => true,
+        });
+        !rule.children.is_empty()
+    });
+}
+
+/// [`remove_unused_variables`] removes the declaration of every custom
+/// property within `stylesheet` that nothing refers to, and returns their
+/// names, sorted. a custom property is only used by the declarations of
+/// another one is removed once that one is.
+///
+/// # notes
+/// every name within a value or an at-rule prelude counts as a use, like
+/// the `--dark` of `@container style(--dark:1)`, not only those within a
+/// `var()`, except in the value of the custom property itself, which would
+/// be a cycle. custom properties that are only read from javascript can't be
+/// detected, which is why this pass is opt-in. a rule left without
+/// declarations is kept for [`crate::passes::empty::remove_empty_rules`] to
+/// remove.
+///
+/// # example
+/// [`remove_unused_variables`] can be used to remove an old theme color:
+/// ```rust
+/// use rcss::{passes::variables::remove_unused_variables, stylesheet::Stylesheet};
+///
+/// let mut stylesheet =
+///     Stylesheet::parse(":root{--brand:red;--old:var(--older);--older:blue}.a{color:var(--brand)}");
+/// assert_eq!(remove_unused_variables(&mut stylesheet), ["--old", "--older"]);
+/// assert_eq!(stylesheet.to_css(), ":root{--brand:red}.a{color:var(--brand)}");
+/// ```
+pub fn remove_unused_variables(stylesheet: &mut Stylesheet) -> Vec<String> {
+    let mut removed = vec![];
+    loop {
+        let mut used = HashSet::new();
+        collect_uses(&stylesheet.nodes, &mut used);
+        let mut is_changed = false;
+        for_each_block(stylesheet, &mut |nodes| {
+            nodes.retain(|node| match node {
+                Node::Declaration(declaration)
+                    if declaration.property.starts_with("--")
+                        && !used.contains(&declaration.property) =>
+                {
+                    removed.push(declaration.property.clone());
+                    is_changed = true;
+                    false
+                }
+                _ => true,
+            });
+        });
+        if !is_changed {
+            break;
+        }
+    }
+    removed.sort();
+    removed.dedup();
+    removed
+}
+
+/// [`collect_uses`] adds every custom property name used within `nodes`,
+/// and within every block within them, to `used`.
+fn collect_uses(nodes: &[Node], used: &mut HashSet<String>) {
+    for node in nodes {
+        match node {
+            // a custom property that refers to itself is a cycle, which
+            // makes it invalid rather than using another definition of it
+            Node::Declaration(declaration) if declaration.property.starts_with("--") => {
+                let mut uses = HashSet::new();
+                add_uses(&declaration.value, &mut uses);
+                uses.remove(&declaration.property);
+                used.extend(uses);
+            }
+            Node::Declaration(declaration) => add_uses(&declaration.value, used),
+            // the name of a `@property` registers it, rather than using it
+            Node::AtRule(at_rule) if !at_rule.name.eq_ignore_ascii_case("property") => {
+                add_uses(&at_rule.prelude, used)
+            }
+            Node::Raw(raw) => add_uses(raw, used),
+            _ => {}
+        }
+        if let Some(children) = node.children() {
+            collect_uses(children, used);
+        }
+    }
+}
+
+/// [`add_uses`] adds every custom property name within `text` to `used`.
+fn add_uses(text: &str, used: &mut HashSet<String>) {
+    used.extend(
+        tokenize(text)
+            .into_iter()
+            .filter(|token| token.kind == TokenKind::Ident && token.text.starts_with("--"))
+            .map(|token| token.text.to_string()),
+    );
+}
+
+/// [`minify_value`] returns the `value` of `property` minified, since the
+/// values of custom properties are kept as written, and so aren't minified
+/// until they are inlined into another property.
+fn minify_value(property: &str, value: String) -> String {
+    if property.starts_with("--") {
+        return value;
+    }
+    let prefix = format!("a{{{property}:");
+    let minified = css::minify(&format!("{prefix}{value}}}"));
+    match minified
+        .strip_prefix(&prefix)
+        .and_then(|minified| minified.strip_suffix('}'))
+    {
+        Some(minified) => minified.to_string(),
+        None => value,
+    }
+}
+
+/// [`inlinable_definitions`] returns the value of every custom property
+/// that is only set within top-level `:root` rules.
+fn inlinable_definitions(stylesheet: &Stylesheet) -> HashMap<String, String> {
+    let mut definitions = HashMap::new();
+    for node in &stylesheet.nodes {
+        let Node::Rule(rule) = node else {
+            continue;
+        };
+        if rule.selector != ":root" {
+            continue;
+        }
+        for child in &rule.children {
+            if let Node::Declaration(declaration) = child {
+                if declaration.property.starts_with("--") && !declaration.important {
+                    definitions.insert(declaration.property.clone(), declaration.value.clone());
+                }
+            }
+        }
+    }
+
+    let mut dynamic = HashSet::new();
+    collect_dynamic(&stylesheet.nodes, true, &mut dynamic);
+    definitions.retain(|name, _| !dynamic.contains(name));
+    definitions
+}
+
+/// [`collect_dynamic`] adds every custom property that is set outside of a
+/// top-level `:root` rule within `nodes` to `dynamic`.
+fn collect_dynamic(nodes: &[Node], is_top_level: bool, dynamic: &mut HashSet<String>) {
+    for node in nodes {
+        match node {
+            Node::Rule(rule) if is_top_level && rule.selector == ":root" => {
+                for child in &rule.children {
+                    match child {
+                        Node::Declaration(declaration) if declaration.important => {
+                            dynamic.insert(declaration.property.clone());
+                        }
+                        Node::Declaration(_) => {}
+                        _ => collect_dynamic(std::slice::from_ref(child), false, dynamic),
+                    }
+                }
+            }
+            Node::Declaration(declaration) if declaration.property.starts_with("--") => {
+                dynamic.insert(declaration.property.clone());
+            }
+            _ => {
+                if let Some(children) = node.children() {
+                    collect_dynamic(children, false, dynamic);
+                }
+            }
+        }
+    }
+}
+
+/// [`resolve`] returns the value of `name` with every custom property it
+/// references inlined, or `None` if it references itself.
+fn resolve(
+    name: &str,
+    definitions: &HashMap<String, String>,
+    resolved: &mut HashMap<String, Option<String>>,
+    resolving: &mut HashSet<String>,
+) -> Option<String> {
+    if let Some(value) = resolved.get(name) {
+        return value.clone();
+    }
+    let value = definitions.get(name)?;
+    if !resolving.insert(name.to_string()) {
+        return None;
+    }
+    let mut references = HashMap::new();
+    let mut is_cyclic = false;
+    for reference in referenced_variables(value) {
+        if !definitions.contains_key(&reference) {
+            continue;
+        }
+        match resolve(&reference, definitions, resolved, resolving) {
+            Some(value) => {
+                references.insert(reference, value);
+            }
+            None => is_cyclic = true,
+        }
+    }
+    resolving.remove(name);
+    let value = (!is_cyclic).then(|| substitute(value, &references));
+    resolved.insert(name.to_string(), value.clone());
+    value
+}
+
+/// [`referenced_variables`] returns the name of every custom property
+/// referenced by a `var()` within `value`.
+fn referenced_variables(value: &str) -> Vec<String> {
+    let tokens = tokenize(value);
+    tokens
+        .windows(2)
+        .filter(|pair| {
+            pair[0].kind == TokenKind::Function && pair[0].text.eq_ignore_ascii_case("var(")
+        })
+        .filter(|pair| pair[1].kind == TokenKind::Ident && pair[1].text.starts_with("--"))
+        .map(|pair| pair[1].text.to_string())
+        .collect()
+}
+
+/// [`substitute`] replaces every `var()` within `value` that references
+/// one of `values` with its value, dropping any fallback.
+fn substitute(value: &str, values: &HashMap<String, String>) -> String {
+    let tokens = tokenize(value);
+    let mut substituted = String::new();
+    let mut index = 0;
+    while index < tokens.len() {
+        let token = &tokens[index];
+        let replacement = (token.kind == TokenKind::Function
+            && token.text.eq_ignore_ascii_case("var("))
+        .then(|| tokens.get(index + 1))
+        .flatten()
+        .and_then(|name| values.get(name.text));
+        let Some(replacement) = replacement else {
+            substituted.push_str(token.text);
+            index += 1;
+            continue;
+        };
+        let mut depth = 0;
+        while index < tokens.len() {
+            match tokens[index].kind {
+                TokenKind::Function | TokenKind::OpenParen => depth += 1,
+                TokenKind::CloseParen => depth -= 1,
+                _ => {}
+            }
+            index += 1;
+            if depth == 0 {
+                break;
+            }
+        }
+        substituted.push_str(replacement);
+    }
+    substituted
+}
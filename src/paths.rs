@@ -0,0 +1,92 @@
+//! comparison of paths however they are spelled, like `css\style.css` on
+//! windows and `./css/style.css`, so the output of an earlier run is never
+//! mistaken for one of the files it was combined from.
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// [`VERBATIM_PREFIX`] is what [`fs::canonicalize`] starts every path with
+/// on windows, like `\\?\C:\css`.
+const VERBATIM_PREFIX: &str = r"\\?\";
+
+/// [`normalize`] spells `path` with `/` separators, a lowercase drive
+/// letter, and no windows verbatim prefix, resolving every `.`, and every
+/// `..` that follows a directory, without touching the filesystem.
+///
+/// # example
+/// [`normalize`] can be used to compare windows and unix style paths:
+/// ```rust
+/// use rcss::paths::normalize;
+///
+/// assert_eq!(normalize(r"C:\some-dir\css\style.css"), "c:/some-dir/css/style.css");
+/// assert_eq!(normalize(r"\\?\C:\some-dir\css\.\style.css"), "c:/some-dir/css/style.css");
+/// assert_eq!(normalize("./css/components/../style.css"), "css/style.css");
+/// ```
+pub fn normalize(path: impl AsRef<Path>) -> String {
+    let path = path.as_ref().to_string_lossy();
+    let path = path.strip_prefix(VERBATIM_PREFIX).unwrap_or(&path);
+    let path = path.replace('\\', "/");
+    let mut segments: Vec<&str> = vec![];
+    for segment in path.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." if segments.last().is_some_and(|last| *last != "..") => {
+                segments.pop();
+            }
+            segment => segments.push(segment),
+        }
+    }
+    let mut normalized = segments.join("/");
+    if let Some(drive) = normalized.get(..2).filter(|drive| is_drive(drive)) {
+        normalized.replace_range(..2, &drive.to_ascii_lowercase());
+    }
+    // keep a root, like `/css`, and both slashes of a network share
+    match path.strip_prefix("//") {
+        Some(_) => normalized.insert_str(0, "//"),
+        None if path.starts_with('/') => normalized.insert(0, '/'),
+        None => {}
+    }
+    normalized
+}
+
+/// [`canonicalize`] returns the absolute path to `path` with every symbolic
+/// link and `..` resolved. the path of a file that doesn't exist yet is
+/// resolved from its directory, or made absolute if that doesn't exist
+/// either.
+pub fn canonicalize(path: impl AsRef<Path>) -> PathBuf {
+    let path = path.as_ref();
+    if let Ok(canonical) = fs::canonicalize(path) {
+        return canonical;
+    }
+    let directory = match path.parent() {
+        Some(directory) if !directory.as_os_str().is_empty() => directory,
+        _ => Path::new("."),
+    };
+    match (fs::canonicalize(directory), path.file_name()) {
+        (Ok(directory), Some(file_name)) => directory.join(file_name),
+        _ => std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf()),
+    }
+}
+
+/// [`same_file`] returns `true` if `left` and `right` are paths to the same
+/// file, whether or not it exists, and however they are spelled.
+///
+/// # example
+/// [`same_file`] can be used to find the output among the files of its
+/// source directory:
+/// ```rust
+/// use rcss::paths::same_file;
+///
+/// assert!(same_file("assets/css/style.css", "./assets/css/../css/style.css"));
+/// assert!(!same_file("assets/css/style.css", "assets/css/base.css"));
+/// ```
+pub fn same_file(left: impl AsRef<Path>, right: impl AsRef<Path>) -> bool {
+    normalize(canonicalize(left)) == normalize(canonicalize(right))
+}
+
+/// [`is_drive`] returns `true` if `prefix` is a windows drive, like `C:`.
+fn is_drive(prefix: &str) -> bool {
+    let bytes = prefix.as_bytes();
+    bytes.len() == 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':'
+}
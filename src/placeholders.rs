@@ -0,0 +1,113 @@
+//! `${NAME}` placeholders within source files, replaced at build time with
+//! environment variables or `[define]` values from the configuration, like
+//! a cdn base url in `url(${CDN}/img/logo.png)`.
+use std::{borrow::Cow, collections::BTreeMap, fmt};
+
+/// [`Undefined`] is a placeholder that has no value, at the 1-based line
+/// and column it starts on.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Undefined {
+    pub name: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl fmt::Display for Undefined {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: ${{{}}} is not defined",
+            self.line, self.column, self.name
+        )
+    }
+}
+
+/// [`lookup`] returns the value of the placeholder `name`: the environment
+/// variable of that name, or else its value within `defines`.
+pub fn lookup(name: &str, defines: &BTreeMap<String, String>) -> Option<String> {
+    std::env::var(name)
+        .ok()
+        .or_else(|| defines.get(name).cloned())
+}
+
+/// [`substitute`] replaces every `${NAME}` placeholder within `content`
+/// with its value from `lookup`. `$${NAME}` is left as `${NAME}`, without
+/// being replaced. a placeholder without a value is an [`Undefined`] error,
+/// unless `allow_undefined` is set, which leaves it as it is.
+///
+/// # example
+/// [`substitute`] can be used to point urls at a cdn:
+/// ```rust
+/// use rcss::placeholders::substitute;
+///
+/// let lookup = |name: &str| (name == "CDN").then(|| "https://cdn.test".to_string());
+/// let css = "a { background: url(${CDN}/a.png) } b::after { content: \"$${CDN}\" }";
+/// assert_eq!(
+///     substitute(css, lookup, false).unwrap(),
+///     "a { background: url(https://cdn.test/a.png) } b::after { content: \"${CDN}\" }"
+/// );
+/// let error = substitute("a {\n  color: ${BRAND};\n}", lookup, false).unwrap_err();
+/// assert_eq!(error.to_string(), "line 2, column 10: ${BRAND} is not defined");
+/// ```
+pub fn substitute(
+    content: &str,
+    lookup: impl Fn(&str) -> Option<String>,
+    allow_undefined: bool,
+) -> Result<Cow<'_, str>, Undefined> {
+    if !content.contains("${") {
+        return Ok(Cow::Borrowed(content));
+    }
+    let mut substituted = String::with_capacity(content.len());
+    let mut rest = content;
+    while let Some(start) = rest.find("${") {
+        let is_escaped = rest[..start].ends_with('$');
+        let after = &rest[start + 2..];
+        let name = after
+            .split('}')
+            .next()
+            .filter(|name| after.len() > name.len() && is_name(name));
+        let Some(name) = name else {
+            // not a placeholder, like `${` at the end of a file
+            substituted.push_str(&rest[..start + 2]);
+            rest = after;
+            continue;
+        };
+        let end = start + 2 + name.len() + 1;
+        if is_escaped {
+            substituted.push_str(&rest[..start - 1]);
+            substituted.push_str(&rest[start..end]);
+        } else {
+            substituted.push_str(&rest[..start]);
+            match lookup(name) {
+                Some(value) => substituted.push_str(&value),
+                None if allow_undefined => substituted.push_str(&rest[start..end]),
+                None => {
+                    let offset = content.len() - rest.len() + start;
+                    let before = &content[..offset];
+                    return Err(Undefined {
+                        name: name.to_string(),
+                        line: before.matches('\n').count() + 1,
+                        column: before
+                            .rsplit('\n')
+                            .next()
+                            .unwrap_or_default()
+                            .chars()
+                            .count()
+                            + 1,
+                    });
+                }
+            }
+        }
+        rest = &rest[end..];
+    }
+    substituted.push_str(rest);
+    Ok(Cow::Owned(substituted))
+}
+
+/// [`is_name`] returns `true` if `name` can be the name of a placeholder,
+/// like `CDN_URL`: letters, digits, and underscores, not starting with a
+/// digit.
+fn is_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
@@ -0,0 +1,139 @@
+//! optimization presets, which turn on a named set of passes at once, like
+//! the presets of cssnano.
+use std::fmt;
+
+use crate::{
+    config::Config,
+    error::{RcssError, Result},
+    Options,
+};
+
+/// [`TurnOn`] turns a single option on.
+type TurnOn = fn(&mut Options);
+
+/// [`SAFE`] is every option that [`Preset::Safe`] turns on, by its key in
+/// `rcss.toml`: the passes that can't change how a page looks.
+const SAFE: &[(&str, TurnOn)] = &[
+    ("remove-empty-rules", |options| {
+        options.remove_empty_rules = true
+    }),
+    ("remove-overridden", |options| {
+        options.remove_overridden = true
+    }),
+    ("keep-fallbacks", |options| options.keep_fallbacks = true),
+];
+
+/// [`DEFAULT`] is every option that [`Preset::Default`] turns on on top of
+/// [`SAFE`]: the passes of `-O2`, which reorder rules that have the same
+/// selector or declarations.
+const DEFAULT: &[(&str, TurnOn)] = &[
+    ("merge-duplicates", |options| {
+        options.merge_duplicates = true
+    }),
+    ("merge-selectors", |options| options.merge_selectors = true),
+    ("collapse-shorthands", |options| {
+        options.collapse_shorthands = true
+    }),
+    ("merge-media", |options| options.merge_media = true),
+];
+
+/// [`AGGRESSIVE`] is every option that [`Preset::Aggressive`] turns on on
+/// top of [`DEFAULT`]: the passes that assume nothing outside of the css,
+/// like javascript, refers to its custom properties or `@keyframes`, and
+/// that no browser needs the prefixes the targeted ones don't.
+const AGGRESSIVE: &[(&str, TurnOn)] = &[
+    ("strip-prefixes", |options| options.strip_prefixes = true),
+    ("inline-variables", |options| {
+        options.inline_variables = true
+    }),
+    ("remove-unused-variables", |options| {
+        options.remove_unused_variables = true
+    }),
+    ("remove-unused-at-rules", |options| {
+        options.remove_unused_at_rules = true
+    }),
+    ("shorten-identifiers", |options| {
+        options.shorten_identifiers = true
+    }),
+];
+
+/// [`Preset`] is a named set of passes, from the safest to the smallest
+/// output.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Preset {
+    /// only the passes that can't change how a page looks.
+    Safe,
+    /// the safe passes, and every pass of `-O2`.
+    Default,
+    /// the default passes, and the passes that rename, inline, and strip
+    /// what the css alone doesn't need.
+    Aggressive,
+}
+
+impl Preset {
+    /// [`Preset::parse`] reads a preset from its name, like `"safe"`.
+    pub fn parse(name: &str) -> Option<Preset> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "safe" => Some(Preset::Safe),
+            "default" => Some(Preset::Default),
+            "aggressive" => Some(Preset::Aggressive),
+            _ => None,
+        }
+    }
+
+    /// [`Preset::from_config`] reads the `preset` key from the root table of
+    /// `config`, like `preset = "aggressive"`, or `None` if it isn't set.
+    pub fn from_config(config: &Config) -> Result<Option<Preset>> {
+        let Some(value) = config.get("", "preset") else {
+            return Ok(None);
+        };
+        value
+            .as_str()
+            .and_then(Preset::parse)
+            .map(Some)
+            .ok_or_else(|| {
+                RcssError::Config(
+                    "preset must be \"safe\", \"default\", or \"aggressive\"".to_string(),
+                )
+            })
+    }
+
+    /// [`Preset::apply`] turns on every option of this preset within
+    /// `options`, except those whose `rcss.toml` key `is_set` already, so
+    /// that an option set on its own overrides the preset.
+    ///
+    /// # example
+    /// [`Preset::apply`] can be used to turn on the default passes, except
+    /// for merging selectors:
+    /// ```rust
+    /// use rcss::{preset::Preset, Options};
+    ///
+    /// let mut options = Options::default();
+    /// Preset::Default.apply(&mut options, |key| key == "merge-selectors");
+    /// assert!(options.merge_duplicates && options.remove_empty_rules);
+    /// assert!(!options.merge_selectors && !options.shorten_identifiers);
+    /// ```
+    pub fn apply(&self, options: &mut Options, is_set: impl Fn(&str) -> bool) {
+        let settings: &[&[(&str, TurnOn)]] = match self {
+            Preset::Safe => &[SAFE],
+            Preset::Default => &[SAFE, DEFAULT],
+            Preset::Aggressive => &[SAFE, DEFAULT, AGGRESSIVE],
+        };
+        for (key, turn_on) in settings.iter().copied().flatten() {
+            if !is_set(key) {
+                turn_on(options);
+            }
+        }
+    }
+}
+
+impl fmt::Display for Preset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Preset::Safe => "safe",
+            Preset::Default => "default",
+            Preset::Aggressive => "aggressive",
+        };
+        write!(f, "{name}")
+    }
+}
@@ -0,0 +1,133 @@
+//! how long minifying every file, and running every structural pass, took
+//! during a build, to find the inputs (like a huge generated file) that
+//! slow it down.
+use std::{fmt, time::Duration};
+
+use crate::json;
+
+/// [`SLOWEST_FILES`] is how many files a printed [`Profile`] lists.
+pub const SLOWEST_FILES: usize = 10;
+
+/// [`FileTime`] is how long a single file took to minify.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FileTime {
+    /// the path of the file, relative to the directory being minified.
+    pub path: String,
+    pub bytes: usize,
+    /// how long it took to minify, which is next to nothing if it was
+    /// cached.
+    pub time: Duration,
+}
+
+/// [`Profile`] is how long every file and structural pass of a build
+/// took.
+///
+/// # example
+/// [`Profile`] can be printed to show the slowest files and every pass:
+/// ```rust
+/// use std::time::Duration;
+///
+/// use rcss::profile::{FileTime, Profile};
+///
+/// let profile = Profile {
+///     files: vec![FileTime {
+///         path: "generated.css".to_string(),
+///         bytes: 5_000_000,
+///         time: Duration::from_millis(250),
+///     }],
+///     passes: vec![("--merge-duplicates", Duration::from_millis(40))],
+/// };
+/// assert!(profile.to_string().contains("  250.00 ms  generated.css (5000000 B)"));
+/// assert!(profile.to_string().contains("   40.00 ms  --merge-duplicates"));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Profile {
+    /// every file that was combined, in the order it was combined in.
+    pub files: Vec<FileTime>,
+    /// every structural pass that ran, by the flag that turns it on, along
+    /// with parsing and printing the stylesheet, in the order they ran.
+    pub passes: Vec<(&'static str, Duration)>,
+}
+
+impl Profile {
+    /// [`Profile::slowest_files`] returns the `count` files that took the
+    /// longest to minify, slowest first.
+    pub fn slowest_files(&self, count: usize) -> Vec<&FileTime> {
+        let mut files = self.files.iter().collect::<Vec<_>>();
+        files.sort_by_key(|file| std::cmp::Reverse(file.time));
+        files.truncate(count);
+        files
+    }
+
+    /// [`Profile::add`] adds the files of `other` to these, and how long
+    /// each of its passes took to the same pass of these.
+    pub fn add(&mut self, other: &Profile) {
+        self.files.extend(other.files.iter().cloned());
+        for (name, time) in &other.passes {
+            match self.passes.iter_mut().find(|(pass, _)| pass == name) {
+                Some((_, total)) => *total += *time,
+                None => self.passes.push((name, *time)),
+            }
+        }
+    }
+
+    /// [`Profile::to_json`] writes this profile as a json object, with
+    /// every time in milliseconds.
+    pub fn to_json(&self) -> String {
+        let files = self
+            .files
+            .iter()
+            .map(|file| {
+                format!(
+                    "{{\"path\":{},\"bytes\":{},\"ms\":{:.3}}}",
+                    json::quote(&file.path),
+                    file.bytes,
+                    milliseconds(file.time)
+                )
+            })
+            .collect::<Vec<_>>();
+        let passes = self
+            .passes
+            .iter()
+            .map(|(name, time)| {
+                format!(
+                    "{{\"pass\":{},\"ms\":{:.3}}}",
+                    json::quote(name),
+                    milliseconds(*time)
+                )
+            })
+            .collect::<Vec<_>>();
+        format!(
+            "{{\"files\":[{}],\"passes\":[{}]}}",
+            files.join(","),
+            passes.join(",")
+        )
+    }
+}
+
+impl fmt::Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "slowest files:")?;
+        for file in self.slowest_files(SLOWEST_FILES) {
+            write!(
+                f,
+                "\n  {:>8.2} ms  {} ({} B)",
+                milliseconds(file.time),
+                file.path,
+                file.bytes
+            )?;
+        }
+        if !self.passes.is_empty() {
+            write!(f, "\npasses:")?;
+            for (name, time) in &self.passes {
+                write!(f, "\n  {:>8.2} ms  {name}", milliseconds(*time))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// [`milliseconds`] returns `duration` in fractional milliseconds.
+fn milliseconds(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
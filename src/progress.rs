@@ -0,0 +1,119 @@
+//! a progress bar for minifying large trees of files, drawn on stderr
+//! (only when it is a terminal) with how many files and bytes are done.
+use std::{
+    io::{self, IsTerminal, Write},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use crate::log;
+
+/// [`WIDTH`] is how many characters wide the bar itself is.
+const WIDTH: usize = 30;
+
+/// [`REDRAW_INTERVAL`] is how often the bar is redrawn at most, so drawing
+/// it doesn't slow down minifying many small files.
+const REDRAW_INTERVAL: Duration = Duration::from_millis(50);
+
+/// [`Progress`] counts the files and bytes that have been minified out of
+/// a total, and draws them as a bar. it can be shared between threads.
+///
+/// # example
+/// [`Progress`] can be advanced from every thread that minifies a file:
+/// ```rust
+/// let progress = rcss::progress::Progress::start(2, 30, true);
+/// progress.advance(10);
+/// progress.advance(20);
+/// assert_eq!(progress.done(), (2, 30));
+/// progress.finish();
+/// ```
+#[derive(Debug)]
+pub struct Progress {
+    is_drawn: bool,
+    total_files: usize,
+    total_bytes: usize,
+    files: AtomicUsize,
+    bytes: AtomicUsize,
+    last_drawn: Mutex<Option<Instant>>,
+}
+
+impl Progress {
+    /// [`Progress::start`] starts counting towards `total_files` files of
+    /// `total_bytes` bytes. nothing is drawn unless `is_enabled` is set,
+    /// stderr is a terminal, and the log level isn't quiet.
+    pub fn start(total_files: usize, total_bytes: usize, is_enabled: bool) -> Progress {
+        Progress {
+            is_drawn: is_enabled && io::stderr().is_terminal() && log::level() > log::Level::Quiet,
+            total_files,
+            total_bytes,
+            files: AtomicUsize::new(0),
+            bytes: AtomicUsize::new(0),
+            last_drawn: Mutex::new(None),
+        }
+    }
+
+    /// [`Progress::advance`] records that one more file, of `bytes` bytes,
+    /// is done.
+    pub fn advance(&self, bytes: usize) {
+        let files = self.files.fetch_add(1, Ordering::Relaxed) + 1;
+        let bytes = self.bytes.fetch_add(bytes, Ordering::Relaxed) + bytes;
+        if !self.is_drawn {
+            return;
+        }
+        let Ok(mut last_drawn) = self.last_drawn.try_lock() else {
+            // another thread is drawing it already
+            return;
+        };
+        let is_due = last_drawn.is_none_or(|drawn| drawn.elapsed() >= REDRAW_INTERVAL);
+        if is_due || files == self.total_files {
+            *last_drawn = Some(Instant::now());
+            self.draw(files, bytes);
+        }
+    }
+
+    /// [`Progress::done`] returns how many files and bytes are done.
+    pub fn done(&self) -> (usize, usize) {
+        (
+            self.files.load(Ordering::Relaxed),
+            self.bytes.load(Ordering::Relaxed),
+        )
+    }
+
+    /// [`Progress::finish`] clears the bar, so whatever is printed next
+    /// starts on an empty line.
+    pub fn finish(&self) {
+        if self.is_drawn {
+            eprint!("\r\x1b[2K");
+            let _ = io::stderr().flush();
+        }
+    }
+
+    fn draw(&self, files: usize, bytes: usize) {
+        let filled = match self.total_bytes {
+            0 => WIDTH,
+            total_bytes => (bytes * WIDTH / total_bytes).min(WIDTH),
+        };
+        eprint!(
+            "\r\x1b[2K[{}{}] {files}/{} files, {}/{}",
+            "#".repeat(filled),
+            "-".repeat(WIDTH - filled),
+            self.total_files,
+            format_bytes(bytes),
+            format_bytes(self.total_bytes)
+        );
+        let _ = io::stderr().flush();
+    }
+}
+
+/// [`format_bytes`] formats `bytes` with the largest unit that keeps it
+/// above 1, like `1.5 MB`.
+fn format_bytes(bytes: usize) -> String {
+    match bytes {
+        0..1024 => format!("{bytes} B"),
+        1024..1_048_576 => format!("{:.1} KB", bytes as f64 / 1024.0),
+        _ => format!("{:.1} MB", bytes as f64 / 1_048_576.0),
+    }
+}
@@ -0,0 +1,323 @@
+//! the minification report printed by a dry run, which describes what
+//! would be written without writing anything, and the summary printed
+//! after a build, which describes what was.
+use std::{fmt, time::Duration};
+
+use crate::{json, profile::Profile};
+
+/// [`FileReport`] is the size of a single combined file, before and
+/// after it was minified.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FileReport {
+    /// the path of the file, relative to the directory being minified.
+    pub path: String,
+    pub original_bytes: usize,
+    pub minified_bytes: usize,
+}
+
+impl FileReport {
+    /// [`FileReport::savings`] returns how much smaller the minified file
+    /// is, as a percentage of its original size.
+    pub fn savings(&self) -> f64 {
+        savings(self.original_bytes, self.minified_bytes)
+    }
+}
+
+/// [`Report`] describes every file that was found and combined, and the
+/// output that would have been written.
+///
+/// # example
+/// [`Report`] can be printed to show the savings of every file:
+/// ```rust
+/// use rcss::report::{FileReport, Report};
+///
+/// let report = Report {
+///     output: "style.css".to_string(),
+///     discovered: 2,
+///     excluded: vec!["vendor/reset.css".to_string()],
+///     files: vec![FileReport {
+///         path: "button.css".to_string(),
+///         original_bytes: 200,
+///         minified_bytes: 150,
+///     }],
+///     output_bytes: 150,
+///     ..Default::default()
+/// };
+/// assert_eq!(report.savings(), 25.0);
+/// assert!(report.to_string().contains("button.css  200 B -> 150 B (25.0% smaller)"));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Report {
+    /// the name of the output file that would have been written.
+    pub output: String,
+    /// how many files of the minified type were found.
+    pub discovered: usize,
+    /// every file that was found but not combined, relative to the
+    /// directory being minified.
+    pub excluded: Vec<String>,
+    /// every file that was combined, in the order it was combined in.
+    pub files: Vec<FileReport>,
+    /// the size of the whole output, including any license comments and
+    /// changes made by structural passes.
+    pub output_bytes: usize,
+    /// the summary of the build, which is filled in whether or not anything
+    /// was written.
+    pub summary: Summary,
+}
+
+impl Report {
+    /// [`Report::original_bytes`] returns the size of every combined file
+    /// before it was minified.
+    pub fn original_bytes(&self) -> usize {
+        self.files.iter().map(|file| file.original_bytes).sum()
+    }
+
+    /// [`Report::savings`] returns how much smaller the output is than
+    /// every combined file, as a percentage of their original size.
+    pub fn savings(&self) -> f64 {
+        savings(self.original_bytes(), self.output_bytes)
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "dry run: would write {}", self.output)?;
+        for file in &self.files {
+            writeln!(
+                f,
+                "  {}  {} B -> {} B ({:.1}% smaller)",
+                file.path,
+                file.original_bytes,
+                file.minified_bytes,
+                file.savings()
+            )?;
+        }
+        if !self.excluded.is_empty() {
+            writeln!(f, "excluded:")?;
+            for file in &self.excluded {
+                writeln!(f, "  {file}")?;
+            }
+        }
+        writeln!(
+            f,
+            "discovered {} files, included {}, excluded {}",
+            self.discovered,
+            self.files.len(),
+            self.excluded.len()
+        )?;
+        write!(
+            f,
+            "total: {} B -> {} B ({:.1}% smaller)",
+            self.original_bytes(),
+            self.output_bytes,
+            self.savings()
+        )
+    }
+}
+
+/// [`savings`] returns how much smaller `minified` is than `original`, as a
+/// percentage, or `0.0` if there was nothing to minify.
+fn savings(original: usize, minified: usize) -> f64 {
+    match original {
+        0 => 0.0,
+        _ => (original as f64 - minified as f64) / original as f64 * 100.0,
+    }
+}
+
+/// [`Phases`] is how long each phase of a build took.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Phases {
+    /// finding the files to combine.
+    pub walk: Duration,
+    /// reading them, or their cached output.
+    pub read: Duration,
+    /// minifying and combining them, including structural passes.
+    pub minify: Duration,
+    /// writing the output, and everything written along with it.
+    pub write: Duration,
+}
+
+impl Phases {
+    /// [`Phases::total`] returns how long every phase took together.
+    pub fn total(&self) -> Duration {
+        self.walk + self.read + self.minify + self.write
+    }
+
+    /// [`Phases::add`] adds how long each of `other` took to these.
+    pub fn add(&mut self, other: &Phases) {
+        self.walk += other.walk;
+        self.read += other.read;
+        self.minify += other.minify;
+        self.write += other.write;
+    }
+}
+
+impl fmt::Display for Phases {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "walk {}, read {}, minify {}, write {}",
+            milliseconds(self.walk),
+            milliseconds(self.read),
+            milliseconds(self.minify),
+            milliseconds(self.write)
+        )
+    }
+}
+
+/// [`Summary`] describes what a build did: how many files it combined, how
+/// much smaller they got, and how long it took.
+///
+/// # example
+/// [`Summary`] can be printed after a build, or written as json:
+/// ```rust
+/// use std::time::Duration;
+///
+/// use rcss::report::{Phases, Summary};
+///
+/// let summary = Summary {
+///     output: "style.css".to_string(),
+///     files: 2,
+///     input_bytes: 400,
+///     output_bytes: 300,
+///     phases: Phases {
+///         minify: Duration::from_millis(3),
+///         ..Default::default()
+///     },
+///     profile: None,
+/// };
+/// assert_eq!(summary.ratio(), 0.75);
+/// assert_eq!(
+///     summary.to_string(),
+///     "style.css: 2 files, 400 B -> 300 B (25.0% smaller, ratio 0.750) in 3.0 ms \
+///      (walk 0.0 ms, read 0.0 ms, minify 3.0 ms, write 0.0 ms)"
+/// );
+/// assert!(summary.to_json().starts_with(r#"{"output":"style.css","files":2,"#));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Summary {
+    /// the path of the output, or what a total is of.
+    pub output: String,
+    /// how many files were combined.
+    pub files: usize,
+    /// the size of every combined file before it was minified.
+    pub input_bytes: usize,
+    /// the size of the output.
+    pub output_bytes: usize,
+    pub phases: Phases,
+    /// how long every file and structural pass took, if the build was
+    /// profiled.
+    pub profile: Option<Profile>,
+}
+
+impl Summary {
+    /// [`Summary::total`] returns the sum of every one of `summaries`, as
+    /// the summary of `output`.
+    pub fn total(output: impl Into<String>, summaries: &[Summary]) -> Summary {
+        let mut total = Summary {
+            output: output.into(),
+            ..Default::default()
+        };
+        for summary in summaries {
+            total.files += summary.files;
+            total.input_bytes += summary.input_bytes;
+            total.output_bytes += summary.output_bytes;
+            total.phases.add(&summary.phases);
+            if let Some(profile) = &summary.profile {
+                total
+                    .profile
+                    .get_or_insert_with(Profile::default)
+                    .add(profile);
+            }
+        }
+        total
+    }
+
+    /// [`Summary::ratio`] returns the size of the output as a fraction of
+    /// the size of every combined file, or `1.0` if there was nothing to
+    /// combine.
+    pub fn ratio(&self) -> f64 {
+        match self.input_bytes {
+            0 => 1.0,
+            _ => self.output_bytes as f64 / self.input_bytes as f64,
+        }
+    }
+
+    /// [`Summary::savings`] returns how much smaller the output is than
+    /// every combined file, as a percentage of their original size.
+    pub fn savings(&self) -> f64 {
+        savings(self.input_bytes, self.output_bytes)
+    }
+
+    /// [`Summary::to_json`] writes this summary as a json object, with
+    /// every phase in milliseconds, and its profile if it has one.
+    pub fn to_json(&self) -> String {
+        let phase = |duration: Duration| duration.as_secs_f64() * 1000.0;
+        let profile = match &self.profile {
+            Some(profile) => format!(",\"profile\":{}", profile.to_json()),
+            None => String::new(),
+        };
+        format!(
+            "{{\"output\":{},\"files\":{},\"input_bytes\":{},\"output_bytes\":{},\
+             \"ratio\":{:.4},\"phases_ms\":{{\"walk\":{:.3},\"read\":{:.3},\
+             \"minify\":{:.3},\"write\":{:.3},\"total\":{:.3}}}{profile}}}",
+            json::quote(&self.output),
+            self.files,
+            self.input_bytes,
+            self.output_bytes,
+            self.ratio(),
+            phase(self.phases.walk),
+            phase(self.phases.read),
+            phase(self.phases.minify),
+            phase(self.phases.write),
+            phase(self.phases.total())
+        )
+    }
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} files, {} B -> {} B ({:.1}% smaller, ratio {:.3}) in {} ({})",
+            self.output,
+            self.files,
+            self.input_bytes,
+            self.output_bytes,
+            self.savings(),
+            self.ratio(),
+            milliseconds(self.phases.total()),
+            self.phases
+        )
+    }
+}
+
+/// [`write_summaries`] writes the total of `summaries`, and then every one
+/// of them, as a json object, like the `--report` file of the binary.
+///
+/// # example
+/// [`write_summaries`] can be used to write the summary of two builds:
+/// ```rust
+/// use rcss::report::{write_summaries, Summary};
+///
+/// let summaries = [Summary::default(), Summary::default()];
+/// let report = write_summaries(&summaries);
+/// assert!(report.starts_with("{\n  \"total\": {"));
+/// assert_eq!(report.matches("\"output\"").count(), 3);
+/// ```
+pub fn write_summaries(summaries: &[Summary]) -> String {
+    let outputs = summaries
+        .iter()
+        .map(|summary| format!("    {}", summary.to_json()))
+        .collect::<Vec<_>>();
+    format!(
+        "{{\n  \"total\": {},\n  \"outputs\": [\n{}\n  ]\n}}\n",
+        Summary::total("", summaries).to_json(),
+        outputs.join(",\n")
+    )
+}
+
+/// [`milliseconds`] returns `duration` in milliseconds, like `12.5 ms`.
+fn milliseconds(duration: Duration) -> String {
+    format!("{:.1} ms", duration.as_secs_f64() * 1000.0)
+}
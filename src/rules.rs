@@ -0,0 +1,220 @@
+//! rule sets, which decide how the files of each extension are minified,
+//! and the registry that the `extension` of a directory is looked up in.
+use std::{
+    collections::HashMap,
+    sync::{Arc, OnceLock, RwLock},
+};
+
+use crate::{css, html, js, json, source_map::Mapping, svg, Options};
+
+/// [`RuleSet`] minifies the files of one or more extensions.
+///
+/// # example
+/// [`RuleSet`] can be implemented to minify a custom file type, like
+/// `.txt` files that only need their trailing whitespace trimmed:
+/// ```rust
+/// use rcss::{rules::{self, RuleSet}, Options};
+///
+/// struct Text;
+///
+/// impl RuleSet for Text {
+///     fn extensions(&self) -> &[&str] {
+///         &["txt"]
+///     }
+///
+///     fn minify(&self, content: &str, _: &Options) -> String {
+///         content.lines().map(str::trim_end).collect::<Vec<_>>().join("\n")
+///     }
+/// }
+///
+/// rules::register(Text);
+/// let (minified, _, _) = rcss::minify_source("txt", "a  \nb ", &Options::default());
+/// assert_eq!(minified, "a\nb");
+/// ```
+pub trait RuleSet: Send + Sync {
+    /// [`RuleSet::extensions`] returns the file extensions (without a `.`)
+    /// that the rule set minifies, like `["js", "mjs"]`.
+    fn extensions(&self) -> &[&str];
+
+    /// [`RuleSet::minify`] returns the minified `content` of one file.
+    fn minify(&self, content: &str, options: &Options) -> String;
+
+    /// [`RuleSet::minify_with_mappings`] returns the minified `content` of
+    /// one file, and its source map mappings. only css has any, so this
+    /// returns no mappings unless it is overridden.
+    fn minify_with_mappings(&self, content: &str, options: &Options) -> (String, Vec<Mapping>) {
+        (self.minify(content, options), vec![])
+    }
+
+    /// [`RuleSet::license_comments`] returns the license comments within
+    /// `content`, which are kept when comments are preserved.
+    fn license_comments<'a>(&self, _content: &'a str) -> Vec<&'a str> {
+        vec![]
+    }
+
+    /// [`RuleSet::separator`] returns what must come between the `previous`
+    /// minified output and the next file, like the `;` that javascript
+    /// needs when a file ends without one.
+    fn separator(&self, _previous: &str) -> &'static str {
+        ""
+    }
+
+    /// [`RuleSet::is_css`] returns `true` if the css-only options, like
+    /// inlining imports and the structural [`crate::passes`], apply.
+    fn is_css(&self) -> bool {
+        false
+    }
+}
+
+/// [`Css`] is the rule set of [`css::minify_with_mappings`], which is used
+/// for every extension that no other rule set is registered for.
+pub struct Css;
+
+impl RuleSet for Css {
+    fn extensions(&self) -> &[&str] {
+        &["css"]
+    }
+
+    fn minify(&self, content: &str, _: &Options) -> String {
+        css::minify(content)
+    }
+
+    fn minify_with_mappings(&self, content: &str, _: &Options) -> (String, Vec<Mapping>) {
+        css::minify_with_mappings(content)
+    }
+
+    fn license_comments<'a>(&self, content: &'a str) -> Vec<&'a str> {
+        css::license_comments(content)
+    }
+
+    fn is_css(&self) -> bool {
+        true
+    }
+}
+
+/// [`Javascript`] is the rule set of [`js::minify`].
+pub struct Javascript;
+
+impl RuleSet for Javascript {
+    fn extensions(&self) -> &[&str] {
+        js::EXTENSIONS
+    }
+
+    fn minify(&self, content: &str, _: &Options) -> String {
+        js::minify(content)
+    }
+
+    fn license_comments<'a>(&self, content: &'a str) -> Vec<&'a str> {
+        js::license_comments(content)
+    }
+
+    // a javascript file could end without a semicolon, which only works
+    // because its line break was the end of the file
+    fn separator(&self, previous: &str) -> &'static str {
+        match previous.is_empty() || previous.ends_with(';') {
+            true => "",
+            false => ";",
+        }
+    }
+}
+
+/// [`Html`] is the rule set of [`html::minify`].
+pub struct Html;
+
+impl RuleSet for Html {
+    fn extensions(&self) -> &[&str] {
+        html::EXTENSIONS
+    }
+
+    fn minify(&self, content: &str, _: &Options) -> String {
+        html::minify(content)
+    }
+}
+
+/// [`Svg`] is the rule set of [`svg::minify`].
+pub struct Svg;
+
+impl RuleSet for Svg {
+    fn extensions(&self) -> &[&str] {
+        svg::EXTENSIONS
+    }
+
+    fn minify(&self, content: &str, _: &Options) -> String {
+        svg::minify(content)
+    }
+}
+
+/// [`Json`] is the rule set of [`json::minify`], which removes the keys
+/// matching [`Options::remove_keys`].
+pub struct Json;
+
+impl RuleSet for Json {
+    fn extensions(&self) -> &[&str] {
+        json::EXTENSIONS
+    }
+
+    fn minify(&self, content: &str, options: &Options) -> String {
+        json::minify(content, &options.remove_keys)
+    }
+}
+
+type Registry = RwLock<HashMap<String, Arc<dyn RuleSet>>>;
+
+/// [`registry`] returns every registered rule set by extension, starting
+/// with the built in ones.
+fn registry() -> &'static Registry {
+    static REGISTRY: OnceLock<Registry> = OnceLock::new();
+    REGISTRY.get_or_init(|| {
+        let built_in: [Arc<dyn RuleSet>; 5] = [
+            Arc::new(Css),
+            Arc::new(Javascript),
+            Arc::new(Html),
+            Arc::new(Svg),
+            Arc::new(Json),
+        ];
+        let mut rule_sets = HashMap::new();
+        for rule_set in built_in {
+            insert(&mut rule_sets, rule_set);
+        }
+        RwLock::new(rule_sets)
+    })
+}
+
+/// [`insert`] adds `rule_set` to `rule_sets` for each of its extensions.
+fn insert(rule_sets: &mut HashMap<String, Arc<dyn RuleSet>>, rule_set: Arc<dyn RuleSet>) {
+    for extension in rule_set.extensions() {
+        rule_sets.insert(normalize(extension), rule_set.clone());
+    }
+}
+
+/// [`normalize`] lowercases `extension`, and removes any leading `.`.
+fn normalize(extension: &str) -> String {
+    extension.trim_start_matches('.').to_ascii_lowercase()
+}
+
+/// [`register`] makes `rule_set` minify the files of each of its
+/// [`RuleSet::extensions`], replacing any rule set (even a built in one)
+/// that was registered for them before.
+pub fn register(rule_set: impl RuleSet + 'static) {
+    let mut rule_sets = registry()
+        .write()
+        .unwrap_or_else(|error| error.into_inner());
+    insert(&mut rule_sets, Arc::new(rule_set));
+}
+
+/// [`find`] returns the rule set registered for `extension`, or [`Css`] if
+/// there isn't one.
+///
+/// # example
+/// [`find`] can be used to check whether the css-only options apply:
+/// ```rust
+/// assert!(rcss::rules::find("css").is_css());
+/// assert!(!rcss::rules::find(".MJS").is_css());
+/// ```
+pub fn find(extension: &str) -> Arc<dyn RuleSet> {
+    let rule_sets = registry().read().unwrap_or_else(|error| error.into_inner());
+    match rule_sets.get(&normalize(extension)) {
+        Some(rule_set) => rule_set.clone(),
+        None => Arc::new(Css),
+    }
+}
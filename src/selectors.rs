@@ -0,0 +1,283 @@
+//! helpers for working with selectors, which are kept as minified
+//! text within a [`crate::stylesheet::Rule`].
+use std::fmt;
+
+use crate::tokenizer::{tokenize, Token, TokenKind};
+
+/// [`split_list`] splits a selector list on its top-level commas, so
+/// commas within `:is(a,b)` or `[title="a,b"]` are left alone.
+///
+/// # example
+/// [`split_list`] can be used to find every selector in a rule:
+/// ```rust
+/// let selectors = rcss::selectors::split_list(r#"a,:is(b,c),[title="d,e"]"#);
+/// assert_eq!(selectors, vec!["a", ":is(b,c)", r#"[title="d,e"]"#]);
+/// ```
+pub fn split_list(selector: &str) -> Vec<String> {
+    let mut selectors = vec![];
+    let mut current = String::new();
+    let mut depth = 0;
+    for token in tokenize(selector) {
+        match token.kind {
+            TokenKind::Function | TokenKind::OpenParen | TokenKind::OpenBracket => depth += 1,
+            TokenKind::CloseParen | TokenKind::CloseBracket => depth -= 1,
+            TokenKind::Comma if depth == 0 => {
+                selectors.push(current.trim().to_string());
+                current.clear();
+                continue;
+            }
+            _ => {}
+        }
+        current.push_str(token.text);
+    }
+    selectors.push(current.trim().to_string());
+    selectors.retain(|selector| !selector.is_empty());
+    selectors
+}
+
+/// [`replace_nesting`] replaces every `&` nesting selector in `selector`
+/// with `parent`, leaving any `&` within strings alone.
+///
+/// # example
+/// [`replace_nesting`] can be used to resolve a nested selector:
+/// ```rust
+/// assert_eq!(rcss::selectors::replace_nesting("&:hover,&-title", ".card"), ".card:hover,.card-title");
+/// ```
+pub fn replace_nesting(selector: &str, parent: &str) -> String {
+    tokenize(selector)
+        .into_iter()
+        .map(|token| match token.kind {
+            TokenKind::Delim if token.text == "&" => parent,
+            _ => token.text,
+        })
+        .collect()
+}
+
+/// [`COMBINATORS`] are the combinators that never need whitespace around
+/// them, unlike the descendant combinator, which is whitespace.
+const COMBINATORS: &[&str] = &[">", "+", "~"];
+
+/// [`is_significant_whitespace`] returns `true` if whitespace between the
+/// tokens `previous` and `next` of a selector must be kept. outside of an
+/// attribute selector, that is when it is a descendant combinator, like the
+/// space of `a :hover`, rather than whitespace around other punctuation,
+/// like the spaces of `a > b` or `:not( a )`. within one (`is_attribute`),
+/// it is only needed before a flag, like the space of `[type="a" i]`.
+pub fn is_significant_whitespace(previous: &Token, next: &Token, is_attribute: bool) -> bool {
+    if is_attribute {
+        return !matches!(previous.kind, TokenKind::OpenBracket | TokenKind::Delim)
+            && !matches!(next.kind, TokenKind::CloseBracket | TokenKind::Delim);
+    }
+    let is_combinator =
+        |token: &Token| token.kind == TokenKind::Delim && COMBINATORS.contains(&token.text);
+    !is_combinator(previous)
+        && !is_combinator(next)
+        && !matches!(
+            previous.kind,
+            TokenKind::Comma
+                | TokenKind::Function
+                | TokenKind::OpenParen
+                | TokenKind::OpenBrace
+                | TokenKind::CloseBrace
+                | TokenKind::Semicolon
+        )
+        && !matches!(
+            next.kind,
+            TokenKind::Comma | TokenKind::CloseParen | TokenKind::OpenBrace
+        )
+}
+
+/// [`minify`] removes the comments of `selector`, and all of its
+/// whitespace except for descendant combinators, which are collapsed to a
+/// single space.
+///
+/// # example
+/// [`minify`] can be used to minify a selector without changing what it
+/// matches:
+/// ```rust
+/// use rcss::selectors::minify;
+///
+/// assert_eq!(minify(" ul > li  :not( .a , .b ) ~ a [ href ^= \"#\" ] "), "ul>li :not(.a,.b)~a [href^=\"#\"]");
+/// assert_eq!(minify("a ::before, [type = \"a\" i]"), "a ::before,[type=\"a\" i]");
+/// ```
+pub fn minify(selector: &str) -> String {
+    let tokens = tokenize(selector)
+        .into_iter()
+        .filter(|token| token.kind != TokenKind::Comment)
+        .collect::<Vec<_>>();
+    let mut minified = String::with_capacity(selector.len());
+    let mut previous: Option<&Token> = None;
+    let mut is_spaced = false;
+    let mut attribute_depth = 0;
+    for (index, token) in tokens.iter().enumerate() {
+        match token.kind {
+            TokenKind::Whitespace => {
+                let next = tokens[index + 1..]
+                    .iter()
+                    .find(|token| token.kind != TokenKind::Whitespace);
+                let is_significant = previous.zip(next).is_some_and(|(previous, next)| {
+                    is_significant_whitespace(previous, next, attribute_depth > 0)
+                });
+                if is_significant && !is_spaced {
+                    minified.push(' ');
+                    is_spaced = true;
+                }
+                continue;
+            }
+            TokenKind::OpenBracket => attribute_depth += 1,
+            TokenKind::CloseBracket => attribute_depth -= 1,
+            _ => {}
+        }
+        minified.push_str(token.text);
+        previous = Some(token);
+        is_spaced = false;
+    }
+    minified
+}
+
+/// [`Specificity`] is how specific a selector is: its number of ids, of
+/// classes, attributes, and pseudo-classes, and of element names and
+/// pseudo-elements. a more specific selector wins, regardless of order.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Specificity(pub u32, pub u32, pub u32);
+
+impl fmt::Display for Specificity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{},{}", self.0, self.1, self.2)
+    }
+}
+
+/// [`LEGACY_PSEUDO_ELEMENTS`] are the pseudo-elements that can still be
+/// written with a single `:`, like `:before`.
+const LEGACY_PSEUDO_ELEMENTS: &[&str] = &["before", "after", "first-line", "first-letter"];
+
+/// [`specificity`] returns the [`Specificity`] of the complex `selector`.
+///
+/// # notes
+/// `:is()`, `:not()`, and `:has()` are as specific as their most specific
+/// argument, and `:where()` adds nothing, like in css selectors level 4.
+///
+/// # example
+/// [`specificity`] can be used to compare two selectors:
+/// ```rust
+/// use rcss::selectors::{specificity, Specificity};
+///
+/// assert_eq!(specificity("#nav .item>a:hover"), Specificity(1, 2, 1));
+/// assert_eq!(specificity("a::before"), Specificity(0, 0, 2));
+/// assert_eq!(specificity(":is(#a,.b) :where(#c)"), Specificity(1, 0, 0));
+/// ```
+pub fn specificity(selector: &str) -> Specificity {
+    let tokens = tokenize(selector);
+    let mut total = Specificity::default();
+    let mut index = 0;
+    while index < tokens.len() {
+        let token = &tokens[index];
+        let previous = index.checked_sub(1).map(|previous| &tokens[previous]);
+        let next = tokens.get(index + 1);
+        match token.kind {
+            TokenKind::Hash => total.0 += 1,
+            TokenKind::OpenBracket => {
+                total.1 += 1;
+                index = closing(&tokens, index);
+            }
+            TokenKind::Ident => match previous {
+                Some(previous) if previous.kind == TokenKind::Delim && previous.text == "." => {
+                    total.1 += 1
+                }
+                // the `svg` of `svg|rect` is a namespace, not an element name
+                _ if next.is_some_and(|next| next.text == "|") => {}
+                _ => total.2 += 1,
+            },
+            TokenKind::Colon => {
+                let is_pseudo_element = next.is_some_and(|next| next.kind == TokenKind::Colon);
+                if is_pseudo_element {
+                    index += 1;
+                }
+                let Some(name) = tokens.get(index + 1) else {
+                    break;
+                };
+                index += 1;
+                let lowercase = name.text.to_ascii_lowercase();
+                match name.kind {
+                    _ if is_pseudo_element => total.2 += 1,
+                    TokenKind::Function => {
+                        let end = closing(&tokens, index);
+                        let arguments = tokens[index + 1..end.max(index + 1)]
+                            .iter()
+                            .map(|token| token.text)
+                            .collect::<String>();
+                        match lowercase.trim_end_matches('(') {
+                            "where" => {}
+                            "is" | "not" | "has" | "matches" | "-webkit-any" | "-moz-any" => {
+                                let most_specific = split_list(&arguments)
+                                    .iter()
+                                    .map(|argument| specificity(argument))
+                                    .max()
+                                    .unwrap_or_default();
+                                total.0 += most_specific.0;
+                                total.1 += most_specific.1;
+                                total.2 += most_specific.2;
+                            }
+                            _ => total.1 += 1,
+                        }
+                        index = end;
+                    }
+                    _ if LEGACY_PSEUDO_ELEMENTS.contains(&lowercase.as_str()) => total.2 += 1,
+                    _ => total.1 += 1,
+                }
+            }
+            _ => {}
+        }
+        index += 1;
+    }
+    total
+}
+
+/// [`compound_count`] returns how many compound selectors the complex
+/// `selector` chains together with combinators, like `3` for `nav .item>a`.
+///
+/// # example
+/// [`compound_count`] can be used to find a selector that is too long:
+/// ```rust
+/// use rcss::selectors::compound_count;
+///
+/// assert_eq!(compound_count("nav .item > a:hover"), 3);
+/// assert_eq!(compound_count("a:not(.b .c)"), 1);
+/// ```
+pub fn compound_count(selector: &str) -> usize {
+    let minified = minify(selector);
+    let tokens = tokenize(&minified);
+    let mut count = 1;
+    let mut index = 0;
+    while index < tokens.len() {
+        let token = &tokens[index];
+        match token.kind {
+            TokenKind::Function | TokenKind::OpenParen | TokenKind::OpenBracket => {
+                index = closing(&tokens, index);
+            }
+            TokenKind::Whitespace => count += 1,
+            TokenKind::Delim if matches!(token.text, ">" | "+" | "~") => count += 1,
+            _ => {}
+        }
+        index += 1;
+    }
+    count
+}
+
+/// [`closing`] returns the index of the token that closes the function,
+/// parenthesis, or bracket at `start`, or the last token if it is never
+/// closed.
+fn closing(tokens: &[Token], start: usize) -> usize {
+    let mut depth = 0;
+    for (index, token) in tokens.iter().enumerate().skip(start) {
+        match token.kind {
+            TokenKind::Function | TokenKind::OpenParen | TokenKind::OpenBracket => depth += 1,
+            TokenKind::CloseParen | TokenKind::CloseBracket => depth -= 1,
+            _ => {}
+        }
+        if depth == 0 {
+            return index;
+        }
+    }
+    tokens.len().saturating_sub(1)
+}
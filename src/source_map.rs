@@ -0,0 +1,133 @@
+//! source map generation for combined output, following the
+//! [source map v3](https://sourcemaps.info/spec.html) format.
+use crate::json;
+
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// [`Mapping`] points a column of minified output back to the 1-based
+/// `line` and `column` it came from in the original file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Mapping {
+    pub generated_column: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// [`SourceMap`] collects the [`Mapping`]s of every file that is combined
+/// into a single line of minified output.
+///
+/// # example
+/// [`SourceMap`] can be used to map two minified files that are combined
+/// into `style.css`:
+/// ```rust
+/// use rcss::{css::minify_with_mappings, source_map::SourceMap};
+///
+/// let mut source_map = SourceMap::new("style.css");
+/// for (source, content) in [("a.css", "a {}"), ("b.css", "b {}")] {
+///     let (minified, mappings) = minify_with_mappings(content);
+///     source_map.add_source(source, &minified, &mappings);
+/// }
+/// assert!(source_map.to_json().contains(r#""sources":["a.css","b.css"]"#));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct SourceMap {
+    file: String,
+    sources: Vec<String>,
+    segments: Vec<(usize, usize, Mapping)>,
+    generated_offset: usize,
+}
+
+impl SourceMap {
+    /// [`SourceMap::new`] creates an empty source map for the output `file`.
+    pub fn new(file: impl Into<String>) -> Self {
+        SourceMap {
+            file: file.into(),
+            ..Default::default()
+        }
+    }
+
+    /// [`SourceMap::set_file`] changes the output `file` the source map is
+    /// for, like when its name depends on its content.
+    pub fn set_file(&mut self, file: impl Into<String>) {
+        self.file = file.into();
+    }
+
+    /// [`SourceMap::add_source`] records the `mappings` of `minified`, which
+    /// is appended to the output right after every previously added source.
+    pub fn add_source(&mut self, source: impl Into<String>, minified: &str, mappings: &[Mapping]) {
+        let source_index = self.sources.len();
+        self.sources.push(source.into());
+        for mapping in mappings {
+            let generated_column = self.generated_offset + mapping.generated_column;
+            self.segments
+                .push((generated_column, source_index, *mapping));
+        }
+        self.generated_offset += minified.encode_utf16().count();
+    }
+
+    /// [`SourceMap::add_generated`] records that `text`, which didn't come
+    /// from any source, is appended to the output.
+    pub fn add_generated(&mut self, text: &str) {
+        self.generated_offset += text.encode_utf16().count();
+    }
+
+    /// [`SourceMap::to_json`] serializes the source map as json, ready to be
+    /// written to a `.map` file.
+    pub fn to_json(&self) -> String {
+        let mut mappings = String::new();
+        let (mut column, mut source, mut line, mut original_column) = (0, 0, 0, 0);
+        for (index, (generated_column, source_index, mapping)) in self.segments.iter().enumerate() {
+            if index > 0 {
+                mappings.push(',');
+            }
+            let original_line = mapping.line as i64 - 1;
+            let original = mapping.column as i64 - 1;
+            encode_vlq(&mut mappings, *generated_column as i64 - column);
+            encode_vlq(&mut mappings, *source_index as i64 - source);
+            encode_vlq(&mut mappings, original_line - line);
+            encode_vlq(&mut mappings, original - original_column);
+            column = *generated_column as i64;
+            source = *source_index as i64;
+            line = original_line;
+            original_column = original;
+        }
+        let sources = self
+            .sources
+            .iter()
+            .map(|source| json::quote(source))
+            .collect::<Vec<_>>()
+            .join(",");
+        format!(
+            r#"{{"version":3,"file":{},"sources":[{}],"names":[],"mappings":"{}"}}"#,
+            json::quote(&self.file),
+            sources,
+            mappings
+        )
+    }
+}
+
+/// [`source_mapping_url`] creates the comment that links minified
+/// output to its `.map` file.
+pub fn source_mapping_url(map_file_name: &str) -> String {
+    format!("/*# sourceMappingURL={map_file_name} */")
+}
+
+/// [`encode_vlq`] appends `value` to `buffer` as a base64 vlq.
+fn encode_vlq(buffer: &mut String, value: i64) {
+    let mut vlq = if value < 0 {
+        ((-value as u64) << 1) | 1
+    } else {
+        (value as u64) << 1
+    };
+    loop {
+        let mut digit = (vlq & 0b11111) as usize;
+        vlq >>= 5;
+        if vlq > 0 {
+            digit |= 0b100000;
+        }
+        buffer.push(BASE64[digit] as char);
+        if vlq == 0 {
+            return;
+        }
+    }
+}
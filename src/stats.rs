@@ -0,0 +1,269 @@
+//! statistics about a tree of stylesheets, like how many rules and
+//! selectors it has, and how much each optimization pass would save,
+//! before any aggressive options are turned on.
+use std::{cmp::Reverse, collections::HashMap, fmt, path::Path};
+
+use crate::{
+    browsers, css,
+    error::Result,
+    glob, ordering, paths,
+    report::FileReport,
+    selectors::{specificity, split_list, Specificity},
+    stylesheet::{Node, Stylesheet},
+    Options,
+};
+
+/// [`LARGEST`] is how many of the largest files and most duplicated
+/// declarations are listed.
+const LARGEST: usize = 5;
+
+/// [`TurnOn`] turns a single pass on.
+type TurnOn = fn(&mut Options);
+
+/// [`PASSES`] are the passes that can be estimated without any other
+/// input, with the flag that turns each of them on.
+const PASSES: &[(&str, TurnOn)] = &[
+    ("--merge-duplicates", |options| {
+        options.merge_duplicates = true
+    }),
+    ("--remove-overridden", |options| {
+        options.remove_overridden = true
+    }),
+    ("--merge-selectors", |options| {
+        options.merge_selectors = true
+    }),
+    ("--collapse-shorthands", |options| {
+        options.collapse_shorthands = true
+    }),
+    ("--remove-empty-rules", |options| {
+        options.remove_empty_rules = true
+    }),
+    ("--merge-media", |options| options.merge_media = true),
+    ("--remove-unused-at-rules", |options| {
+        options.remove_unused_at_rules = true
+    }),
+    ("--strip-prefixes", |options| options.strip_prefixes = true),
+    ("--strip-hacks", |options| options.strip_hacks = true),
+    ("--normalize-case", |options| options.normalize_case = true),
+    ("--inline-variables", |options| {
+        options.inline_variables = true
+    }),
+    ("--remove-unused-variables", |options| {
+        options.remove_unused_variables = true
+    }),
+    ("--shorten-identifiers", |options| {
+        options.shorten_identifiers = true
+    }),
+];
+
+/// [`Stats`] describes the stylesheets within a directory, combined and
+/// minified like [`crate::minify_directory_with`] would.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Stats {
+    /// the directory that was analyzed.
+    pub directory: String,
+    /// every combined file, in the order it was combined in.
+    pub files: Vec<FileReport>,
+    /// the size of the combined and minified output, without any passes.
+    pub output_bytes: usize,
+    pub rules: usize,
+    pub selectors: usize,
+    pub declarations: usize,
+    /// how many selectors have each specificity, most specific first.
+    pub specificity: Vec<(Specificity, usize)>,
+    /// every declaration (like `display:flex`) that is set more than once,
+    /// with how many times it is set, most repeated first.
+    pub duplicate_declarations: Vec<(String, usize)>,
+    /// each pass flag, with the size of the output if only it was on.
+    pub passes: Vec<(&'static str, usize)>,
+}
+
+impl Stats {
+    /// [`Stats::analyze`] gathers the statistics of the minified `css`,
+    /// which was combined from `files`, estimating the passes for the
+    /// browser targets of `options`.
+    pub fn analyze(
+        directory: impl Into<String>,
+        files: Vec<FileReport>,
+        css: &str,
+        options: &Options,
+    ) -> Result<Stats> {
+        let mut stats = Stats {
+            directory: directory.into(),
+            files,
+            output_bytes: css.len(),
+            ..Default::default()
+        };
+        let mut specificity_counts = HashMap::new();
+        let mut declaration_counts = HashMap::new();
+        count_block(
+            &Stylesheet::parse(css).nodes,
+            &mut stats,
+            &mut specificity_counts,
+            &mut declaration_counts,
+        );
+        stats.specificity = specificity_counts.into_iter().collect();
+        stats.specificity.sort_by(|left, right| right.cmp(left));
+        stats.duplicate_declarations = declaration_counts
+            .into_iter()
+            .filter(|(_, count)| *count > 1)
+            .collect();
+        stats
+            .duplicate_declarations
+            .sort_by(|(left, left_count), (right, right_count)| {
+                right_count.cmp(left_count).then(left.cmp(right))
+            });
+
+        let targets = browsers::Targets::parse(&options.browsers)?;
+        for (flag, turn_on) in PASSES {
+            let mut pass_options = Options {
+                browsers: options.browsers.clone(),
+                ..Default::default()
+            };
+            turn_on(&mut pass_options);
+            let (output, _) = crate::apply_passes(
+                css,
+                &pass_options,
+                &targets,
+                &Default::default(),
+                None,
+                &mut vec![],
+            );
+            stats.passes.push((flag, output.len()));
+        }
+        Ok(stats)
+    }
+
+    /// [`Stats::duplicates`] returns how many declarations repeat one that
+    /// was already set.
+    pub fn duplicates(&self) -> usize {
+        self.duplicate_declarations
+            .iter()
+            .map(|(_, count)| count - 1)
+            .sum()
+    }
+}
+
+/// [`count_block`] adds the rules, selectors, and declarations within
+/// `nodes`, and every block within them, to the counts.
+fn count_block(
+    nodes: &[Node],
+    stats: &mut Stats,
+    specificity_counts: &mut HashMap<Specificity, usize>,
+    declaration_counts: &mut HashMap<String, usize>,
+) {
+    for node in nodes {
+        match node {
+            Node::Rule(rule) => {
+                stats.rules += 1;
+                for selector in split_list(&rule.selector) {
+                    stats.selectors += 1;
+                    *specificity_counts
+                        .entry(specificity(&selector))
+                        .or_default() += 1;
+                }
+            }
+            Node::Declaration(declaration) => {
+                stats.declarations += 1;
+                *declaration_counts.entry(declaration.to_css()).or_default() += 1;
+            }
+            _ => {}
+        }
+        // the `from` and `50%` of a keyframe aren't selectors
+        let is_keyframes = match node {
+            Node::AtRule(at_rule) => at_rule.name.to_ascii_lowercase().ends_with("keyframes"),
+            _ => false,
+        };
+        if let Some(children) = node.children().filter(|_| !is_keyframes) {
+            count_block(children, stats, specificity_counts, declaration_counts);
+        }
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let original_bytes = self
+            .files
+            .iter()
+            .map(|file| file.original_bytes)
+            .sum::<usize>();
+        writeln!(f, "stats for {}", self.directory)?;
+        writeln!(
+            f,
+            "files: {}, {} B -> {} B minified",
+            self.files.len(),
+            original_bytes,
+            self.output_bytes
+        )?;
+        let mut largest = self.files.iter().collect::<Vec<_>>();
+        largest.sort_by_key(|file| Reverse(file.minified_bytes));
+        if !largest.is_empty() {
+            writeln!(f, "largest files:")?;
+        }
+        for file in largest.iter().take(LARGEST) {
+            writeln!(
+                f,
+                "  {}  {} B -> {} B",
+                file.path, file.original_bytes, file.minified_bytes
+            )?;
+        }
+        writeln!(
+            f,
+            "rules: {}, selectors: {}, declarations: {}",
+            self.rules, self.selectors, self.declarations
+        )?;
+        if !self.specificity.is_empty() {
+            writeln!(f, "specificity:")?;
+        }
+        for (specificity, count) in &self.specificity {
+            writeln!(f, "  {specificity}  {count} selectors")?;
+        }
+        writeln!(f, "duplicate declarations: {}", self.duplicates())?;
+        for (declaration, count) in self.duplicate_declarations.iter().take(LARGEST) {
+            writeln!(f, "  {declaration}  set {count} times")?;
+        }
+        write!(f, "estimated savings:")?;
+        for (flag, bytes) in &self.passes {
+            let saved = self.output_bytes as i64 - *bytes as i64;
+            let percent = match self.output_bytes {
+                0 => 0.0,
+                output_bytes => saved as f64 / output_bytes as f64 * 100.0,
+            };
+            write!(f, "\n  {flag}  {saved} B ({percent:.1}%)")?;
+        }
+        Ok(())
+    }
+}
+
+/// [`stats_directory`] combines and minifies the css files within
+/// `directory` (leaving out `output`, which is where they would be
+/// combined into) like [`crate::dry_run_directory`], and returns their
+/// [`Stats`]. nothing is written.
+///
+/// # example
+/// [`stats_directory`] can be used to print the stats of `./assets/css`:
+/// ```rust,no_run
+/// let stats = rcss::stats::stats_directory("./assets/css", "style.css", &Default::default()).unwrap();
+/// println!("{stats}");
+/// ```
+pub fn stats_directory(directory: &str, output: &str, options: &Options) -> Result<Stats> {
+    let filter = glob::FileFilter::new(&options.include, &options.exclude);
+    let output_path = Path::new(directory).join(output);
+    let files = crate::recurse_files(directory, &filter, options)?
+        .into_iter()
+        .filter(|file| crate::has_extension(file, "css") && !paths::same_file(file, &output_path))
+        .collect();
+    let files = ordering::order_files(files, directory, &options.order, options.sort)?;
+    let mut css = String::new();
+    let mut reports = vec![];
+    for (file_path, content) in crate::read_sources(&files, options)? {
+        let minified = css::minify(&content);
+        reports.push(FileReport {
+            path: ordering::relative_path(&file_path, directory),
+            original_bytes: content.len(),
+            minified_bytes: minified.len(),
+        });
+        css.push_str(&minified);
+    }
+    Stats::analyze(directory, reports, &css, options)
+}
@@ -0,0 +1,300 @@
+//! a block-structured model of a stylesheet, used by optimization
+//! passes that need to work with whole rules and declarations rather
+//! than individual tokens.
+use crate::tokenizer::{remove_comments, tokenize, Token, TokenKind};
+
+/// [`MAX_DEPTH`] is how many blocks deep a [`Stylesheet`] is parsed. every
+/// block nested deeper than that is kept as it is, in a [`Node::Raw`], so
+/// that passes can't overflow the stack on a pathological input.
+pub const MAX_DEPTH: usize = 128;
+
+/// [`Stylesheet`] is a parsed stylesheet, made up of top-level [`Node`]s.
+///
+/// # example
+/// [`Stylesheet`] can be used to parse css, change it, and turn it back
+/// into minified css:
+/// ```rust
+/// use rcss::stylesheet::{Node, Stylesheet};
+///
+/// let mut stylesheet = Stylesheet::parse("a { color: red } b { color: blue }");
+/// stylesheet.nodes.retain(|node| matches!(node, Node::Rule(rule) if rule.selector == "a"));
+/// assert_eq!(stylesheet.to_css(), "a{color:red}");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Stylesheet {
+    pub nodes: Vec<Node>,
+}
+
+/// [`Node`] is a single item within a [`Stylesheet`] or a block.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Node {
+    /// a style rule, like `a{color:red}`.
+    Rule(Rule),
+    /// an at-rule, with or without a block, like `@media print{...}`
+    /// or `@import "a.css";`.
+    AtRule(AtRule),
+    /// a declaration within a block, like `color:red`.
+    Declaration(Declaration),
+    /// anything that couldn't be parsed, or a block nested deeper than
+    /// [`MAX_DEPTH`], which is kept as-is.
+    Raw(String),
+}
+
+/// [`Rule`] is a style rule: a selector followed by a block.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Rule {
+    /// the selector list, like `a,b>c`.
+    pub selector: String,
+    pub children: Vec<Node>,
+}
+
+/// [`AtRule`] is an at-rule, like `@media` or `@font-face`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AtRule {
+    /// the name of the at-rule, without the `@`, like `media`.
+    pub name: String,
+    /// everything between the name and the block or `;`, like
+    /// `screen and (min-width:768px)`.
+    pub prelude: String,
+    /// the content of the block, or `None` for statements like `@import`.
+    pub children: Option<Vec<Node>>,
+}
+
+/// [`Declaration`] is a property and its value, like `color:red!important`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Declaration {
+    pub property: String,
+    /// the value, without any `!important`.
+    pub value: String,
+    pub important: bool,
+}
+
+impl Stylesheet {
+    /// [`Stylesheet::parse`] parses `css` into a [`Stylesheet`]. comments
+    /// are dropped, and whitespace within selectors, preludes, and values
+    /// is collapsed to a single space.
+    pub fn parse(css: &str) -> Stylesheet {
+        let tokens = remove_comments(tokenize(css));
+        let mut index = 0;
+        let mut nodes = vec![];
+        // an unmatched `}` ends a block early, so keep parsing until
+        // every token has been used.
+        while index < tokens.len() {
+            nodes.extend(parse_block(&tokens, &mut index, 0));
+        }
+        Stylesheet { nodes }
+    }
+
+    /// [`Stylesheet::to_css`] serializes the stylesheet as minified css.
+    pub fn to_css(&self) -> String {
+        let mut css = String::new();
+        write_nodes(&mut css, &self.nodes);
+        css
+    }
+}
+
+impl Node {
+    /// [`Node::children`] returns the block of a rule or an at-rule.
+    pub fn children(&self) -> Option<&Vec<Node>> {
+        match self {
+            Node::Rule(rule) => Some(&rule.children),
+            Node::AtRule(at_rule) => at_rule.children.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// [`Node::children_mut`] returns the block of a rule or an at-rule.
+    pub fn children_mut(&mut self) -> Option<&mut Vec<Node>> {
+        match self {
+            Node::Rule(rule) => Some(&mut rule.children),
+            Node::AtRule(at_rule) => at_rule.children.as_mut(),
+            _ => None,
+        }
+    }
+}
+
+impl Declaration {
+    /// [`Declaration::to_css`] serializes the declaration as minified css.
+    pub fn to_css(&self) -> String {
+        let important = if self.important { "!important" } else { "" };
+        format!("{}:{}{}", self.property, self.value, important)
+    }
+}
+
+/// [`join_tokens`] joins the text of `tokens`, collapsing whitespace to
+/// a single space and trimming it from either end.
+fn join_tokens(tokens: &[Token]) -> String {
+    let mut text = String::new();
+    for token in tokens {
+        if token.kind == TokenKind::Whitespace {
+            if !text.is_empty() && !text.ends_with(' ') {
+                text.push(' ');
+            }
+        } else {
+            text.push_str(token.text);
+        }
+    }
+    text.trim_end().to_string()
+}
+
+/// [`statement_end`] returns the index of the first `;`, `{`, or `}` at
+/// or after `start` that isn't nested within parentheses or brackets.
+fn statement_end(tokens: &[Token], start: usize) -> usize {
+    let mut depth = 0;
+    for (index, token) in tokens.iter().enumerate().skip(start) {
+        match token.kind {
+            TokenKind::OpenParen | TokenKind::Function | TokenKind::OpenBracket => depth += 1,
+            TokenKind::CloseParen | TokenKind::CloseBracket => depth -= 1,
+            TokenKind::Semicolon | TokenKind::OpenBrace | TokenKind::CloseBrace if depth <= 0 => {
+                return index
+            }
+            _ => {}
+        }
+    }
+    tokens.len()
+}
+
+/// [`skip_block`] returns the index after the `}` that closes the block
+/// starting at `tokens[start]`, or the end of `tokens`.
+fn skip_block(tokens: &[Token], start: usize) -> usize {
+    let mut depth = 0;
+    for (index, token) in tokens.iter().enumerate().skip(start) {
+        match token.kind {
+            TokenKind::OpenBrace => depth += 1,
+            TokenKind::CloseBrace if depth == 0 => return index + 1,
+            TokenKind::CloseBrace => depth -= 1,
+            _ => {}
+        }
+    }
+    tokens.len()
+}
+
+/// [`parse_block`] parses nodes starting at `tokens[*index]`, until the `}`
+/// that closes the block (which is consumed) or the end of `tokens`. the
+/// block is `depth` blocks deep, and any block nested deeper than
+/// [`MAX_DEPTH`] is kept as a single [`Node::Raw`].
+fn parse_block(tokens: &[Token], index: &mut usize, depth: usize) -> Vec<Node> {
+    let mut nodes = vec![];
+    while *index < tokens.len() {
+        let token = &tokens[*index];
+        match token.kind {
+            TokenKind::Whitespace | TokenKind::Semicolon => {
+                *index += 1;
+                continue;
+            }
+            TokenKind::CloseBrace => {
+                *index += 1;
+                return nodes;
+            }
+            _ => {}
+        }
+        let start = *index;
+        let end = statement_end(tokens, start);
+        let opens_block = tokens
+            .get(end)
+            .is_some_and(|token| token.kind == TokenKind::OpenBrace);
+        *index = if opens_block
+            || tokens
+                .get(end)
+                .is_some_and(|token| token.kind == TokenKind::Semicolon)
+        {
+            end + 1
+        } else {
+            end
+        };
+
+        if opens_block && depth >= MAX_DEPTH {
+            *index = skip_block(tokens, *index);
+            nodes.push(Node::Raw(join_tokens(&tokens[start..*index])));
+        } else if token.kind == TokenKind::AtKeyword {
+            let children = opens_block.then(|| parse_block(tokens, index, depth + 1));
+            nodes.push(Node::AtRule(AtRule {
+                name: token.text[1..].to_string(),
+                prelude: join_tokens(&tokens[start + 1..end])
+                    .trim_start()
+                    .to_string(),
+                children,
+            }));
+        } else if opens_block {
+            nodes.push(Node::Rule(Rule {
+                selector: join_tokens(&tokens[start..end]),
+                children: parse_block(tokens, index, depth + 1),
+            }));
+        } else {
+            nodes.push(parse_declaration(&tokens[start..end]));
+        }
+    }
+    nodes
+}
+
+/// [`parse_declaration`] parses the tokens of a `property:value` statement,
+/// or keeps them as [`Node::Raw`] if there is no property.
+fn parse_declaration(tokens: &[Token]) -> Node {
+    let colon = tokens
+        .iter()
+        .position(|token| token.kind == TokenKind::Colon);
+    let Some(colon) = colon.filter(|colon| {
+        let property = join_tokens(&tokens[..*colon]);
+        !property.is_empty() && !property.contains(' ')
+    }) else {
+        return Node::Raw(join_tokens(tokens));
+    };
+    let mut value = join_tokens(&tokens[colon + 1..]).trim_start().to_string();
+    let mut important = false;
+    if let Some(bang) = value.rfind('!') {
+        if value[bang + 1..].trim().eq_ignore_ascii_case("important") {
+            important = true;
+            value = value[..bang].trim_end().to_string();
+        }
+    }
+    Node::Declaration(Declaration {
+        property: join_tokens(&tokens[..colon]),
+        value,
+        important,
+    })
+}
+
+/// [`write_nodes`] appends `nodes` to `css` as minified css. declarations
+/// are separated by `;`, with no `;` after the last one.
+fn write_nodes(css: &mut String, nodes: &[Node]) {
+    for (index, node) in nodes.iter().enumerate() {
+        let is_last = index + 1 == nodes.len();
+        match node {
+            Node::Rule(rule) => {
+                css.push_str(&rule.selector);
+                css.push('{');
+                write_nodes(css, &rule.children);
+                css.push('}');
+            }
+            Node::AtRule(at_rule) => {
+                css.push('@');
+                css.push_str(&at_rule.name);
+                if !at_rule.prelude.is_empty() {
+                    css.push(' ');
+                    css.push_str(&at_rule.prelude);
+                }
+                match &at_rule.children {
+                    Some(children) => {
+                        css.push('{');
+                        write_nodes(css, children);
+                        css.push('}');
+                    }
+                    None => css.push(';'),
+                }
+            }
+            Node::Declaration(declaration) => {
+                css.push_str(&declaration.to_css());
+                if !is_last {
+                    css.push(';');
+                }
+            }
+            Node::Raw(raw) => {
+                css.push_str(raw);
+                // a block kept as it is already ends
+                if !is_last && !raw.ends_with('}') {
+                    css.push(';');
+                }
+            }
+        }
+    }
+}
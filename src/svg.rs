@@ -0,0 +1,236 @@
+//! the svg rule set, which strips comments, editor metadata, and
+//! whitespace, and rounds the numbers within path data.
+use crate::html::{collapse_whitespace, tag_end, Tag};
+
+/// [`EXTENSIONS`] are the file extensions that are minified as svg.
+pub const EXTENSIONS: &[&str] = &["svg"];
+
+/// [`DEFAULT_PRECISION`] is how many decimal places [`minify`] rounds the
+/// numbers within path data to.
+pub const DEFAULT_PRECISION: usize = 3;
+
+/// [`EDITOR_PREFIXES`] are the namespace prefixes of elements and attributes
+/// that editors like inkscape and sketch add, which browsers ignore.
+const EDITOR_PREFIXES: &[&str] = &["inkscape", "sodipodi", "sketch", "rdf", "cc", "dc"];
+
+/// [`TEXT_ELEMENTS`] are the elements whose whitespace is rendered.
+const TEXT_ELEMENTS: &[&str] = &["text", "tspan", "textPath", "title", "desc"];
+
+/// [`is_extension`] returns `true` if files with `extension` are minified
+/// as svg.
+///
+/// # example
+/// [`is_extension`] can be used to pick a rule set for a file:
+/// ```rust
+/// assert!(rcss::svg::is_extension("svg"));
+/// assert!(!rcss::svg::is_extension("css"));
+/// ```
+pub fn is_extension(extension: &str) -> bool {
+    EXTENSIONS
+        .iter()
+        .any(|known| known.eq_ignore_ascii_case(extension.trim_start_matches('.')))
+}
+
+/// [`minify`] will minify the svg in `source`, rounding path data to
+/// [`DEFAULT_PRECISION`] decimal places.
+///
+/// # example
+/// [`minify`] can be used to minify an icon exported from inkscape:
+/// ```rust
+/// let icon = r#"<svg xmlns="http://www.w3.org/2000/svg" xmlns:inkscape="http://www.inkscape.org/namespaces/inkscape">
+///   <!-- layer 1 -->
+///   <metadata><rdf:RDF /></metadata>
+///   <path inkscape:label="arrow" d="M 10.00000 0.5 L -0.25001 20" />
+/// </svg>"#;
+/// assert_eq!(
+///     rcss::svg::minify(icon),
+///     r#"<svg xmlns="http://www.w3.org/2000/svg"><path d="M10 .5L-.25 20"/></svg>"#
+/// );
+/// ```
+pub fn minify(source: &str) -> String {
+    minify_with_precision(source, DEFAULT_PRECISION)
+}
+
+/// [`minify_with_precision`] will minify the svg in `source`, like [`minify`],
+/// rounding path data to `precision` decimal places.
+pub fn minify_with_precision(source: &str, precision: usize) -> String {
+    let mut minified = String::new();
+    // the names of the open elements, and whether each is being removed
+    let mut open = Vec::<(String, bool)>::new();
+    let mut position = 0;
+    while position < source.len() {
+        let is_removing = open.last().is_some_and(|(_, removing)| *removing);
+        let rest = &source[position..];
+        let end = if rest.starts_with("<!--") {
+            rest.find("-->")
+                .map_or(source.len(), |index| position + index + 3)
+        } else if rest.starts_with("<![CDATA[") {
+            rest.find("]]>")
+                .map_or(source.len(), |index| position + index + 3)
+        } else if rest.starts_with('<') {
+            tag_end(source, position)
+        } else {
+            rest.find('<')
+                .map_or(source.len(), |index| position + index)
+        };
+        let markup = &source[position..end];
+        position = end;
+
+        if is_removing && !markup.starts_with('<') || markup.starts_with("<!--") {
+            continue;
+        }
+        if !markup.starts_with('<') || markup.starts_with("<![CDATA[") {
+            let is_text = open
+                .iter()
+                .any(|(name, _)| TEXT_ELEMENTS.contains(&name.as_str()));
+            match is_text || markup.starts_with("<![CDATA[") {
+                true => minified.push_str(&collapse_whitespace(markup)),
+                false => minified.push_str(markup.trim()),
+            }
+            continue;
+        }
+        if markup.starts_with("<?") || markup.starts_with("<!") {
+            if !is_removing {
+                minified.push_str(markup);
+            }
+            continue;
+        }
+
+        let mut tag = Tag::parse(markup);
+        if tag.is_closing {
+            let is_removed = open.pop().is_some_and(|(_, removing)| removing);
+            if !is_removed {
+                minified.push_str(&tag.to_html());
+            }
+            continue;
+        }
+        let is_removed = is_removing || tag.name == "metadata" || is_editor_name(tag.name);
+        if is_removed {
+            if !tag.is_self_closing {
+                open.push((tag.name.to_string(), true));
+            }
+            continue;
+        }
+        if !tag.is_self_closing {
+            open.push((tag.name.to_string(), false));
+        }
+        tag.attributes.retain(|(attribute, _)| {
+            let namespace = attribute.strip_prefix("xmlns:").unwrap_or(attribute);
+            !is_editor_name(namespace) && !is_editor_prefix(namespace)
+        });
+        let paths = tag
+            .attributes
+            .iter()
+            .map(|(attribute, value)| match (*attribute, value) {
+                ("d" | "points", Some(value)) => Some(shorten_path(value, precision)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        for ((_, value), path) in tag.attributes.iter_mut().zip(&paths) {
+            if let Some(path) = path {
+                *value = Some(path);
+            }
+        }
+        minified.push_str(&tag.to_html());
+    }
+    minified
+}
+
+/// [`is_editor_name`] returns `true` for an element or attribute, like
+/// `sodipodi:namedview`, in an editor's namespace.
+fn is_editor_name(name: &str) -> bool {
+    name.split_once(':')
+        .is_some_and(|(prefix, _)| EDITOR_PREFIXES.contains(&prefix))
+}
+
+fn is_editor_prefix(name: &str) -> bool {
+    EDITOR_PREFIXES.contains(&name)
+}
+
+/// [`shorten_path`] rounds every number within the quoted path data `path`
+/// to `precision` decimal places, and removes every separator that isn't
+/// needed.
+///
+/// # example
+/// [`shorten_path`] can be used to shrink the `d` of a path:
+/// ```rust
+/// assert_eq!(rcss::svg::shorten_path(r#""M 0.5, 1.23456 L 10 -20 z""#, 2), r#""M.5 1.23L10-20z""#);
+/// ```
+pub fn shorten_path(path: &str, precision: usize) -> String {
+    let quote = path.chars().next().filter(|c| matches!(c, '"' | '\''));
+    let data = match quote {
+        Some(quote) => path.trim_start_matches(quote).trim_end_matches(quote),
+        None => path,
+    };
+    let mut shortened = String::new();
+    let mut characters = data.char_indices().peekable();
+    let mut previous_number: Option<String> = None;
+    while let Some((start, character)) = characters.next() {
+        if character.is_ascii_alphabetic() {
+            shortened.push(character);
+            previous_number = None;
+            continue;
+        }
+        if !(character.is_ascii_digit() || matches!(character, '-' | '+' | '.')) {
+            continue;
+        }
+        let mut end = start + character.len_utf8();
+        let mut has_dot = character == '.';
+        while let Some(&(index, next)) = characters.peek() {
+            let is_exponent_sign = matches!(next, '-' | '+') && data[..index].ends_with(['e', 'E']);
+            let is_part = next.is_ascii_digit()
+                || (next == '.' && !has_dot)
+                || (matches!(next, 'e' | 'E')
+                    && data[index + 1..]
+                        .starts_with(|c: char| c.is_ascii_digit() || c == '-' || c == '+'))
+                || is_exponent_sign;
+            if !is_part {
+                break;
+            }
+            has_dot |= next == '.';
+            end = index + next.len_utf8();
+            characters.next();
+        }
+        let number = match data[start..end].parse::<f64>() {
+            Ok(value) => format_number(value, precision),
+            Err(_) => data[start..end].to_string(),
+        };
+        // a sign, or a second decimal point, already separates two numbers
+        let needs_separator = previous_number.as_ref().is_some_and(|previous| {
+            let is_separated =
+                number.starts_with('-') || (number.starts_with('.') && previous.contains('.'));
+            !is_separated
+        });
+        if needs_separator {
+            shortened.push(' ');
+        }
+        shortened.push_str(&number);
+        previous_number = Some(number);
+    }
+    match quote {
+        Some(quote) => format!("{quote}{shortened}{quote}"),
+        None => shortened,
+    }
+}
+
+/// [`format_number`] writes `value` rounded to `precision` decimal places,
+/// without any trailing zeros or leading zero.
+fn format_number(value: f64, precision: usize) -> String {
+    let rounded = format!("{value:.precision$}");
+    let mut number = match rounded.contains('.') {
+        true => rounded
+            .trim_end_matches('0')
+            .trim_end_matches('.')
+            .to_string(),
+        false => rounded,
+    };
+    if number == "-0" {
+        number = "0".to_string();
+    }
+    if let Some(fraction) = number.strip_prefix("0.") {
+        number = format!(".{fraction}");
+    } else if let Some(fraction) = number.strip_prefix("-0.") {
+        number = format!("-.{fraction}");
+    }
+    number
+}
@@ -0,0 +1,432 @@
+//! a small css tokenizer, loosely following the css syntax level 3
+//! tokenization rules. it recognizes strings, urls, comments, and
+//! at-rules so that minification only ever rewrites the parts of a
+//! stylesheet that are safe to rewrite.
+
+/// [`TokenKind`] describes what a [`Token`] is, so minification rules
+/// can decide whether a token is a literal (and must be left alone) or
+/// punctuation that surrounding whitespace can be removed from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TokenKind {
+    /// a run of spaces, tabs, and newlines.
+    Whitespace,
+    /// a `/* ... */` comment, including the delimiters.
+    Comment,
+    /// a single or double quoted string, including the quotes.
+    String,
+    /// an unquoted `url(...)`, including `url(` and `)`.
+    Url,
+    /// an `@` followed by a name, like `@media`.
+    AtKeyword,
+    /// a name, like `color` or `-webkit-box`.
+    Ident,
+    /// a name followed by `(`, like `calc(`, including the `(`.
+    Function,
+    /// a number with an optional unit or `%`, like `10px` or `.5`.
+    Number,
+    /// a `#` followed by a name, like `#fff` or `#header`.
+    Hash,
+    /// `:`
+    Colon,
+    /// `;`
+    Semicolon,
+    /// `,`
+    Comma,
+    /// `{`
+    OpenBrace,
+    /// `}`
+    CloseBrace,
+    /// `(`
+    OpenParen,
+    /// `)`
+    CloseParen,
+    /// `[`
+    OpenBracket,
+    /// `]`
+    CloseBracket,
+    /// any other single character, like `>`, `*`, or `!`.
+    Delim,
+}
+
+/// [`Token`] is a slice of the source stylesheet along with its
+/// [`TokenKind`], the byte offset it started at, and the 1-based
+/// line and column it started on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Token<'a> {
+    pub kind: TokenKind,
+    pub text: &'a str,
+    pub offset: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// [`tokenize`] will split `source` into a list of [`Token`]s. every
+/// byte of `source` belongs to exactly one token, so joining the text
+/// of every token reproduces `source` exactly.
+///
+/// # example
+/// [`tokenize`] can be used to find every string in a stylesheet:
+/// ```rust
+/// use rcss::tokenizer::{tokenize, TokenKind};
+///
+/// let strings = tokenize(r#"a::after { content: "a  b"; }"#)
+///     .into_iter()
+///     .filter(|token| token.kind == TokenKind::String)
+///     .map(|token| token.text)
+///     .collect::<Vec<_>>();
+/// assert_eq!(strings, vec![r#""a  b""#]);
+/// ```
+pub fn tokenize(source: &str) -> Vec<Token<'_>> {
+    let mut tokens = vec![];
+    let mut cursor = Cursor {
+        source,
+        position: 0,
+        line: 1,
+        column: 1,
+    };
+    while let Some(current) = cursor.peek(0) {
+        let (start, line, column) = (cursor.position, cursor.line, cursor.column);
+        let kind = match current {
+            c if is_whitespace(c) => {
+                cursor.eat_while(is_whitespace);
+                TokenKind::Whitespace
+            }
+            '/' if cursor.peek(1) == Some('*') => {
+                cursor.eat_comment();
+                TokenKind::Comment
+            }
+            '"' | '\'' => {
+                cursor.eat_string(current);
+                TokenKind::String
+            }
+            '@' if cursor.starts_ident(1) => {
+                cursor.bump();
+                cursor.eat_name();
+                TokenKind::AtKeyword
+            }
+            '#' if cursor.peek(1).is_some_and(|c| is_name(c) || c == '\\') => {
+                cursor.bump();
+                cursor.eat_name();
+                TokenKind::Hash
+            }
+            _ if cursor.starts_number(0) => {
+                cursor.eat_number();
+                TokenKind::Number
+            }
+            _ if cursor.starts_ident(0) => cursor.eat_ident_like(),
+            ':' => cursor.single(TokenKind::Colon),
+            ';' => cursor.single(TokenKind::Semicolon),
+            ',' => cursor.single(TokenKind::Comma),
+            '{' => cursor.single(TokenKind::OpenBrace),
+            '}' => cursor.single(TokenKind::CloseBrace),
+            '(' => cursor.single(TokenKind::OpenParen),
+            ')' => cursor.single(TokenKind::CloseParen),
+            '[' => cursor.single(TokenKind::OpenBracket),
+            ']' => cursor.single(TokenKind::CloseBracket),
+            _ => cursor.single(TokenKind::Delim),
+        };
+        tokens.push(Token {
+            kind,
+            text: &source[start..cursor.position],
+            offset: start,
+            line,
+            column,
+        });
+    }
+    tokens
+}
+
+/// [`SyntaxError`] is a mistake that makes a stylesheet malformed, at the
+/// 1-based line and column it starts on.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SyntaxError {
+    pub line: usize,
+    pub column: usize,
+    pub message: &'static str,
+}
+
+impl std::fmt::Display for SyntaxError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+/// [`remove_comments`] returns `tokens` without their comments. a comment
+/// between two tokens that would be read as one without it, like the
+/// `1px` and `2px` of `1px/**/2px`, is replaced with a single space.
+///
+/// # example
+/// [`remove_comments`] keeps the two values of a margin apart:
+/// ```rust
+/// use rcss::{css::minify, tokenizer::{remove_comments, tokenize}};
+///
+/// let tokens = remove_comments(tokenize("1px/**/2px"));
+/// let text = tokens.iter().map(|token| token.text).collect::<String>();
+/// assert_eq!(text, "1px 2px");
+/// assert_eq!(minify("a{margin:1px/**/2px}"), "a{margin:1px 2px}");
+/// assert_eq!(minify("a{color:red/**/}"), "a{color:red}");
+/// ```
+pub fn remove_comments(tokens: Vec<Token<'_>>) -> Vec<Token<'_>> {
+    let mut removed: Vec<Token> = Vec::with_capacity(tokens.len());
+    let mut comment = None;
+    for token in tokens {
+        if token.kind == TokenKind::Comment {
+            comment = comment.or(Some(token));
+            continue;
+        }
+        if let (Some(comment), Some(previous)) = (comment.take(), removed.last()) {
+            if would_merge(previous, &token) {
+                removed.push(Token {
+                    kind: TokenKind::Whitespace,
+                    text: " ",
+                    ..comment
+                });
+            }
+        }
+        removed.push(token);
+    }
+    removed
+}
+
+/// [`would_merge`] returns `true` if `left` and `right`, written next to
+/// each other, wouldn't be read as the same two tokens again.
+fn would_merge(left: &Token, right: &Token) -> bool {
+    let joined = format!("{}{}", left.text, right.text);
+    let tokens = tokenize(&joined);
+    !matches!(&tokens[..], [first, second] if first.kind == left.kind && second.kind == right.kind)
+}
+
+/// [`validate`] returns the first [`SyntaxError`] in `source`, like an
+/// unbalanced brace or an unterminated string or comment, that would leave
+/// the rest of a combined stylesheet broken.
+///
+/// # example
+/// [`validate`] can be used to find where a string was left open:
+/// ```rust
+/// use rcss::tokenizer::validate;
+///
+/// assert!(validate("a { content: \"}\"; }").is_ok());
+/// let error = validate("a {\n  content: \"open;\n}").unwrap_err();
+/// assert_eq!(error.to_string(), "line 2, column 12: unterminated string");
+/// ```
+pub fn validate(source: &str) -> Result<(), SyntaxError> {
+    let error = |token: &Token, message| SyntaxError {
+        line: token.line,
+        column: token.column,
+        message,
+    };
+    let mut open_braces = vec![];
+    for token in tokenize(source) {
+        match token.kind {
+            TokenKind::Comment if token.text.len() < 4 || !token.text.ends_with("*/") => {
+                return Err(error(&token, "unterminated comment"));
+            }
+            TokenKind::String if !is_terminated_string(token.text) => {
+                return Err(error(&token, "unterminated string"));
+            }
+            TokenKind::OpenBrace => open_braces.push(token),
+            TokenKind::CloseBrace if open_braces.pop().is_none() => {
+                return Err(error(&token, "unexpected }"));
+            }
+            _ => {}
+        }
+    }
+    match open_braces.pop() {
+        Some(token) => Err(error(&token, "unclosed {")),
+        None => Ok(()),
+    }
+}
+
+/// [`is_terminated_string`] returns `true` if the string token `text` ends
+/// with the quote it started with, rather than a line break or the end of
+/// the stylesheet.
+fn is_terminated_string(text: &str) -> bool {
+    let mut chars = text.chars();
+    let quote = chars.next();
+    while let Some(current) = chars.next() {
+        match current {
+            '\\' => {
+                chars.next();
+            }
+            _ if Some(current) == quote => return true,
+            _ => {}
+        }
+    }
+    false
+}
+
+fn is_whitespace(c: char) -> bool {
+    matches!(c, ' ' | '\t' | '\n' | '\r' | '\x0C')
+}
+
+fn is_name_start(c: char) -> bool {
+    c.is_ascii_alphabetic() || c == '_' || !c.is_ascii()
+}
+
+fn is_name(c: char) -> bool {
+    is_name_start(c) || c.is_ascii_digit() || c == '-'
+}
+
+/// [`Cursor`] walks through the characters of a stylesheet while
+/// keeping track of the current line and column.
+struct Cursor<'a> {
+    source: &'a str,
+    position: usize,
+    line: usize,
+    column: usize,
+}
+
+impl Cursor<'_> {
+    fn peek(&self, offset: usize) -> Option<char> {
+        self.source[self.position..].chars().nth(offset)
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let current = self.peek(0)?;
+        self.position += current.len_utf8();
+        if current == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(current)
+    }
+
+    fn single(&mut self, kind: TokenKind) -> TokenKind {
+        self.bump();
+        kind
+    }
+
+    fn eat_while(&mut self, predicate: impl Fn(char) -> bool) {
+        while self.peek(0).is_some_and(&predicate) {
+            self.bump();
+        }
+    }
+
+    fn eat_comment(&mut self) {
+        self.bump();
+        self.bump();
+        while let Some(current) = self.bump() {
+            if current == '*' && self.peek(0) == Some('/') {
+                self.bump();
+                return;
+            }
+        }
+    }
+
+    fn eat_string(&mut self, quote: char) {
+        self.bump();
+        while let Some(current) = self.peek(0) {
+            match current {
+                '\n' => return,
+                '\\' => {
+                    self.bump();
+                    self.bump();
+                }
+                _ => {
+                    self.bump();
+                    if current == quote {
+                        return;
+                    }
+                }
+            }
+        }
+    }
+
+    fn eat_name(&mut self) {
+        while let Some(current) = self.peek(0) {
+            if current == '\\' && self.peek(1).is_some_and(|c| c != '\n') {
+                self.bump();
+                self.bump();
+            } else if is_name(current) {
+                self.bump();
+            } else {
+                return;
+            }
+        }
+    }
+
+    fn starts_ident(&self, offset: usize) -> bool {
+        match (self.peek(offset), self.peek(offset + 1)) {
+            (Some('-'), Some(next)) => is_name_start(next) || next == '-' || next == '\\',
+            (Some('\\'), Some(next)) => next != '\n',
+            (Some(current), _) => is_name_start(current),
+            _ => false,
+        }
+    }
+
+    fn starts_number(&self, offset: usize) -> bool {
+        let digit = |c: Option<char>| c.is_some_and(|c| c.is_ascii_digit());
+        match self.peek(offset) {
+            Some('+' | '-') => {
+                digit(self.peek(offset + 1))
+                    || (self.peek(offset + 1) == Some('.') && digit(self.peek(offset + 2)))
+            }
+            Some('.') => digit(self.peek(offset + 1)),
+            current => digit(current),
+        }
+    }
+
+    fn eat_number(&mut self) {
+        let digit = |c: Option<char>| c.is_some_and(|c| c.is_ascii_digit());
+        if matches!(self.peek(0), Some('+' | '-')) {
+            self.bump();
+        }
+        self.eat_while(|c| c.is_ascii_digit());
+        if self.peek(0) == Some('.') && digit(self.peek(1)) {
+            self.bump();
+            self.eat_while(|c| c.is_ascii_digit());
+        }
+        if matches!(self.peek(0), Some('e' | 'E'))
+            && (digit(self.peek(1))
+                || (matches!(self.peek(1), Some('+' | '-')) && digit(self.peek(2))))
+        {
+            self.bump();
+            self.bump();
+            self.eat_while(|c| c.is_ascii_digit());
+        }
+        if self.peek(0) == Some('%') {
+            self.bump();
+        } else if self.starts_ident(0) {
+            self.eat_name();
+        }
+    }
+
+    fn eat_ident_like(&mut self) -> TokenKind {
+        let start = self.position;
+        self.eat_name();
+        if self.peek(0) != Some('(') {
+            return TokenKind::Ident;
+        }
+        let is_url = self.source[start..self.position].eq_ignore_ascii_case("url");
+        self.bump();
+        if !is_url {
+            return TokenKind::Function;
+        }
+
+        // a quoted url is a function followed by a string,
+        // otherwise the whole url is a single token.
+        let mut offset = 0;
+        while self.peek(offset).is_some_and(is_whitespace) {
+            offset += 1;
+        }
+        if matches!(self.peek(offset), Some('"' | '\'')) {
+            return TokenKind::Function;
+        }
+        while let Some(current) = self.bump() {
+            match current {
+                ')' => break,
+                '\\' => {
+                    self.bump();
+                }
+                _ => {}
+            }
+        }
+        TokenKind::Url
+    }
+}
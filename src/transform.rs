@@ -0,0 +1,68 @@
+//! custom structural passes, which library users can run over the parsed
+//! combined output alongside the built in [`crate::passes`].
+use std::fmt;
+
+use crate::stylesheet::Stylesheet;
+
+/// [`Transform`] changes a parsed stylesheet, like a built in pass. every
+/// transform in [`crate::Options::transforms`] runs over the combined output
+/// right after it is parsed, in order, before any built in pass, so those
+/// passes can optimize whatever a transform adds.
+///
+/// # notes
+/// any `Fn(&mut Stylesheet)` that can be shared between threads is a
+/// transform, named `"transform"`.
+///
+/// # example
+/// [`Transform`] can be implemented to move every custom property from an
+/// old design token namespace to a new one:
+/// ```rust
+/// use std::sync::Arc;
+///
+/// use rcss::{minifier::Minifier, stylesheet::{Node, Stylesheet}, transform::Transform};
+///
+/// struct RenameTokens;
+///
+/// impl Transform for RenameTokens {
+///     fn name(&self) -> &'static str {
+///         "rename-tokens"
+///     }
+///
+///     fn apply(&self, stylesheet: &mut Stylesheet) {
+///         for node in &mut stylesheet.nodes {
+///             if let Node::Rule(rule) = node {
+///                 for child in &mut rule.children {
+///                     if let Node::Declaration(declaration) = child {
+///                         declaration.value = declaration.value.replace("--old-", "--new-");
+///                     }
+///                 }
+///             }
+///         }
+///     }
+/// }
+///
+/// let minifier = Minifier::new().transform(RenameTokens);
+/// assert_eq!(minifier.minify("a { color: var(--old-red) }").unwrap(), "a{color:var(--new-red)}");
+/// ```
+pub trait Transform: Send + Sync {
+    /// [`Transform::name`] returns the name that the transform is profiled
+    /// as, like `"rename-tokens"`.
+    fn name(&self) -> &'static str {
+        "transform"
+    }
+
+    /// [`Transform::apply`] changes `stylesheet` in place.
+    fn apply(&self, stylesheet: &mut Stylesheet);
+}
+
+impl<F: Fn(&mut Stylesheet) + Send + Sync> Transform for F {
+    fn apply(&self, stylesheet: &mut Stylesheet) {
+        self(stylesheet)
+    }
+}
+
+impl fmt::Debug for dyn Transform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Transform({})", self.name())
+    }
+}
@@ -0,0 +1,228 @@
+//! rewriting of the relative `url()`s in a stylesheet, so they still point
+//! at the same files once it is combined into a file in another directory,
+//! or are inlined as data uris.
+use std::{fs, path::Path};
+
+use crate::{
+    log,
+    source_map::Mapping,
+    tokenizer::{tokenize, TokenKind},
+};
+
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// [`MIME_TYPES`] are the images and fonts that can be inlined, by file
+/// extension.
+const MIME_TYPES: &[(&str, &str)] = &[
+    ("png", "image/png"),
+    ("jpg", "image/jpeg"),
+    ("jpeg", "image/jpeg"),
+    ("gif", "image/gif"),
+    ("webp", "image/webp"),
+    ("avif", "image/avif"),
+    ("svg", "image/svg+xml"),
+    ("ico", "image/x-icon"),
+    ("bmp", "image/bmp"),
+    ("woff", "font/woff"),
+    ("woff2", "font/woff2"),
+    ("ttf", "font/ttf"),
+    ("otf", "font/otf"),
+    ("eot", "application/vnd.ms-fontobject"),
+];
+
+/// [`Edit`] is a url that [`rewrite`] changed, at the utf-16 `column` of
+/// the css it starts at, which made the css `delta` utf-16 units longer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Edit {
+    pub column: usize,
+    pub delta: isize,
+}
+
+/// [`is_relative`] returns `true` if `url` is relative to the stylesheet it
+/// is in, rather than being absolute, like `/img/a.png`, `//cdn/a.png`,
+/// `https://cdn/a.png`, `data:...`, or a fragment like `#filter`.
+///
+/// # example
+/// [`is_relative`] can be used to tell which urls need rewriting:
+/// ```rust
+/// use rcss::urls::is_relative;
+///
+/// assert!(is_relative("../img/icon.png"));
+/// assert!(is_relative("icon.png"));
+/// assert!(!is_relative("/img/icon.png"));
+/// assert!(!is_relative("data:image/png;base64,AAAA"));
+/// assert!(!is_relative("#shadow"));
+/// ```
+pub fn is_relative(url: &str) -> bool {
+    let scheme = url.split(['/', '?', '#']).next().unwrap_or_default();
+    !url.is_empty() && !url.starts_with(['/', '#', '\\']) && !scheme.contains(':')
+}
+
+/// [`join`] joins the relative `url` onto `prefix`, which is the path from
+/// the directory of the combined file to the directory of the stylesheet
+/// the url is in (with `/` separators), resolving every `.` and `..`.
+///
+/// # example
+/// [`join`] can be used to find `../img/icon.png`, from `components`:
+/// ```rust
+/// use rcss::urls::join;
+///
+/// assert_eq!(join("components", "../img/icon.png"), "img/icon.png");
+/// assert_eq!(join("../src", "./fonts/a.woff2?v=2#x"), "../src/fonts/a.woff2?v=2#x");
+/// ```
+pub fn join(prefix: &str, url: &str) -> String {
+    let (path, suffix) = url.split_at(url.find(['?', '#']).unwrap_or(url.len()));
+    let mut segments: Vec<&str> = vec![];
+    for segment in prefix.split('/').chain(path.split('/')) {
+        match segment {
+            "" | "." => {}
+            ".." if segments.last().is_some_and(|last| *last != "..") => {
+                segments.pop();
+            }
+            segment => segments.push(segment),
+        }
+    }
+    let mut joined = segments.join("/");
+    if path.ends_with('/') {
+        joined.push('/');
+    }
+    joined.push_str(suffix);
+    joined
+}
+
+/// [`data_uri`] returns the file at `url`, relative to `directory`, as a
+/// base64 data uri, or `None` if it isn't an image or font, is bigger than
+/// `threshold` bytes, or can't be read. a url with a fragment, like
+/// `sprite.svg#icon`, is never inlined.
+pub fn data_uri(directory: &Path, url: &str, threshold: usize) -> Option<String> {
+    if url.contains('#') {
+        return None;
+    }
+    let path = directory.join(url.split('?').next().unwrap_or(url));
+    let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+    let (_, mime_type) = MIME_TYPES.iter().find(|(known, _)| *known == extension)?;
+    if fs::metadata(&path).ok()?.len() > threshold as u64 {
+        return None;
+    }
+    let bytes = fs::read(&path).ok()?;
+    log::debug(format_args!("inlined {}", path.display()));
+    Some(format!("data:{mime_type};base64,{}", base64(&bytes)))
+}
+
+/// [`base64`] encodes `bytes` as padded base64.
+///
+/// # example
+/// ```rust
+/// assert_eq!(rcss::urls::base64(b"rcss!"), "cmNzcyE=");
+/// ```
+pub fn base64(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |group, (index, byte)| {
+            group | (*byte as u32) << (16 - index * 8)
+        });
+        for index in 0..4 {
+            match index <= chunk.len() {
+                true => {
+                    encoded.push(BASE64[(group >> (18 - index * 6)) as usize & 0b111111] as char)
+                }
+                false => encoded.push('='),
+            }
+        }
+    }
+    encoded
+}
+
+/// [`rewrite`] replaces every relative url within the minified `css` with
+/// what `replace` returns for it (if anything), returning the rewritten css
+/// and an [`Edit`] for every url that was replaced, so source map mappings
+/// can be moved along with them with [`shift`].
+///
+/// # example
+/// [`rewrite`] can be used to point a stylesheet in `components` at its
+/// images, once it is combined into its parent directory:
+/// ```rust
+/// use rcss::urls::{join, rewrite};
+///
+/// let css = r#"a{background:url(../img/a.png)}b{src:url("b c.woff")}"#;
+/// let (rewritten, _) = rewrite(css, |url| Some(join("components", url)));
+/// assert_eq!(rewritten, r#"a{background:url(img/a.png)}b{src:url("components/b c.woff")}"#);
+/// ```
+pub fn rewrite(css: &str, mut replace: impl FnMut(&str) -> Option<String>) -> (String, Vec<Edit>) {
+    let mut rewritten = String::new();
+    let mut edits = vec![];
+    let mut column = 0;
+    let mut is_url_function = false;
+    for token in tokenize(css) {
+        let replacement = match token.kind {
+            TokenKind::Url => {
+                let url = token.text[4..].strip_suffix(')').map(str::trim);
+                url.and_then(|url| relative_url(url, &mut replace))
+                    .map(|url| match needs_quotes(&url) {
+                        true => format!("url(\"{}\")", url.replace('"', "\\\"")),
+                        false => format!("url({url})"),
+                    })
+            }
+            TokenKind::String if is_url_function => {
+                let quote = &token.text[..1];
+                let url = token.text[1..]
+                    .strip_suffix(quote)
+                    .unwrap_or(&token.text[1..]);
+                relative_url(url, &mut replace).map(|url| {
+                    let escaped = url.replace(quote, &format!("\\{quote}"));
+                    format!("{quote}{escaped}{quote}")
+                })
+            }
+            _ => None,
+        };
+        if token.kind != TokenKind::Whitespace {
+            is_url_function =
+                token.kind == TokenKind::Function && token.text.eq_ignore_ascii_case("url(");
+        }
+        let length = token.text.encode_utf16().count();
+        match replacement {
+            Some(replacement) if replacement != token.text => {
+                let delta = replacement.encode_utf16().count() as isize - length as isize;
+                edits.push(Edit { column, delta });
+                rewritten.push_str(&replacement);
+            }
+            _ => rewritten.push_str(token.text),
+        }
+        column += length;
+    }
+    (rewritten, edits)
+}
+
+/// [`shift`] moves every one of `mappings` to where the css it points at
+/// was moved to by the `edits` of [`rewrite`].
+pub fn shift(mappings: &[Mapping], edits: &[Edit]) -> Vec<Mapping> {
+    mappings
+        .iter()
+        .map(|mapping| {
+            let delta = edits
+                .iter()
+                .filter(|edit| edit.column < mapping.generated_column)
+                .map(|edit| edit.delta)
+                .sum::<isize>();
+            Mapping {
+                generated_column: mapping.generated_column.saturating_add_signed(delta),
+                ..*mapping
+            }
+        })
+        .collect()
+}
+
+/// [`relative_url`] returns what `replace` returns for `url`, or `None` if
+/// it isn't relative, or uses escapes that can't safely be rewritten.
+fn relative_url(url: &str, replace: impl FnOnce(&str) -> Option<String>) -> Option<String> {
+    match is_relative(url) && !url.contains('\\') {
+        true => replace(url),
+        false => None,
+    }
+}
+
+/// [`needs_quotes`] returns `true` if `url` can't be written without
+/// quotes, like a url with a space in it.
+fn needs_quotes(url: &str) -> bool {
+    url.contains(|c: char| c.is_whitespace() || "()'\"".contains(c))
+}
@@ -0,0 +1,322 @@
+//! walking of source directories, either lazily on the calling thread or
+//! on several threads at once, skipping (with a warning) whatever can't be
+//! read rather than failing the whole walk.
+use std::{
+    collections::HashSet,
+    fs::{self, DirEntry, ReadDir},
+    io,
+    path::{Path, PathBuf},
+    sync::{Condvar, Mutex, PoisonError},
+    thread,
+};
+
+use crate::{
+    error::{RcssError, Result},
+    glob::FileFilter,
+    ignore::{self, Ignore},
+    log, ordering, parallel, Options,
+};
+
+/// [`Walk`] is a lazy iterator over every file within a directory that is
+/// allowed by a [`FileFilter`], in the order they are found.
+///
+/// # notes
+/// symbolic links are skipped unless [`Options::follow_symlinks`] is set,
+/// and so are hidden files and directories, like `.git`, unless
+/// [`Options::hidden`] is. a directory that was already walked (through a
+/// link) is never walked again, so a link cycle can't loop forever.
+/// directories are walked with a stack, rather than recursion, so a deeply
+/// nested tree can't overflow the stack, and no deeper than
+/// [`Options::max_depth`]. whatever the ignore files of
+/// [`ignore::ignore_file_names`] ignore is skipped, like git does, and so
+/// is every subdirectory or entry that can't be read, like one without
+/// permission, with a warning. only a root that can't be read is an error.
+///
+/// # example
+/// [`Walk`] can be used to list the files of `./assets/css` as they are
+/// found, skipping vendored ones:
+/// ```rust,no_run
+/// use rcss::{glob::FileFilter, walk::Walk};
+///
+/// let filter = FileFilter::new(&[], &["**/vendor/**".to_string()]);
+/// let options = rcss::Options::default();
+/// for file in Walk::new("./assets/css", &filter, &options).unwrap() {
+///     println!("{}", file.display());
+/// }
+/// ```
+pub struct Walk<'a> {
+    walker: Walker<'a>,
+    directories: Vec<Directory>,
+    entries: Option<(Directory, ReadDir)>,
+}
+
+impl<'a> Walk<'a> {
+    /// [`Walk::new`] starts walking `root`, failing if it can't be read.
+    pub fn new(
+        root: impl AsRef<Path>,
+        filter: &'a FileFilter,
+        options: &'a Options,
+    ) -> Result<Walk<'a>> {
+        let (walker, root, entries) = Walker::new(root.as_ref(), filter, options)?;
+        Ok(Walk {
+            walker,
+            directories: vec![],
+            entries: Some((root, entries)),
+        })
+    }
+}
+
+impl Iterator for Walk<'_> {
+    type Item = PathBuf;
+
+    fn next(&mut self) -> Option<PathBuf> {
+        loop {
+            if let Some((directory, entries)) = &mut self.entries {
+                if let Some(entry) = entries.next() {
+                    let file = self.walker.visit(directory, entry, &mut self.directories);
+                    if file.is_some() {
+                        return file;
+                    }
+                    continue;
+                }
+            }
+            let directory = self.directories.pop()?;
+            self.entries = self.walker.read(directory);
+        }
+    }
+}
+
+/// [`walk_parallel`] returns every file that a [`Walk`] of `root` would,
+/// reading up to `jobs` directories at once (or one per core if `jobs` is
+/// `0`), in no particular order.
+///
+/// # example
+/// [`walk_parallel`] can be used to list every file of a large tree on
+/// every core, sorted afterwards:
+/// ```rust,no_run
+/// let options = rcss::Options::default();
+/// let mut files =
+///     rcss::walk::walk_parallel("./node_modules", &Default::default(), &options, 0).unwrap();
+/// files.sort();
+/// ```
+pub fn walk_parallel(
+    root: impl AsRef<Path>,
+    filter: &FileFilter,
+    options: &Options,
+    jobs: usize,
+) -> Result<Vec<PathBuf>> {
+    let jobs = parallel::available_jobs(jobs);
+    if jobs <= 1 {
+        return Ok(Walk::new(root, filter, options)?.collect());
+    }
+    let (walker, root, entries) = Walker::new(root.as_ref(), filter, options)?;
+    let mut directories = vec![];
+    let mut files = entries
+        .filter_map(|entry| walker.visit(&root, entry, &mut directories))
+        .collect::<Vec<_>>();
+    if directories.is_empty() {
+        return Ok(files);
+    }
+    let queue = Mutex::new(Queue {
+        directories,
+        busy: 0,
+    });
+    let changed = Condvar::new();
+    let lock = || queue.lock().unwrap_or_else(PoisonError::into_inner);
+    thread::scope(|scope| {
+        let handles = (0..jobs)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut files = vec![];
+                    loop {
+                        let directory = {
+                            let mut queue = lock();
+                            loop {
+                                if let Some(next) = queue.directories.pop() {
+                                    queue.busy += 1;
+                                    break next;
+                                }
+                                // nothing is left, and nothing being read
+                                // could add any more
+                                if queue.busy == 0 {
+                                    changed.notify_all();
+                                    return files;
+                                }
+                                queue = changed.wait(queue).unwrap_or_else(PoisonError::into_inner);
+                            }
+                        };
+                        let mut directories = vec![];
+                        if let Some((directory, entries)) = walker.read(directory) {
+                            for entry in entries {
+                                files.extend(walker.visit(&directory, entry, &mut directories));
+                            }
+                        }
+                        let mut queue = lock();
+                        queue.directories.append(&mut directories);
+                        queue.busy -= 1;
+                        changed.notify_all();
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        for handle in handles {
+            files.extend(
+                handle
+                    .join()
+                    .unwrap_or_else(|panic| std::panic::resume_unwind(panic)),
+            );
+        }
+    });
+    Ok(files)
+}
+
+/// [`Queue`] is the directories that a [`walk_parallel`] has found but not
+/// read yet, and how many directories are being read.
+struct Queue {
+    directories: Vec<Directory>,
+    busy: usize,
+}
+
+/// [`Directory`] is a directory to walk, `depth` levels below the root, and
+/// the ignore files that apply to it.
+struct Directory {
+    path: PathBuf,
+    depth: usize,
+    ignore: Ignore,
+}
+
+/// [`Walker`] decides what to do with every entry of a walk, which can be
+/// shared between threads.
+struct Walker<'a> {
+    root: PathBuf,
+    filter: &'a FileFilter,
+    options: &'a Options,
+    /// every directory walked so far, which only needs to be kept while
+    /// following links, since nothing else can lead to one twice.
+    visited: Mutex<HashSet<PathBuf>>,
+}
+
+impl<'a> Walker<'a> {
+    /// [`Walker::new`] returns a walker of `root`, along with the root
+    /// itself and its entries.
+    fn new(
+        root: &Path,
+        filter: &'a FileFilter,
+        options: &'a Options,
+    ) -> Result<(Walker<'a>, Directory, ReadDir)> {
+        let mut visited = HashSet::new();
+        if options.follow_symlinks {
+            visited.insert(fs::canonicalize(root).map_err(RcssError::io(root))?);
+        }
+        log::debug(format_args!("walking {}", root.display()));
+        let entries = fs::read_dir(root).map_err(RcssError::io(root))?;
+        let names = ignore::ignore_file_names(options.gitignore);
+        let ignore = Ignore::above(root, names)
+            .and_then(|above| above.read(root, "", names))
+            .map_err(RcssError::io(root))?;
+        let walker = Walker {
+            root: root.to_path_buf(),
+            filter,
+            options,
+            visited: Mutex::new(visited),
+        };
+        let root = Directory {
+            path: root.to_path_buf(),
+            depth: 0,
+            ignore,
+        };
+        Ok((walker, root, entries))
+    }
+
+    /// [`Walker::read`] returns `directory`, with its own ignore files, and
+    /// its entries, or `None` after warning that it can't be read.
+    fn read(&self, mut directory: Directory) -> Option<(Directory, ReadDir)> {
+        log::debug(format_args!("walking {}", directory.path.display()));
+        let entries = fs::read_dir(&directory.path)
+            .map_err(|error| skip(&directory.path, &error))
+            .ok()?;
+        let within = ordering::relative_path(&directory.path, &self.root);
+        let names = ignore::ignore_file_names(self.options.gitignore);
+        directory.ignore = directory
+            .ignore
+            .read(&directory.path, &within, names)
+            .map_err(|error| skip(&directory.path, &error))
+            .ok()?;
+        Some((directory, entries))
+    }
+
+    /// [`Walker::visit`] returns the path of `entry` of `directory` if it
+    /// is a file to walk, or pushes it to `directories` if it is a directory
+    /// to walk.
+    fn visit(
+        &self,
+        directory: &Directory,
+        entry: io::Result<DirEntry>,
+        directories: &mut Vec<Directory>,
+    ) -> Option<PathBuf> {
+        let entry = entry.map_err(|error| skip(&directory.path, &error)).ok()?;
+        if !self.options.hidden && entry.file_name().as_encoded_bytes().starts_with(b".") {
+            return None;
+        }
+        let path = entry.path();
+        let file_type = entry
+            .file_type()
+            .map_err(|error| skip(&path, &error))
+            .ok()?;
+        let file_type = match file_type.is_symlink() {
+            false => file_type,
+            true if !self.options.follow_symlinks => return None,
+            // a broken link has nothing to combine
+            true => fs::metadata(&path).ok()?.file_type(),
+        };
+        let relative_path = ordering::relative_path(&path, &self.root);
+        let is_too_deep = self
+            .options
+            .max_depth
+            .is_some_and(|max_depth| directory.depth >= max_depth);
+        if file_type.is_dir()
+            && !is_too_deep
+            && self.filter.allows_directory(&relative_path)
+            && !directory.ignore.is_ignored(&relative_path, true)
+            && self.is_new_directory(&path)
+        {
+            directories.push(Directory {
+                path,
+                depth: directory.depth + 1,
+                ignore: directory.ignore.clone(),
+            });
+            return None;
+        }
+        match file_type.is_file()
+            && self.filter.allows_file(&relative_path)
+            && !directory.ignore.is_ignored(&relative_path, false)
+        {
+            true => Some(path),
+            false => None,
+        }
+    }
+
+    /// [`Walker::is_new_directory`] returns `true` if the directory at
+    /// `path` hasn't been walked yet.
+    fn is_new_directory(&self, path: &Path) -> bool {
+        if !self.options.follow_symlinks {
+            return true;
+        }
+        match fs::canonicalize(path) {
+            Ok(canonical) => self
+                .visited
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .insert(canonical),
+            Err(error) => {
+                skip(path, &error);
+                false
+            }
+        }
+    }
+}
+
+/// [`skip`] warns that `path` is skipped, since it couldn't be read.
+fn skip(path: &Path, error: &io::Error) {
+    log::warn(format_args!("skipping {}: {error}", path.display()));
+}
@@ -0,0 +1,20 @@
+//! bindings for running the minifier in a browser or node with
+//! `wasm-bindgen`, behind the `wasm` feature. only the string in, string
+//! out minifiers are exposed, since there is no filesystem to combine files
+//! from.
+use wasm_bindgen::prelude::wasm_bindgen;
+
+/// [`minify`] returns `css` minified, like [`crate::minify_string`].
+#[wasm_bindgen]
+pub fn minify(css: &str) -> String {
+    crate::minify_string(css)
+}
+
+/// [`minify_source`] returns `content` minified with the rule set for
+/// files with `extension`, like `js` or `svg`, like [`crate::minify_source`].
+/// it is exported to javascript as `minifySource`.
+#[wasm_bindgen(js_name = minifySource)]
+pub fn minify_source(extension: &str, content: &str) -> String {
+    let (minified, _, _) = crate::minify_source(extension, content, &crate::Options::default());
+    minified
+}
@@ -0,0 +1,75 @@
+//! polling based file watching, used to rebuild combined output
+//! whenever a source file changes.
+use std::{
+    io,
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use crate::{error::Result, glob::FileFilter, log, walk::Walk, Options};
+
+/// [`snapshot`] returns every file within `directory` that a build with
+/// `options` would walk, along with the time it was last modified. a file
+/// whose modification time can't be read is skipped, with a warning.
+pub fn snapshot(
+    directory: impl AsRef<Path>,
+    options: &Options,
+) -> Result<Vec<(PathBuf, SystemTime)>> {
+    let filter = FileFilter::new(&options.include, &options.exclude);
+    let mut files = vec![];
+    for file in Walk::new(directory, &filter, options)? {
+        match file.metadata().and_then(|metadata| metadata.modified()) {
+            Ok(modified) => files.push((file, modified)),
+            // a file removed since it was walked is simply gone
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {}
+            Err(error) => log::warn(format_args!("skipping {}: {error}", file.display())),
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// [`watch`] will check every one of `directories`, walked with its own
+/// options, for added, removed, or modified files every `interval`, and
+/// call `on_change` whenever something changed. it never returns unless a
+/// directory can't be read.
+///
+/// # notes
+/// changes that `on_change` makes itself, like writing the combined
+/// output, don't cause another call to `on_change`. only the files that a
+/// build would combine are watched, so hidden, ignored, and excluded files,
+/// like those within `.git`, can change without a rebuild.
+///
+/// # example
+/// [`watch`] can be used to rebuild `./assets/css/style.css` whenever a
+/// file in `./assets/css` changes:
+/// ```rust,no_run
+/// let options = rcss::Options::default();
+/// rcss::watch::watch(&[("./assets/css", &options)], std::time::Duration::from_millis(500), || {
+///     rcss::minify_directory("css", "./assets/css", "style.css").unwrap();
+/// })
+/// .unwrap();
+/// ```
+pub fn watch(
+    directories: &[(impl AsRef<Path>, &Options)],
+    interval: Duration,
+    mut on_change: impl FnMut(),
+) -> Result<()> {
+    let snapshot_all = || -> Result<Vec<_>> {
+        let mut files = vec![];
+        for (directory, options) in directories {
+            files.append(&mut snapshot(directory, options)?);
+        }
+        Ok(files)
+    };
+    let mut previous = snapshot_all()?;
+    loop {
+        thread::sleep(interval);
+        let current = snapshot_all()?;
+        if current != previous {
+            on_change();
+            previous = snapshot_all()?;
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use rcss::{
+    css::minify,
+    level::Level,
+    minifier::Minifier,
+    stylesheet::{Node, Stylesheet, MAX_DEPTH},
+};
+
+/// [`NESTED`] is a `@supports` block containing a `@media` block, which
+/// contains rules and another `@media` block with its own `@supports`.
+const NESTED: &str = "
+    @supports (display: grid) and (not (display: inline-grid)) {
+        @media screen and (min-width: 768px) {
+            .a { display : grid ; color : #ffffff }
+            @media (prefers-color-scheme: dark) {
+                .a > .b:hover { color : #ffffff ; }
+                @supports selector(:has(a)) { .c:has( > a ) { margin : 0px } }
+            }
+        }
+        .d { color: red }
+    }
+    .e { color: blue }
+";
+
+/// [`MINIFIED`] is [`NESTED`] minified.
+const MINIFIED: &str = concat!(
+    "@supports (display:grid) and (not (display:inline-grid)){",
+    "@media screen and (min-width:768px){",
+    ".a{display:grid;color:#fff}",
+    "@media (prefers-color-scheme:dark){",
+    ".a>.b:hover{color:#fff}",
+    "@supports selector(:has(a)){.c:has(>a){margin:0}}",
+    "}}",
+    ".d{color:red}",
+    "}",
+    ".e{color:blue}",
+);
+
+/// [`nested`] returns `rule` within `depth` `@supports` blocks.
+fn nested(depth: usize, rule: &str) -> String {
+    format!(
+        "{}{rule}{}",
+        "@supports (display:grid){".repeat(depth),
+        "}".repeat(depth)
+    )
+}
+
+#[test]
+fn nested_at_rules_are_minified() {
+    assert_eq!(minify(NESTED), MINIFIED);
+}
+
+#[test]
+fn nested_at_rules_survive_restructuring() {
+    let minifier = Minifier::new().level(Level::Restructure);
+    assert_eq!(minifier.minify(NESTED).unwrap(), MINIFIED);
+}
+
+#[test]
+fn nested_at_rules_are_parsed_as_blocks() {
+    let stylesheet = Stylesheet::parse(MINIFIED);
+    let [Node::AtRule(supports), Node::Rule(rule)] = &stylesheet.nodes[..] else {
+        panic!("expected @supports and a rule, not {:?}", stylesheet.nodes);
+    };
+    assert_eq!(supports.name, "supports");
+    assert_eq!(rule.selector, ".e");
+    let children = supports.children.as_ref().unwrap();
+    let [Node::AtRule(media), Node::Rule(rule)] = &children[..] else {
+        panic!("expected @media and a rule, not {children:?}");
+    };
+    assert_eq!(media.prelude, "screen and (min-width:768px)");
+    assert_eq!(rule.selector, ".d");
+    assert_eq!(media.children.as_ref().unwrap().len(), 2);
+    assert_eq!(stylesheet.to_css(), MINIFIED);
+}
+
+#[test]
+fn duplicates_are_only_merged_within_the_same_block() {
+    let minifier = Minifier::new().merge_duplicates(true);
+    assert_eq!(
+        minifier
+            .minify("@supports (x:y){@media print{.a{color:red}.b{margin:0}.a{color:red}}}.a{color:red}")
+            .unwrap(),
+        "@supports (x:y){@media print{.a{color:red}.b{margin:0}}}.a{color:red}"
+    );
+}
+
+#[test]
+fn media_queries_are_only_merged_within_the_same_block() {
+    let minifier = Minifier::new().merge_media_queries(true);
+    assert_eq!(
+        minifier
+            .minify("@supports (x:y){@media print{.a{color:red}}@media print{.b{color:blue}}}@supports (gap:0){@media print{.c{color:red}}}")
+            .unwrap(),
+        "@supports (x:y){@media print{.a{color:red}.b{color:blue}}}@supports (gap:0){@media print{.c{color:red}}}"
+    );
+}
+
+#[test]
+fn empty_nested_at_rules_are_removed() {
+    let minifier = Minifier::new().remove_empty_rules(true);
+    assert_eq!(
+        minifier
+            .minify("@supports (x:y){@media print{.a{}@media (min-width:1px){}}}.b{color:red}")
+            .unwrap(),
+        ".b{color:red}"
+    );
+}
+
+#[test]
+fn at_rules_nested_in_rules_are_flattened() {
+    let minifier = Minifier::new().flatten_nesting(true);
+    assert_eq!(
+        minifier
+            .minify(".a{color:red;@media print{color:blue;@supports (x:y){margin:0}}}")
+            .unwrap(),
+        ".a{color:red}@media print{.a{color:blue}@supports (x:y){.a{margin:0}}}"
+    );
+}
+
+#[test]
+fn at_rules_with_declarations_are_kept_within_nested_at_rules() {
+    let css = concat!(
+        "@media print{@page{margin:1cm;@top-center{content:\"x\"}}",
+        "@keyframes k{0%{opacity:0}to{opacity:1}}",
+        "@font-face{font-family:A;src:url(a.woff)}}",
+    );
+    let minifier = Minifier::new().level(Level::Restructure);
+    assert_eq!(minifier.minify(css).unwrap(), css);
+}
+
+#[test]
+fn deeply_nested_at_rules_are_kept() {
+    let css = nested(MAX_DEPTH * 2, ".a{color:red}");
+    let minifier = Minifier::new().level(Level::Restructure);
+    assert_eq!(minifier.minify(&css).unwrap(), css);
+    assert_eq!(Stylesheet::parse(&css).to_css(), css);
+}
+
+#[test]
+fn blocks_beyond_the_maximum_depth_are_kept_as_they_are() {
+    let minifier = Minifier::new().remove_empty_rules(true);
+    assert_eq!(minifier.minify(&nested(MAX_DEPTH - 1, ".a{}")).unwrap(), "");
+    let css = format!("{}.b{{color:red}}", nested(MAX_DEPTH, ".a{}"));
+    assert_eq!(minifier.minify(&css).unwrap(), css);
+}
+
+#[test]
+fn pathologically_nested_at_rules_do_not_overflow_the_stack() {
+    let css = nested(100_000, ".a{color:red}");
+    let minifier = Minifier::new().level(Level::Restructure);
+    assert_eq!(minifier.minify(&css).unwrap(), css);
+}
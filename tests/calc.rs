@@ -0,0 +1,76 @@
+use rcss::{css::minify, level::Level, minifier::Minifier};
+
+#[test]
+fn minify_keeps_the_whitespace_around_operators() {
+    assert_eq!(
+        minify("a { width: calc(100% - 20px); height: calc(1em + 2px) }"),
+        "a{width:calc(100% - 20px);height:calc(1em + 2px)}"
+    );
+    assert_eq!(
+        minify("a { width: calc( 100%\n  -\t20px ) }"),
+        "a{width:calc( 100% - 20px )}"
+    );
+}
+
+#[test]
+fn minify_keeps_the_whitespace_around_operators_next_to_comments() {
+    assert_eq!(
+        minify("a { width: calc(100%/* gutter */- 20px) }"),
+        "a{width:calc(100% - 20px)}"
+    );
+    assert_eq!(
+        minify("a { width: calc(10px/**/+/**/2px) }"),
+        "a{width:calc(10px + 2px)}"
+    );
+}
+
+#[test]
+fn minify_keeps_the_whitespace_around_operators_of_every_math_function() {
+    assert_eq!(
+        minify("a { width: min(10px, calc(100% - (2 * 10px))) }"),
+        "a{width:min(10px,calc(100% - (2 * 10px)))}"
+    );
+    assert_eq!(
+        minify("a { font-size: clamp(1rem, 2.5vw + 1rem, 3rem) }"),
+        "a{font-size:clamp(1rem,2.5vw + 1rem,3rem)}"
+    );
+    assert_eq!(
+        minify("a { width: -webkit-calc((100% - 10px)-(2em)) }"),
+        "a{width:-webkit-calc((100% - 10px) - (2em))}"
+    );
+    assert_eq!(
+        minify("a { margin: calc(1px - -2px) calc(100% - -.5em) }"),
+        "a{margin:calc(1px - -2px) calc(100% - -.5em)}"
+    );
+}
+
+#[test]
+fn minify_keeps_the_whitespace_around_operators_of_media_queries() {
+    assert_eq!(
+        minify("@media (min-width: calc(100px + 2em)) { a { color: red } }"),
+        "@media (min-width:calc(100px + 2em)){a{color:red}}"
+    );
+}
+
+#[test]
+fn minify_only_spaces_operators_within_math_functions() {
+    assert_eq!(
+        minify("a+b, c-d { margin: 1px -2px }"),
+        "a+b,c-d{margin:1px -2px}"
+    );
+    assert_eq!(minify("a { --gap: var(--x,-) }"), "a{--gap:var(--x,-)}");
+}
+
+#[test]
+fn restructuring_keeps_the_whitespace_around_operators() {
+    let minifier = Minifier::new().level(Level::Restructure);
+    let minified = minifier
+        .minify(
+            "a { margin-top: calc(1px + 2px) }\nb { color: red }\na { width: calc(100% - 20px) }",
+        )
+        .unwrap();
+    assert_eq!(
+        minified,
+        "a{margin-top:calc(1px + 2px);width:calc(100% - 20px)}b{color:red}"
+    );
+}
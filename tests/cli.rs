@@ -0,0 +1,111 @@
+mod common;
+
+use std::{fs, process::Command};
+
+use common::temp_directory;
+
+/// [`rcss`] returns a command that runs the rcss binary within `directory`.
+fn rcss(directory: &std::path::Path) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_rcss"));
+    command.current_dir(directory);
+    command
+}
+
+#[test]
+fn a_list_of_existing_files_is_never_overwritten() {
+    let directory = temp_directory("cli-file-list");
+    fs::write(directory.join("a.css"), "a { color: red }").unwrap();
+    fs::write(directory.join("b.css"), "b { color: blue }").unwrap();
+    let status = rcss(&directory).args(["a.css", "b.css"]).status().unwrap();
+    assert!(status.success());
+    assert_eq!(
+        fs::read_to_string(directory.join("a.css")).unwrap(),
+        "a { color: red }"
+    );
+    assert_eq!(
+        fs::read_to_string(directory.join("b.css")).unwrap(),
+        "b { color: blue }"
+    );
+    assert_eq!(
+        fs::read_to_string(directory.join("style.css")).unwrap(),
+        "a{color:red}b{color:blue}"
+    );
+}
+
+#[test]
+fn a_last_file_that_does_not_exist_is_the_output() {
+    let directory = temp_directory("cli-file-list-output");
+    fs::write(directory.join("a.css"), "a { color: red }").unwrap();
+    fs::write(directory.join("b.css"), "b { color: blue }").unwrap();
+    let status = rcss(&directory)
+        .args(["a.css", "b.css", "bundle.css"])
+        .status()
+        .unwrap();
+    assert!(status.success());
+    assert_eq!(
+        fs::read_to_string(directory.join("bundle.css")).unwrap(),
+        "a{color:red}b{color:blue}"
+    );
+}
+
+#[test]
+fn a_file_that_is_also_the_output_is_not_overwritten() {
+    let directory = temp_directory("cli-file-list-default-output");
+    fs::write(directory.join("a.css"), "a { color: red }").unwrap();
+    fs::write(directory.join("style.css"), "b { color: blue }").unwrap();
+    let output = rcss(&directory)
+        .args(["a.css", "style.css"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert_eq!(
+        fs::read_to_string(directory.join("style.css")).unwrap(),
+        "b { color: blue }"
+    );
+}
+
+#[test]
+fn options_set_in_the_config_override_the_preset() {
+    let directory = temp_directory("cli-preset-config");
+    fs::create_dir_all(directory.join("css")).unwrap();
+    fs::write(
+        directory.join("css/a.css"),
+        "a { color: red }\nb { color: red }",
+    )
+    .unwrap();
+    fs::write(directory.join("rcss.toml"), "merge-selectors = false").unwrap();
+    let status = rcss(&directory)
+        .args(["css", "--preset", "default"])
+        .status()
+        .unwrap();
+    assert!(status.success());
+    assert_eq!(
+        fs::read_to_string(directory.join("css/style.css")).unwrap(),
+        "a{color:red}b{color:red}"
+    );
+}
+
+#[test]
+fn options_set_for_a_named_bundle_override_the_preset() {
+    let directory = temp_directory("cli-preset-named-bundle");
+    fs::create_dir_all(directory.join("css")).unwrap();
+    fs::write(
+        directory.join("css/a.css"),
+        "a { color: red }\nb { color: red }",
+    )
+    .unwrap();
+    fs::write(
+        directory.join("rcss.toml"),
+        "[bundles.site]\ndirectory = \"css\"\nmerge-selectors = false",
+    )
+    .unwrap();
+    let status = rcss(&directory)
+        .args(["build", "--preset", "default"])
+        .status()
+        .unwrap();
+    assert!(status.success());
+    assert_eq!(
+        fs::read_to_string(directory.join("css/style.css")).unwrap(),
+        "a{color:red}b{color:red}"
+    );
+}
@@ -0,0 +1,21 @@
+use rcss::css::minify;
+
+#[test]
+fn hex_colors_are_shortened() {
+    assert_eq!(
+        minify(".a { color: #ffffff; background: #AABBCC }"),
+        ".a{color:#fff;background:#abc}"
+    );
+}
+
+#[test]
+fn hex_colors_of_ie_filters_are_kept() {
+    assert_eq!(
+        minify(".a { filter: progid:DXImageTransform.Microsoft.gradient(startColorstr=#00000000, endColorstr=#80000000) }"),
+        ".a{filter:progid:DXImageTransform.Microsoft.gradient(startColorstr=#00000000,endColorstr=#80000000)}"
+    );
+    assert_eq!(
+        minify(".a { -ms-filter: progid:DXImageTransform.Microsoft.gradient(startColorstr=#ffffffff) }"),
+        ".a{-ms-filter:progid:DXImageTransform.Microsoft.gradient(startColorstr=#ffffffff)}"
+    );
+}
@@ -0,0 +1,10 @@
+//! helpers shared by the integration tests.
+use std::{fs, path::PathBuf, process};
+
+/// [`temp_directory`] creates an empty directory for a single test.
+pub fn temp_directory(name: &str) -> PathBuf {
+    let directory = std::env::temp_dir().join(format!("rcss-{name}-{}", process::id()));
+    let _ = fs::remove_dir_all(&directory);
+    fs::create_dir_all(&directory).unwrap();
+    directory
+}
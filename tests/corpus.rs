@@ -0,0 +1,165 @@
+use std::{fs, path::PathBuf};
+
+use rcss::{
+    css::minify,
+    minifier::Minifier,
+    selectors,
+    stylesheet::{Node, Stylesheet},
+    tokenizer::{tokenize, TokenKind},
+};
+
+/// [`Shape`] is the structure of a [`Node`]: what kind of node it is, the
+/// selectors of a rule, and the name and prelude of an at-rule, without the
+/// values that minification is free to shorten.
+#[derive(Debug, PartialEq, Eq)]
+enum Shape {
+    Rule(Vec<String>, Vec<Shape>),
+    AtRule(String, String, Option<Vec<Shape>>),
+    Declaration(String, bool),
+    Raw,
+}
+
+/// [`corpus`] returns the path and content of every stylesheet of the
+/// corpus, in alphabetical order.
+fn corpus() -> Vec<(PathBuf, String)> {
+    let directory = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/corpus");
+    let mut files = fs::read_dir(&directory)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "css"))
+        .collect::<Vec<_>>();
+    files.sort();
+    assert!(
+        !files.is_empty(),
+        "{} has no stylesheets",
+        directory.display()
+    );
+    files
+        .into_iter()
+        .map(|path| {
+            let content = fs::read_to_string(&path).unwrap();
+            (path, content)
+        })
+        .collect()
+}
+
+/// [`shapes`] returns the [`Shape`] of every node of `nodes`. selectors and
+/// preludes are minified, so they compare the same however they are
+/// written.
+fn shapes(nodes: &[Node]) -> Vec<Shape> {
+    nodes
+        .iter()
+        .map(|node| match node {
+            Node::Rule(rule) => Shape::Rule(
+                selectors::split_list(&rule.selector)
+                    .iter()
+                    .map(|selector| selectors::minify(selector))
+                    .collect(),
+                shapes(&rule.children),
+            ),
+            Node::AtRule(at_rule) => {
+                let prelude = minify(&format!("@{} {}{{}}", at_rule.name, at_rule.prelude));
+                Shape::AtRule(
+                    at_rule.name.to_ascii_lowercase(),
+                    prelude,
+                    at_rule.children.as_deref().map(shapes),
+                )
+            }
+            Node::Declaration(declaration) => Shape::Declaration(
+                declaration.property.to_ascii_lowercase(),
+                declaration.important,
+            ),
+            Node::Raw(_) => Shape::Raw,
+        })
+        .collect()
+}
+
+/// [`count_raw`] returns how many nodes within `nodes`, and within every
+/// block within them, couldn't be parsed.
+fn count_raw(nodes: &[Node]) -> usize {
+    nodes
+        .iter()
+        .map(|node| match node {
+            Node::Raw(_) => 1,
+            node => node.children().map_or(0, |children| count_raw(children)),
+        })
+        .sum()
+}
+
+#[test]
+fn minifying_the_corpus_keeps_its_structure() {
+    for (path, content) in corpus() {
+        let expected = shapes(&Stylesheet::parse(&content).nodes);
+        let minified = minify(&content);
+        assert_eq!(
+            shapes(&Stylesheet::parse(&minified).nodes),
+            expected,
+            "minifying {} changed its structure",
+            path.display()
+        );
+    }
+}
+
+#[test]
+fn minifying_the_corpus_twice_changes_nothing() {
+    for (path, content) in corpus() {
+        let minified = minify(&content);
+        assert_eq!(
+            minify(&minified),
+            minified,
+            "minifying {} again changed it",
+            path.display()
+        );
+    }
+}
+
+#[test]
+fn minifying_the_corpus_is_smaller() {
+    for (path, content) in corpus() {
+        let minified = minify(&content);
+        assert!(
+            minified.len() < content.len(),
+            "minifying {} didn't make it smaller",
+            path.display()
+        );
+    }
+}
+
+#[test]
+fn minifying_the_corpus_keeps_every_string() {
+    for (path, content) in corpus() {
+        let minified = minify(&content);
+        for token in tokenize(&content) {
+            if token.kind == TokenKind::String {
+                assert!(
+                    minified.contains(token.text),
+                    "minifying {} lost the string {}",
+                    path.display(),
+                    token.text
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn the_corpus_is_parsed_without_raw_nodes() {
+    for (path, content) in corpus() {
+        let minified = minify(&content);
+        let stylesheet = Stylesheet::parse(&minified);
+        assert_eq!(count_raw(&stylesheet.nodes), 0, "{}", path.display());
+    }
+}
+
+#[test]
+fn minifying_the_corpus_is_the_same_with_the_minifier() {
+    let minifier = Minifier::new();
+    for (path, content) in corpus() {
+        assert_eq!(
+            minifier.minify(&content).unwrap(),
+            minify(&content),
+            "{}",
+            path.display()
+        );
+    }
+}
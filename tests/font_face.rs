@@ -0,0 +1,93 @@
+mod common;
+
+use std::fs;
+
+use common::temp_directory;
+
+use rcss::{css::minify, level::Level, minifier::Minifier, Options};
+
+/// [`FONT_FACE`] is a minified `@font-face` block with every descriptor that
+/// minification must leave alone.
+const FONT_FACE: &str = concat!(
+    r#"@font-face{font-family:"Inter Var";"#,
+    r#"src:url("inter.woff2") format("woff2") tech(variations),url(inter.woff) format("woff"),local("Inter");"#,
+    "unicode-range:U+0000-00FF,U+0131,U+0152-0153,U+2000-206F,U+0025-00FF,u+4??;",
+    "font-weight:100 900;font-display:swap}",
+);
+
+#[test]
+fn unicode_ranges_are_kept_as_written() {
+    assert_eq!(
+        minify("@font-face { unicode-range: U+0000-00FF, U+0131 , u+4?? ; }"),
+        "@font-face{unicode-range:U+0000-00FF,U+0131,u+4??}"
+    );
+    assert_eq!(
+        minify("@font-face { unicode-range: U+0025-00FF, U+1e00 }"),
+        "@font-face{unicode-range:U+0025-00FF,U+1e00}"
+    );
+}
+
+#[test]
+fn src_lists_and_format_hints_are_kept_as_written() {
+    let font_face = r#"
+        @font-face {
+            font-family: "Inter Var";
+            src: url("inter.woff2") format("woff2") tech(variations),
+                 url(inter.woff) format("woff"),
+                 local("Inter");
+            unicode-range: U+0000-00FF, U+0131, U+0152-0153, U+2000-206F, U+0025-00FF, u+4??;
+            font-weight: 100 900;
+            font-display: swap;
+        }
+    "#;
+    assert_eq!(minify(font_face), FONT_FACE);
+}
+
+#[test]
+fn font_faces_survive_restructuring() {
+    let minifier = Minifier::new().level(Level::Restructure);
+    assert_eq!(minifier.minify(FONT_FACE).unwrap(), FONT_FACE);
+}
+
+#[test]
+fn duplicate_font_faces_are_merged() {
+    let minifier = Minifier::new().merge_duplicates(true);
+    let css = format!("{FONT_FACE}.a{{font-family:\"Inter Var\"}}{FONT_FACE}");
+    assert_eq!(
+        minifier.minify(&css).unwrap(),
+        format!(".a{{font-family:\"Inter Var\"}}{FONT_FACE}")
+    );
+}
+
+#[test]
+fn different_font_faces_are_kept() {
+    let minifier = Minifier::new().merge_duplicates(true);
+    let bold = FONT_FACE.replace("font-weight:100 900", "font-weight:700");
+    let css = format!("{FONT_FACE}{bold}");
+    assert_eq!(minifier.minify(&css).unwrap(), css);
+}
+
+#[test]
+fn duplicate_font_faces_across_files_are_merged() {
+    let directory = temp_directory("font-face");
+    fs::write(
+        directory.join("a.css"),
+        format!("{FONT_FACE}.a{{color:red}}"),
+    )
+    .unwrap();
+    fs::write(
+        directory.join("b.css"),
+        format!("{FONT_FACE}.b{{color:blue}}"),
+    )
+    .unwrap();
+    let options = Options {
+        level: Level::Restructure,
+        ..Default::default()
+    };
+    rcss::minify_directory_with("css", directory.to_str().unwrap(), "style.css", &options).unwrap();
+    assert_eq!(
+        fs::read_to_string(directory.join("style.css")).unwrap(),
+        format!(".a{{color:red}}{FONT_FACE}.b{{color:blue}}")
+    );
+    fs::remove_dir_all(directory).unwrap();
+}
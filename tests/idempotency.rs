@@ -0,0 +1,136 @@
+use std::{fs, path::PathBuf};
+
+use rcss::{css::minify, level::Level, minifier::Minifier};
+
+/// [`minifiers`] returns a name and a [`Minifier`] for every level, and for
+/// every pass that removes, merges, or rewrites, all turned on at once.
+fn minifiers() -> Vec<(&'static str, Minifier)> {
+    let aggressive = Minifier::new()
+        .level(Level::Restructure)
+        .flatten_nesting(true)
+        .inline_variables(true)
+        .remove_unused_variables(true)
+        .remove_unused_at_rules(true)
+        .strip_hacks(true)
+        .normalize_case(true)
+        .add_prefixes(true)
+        .strip_prefixes(true)
+        .downlevel_colors(true)
+        .browsers("ie 11, safari >= 9, last 2 versions");
+    vec![
+        ("-O0", Minifier::new().level(Level::Concatenate)),
+        ("-O1", Minifier::new()),
+        ("-O2", Minifier::new().level(Level::Restructure)),
+        ("every pass", aggressive),
+    ]
+}
+
+/// [`assert_idempotent`] asserts that minifying `css` with `minifier`, and
+/// then minifying its output again, writes the same output both times.
+fn assert_idempotent(name: &str, minifier: &Minifier, css: &str) {
+    let once = minifier.minify(css).unwrap();
+    let twice = minifier.minify(&once).unwrap();
+    assert_eq!(
+        twice, once,
+        "minifying {css:?} again with {name} changed it"
+    );
+}
+
+#[test]
+fn minifying_the_corpus_again_changes_nothing() {
+    let directory = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/corpus");
+    for entry in fs::read_dir(directory).unwrap() {
+        let content = fs::read_to_string(entry.unwrap().path()).unwrap();
+        for (name, minifier) in minifiers() {
+            assert_idempotent(name, &minifier, &content);
+        }
+    }
+}
+
+#[test]
+fn zeros_and_spaces_are_only_shortened_once() {
+    for css in [
+        "a { margin: 0 0 0 0; padding: 0px 0em; }",
+        "a { margin: 0.0px  0.50em   00.5% ; }",
+        "a { transition: opacity 0s, transform 0ms ; flex: 1 1 0% }",
+        "a { b: 1.5.0; c: +.50.0em }",
+        "a { color: #FFFFFF; background: rgb(255, 0, 0) }",
+        "a , b  >  c { width: calc( 100% - ( 2 * 1px ) ) }",
+    ] {
+        for (name, minifier) in minifiers() {
+            assert_idempotent(name, &minifier, css);
+        }
+    }
+}
+
+#[test]
+fn adjacent_numbers_are_kept_apart() {
+    assert_eq!(minify("a{b:1.5.0}"), "a{b:1.5.0}");
+    assert_eq!(minify(&minify("a{b:+.50.0em}")), minify("a{b:+.50.0em}"));
+}
+
+#[test]
+fn rules_left_empty_do_not_stop_merging() {
+    // the empty nested rule used to keep the first `.a` from merging with
+    // the second until it was removed, on the next run
+    let css = ".a { color: red; &:hover { } } .b { top: 0 } .a { margin: 0 }";
+    let minifier = Minifier::new().level(Level::Restructure);
+    assert_idempotent("-O2", &minifier, css);
+    assert_eq!(
+        minifier.minify(css).unwrap(),
+        ".a{color:red;margin:0}.b{top:0}"
+    );
+}
+
+#[test]
+fn media_queries_left_next_to_each_other_are_merged() {
+    // merging the two `.a` rules leaves the two `@media` blocks next to
+    // each other, which have to be merged in the same run
+    let css = ".a { color: red } @media print { .b { top: 0 } } \
+               .a { margin: 0 } @media print { .c { top: 1px } }";
+    let minifier = Minifier::new().level(Level::Restructure);
+    assert_idempotent("-O2", &minifier, css);
+    assert_eq!(
+        minifier.minify(css).unwrap(),
+        ".a{color:red;margin:0}@media print{.b{top:0}.c{top:1px}}"
+    );
+}
+
+#[test]
+fn inlined_variables_are_minified() {
+    let minifier = Minifier::new().inline_variables(true);
+    let css =
+        ":root { --gap: 0px; --color: #FFFFFF } .a { margin: var(--gap); color: var(--color) }";
+    assert_eq!(minifier.minify(css).unwrap(), ".a{margin:0;color:#fff}");
+    assert_idempotent("--inline-variables", &minifier, css);
+}
+
+#[test]
+fn at_rules_only_used_by_removed_variables_are_removed() {
+    let minifier = Minifier::new()
+        .remove_unused_variables(true)
+        .remove_unused_at_rules(true);
+    let css = ".a { --animation: spin 1s } @keyframes spin { to { rotate: 1turn } } .b { top: 0 }";
+    assert_eq!(minifier.minify(css).unwrap(), ".a{}.b{top:0}");
+    assert_idempotent("--remove-unused-variables", &minifier, css);
+}
+
+#[test]
+fn variables_only_used_by_themselves_are_removed() {
+    let minifier = Minifier::new().remove_unused_variables(true);
+    let css = ":root { --a: var(--a, 1px); --b: 2px } .a { top: var(--b) }";
+    assert_eq!(
+        minifier.minify(css).unwrap(),
+        ":root{--b:2px}.a{top:var(--b)}"
+    );
+}
+
+#[test]
+fn downleveled_colors_are_merged_in_one_run() {
+    let minifier = Minifier::new()
+        .level(Level::Restructure)
+        .downlevel_colors(true)
+        .browsers("safari >= 11");
+    let css = ".a { color: rgb(0 0 0 / 50%); color: rgba(0, 0, 0, .5) }";
+    assert_idempotent("--downlevel-colors", &minifier, css);
+}
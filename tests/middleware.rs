@@ -0,0 +1,192 @@
+#![cfg(feature = "tower")]
+
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    future::{self, Future},
+    pin::pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Waker},
+};
+
+use bytes::Bytes;
+use http::{header, Method, Request, Response, StatusCode};
+use http_body_util::{BodyExt, Full};
+use rcss::middleware::MinifyLayer;
+use tower_layer::Layer;
+use tower_service::Service;
+
+/// [`Files`] are the content type, `ETag`, and body that [`Server`]
+/// responds with at every path.
+type Files = Arc<Mutex<HashMap<String, (&'static str, &'static str, &'static str)>>>;
+
+/// [`Server`] is an inner service that serves [`Files`], like a static
+/// file server that builds weak `ETag`s from their size and mtime. it
+/// responds to `HEAD` requests without a body, and to `Range` requests
+/// with the first 4 bytes of a file.
+#[derive(Clone, Default)]
+struct Server {
+    files: Files,
+}
+
+impl Service<Request<()>> for Server {
+    type Response = Response<Full<Bytes>>;
+    type Error = Infallible;
+    type Future = future::Ready<Result<Self::Response, Infallible>>;
+
+    fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Infallible>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: Request<()>) -> Self::Future {
+        let files = self.files.lock().unwrap();
+        let (content_type, etag, body) = files[request.uri().path()];
+        let mut response = Response::builder()
+            .header(header::CONTENT_TYPE, content_type)
+            .header(header::ETAG, etag);
+        let body = match request.headers().contains_key(header::RANGE) {
+            true => {
+                response = response
+                    .status(StatusCode::PARTIAL_CONTENT)
+                    .header(header::CONTENT_RANGE, format!("bytes 0-3/{}", body.len()));
+                &body[..4]
+            }
+            false if request.method() == Method::HEAD => "",
+            false => body,
+        };
+        let response = response.body(Full::new(Bytes::from(body))).unwrap();
+        future::ready(Ok(response))
+    }
+}
+
+impl Server {
+    /// [`Server::serve`] serves `body` at `path`, with `etag`.
+    fn serve(
+        &self,
+        path: &str,
+        content_type: &'static str,
+        etag: &'static str,
+        body: &'static str,
+    ) {
+        let mut files = self.files.lock().unwrap();
+        files.insert(path.to_string(), (content_type, etag, body));
+    }
+}
+
+/// [`block_on`] polls `future` until it is ready, which the futures of
+/// [`Server`] always are.
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let mut context = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+            return output;
+        }
+    }
+}
+
+/// [`send`] sends `request` to `service`, and returns the status and body
+/// of its response.
+fn send<S, B>(service: &mut S, request: Request<()>) -> (StatusCode, String)
+where
+    S: Service<Request<()>, Response = Response<B>, Error = Infallible>,
+    B: http_body::Body<Data = Bytes>,
+    B::Error: std::fmt::Debug,
+{
+    let response = block_on(service.call(request)).unwrap();
+    let status = response.status();
+    let body = block_on(response.into_body().collect()).unwrap();
+    (status, String::from_utf8(body.to_bytes().to_vec()).unwrap())
+}
+
+/// [`get`] requests `path` from `service`, and returns the body of its
+/// response.
+fn get<S, B>(service: &mut S, path: &str) -> String
+where
+    S: Service<Request<()>, Response = Response<B>, Error = Infallible>,
+    B: http_body::Body<Data = Bytes>,
+    B::Error: std::fmt::Debug,
+{
+    send(service, Request::get(path).body(()).unwrap()).1
+}
+
+#[test]
+fn css_responses_are_minified() {
+    let server = Server::default();
+    server.serve(
+        "/a.css",
+        "text/css; charset=utf-8",
+        "W/\"1\"",
+        ".a { color: red; }",
+    );
+    let mut service = MinifyLayer::new().layer(server);
+    assert_eq!(get(&mut service, "/a.css"), ".a{color:red}");
+}
+
+#[test]
+fn other_responses_are_passed_through() {
+    let server = Server::default();
+    server.serve("/a.js", "text/javascript", "W/\"1\"", "let a = 1 ;");
+    let mut service = MinifyLayer::new().layer(server);
+    assert_eq!(get(&mut service, "/a.js"), "let a = 1 ;");
+}
+
+#[test]
+fn responses_with_the_same_etag_at_different_paths_are_cached_apart() {
+    let server = Server::default();
+    server.serve(
+        "/a.css",
+        "text/css",
+        "W/\"12-1700000000\"",
+        ".a { color: red; }",
+    );
+    server.serve(
+        "/b.css",
+        "text/css",
+        "W/\"12-1700000000\"",
+        ".b { color: red; }",
+    );
+    let mut service = MinifyLayer::new().layer(server);
+    assert_eq!(get(&mut service, "/a.css"), ".a{color:red}");
+    assert_eq!(get(&mut service, "/b.css"), ".b{color:red}");
+    assert_eq!(get(&mut service, "/a.css"), ".a{color:red}");
+}
+
+#[test]
+fn responses_with_the_same_path_and_etag_are_minified_once() {
+    let server = Server::default();
+    server.serve("/a.css", "text/css", "\"1\"", ".a { color: red; }");
+    let mut service = MinifyLayer::new().layer(server.clone());
+    assert_eq!(get(&mut service, "/a.css"), ".a{color:red}");
+    // the cached body is served, since the etag says nothing changed
+    server.serve("/a.css", "text/css", "\"1\"", ".a { color: blue; }");
+    assert_eq!(get(&mut service, "/a.css"), ".a{color:red}");
+    server.serve("/a.css", "text/css", "\"2\"", ".a { color: blue; }");
+    assert_eq!(get(&mut service, "/a.css"), ".a{color:blue}");
+}
+
+#[test]
+fn head_responses_are_passed_through_and_not_cached() {
+    let server = Server::default();
+    server.serve("/a.css", "text/css", "\"1\"", ".a { color: red; }");
+    let mut service = MinifyLayer::new().layer(server);
+    let head = Request::head("/a.css").body(()).unwrap();
+    assert_eq!(send(&mut service, head), (StatusCode::OK, String::new()));
+    assert_eq!(get(&mut service, "/a.css"), ".a{color:red}");
+}
+
+#[test]
+fn partial_responses_are_passed_through_and_not_cached() {
+    let server = Server::default();
+    server.serve("/a.css", "text/css", "\"1\"", ".a { color: red; }");
+    let mut service = MinifyLayer::new().layer(server);
+    let range = Request::get("/a.css")
+        .header(header::RANGE, "bytes=0-3")
+        .body(())
+        .unwrap();
+    assert_eq!(
+        send(&mut service, range),
+        (StatusCode::PARTIAL_CONTENT, ".a {".to_string())
+    );
+    assert_eq!(get(&mut service, "/a.css"), ".a{color:red}");
+}
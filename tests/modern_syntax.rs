@@ -0,0 +1,143 @@
+use rcss::{
+    css::minify,
+    level::Level,
+    minifier::Minifier,
+    passes::{class_names::hash_class_names, scope::scope_selectors},
+    stylesheet::{Node, Stylesheet},
+};
+
+/// [`MODERN`] is css that uses container queries, cascade layers,
+/// `:has()`, nesting, and `@scope`.
+const MODERN: &str = "
+    @layer reset, base, components;
+    @import url(theme.css) layer(base) supports(display: grid);
+    @layer components {
+        .card {
+            container: card / inline-size;
+            color : #ffffff;
+            & > .title { margin : 0px }
+            &:has( > img ) { padding : 1em }
+            .dark & { color : #000000 }
+        }
+    }
+    @layer { .anonymous { color: red } }
+    @container card (inline-size > 30em) { .title { font-size : 2em } }
+    @container (width >= 600px) and style(--compact: true) { .b { gap : 0 } }
+    @media (400px <= width <= 800px) { .c { color : red } }
+    @scope (.card) to (.content) {
+        :scope { border : 1px solid }
+        img { max-width : 100% }
+    }
+";
+
+/// [`MINIFIED`] is [`MODERN`] minified.
+const MINIFIED: &str = concat!(
+    "@layer reset,base,components;",
+    "@import url(theme.css) layer(base) supports(display:grid);",
+    "@layer components{.card{container:card / inline-size;color:#fff;",
+    "&>.title{margin:0}&:has(>img){padding:1em}.dark &{color:#000}}}",
+    "@layer{.anonymous{color:red}}",
+    "@container card (inline-size>30em){.title{font-size:2em}}",
+    "@container (width>=600px) and style(--compact:true){.b{gap:0}}",
+    "@media (400px<=width<=800px){.c{color:red}}",
+    "@scope (.card) to (.content){:scope{border:1px solid}img{max-width:100%}}",
+);
+
+#[test]
+fn modern_syntax_is_minified() {
+    assert_eq!(minify(MODERN), MINIFIED);
+}
+
+#[test]
+fn modern_syntax_survives_restructuring() {
+    let minifier = Minifier::new().level(Level::Restructure);
+    assert_eq!(minifier.minify(MODERN).unwrap(), MINIFIED);
+    assert_eq!(Stylesheet::parse(MINIFIED).to_css(), MINIFIED);
+}
+
+#[test]
+fn layers_and_scopes_are_parsed_as_blocks() {
+    let stylesheet = Stylesheet::parse(MINIFIED);
+    let names = stylesheet
+        .nodes
+        .iter()
+        .map(|node| match node {
+            Node::AtRule(at_rule) => at_rule.name.as_str(),
+            _ => "",
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        [
+            "layer",
+            "import",
+            "layer",
+            "layer",
+            "container",
+            "container",
+            "media",
+            "scope"
+        ]
+    );
+    let Node::AtRule(scope) = &stylesheet.nodes[7] else {
+        unreachable!();
+    };
+    assert_eq!(scope.prelude, "(.card) to (.content)");
+    assert_eq!(scope.children.as_ref().unwrap().len(), 2);
+}
+
+#[test]
+fn nesting_selectors_are_flattened() {
+    let minifier = Minifier::new().flatten_nesting(true);
+    assert_eq!(
+        minifier
+            .minify(
+                ".card{color:red;&>.title{margin:0}&:has(>img){padding:1em}.dark &{color:#000}}"
+            )
+            .unwrap(),
+        ".card{color:red}.card>.title{margin:0}.card:has(>img){padding:1em}.dark .card{color:#000}"
+    );
+}
+
+#[test]
+fn nesting_within_layers_and_containers_is_flattened() {
+    let minifier = Minifier::new().flatten_nesting(true);
+    assert_eq!(
+        minifier
+            .minify("@layer a{.b{@container (width>1px){&.c{margin:0}}}}")
+            .unwrap(),
+        "@layer a{@container (width>1px){.b.c{margin:0}}}"
+    );
+}
+
+#[test]
+fn duplicates_are_not_merged_across_layers() {
+    let minifier = Minifier::new().merge_duplicates(true);
+    let css = "@layer a{.b{color:red}}@layer c{.b{color:red}}";
+    assert_eq!(minifier.minify(css).unwrap(), css);
+}
+
+#[test]
+fn scope_roots_are_scoped_rather_than_their_rules() {
+    let mut stylesheet =
+        Stylesheet::parse("@scope (.card,.panel) to (.content){:scope{margin:0}img{width:100%}}");
+    scope_selectors(&mut stylesheet, ".widget");
+    assert_eq!(
+        stylesheet.to_css(),
+        "@scope (.widget .card,.widget .panel) to (.content){:scope{margin:0}img{width:100%}}"
+    );
+}
+
+#[test]
+fn scope_roots_and_limits_are_hashed() {
+    let mut stylesheet =
+        Stylesheet::parse(".card{margin:0}@scope (.card) to (.content){:scope>.title{margin:0}}");
+    let names = hash_class_names(&mut stylesheet);
+    assert_eq!(
+        stylesheet.to_css(),
+        format!(
+            ".{}{{margin:0}}@scope (.{}) to (.{}){{:scope>.{}{{margin:0}}}}",
+            names["card"], names["card"], names["content"], names["title"]
+        )
+    );
+}
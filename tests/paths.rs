@@ -0,0 +1,142 @@
+mod common;
+
+use std::fs;
+
+use common::temp_directory;
+
+use rcss::paths::{normalize, same_file};
+
+#[test]
+fn normalize_treats_windows_and_unix_separators_alike() {
+    assert_eq!(
+        normalize(r"css\components\button.css"),
+        "css/components/button.css"
+    );
+    assert_eq!(
+        normalize(r"css/components\button.css"),
+        "css/components/button.css"
+    );
+    assert_eq!(
+        normalize(r"C:\some-dir\css\style.css"),
+        "c:/some-dir/css/style.css"
+    );
+    assert_eq!(
+        normalize("c:/some-dir/css/style.css"),
+        "c:/some-dir/css/style.css"
+    );
+}
+
+#[test]
+fn normalize_strips_the_verbatim_prefix_of_canonical_windows_paths() {
+    assert_eq!(
+        normalize(r"\\?\C:\some-dir\css\style.css"),
+        "c:/some-dir/css/style.css"
+    );
+}
+
+#[test]
+fn normalize_resolves_dots() {
+    assert_eq!(normalize(r".\css\.\style.css"), "css/style.css");
+    assert_eq!(normalize(r"css\components\..\style.css"), "css/style.css");
+    assert_eq!(normalize(r"..\shared\css\"), "../shared/css");
+    assert_eq!(normalize("/srv//site/css/"), "/srv/site/css");
+    assert_eq!(normalize(r"\\server\share\css"), "//server/share/css");
+}
+
+#[test]
+fn same_file_matches_every_spelling_of_an_existing_file() {
+    let directory = temp_directory("same-file");
+    fs::write(directory.join("style.css"), "a{color:red}").unwrap();
+    let directory = directory.to_string_lossy().to_string();
+    assert!(same_file(
+        format!("{directory}/style.css"),
+        format!("{directory}/./style.css")
+    ));
+    assert!(same_file(
+        format!("{directory}/style.css"),
+        format!(
+            "{directory}/../{}/style.css",
+            directory.rsplit(['/', '\\']).next().unwrap()
+        )
+    ));
+    assert!(!same_file(
+        format!("{directory}/style.css"),
+        format!("{directory}/base.css")
+    ));
+}
+
+#[test]
+fn output_is_not_combined_into_itself_when_spelled_differently() {
+    let directory = temp_directory("rerun");
+    fs::write(directory.join("a.css"), "a { color: red }").unwrap();
+    let directory = directory.to_string_lossy().to_string();
+    let options = rcss::Options {
+        out_dir: Some(format!("{directory}/.")),
+        ..Default::default()
+    };
+    for _ in 0..2 {
+        rcss::minify_directory_with("css", &directory, "style.css", &options).unwrap();
+    }
+    let output = fs::read_to_string(format!("{directory}/style.css")).unwrap();
+    assert_eq!(output, "a{color:red}");
+}
+
+#[test]
+fn files_named_like_the_output_are_still_combined() {
+    let directory = temp_directory("named-like-output");
+    fs::create_dir_all(directory.join("old")).unwrap();
+    fs::write(directory.join("old/style.css"), ".old { color: red }").unwrap();
+    fs::write(directory.join("my-style.css"), ".mine { color: blue }").unwrap();
+    fs::write(directory.join("style.css.bak"), ".backup { color: green }").unwrap();
+    let directory = directory.to_string_lossy().to_string();
+    for _ in 0..2 {
+        rcss::minify_directory_with("css", &directory, "style.css", &Default::default()).unwrap();
+    }
+    let output = fs::read_to_string(format!("{directory}/style.css")).unwrap();
+    assert_eq!(output.matches(".old{color:red}").count(), 1, "{output}");
+    assert_eq!(output.matches(".mine{color:blue}").count(), 1, "{output}");
+    assert!(!output.contains(".backup"), "{output}");
+}
+
+#[test]
+fn fingerprinted_files_named_like_the_output_elsewhere_are_still_combined() {
+    let directory = temp_directory("fingerprinted-elsewhere");
+    fs::create_dir_all(directory.join("old")).unwrap();
+    fs::write(directory.join("a.css"), ".a { color: red }").unwrap();
+    fs::write(directory.join("old/style.0a1b2c3d.css"), ".old { top: 0 }").unwrap();
+    let directory = directory.to_string_lossy().to_string();
+    let options = rcss::Options {
+        hash: true,
+        ..Default::default()
+    };
+    for _ in 0..2 {
+        rcss::minify_directory_with("css", &directory, "style.css", &options).unwrap();
+    }
+    let outputs = fs::read_dir(&directory)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+        .filter(|name| rcss::fingerprint::is_hashed_name(name, "style.css"))
+        .collect::<Vec<_>>();
+    assert_eq!(outputs.len(), 1, "{outputs:?}");
+    let output = fs::read_to_string(format!("{directory}/{}", outputs[0])).unwrap();
+    assert_eq!(output.matches(".old{top:0}").count(), 1, "{output}");
+    assert_eq!(output.matches(".a{color:red}").count(), 1, "{output}");
+}
+
+#[cfg(windows)]
+#[test]
+fn output_is_not_combined_into_itself_with_backslashes() {
+    let directory = temp_directory("rerun-backslashes");
+    fs::write(directory.join("a.css"), "a { color: red }").unwrap();
+    let directory = directory.to_string_lossy().to_string();
+    let options = rcss::Options {
+        out_dir: Some(directory.replace('/', "\\")),
+        ..Default::default()
+    };
+    for _ in 0..2 {
+        rcss::minify_directory_with("css", &directory.replace('\\', "/"), "style.css", &options)
+            .unwrap();
+    }
+    let output = fs::read_to_string(format!("{directory}/style.css")).unwrap();
+    assert_eq!(output, "a{color:red}");
+}
@@ -0,0 +1,70 @@
+use rcss::{css, selectors};
+
+/// [`assert_minifies`] checks that both the stylesheet minifier and the
+/// selector minifier turn the rule `{selector}{color:red}` into `expected`.
+fn assert_minifies(selector: &str, expected: &str) {
+    assert_eq!(
+        css::minify(&format!("{selector} {{ color: red }}")),
+        format!("{expected}{{color:red}}")
+    );
+    assert_eq!(selectors::minify(selector), expected);
+}
+
+#[test]
+fn descendant_combinators_are_kept() {
+    assert_minifies("nav   ul\n\tli", "nav ul li");
+    assert_minifies(".a /* note */ .b", ".a .b");
+    assert_minifies("[data-a]  [data-b]", "[data-a] [data-b]");
+    assert_minifies("*  .a", "* .a");
+    assert_minifies(r"#a\  .b", r"#a\  .b");
+}
+
+#[test]
+fn descendant_combinators_before_pseudo_classes_are_kept() {
+    assert_minifies("a :hover", "a :hover");
+    assert_minifies("article :first-child", "article :first-child");
+    assert_minifies(":is(a, b) :where(c)", ":is(a,b) :where(c)");
+    assert_minifies("a:not( .b ) :focus", "a:not(.b) :focus");
+}
+
+#[test]
+fn descendant_combinators_before_pseudo_elements_are_kept() {
+    assert_minifies("p ::before", "p ::before");
+    assert_minifies("p::before , p :after", "p::before,p :after");
+}
+
+#[test]
+fn whitespace_around_other_combinators_is_removed() {
+    assert_minifies("ul > li", "ul>li");
+    assert_minifies("h1 ~ p", "h1~p");
+    assert_minifies("h1 + p", "h1+p");
+    assert_minifies(".a > * + *", ".a>*+*");
+    assert_minifies("a:has( > img )", "a:has(>img)");
+}
+
+#[test]
+fn whitespace_within_functional_pseudo_classes_is_removed() {
+    assert_minifies(":not( .a , .b )", ":not(.a,.b)");
+    assert_minifies("li:nth-child( 2n + 1 )", "li:nth-child(2n+1)");
+    assert_minifies(".a :is( .b  .c )", ".a :is(.b .c)");
+}
+
+#[test]
+fn whitespace_within_attribute_selectors_is_removed() {
+    assert_minifies("a[ href ]", "a[href]");
+    assert_minifies(r#"a[ href ^= "https:" ]"#, r#"a[href^="https:"]"#);
+    assert_minifies(r#"[ type = "a" i ]"#, r#"[type="a" i]"#);
+    assert_minifies(r#"[data-label="a, b"]  >  b"#, r#"[data-label="a, b"]>b"#);
+}
+
+#[test]
+fn nested_selectors_keep_their_descendant_combinators() {
+    assert_eq!(
+        css::minify(".card { & .title { color: red } & > p { color: blue } color: green }"),
+        ".card{& .title{color:red}&>p{color:blue}color:green}"
+    );
+    assert_eq!(
+        css::minify("@media print { nav  a :hover { color: red } }"),
+        "@media print{nav a :hover{color:red}}"
+    );
+}